@@ -0,0 +1,258 @@
+//! Encode and decode cost of the protocols over an in-memory transport.
+//!
+//! Every async benchmark has a `sync_binary` counterpart doing the same work
+//! with the `thrift` crate. Reads and writes served from the transport's
+//! buffer complete without a boxed future, so the two should be close. Run
+//! with
+//!
+//! ```sh
+//! cargo bench -p async_thrift --bench protocol
+//! ```
+//!
+//! `record.rs` and `sync_record.rs` are generated from the structs of
+//! `benchmark/bench.thrift`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use futures::executor::block_on;
+
+use async_thrift::{Bytes, OrderedFloat};
+use async_thrift::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol, TFieldIdentifier, TMessageIdentifier, TMessageType, TStructIdentifier, TType};
+use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+use async_thrift::protocol::async_metadata::{TAsyncMetadataInputProtocol, TAsyncMetadataOutputProtocol, TRACE_ID_KEY};
+use async_thrift::transport::async_mem::TAsyncBufferChannel;
+use thrift::protocol::{TBinaryInputProtocol, TBinaryOutputProtocol, TInputProtocol, TOutputProtocol};
+use thrift::transport::TBufferChannel;
+
+mod record;
+mod sync_record;
+
+/// Items, map entries and labels of the benchmarked records.
+const RECORD_ITEMS: [usize; 3] = [1, 100, 10_000];
+
+/// Values written and read by the primitive benchmarks.
+const PRIMITIVES: usize = 1000;
+
+/// Sizes of the binary fields read by the binary benchmarks.
+const BINARY_SIZES: [usize; 2] = [64 * 1024, 1024 * 1024];
+
+// the same records as the benchmark crate's echo_record calls
+fn record(items: usize) -> record::Record {
+    let item = |i: usize| record::Item::new(i as i64, format!("item {}", i), OrderedFloat::from(i as f64), (0..8).collect::<Vec<i32>>());
+    record::Record::new(0, "record".to_owned(), item(0),
+                        (0..items).map(item).collect::<Vec<_>>(),
+                        (0..items).map(|i| (format!("item {}", i), item(i))).collect::<BTreeMap<_, _>>(),
+                        (0..items).map(|i| format!("label {}", i)).collect::<BTreeSet<_>>())
+}
+
+fn sync_record(items: usize) -> sync_record::Record {
+    let item = |i: usize| sync_record::Item::new(i as i64, format!("item {}", i), thrift::OrderedFloat::from(i as f64), (0..8).collect::<Vec<i32>>());
+    sync_record::Record::new(0, "record".to_owned(), item(0),
+                             (0..items).map(item).collect::<Vec<_>>(),
+                             (0..items).map(|i| (format!("item {}", i), item(i))).collect::<BTreeMap<_, _>>(),
+                             (0..items).map(|i| format!("label {}", i)).collect::<BTreeSet<_>>())
+}
+
+fn encode_record(record: &record::Record) -> Vec<u8> {
+    let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+    block_on(record.write_to_out_protocol(&mut o_prot)).unwrap();
+    o_prot.transport.take_write_bytes()
+}
+
+fn decode_record(bytes: Vec<u8>) -> record::Record {
+    let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+    block_on(record::Record::read_from_in_protocol(&mut i_prot)).unwrap()
+}
+
+// TBufferChannel has a fixed capacity, `size` is the encoded size
+fn sync_encode_record(record: &sync_record::Record, size: usize) -> Vec<u8> {
+    let mut o_prot = TBinaryOutputProtocol::new(TBufferChannel::with_capacity(0, size), true);
+    record.write_to_out_protocol(&mut o_prot).unwrap();
+    o_prot.transport.write_bytes()
+}
+
+fn sync_channel(bytes: &[u8]) -> TBufferChannel {
+    let mut channel = TBufferChannel::with_capacity(bytes.len(), 0);
+    channel.set_readable_bytes(bytes);
+    channel
+}
+
+fn sync_decode_record(channel: TBufferChannel) -> sync_record::Record {
+    let mut i_prot = TBinaryInputProtocol::new(channel, true);
+    sync_record::Record::read_from_in_protocol(&mut i_prot).unwrap()
+}
+
+fn batch_size(items: usize) -> BatchSize {
+    if items > 100 { BatchSize::LargeInput } else { BatchSize::SmallInput }
+}
+
+fn bench_record(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_record");
+    for &items in RECORD_ITEMS.iter() {
+        let record = record(items);
+        let sync_record = sync_record(items);
+        let size = encode_record(&record).len();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("async_binary", items), &record, |b, record| {
+            b.iter(|| encode_record(black_box(record)))
+        });
+        group.bench_with_input(BenchmarkId::new("sync_binary", items), &sync_record, |b, record| {
+            b.iter(|| sync_encode_record(black_box(record), size))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("decode_record");
+    for &items in RECORD_ITEMS.iter() {
+        let bytes = encode_record(&record(items));
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("async_binary", items), &bytes, |b, bytes| {
+            b.iter_batched(|| bytes.clone(), decode_record, batch_size(items))
+        });
+        group.bench_with_input(BenchmarkId::new("sync_binary", items), &bytes, |b, bytes| {
+            b.iter_batched(|| sync_channel(bytes), sync_decode_record, batch_size(items))
+        });
+    }
+    group.finish();
+}
+
+fn bench_primitives(c: &mut Criterion) {
+    let mut group = c.benchmark_group("primitives");
+    group.throughput(Throughput::Elements(PRIMITIVES as u64));
+
+    group.bench_function("async_binary/write_i64", |b| b.iter(|| {
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(async {
+            for i in 0..PRIMITIVES {
+                o_prot.write_i64(i as i64).await.unwrap();
+            }
+        });
+        o_prot.transport.take_write_bytes()
+    }));
+    group.bench_function("sync_binary/write_i64", |b| b.iter(|| {
+        let mut o_prot = TBinaryOutputProtocol::new(TBufferChannel::with_capacity(0, PRIMITIVES * 8), true);
+        for i in 0..PRIMITIVES {
+            o_prot.write_i64(i as i64).unwrap();
+        }
+        o_prot.transport.write_bytes()
+    }));
+
+    let bytes = (0..PRIMITIVES as i64).flat_map(|i| i.to_be_bytes().to_vec()).collect::<Vec<u8>>();
+    group.bench_function("async_binary/read_i64", |b| b.iter_batched(|| bytes.clone(), |bytes| {
+        let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+        block_on(async {
+            for _ in 0..PRIMITIVES {
+                black_box(i_prot.read_i64().await.unwrap());
+            }
+        })
+    }, BatchSize::SmallInput));
+    group.bench_function("sync_binary/read_i64", |b| b.iter_batched(|| sync_channel(&bytes), |channel| {
+        let mut i_prot = TBinaryInputProtocol::new(channel, true);
+        for _ in 0..PRIMITIVES {
+            black_box(i_prot.read_i64().unwrap());
+        }
+    }, BatchSize::SmallInput));
+    group.finish();
+}
+
+fn bench_binary(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_binary_field");
+    for &size in BINARY_SIZES.iter() {
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(o_prot.write_bytes(&vec![7u8; size])).unwrap();
+        let bytes = Bytes::from(o_prot.transport.take_write_bytes());
+        group.throughput(Throughput::Bytes(size as u64));
+        // cloning `Bytes` does not copy, so only the read itself is measured
+        group.bench_with_input(BenchmarkId::new("read_bytes", size), &bytes, |b, bytes| {
+            b.iter_batched(|| bytes.clone(), |bytes| {
+                let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+                block_on(i_prot.read_bytes()).unwrap()
+            }, BatchSize::SmallInput)
+        });
+        group.bench_with_input(BenchmarkId::new("read_binary", size), &bytes, |b, bytes| {
+            b.iter_batched(|| bytes.clone(), |bytes| {
+                let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+                block_on(i_prot.read_binary()).unwrap()
+            }, BatchSize::SmallInput)
+        });
+    }
+    group.finish();
+}
+
+// a call of echo_record as a client writes it
+async fn write_call(o_prot: &mut (dyn TAsyncOutputProtocol + Send), record: &record::Record) -> async_thrift::Result<()> {
+    o_prot.write_message_begin(&TMessageIdentifier::new("echo_record", TMessageType::Call, 1)).await?;
+    o_prot.write_struct_begin(&TStructIdentifier::new("echo_record_args")).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("record", TType::Struct, 1)).await?;
+    record.write_to_out_protocol(o_prot).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await?;
+    o_prot.write_message_end().await?;
+    o_prot.flush().await
+}
+
+// a call of echo_record as a processor reads it
+async fn read_call(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Option<record::Record>> {
+    i_prot.read_message_begin().await?;
+    i_prot.read_struct_begin().await?;
+    let mut record = None;
+    loop {
+        let field_ident = i_prot.read_field_begin().await?;
+        if field_ident.field_type == TType::Stop {
+            break;
+        }
+        match field_ident.id {
+            Some(1) => record = Some(record::Record::read_from_in_protocol(i_prot).await?),
+            _ => i_prot.skip(field_ident.field_type).await?,
+        }
+        i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    i_prot.read_message_end().await?;
+    Ok(record)
+}
+
+fn metadata_output_protocol() -> TAsyncMetadataOutputProtocol<TAsyncBinaryOutputProtocol<TAsyncBufferChannel>> {
+    let mut o_prot = TAsyncMetadataOutputProtocol::new(TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true));
+    o_prot.metadata_mut().insert(TRACE_ID_KEY.to_owned(), "4bf92f3577b34da6a3ce929d0e0e4736".to_owned());
+    o_prot
+}
+
+fn bench_call(c: &mut Criterion) {
+    let mut group = c.benchmark_group("call");
+    let record = record(1);
+
+    group.bench_function("binary/write", |b| b.iter(|| {
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(write_call(&mut o_prot, black_box(&record))).unwrap();
+        o_prot.transport.take_write_bytes()
+    }));
+    group.bench_function("metadata/write", |b| b.iter(|| {
+        let mut o_prot = metadata_output_protocol();
+        block_on(write_call(&mut o_prot, black_box(&record))).unwrap();
+        o_prot.into_inner().transport.take_write_bytes()
+    }));
+
+    let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+    block_on(write_call(&mut o_prot, &record)).unwrap();
+    let bytes = o_prot.transport.take_write_bytes();
+    group.bench_function("binary/read", |b| b.iter_batched(|| bytes.clone(), |bytes| {
+        let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+        block_on(read_call(&mut i_prot)).unwrap()
+    }, BatchSize::SmallInput));
+
+    let mut o_prot = metadata_output_protocol();
+    block_on(write_call(&mut o_prot, &record)).unwrap();
+    let bytes = o_prot.into_inner().transport.take_write_bytes();
+    group.bench_function("metadata/read", |b| b.iter_batched(|| bytes.clone(), |bytes| {
+        let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+        let mut i_prot = TAsyncMetadataInputProtocol::new(i_prot);
+        block_on(read_call(&mut i_prot)).unwrap()
+    }, BatchSize::SmallInput));
+    group.finish();
+}
+
+criterion_group!(benches, bench_record, bench_primitives, bench_binary, bench_call);
+criterion_main!(benches);
@@ -0,0 +1,342 @@
+// Autogenerated by Thrift Compiler ()
+// DO NOT EDIT UNLESS YOU ARE SURE THAT YOU KNOW WHAT YOU ARE DOING
+
+#![allow(unused_imports)]
+#![allow(unused_extern_crates)]
+#![cfg_attr(feature = "cargo-clippy", allow(too_many_arguments, type_complexity))]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate async_thrift;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::{From, TryFrom};
+use std::default::Default;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use async_thrift::{ApplicationError, ApplicationErrorKind, ProtocolError, ProtocolErrorKind, TThriftClient};
+use async_thrift::protocol::{TFieldIdentifier, TListIdentifier, TMapIdentifier, TMessageIdentifier, TMessageType, TAsyncInputProtocol, TAsyncOutputProtocol, TSetIdentifier, TStructIdentifier, TType};
+use async_thrift::protocol::field_id;
+use async_thrift::protocol::verify_expected_message_type;
+use async_thrift::protocol::verify_expected_sequence_number;
+use async_thrift::protocol::verify_expected_service_call;
+use async_thrift::protocol::verify_required_field_exists;
+use async_trait::async_trait;
+use async_thrift::server::TAsyncProcessor;
+use async_thrift::OrderedFloat;
+
+//
+// Item
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Item {
+  pub id: Option<i64>,
+  pub name: Option<String>,
+  pub score: Option<OrderedFloat<f64>>,
+  pub tags: Option<Vec<i32>>,
+}
+
+impl Item {
+  pub fn new<F1, F2, F3, F4>(id: F1, name: F2, score: F3, tags: F4) -> Item where F1: Into<Option<i64>>, F2: Into<Option<String>>, F3: Into<Option<OrderedFloat<f64>>>, F4: Into<Option<Vec<i32>>> {
+    Item {
+      id: id.into(),
+      name: name.into(),
+      score: score.into(),
+      tags: tags.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Item> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i64> = Some(0);
+    let mut f_2: Option<String> = Some("".to_owned());
+    let mut f_3: Option<OrderedFloat<f64>> = Some(OrderedFloat::from(0.0));
+    let mut f_4: Option<Vec<i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i64().await?;
+          f_1 = Some(val);
+        },
+        2 => {
+          let val = i_prot.read_string().await?;
+          f_2 = Some(val);
+        },
+        3 => {
+          let val = OrderedFloat::from(i_prot.read_double().await?);
+          f_3 = Some(val);
+        },
+        4 => {
+          let list_ident = i_prot.read_list_begin().await?;
+          let mut val: Vec<i32> = Vec::with_capacity(list_ident.size as usize);
+          for _ in 0..list_ident.size {
+            let list_elem_0 = i_prot.read_i32().await?;
+            val.push(list_elem_0);
+          }
+          i_prot.read_list_end().await?;
+          f_4 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Item {
+      id: f_1,
+      name: f_2,
+      score: f_3,
+      tags: f_4,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("Item");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.id {
+      o_prot.write_field_begin(&TFieldIdentifier::new("id", TType::I64, 1)).await?;
+      o_prot.write_i64(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.name {
+      o_prot.write_field_begin(&TFieldIdentifier::new("name", TType::String, 2)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(fld_var) = self.score {
+      o_prot.write_field_begin(&TFieldIdentifier::new("score", TType::Double, 3)).await?;
+      o_prot.write_double(fld_var.into()).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.tags {
+      o_prot.write_field_begin(&TFieldIdentifier::new("tags", TType::List, 4)).await?;
+      o_prot.write_list_begin(&TListIdentifier::new(TType::I32, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        o_prot.write_i32(*e).await?;
+      }
+      o_prot.write_list_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+async_thrift::impl_serializable!(Item);
+
+impl Default for Item {
+  fn default() -> Self {
+    Item{
+      id: Some(0),
+      name: Some("".to_owned()),
+      score: Some(OrderedFloat::from(0.0)),
+      tags: None,
+    }
+  }
+}
+
+//
+// Record
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Record {
+  pub id: Option<i64>,
+  pub name: Option<String>,
+  pub head: Option<Item>,
+  pub items: Option<Vec<Item>>,
+  pub index: Option<BTreeMap<String, Item>>,
+  pub labels: Option<BTreeSet<String>>,
+}
+
+impl Record {
+  pub fn new<F1, F2, F3, F4, F5, F6>(id: F1, name: F2, head: F3, items: F4, index: F5, labels: F6) -> Record where F1: Into<Option<i64>>, F2: Into<Option<String>>, F3: Into<Option<Item>>, F4: Into<Option<Vec<Item>>>, F5: Into<Option<BTreeMap<String, Item>>>, F6: Into<Option<BTreeSet<String>>> {
+    Record {
+      id: id.into(),
+      name: name.into(),
+      head: head.into(),
+      items: items.into(),
+      index: index.into(),
+      labels: labels.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Record> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i64> = Some(0);
+    let mut f_2: Option<String> = Some("".to_owned());
+    let mut f_3: Option<Item> = None;
+    let mut f_4: Option<Vec<Item>> = None;
+    let mut f_5: Option<BTreeMap<String, Item>> = None;
+    let mut f_6: Option<BTreeSet<String>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i64().await?;
+          f_1 = Some(val);
+        },
+        2 => {
+          let val = i_prot.read_string().await?;
+          f_2 = Some(val);
+        },
+        3 => {
+          let val = Item::read_from_in_protocol(i_prot).await?;
+          f_3 = Some(val);
+        },
+        4 => {
+          let list_ident = i_prot.read_list_begin().await?;
+          let mut val: Vec<Item> = Vec::with_capacity(list_ident.size as usize);
+          for _ in 0..list_ident.size {
+            let list_elem_1 = Item::read_from_in_protocol(i_prot).await?;
+            val.push(list_elem_1);
+          }
+          i_prot.read_list_end().await?;
+          f_4 = Some(val);
+        },
+        5 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<String, Item> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_2 = i_prot.read_string().await?;
+            let map_val_3 = Item::read_from_in_protocol(i_prot).await?;
+            val.insert(map_key_2, map_val_3);
+          }
+          i_prot.read_map_end().await?;
+          f_5 = Some(val);
+        },
+        6 => {
+          let set_ident = i_prot.read_set_begin().await?;
+          let mut val: BTreeSet<String> = BTreeSet::new();
+          for _ in 0..set_ident.size {
+            let set_elem_4 = i_prot.read_string().await?;
+            val.insert(set_elem_4);
+          }
+          i_prot.read_set_end().await?;
+          f_6 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Record {
+      id: f_1,
+      name: f_2,
+      head: f_3,
+      items: f_4,
+      index: f_5,
+      labels: f_6,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("Record");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.id {
+      o_prot.write_field_begin(&TFieldIdentifier::new("id", TType::I64, 1)).await?;
+      o_prot.write_i64(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.name {
+      o_prot.write_field_begin(&TFieldIdentifier::new("name", TType::String, 2)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.head {
+      o_prot.write_field_begin(&TFieldIdentifier::new("head", TType::Struct, 3)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.items {
+      o_prot.write_field_begin(&TFieldIdentifier::new("items", TType::List, 4)).await?;
+      o_prot.write_list_begin(&TListIdentifier::new(TType::Struct, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        e.write_to_out_protocol(o_prot).await?;
+      }
+      o_prot.write_list_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.index {
+      o_prot.write_field_begin(&TFieldIdentifier::new("index", TType::Map, 5)).await?;
+      o_prot.write_map_begin(&TMapIdentifier::new(TType::String, TType::Struct, fld_var.len() as i32)).await?;
+      for (k, v) in fld_var {
+        o_prot.write_string(k).await?;
+        v.write_to_out_protocol(o_prot).await?;
+      }
+      o_prot.write_map_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.labels {
+      o_prot.write_field_begin(&TFieldIdentifier::new("labels", TType::Set, 6)).await?;
+      o_prot.write_set_begin(&TSetIdentifier::new(TType::String, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        o_prot.write_string(e).await?;
+      }
+      o_prot.write_set_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+async_thrift::impl_serializable!(Record);
+
+impl Default for Record {
+  fn default() -> Self {
+    Record{
+      id: Some(0),
+      name: Some("".to_owned()),
+      head: None,
+      items: None,
+      index: None,
+      labels: None,
+    }
+  }
+}
+
@@ -0,0 +1,683 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use async_std::{future, task};
+use async_trait::async_trait;
+use futures::future::{self as futures_future, BoxFuture, Either};
+use rand::Rng;
+
+use crate::errors::{new_transport_error, TransportErrorKind};
+use crate::protocol::async_metadata::{current_deadline, with_deadline};
+
+use super::hedge::{THedgePolicy, TLatencyWindow};
+use super::retry::{TCircuitBreaker, TCircuitState};
+use super::{TAsyncClientFactory, TCallFailure};
+
+/// Default number of idle connections kept per endpoint.
+const DEFAULT_MAX_IDLE_PER_ENDPOINT: usize = 8;
+
+/// Supplies the addresses a `TBalancedClient` spreads calls across.
+#[async_trait]
+pub trait TAsyncResolver: Send + Sync {
+    /// Return the addresses currently serving the service.
+    async fn resolve(&self) -> crate::Result<Vec<String>>;
+}
+
+/// `TAsyncResolver` that always returns the same addresses.
+#[derive(Clone, Debug)]
+pub struct TStaticResolver {
+    addresses: Vec<String>,
+}
+
+impl TStaticResolver {
+    /// Create a `TStaticResolver` that returns `addresses`.
+    pub fn new<I, S>(addresses: I) -> TStaticResolver
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+    {
+        TStaticResolver {
+            addresses: addresses.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl TAsyncResolver for TStaticResolver {
+    async fn resolve(&self) -> crate::Result<Vec<String>> {
+        Ok(self.addresses.clone())
+    }
+}
+
+/// How a `TBalancedClient` picks the endpoint for a call.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TBalanceStrategy {
+    /// Take endpoints in turn.
+    RoundRobin,
+    /// Take the endpoint with the fewest calls in progress.
+    LeastOutstanding,
+    /// Take the less busy of two endpoints chosen at random.
+    PowerOfTwoChoices,
+}
+
+/// Snapshot of one endpoint of a `TBalancedClient`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TEndpointStatus {
+    /// Address of the endpoint.
+    pub address: String,
+    /// Number of calls in progress.
+    pub outstanding: usize,
+    /// Number of idle connections.
+    pub idle_connections: usize,
+    /// `Open` while the endpoint is ejected.
+    pub state: TCircuitState,
+}
+
+/// Connections to, and health of, one server.
+struct TEndpoint<C> {
+    address: String,
+    idle: Mutex<Vec<C>>,
+    outstanding: AtomicUsize,
+    breaker: Mutex<TCircuitBreaker>,
+}
+
+/// Tracks a call in progress on an endpoint.
+///
+/// Dropping an unfinished call (e.g. because its future was cancelled)
+/// that was let through as the endpoint's half-open probe releases the
+/// probe so it can be probed again.
+struct TEndpointCall<'a, C> {
+    endpoint: &'a TEndpoint<C>,
+    probe: bool,
+    finished: bool,
+}
+
+impl<'a, C> TEndpointCall<'a, C> {
+    fn new(endpoint: &'a TEndpoint<C>, probe: bool) -> TEndpointCall<'a, C> {
+        endpoint.outstanding.fetch_add(1, Ordering::Relaxed);
+        TEndpointCall { endpoint, probe, finished: false }
+    }
+
+    fn finish(&mut self, healthy: bool) {
+        let mut breaker = self.endpoint.breaker.lock().unwrap();
+        if healthy {
+            breaker.record_success();
+        } else {
+            breaker.record_failure();
+        }
+        self.finished = true;
+    }
+}
+
+impl<'a, C> Drop for TEndpointCall<'a, C> {
+    fn drop(&mut self) {
+        self.endpoint.outstanding.fetch_sub(1, Ordering::Relaxed);
+        if self.probe && !self.finished {
+            self.endpoint.breaker.lock().unwrap().cancel_request();
+        }
+    }
+}
+
+/// Client that spreads calls across several servers.
+///
+/// Addresses come from a `TAsyncResolver` and are refreshed on the first
+/// call and then, if a refresh interval is set, whenever it has elapsed.
+/// Idle connections to each endpoint are pooled and reused; new ones are
+/// created by the factory when needed, so concurrent calls through a shared
+/// `TBalancedClient` each get their own connection.
+///
+/// An endpoint whose calls fail with a `TransportError` or `ProtocolError`
+/// a number of times in a row is ejected: no calls are sent to it until the
+/// ejection period has passed, after which a single call probes it and
+/// either brings it back or ejects it again. Failed calls are not retried.
+///
+/// Calls get a deadline from `with_call_timeout` or, if earlier, from the
+/// code making them (see `async_metadata::current_deadline`). A call still
+/// running at its deadline fails with a `TimedOut` `TransportError`, and
+/// clients built on `TAsyncMetadataOutputProtocol` send the deadline to the
+/// server. Latency-sensitive idempotent calls can be made with
+/// `call_hedged`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use async_thrift::client::balance::{TBalanceStrategy, TBalancedClient};
+/// use async_thrift::client::TAsyncTcpClientFactory;
+/// use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+/// use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+///
+/// # async_std::task::block_on(async {
+/// let factory = TAsyncTcpClientFactory::new(
+///     TAsyncBufferedReadTransportFactory::new(),
+///     TAsyncBinaryInputProtocolFactory::new(),
+///     TAsyncBufferedWriteTransportFactory::new(),
+///     TAsyncBinaryOutputProtocolFactory::new(),
+///     |i_prot, o_prot| (i_prot, o_prot), // e.g. `CalculatorSyncClient::new`
+/// );
+/// let client = TBalancedClient::with_addresses(vec!["10.0.0.1:9090", "10.0.0.2:9090"], factory)
+///     .with_strategy(TBalanceStrategy::PowerOfTwoChoices)
+///     .with_ejection(3, Duration::from_secs(30));
+///
+/// // with a generated client: client.call(|c| c.ping()).await?;
+/// client.call(|(_, o_prot)| o_prot.flush()).await?;
+/// # Ok::<(), async_thrift::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct TBalancedClient<F>
+    where
+        F: TAsyncClientFactory,
+{
+    factory: F,
+    resolver: Box<dyn TAsyncResolver>,
+    strategy: TBalanceStrategy,
+    ejection: TCircuitBreaker,
+    max_idle_per_endpoint: usize,
+    refresh_interval: Option<Duration>,
+    call_timeout: Option<Duration>,
+    hedge_policy: THedgePolicy,
+    endpoints: RwLock<Vec<Arc<TEndpoint<F::Client>>>>,
+    last_refresh: Mutex<Option<Instant>>,
+    latencies: Mutex<TLatencyWindow>,
+    next: AtomicUsize,
+}
+
+impl<F> TBalancedClient<F>
+    where
+        F: TAsyncClientFactory + Send + Sync,
+{
+    /// Create a `TBalancedClient` over the addresses returned by `resolver`.
+    ///
+    /// Calls are spread round-robin, and endpoints are ejected for 10
+    /// seconds after 5 consecutive failures.
+    pub fn new<R>(resolver: R, factory: F) -> TBalancedClient<F>
+        where
+            R: TAsyncResolver + 'static,
+    {
+        TBalancedClient {
+            factory,
+            resolver: Box::new(resolver),
+            strategy: TBalanceStrategy::RoundRobin,
+            ejection: TCircuitBreaker::default(),
+            max_idle_per_endpoint: DEFAULT_MAX_IDLE_PER_ENDPOINT,
+            refresh_interval: None,
+            call_timeout: None,
+            hedge_policy: THedgePolicy::default(),
+            endpoints: RwLock::new(Vec::new()),
+            last_refresh: Mutex::new(None),
+            latencies: Mutex::new(TLatencyWindow::new()),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Create a `TBalancedClient` over a fixed list of addresses.
+    pub fn with_addresses<I, S>(addresses: I, factory: F) -> TBalancedClient<F>
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+    {
+        TBalancedClient::new(TStaticResolver::new(addresses), factory)
+    }
+
+    /// Pick endpoints with `strategy`.
+    pub fn with_strategy(mut self, strategy: TBalanceStrategy) -> TBalancedClient<F> {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Eject an endpoint for `ejection_time` after `failure_threshold`
+    /// consecutive failed calls.
+    pub fn with_ejection(mut self, failure_threshold: u32, ejection_time: Duration) -> TBalancedClient<F> {
+        self.ejection = TCircuitBreaker::new(failure_threshold, ejection_time);
+        self
+    }
+
+    /// Ask the resolver for addresses again once `interval` has passed since
+    /// the last refresh.
+    pub fn with_refresh_interval(mut self, interval: Duration) -> TBalancedClient<F> {
+        self.refresh_interval = Some(interval);
+        self
+    }
+
+    /// Give up on calls that have not completed within `timeout`.
+    pub fn with_call_timeout(mut self, timeout: Duration) -> TBalancedClient<F> {
+        self.call_timeout = Some(timeout);
+        self
+    }
+
+    /// Decide when `call_hedged` sends its duplicate request with `policy`.
+    pub fn with_hedge_policy(mut self, policy: THedgePolicy) -> TBalancedClient<F> {
+        self.hedge_policy = policy;
+        self
+    }
+
+    /// Keep at most `max_idle` idle connections per endpoint.
+    pub fn with_max_idle_per_endpoint(mut self, max_idle: usize) -> TBalancedClient<F> {
+        self.max_idle_per_endpoint = max_idle;
+        self
+    }
+
+    /// Replace the endpoint list with the addresses returned by the
+    /// resolver.
+    ///
+    /// Endpoints whose address is still present keep their connections and
+    /// ejection state.
+    pub async fn refresh(&self) -> crate::Result<()> {
+        *self.last_refresh.lock().unwrap() = Some(Instant::now());
+        let addresses = self.resolver.resolve().await?;
+
+        let mut endpoints = self.endpoints.write().unwrap();
+        let mut existing: HashMap<String, Arc<TEndpoint<F::Client>>> = endpoints
+            .drain(..)
+            .map(|endpoint| (endpoint.address.clone(), endpoint))
+            .collect();
+        for address in addresses {
+            let endpoint = match existing.remove(&address) {
+                Some(endpoint) => endpoint,
+                None => Arc::new(TEndpoint {
+                    address,
+                    idle: Mutex::new(Vec::new()),
+                    outstanding: AtomicUsize::new(0),
+                    breaker: Mutex::new(self.ejection.clone()),
+                }),
+            };
+            endpoints.push(endpoint);
+        }
+        Ok(())
+    }
+
+    /// Return the state of every endpoint.
+    pub fn endpoints(&self) -> Vec<TEndpointStatus> {
+        self.endpoints
+            .read()
+            .unwrap()
+            .iter()
+            .map(|endpoint| TEndpointStatus {
+                address: endpoint.address.clone(),
+                outstanding: endpoint.outstanding.load(Ordering::Relaxed),
+                idle_connections: endpoint.idle.lock().unwrap().len(),
+                state: endpoint.breaker.lock().unwrap().state(),
+            })
+            .collect()
+    }
+
+    /// Make a call by running `call` on a client connected to the endpoint
+    /// picked for it.
+    ///
+    /// Return `Err` if no endpoint is available, if a connection cannot be
+    /// opened, if `call` fails or if the call deadline passes.
+    pub async fn call<T, C>(&self, call: C) -> crate::Result<T>
+        where
+            C: for<'c> FnOnce(&'c mut F::Client) -> BoxFuture<'c, crate::Result<T>>,
+    {
+        self.refresh_if_due().await?;
+        self.within_deadline(async {
+            let (endpoint, probe) = self.pick(None)?;
+            self.call_endpoint(endpoint, probe, call).await
+        }).await
+    }
+
+    /// Make a call that is sent to a second endpoint as well if the first
+    /// has not answered within the delay given by the hedge policy.
+    ///
+    /// The first answer wins and the other call is cancelled; its
+    /// connection is closed since a reply may still be on its way. If the
+    /// first answer is an error, the other call's answer is used instead.
+    /// Only use this for idempotent calls, since both requests may be
+    /// processed.
+    pub async fn call_hedged<T, C>(&self, call: C) -> crate::Result<T>
+        where
+            C: for<'c> Fn(&'c mut F::Client) -> BoxFuture<'c, crate::Result<T>>,
+    {
+        self.refresh_if_due().await?;
+        self.within_deadline(self.hedge(&call)).await
+    }
+
+    async fn hedge<T, C>(&self, call: &C) -> crate::Result<T>
+        where
+            C: for<'c> Fn(&'c mut F::Client) -> BoxFuture<'c, crate::Result<T>>,
+    {
+        let (endpoint, probe) = self.pick(None)?;
+        let address = endpoint.address.clone();
+        let delay = self.latencies.lock().unwrap().hedge_delay(&self.hedge_policy);
+
+        let first = Box::pin(self.call_endpoint(endpoint, probe, call));
+        let first = match futures_future::select(first, Box::pin(task::sleep(delay))).await {
+            Either::Left((res, _)) => return res,
+            Either::Right(((), first)) => first,
+        };
+
+        let (endpoint, probe) = match self.pick(Some(&address)) {
+            Ok(picked) => picked,
+            // there is nowhere else to send the call
+            Err(_) => return first.await,
+        };
+        let second = Box::pin(self.call_endpoint(endpoint, probe, call));
+        // dropping the slower call cancels it
+        match futures_future::select(first, second).await {
+            Either::Left((Ok(res), _)) | Either::Right((Ok(res), _)) => Ok(res),
+            Either::Left((Err(_), other)) | Either::Right((Err(_), other)) => other.await,
+        }
+    }
+
+    /// run `call` on a client connected to `endpoint`, returning the client
+    /// to the pool if it can be reused; `probe` is set if `pick` let the
+    /// call through as the endpoint's half-open probe
+    async fn call_endpoint<T, C>(&self, endpoint: Arc<TEndpoint<F::Client>>, probe: bool, call: C) -> crate::Result<T>
+        where
+            C: for<'c> FnOnce(&'c mut F::Client) -> BoxFuture<'c, crate::Result<T>>,
+    {
+        let mut endpoint_call = TEndpointCall::new(&endpoint, probe);
+
+        let idle = endpoint.idle.lock().unwrap().pop();
+        let mut client = match idle {
+            Some(client) => client,
+            None => match self.factory.create(&endpoint.address).await {
+                Ok(client) => client,
+                Err(e) => {
+                    endpoint_call.finish(false);
+                    return Err(e);
+                }
+            },
+        };
+
+        let started = Instant::now();
+        let res = call(&mut client).await;
+        let reusable = match res {
+            Ok(_) => {
+                self.latencies.lock().unwrap().record(started.elapsed(), &self.hedge_policy);
+                endpoint_call.finish(true);
+                true
+            }
+            Err(ref e) => match TCallFailure::of(e) {
+                TCallFailure::Connection => {
+                    endpoint_call.finish(false);
+                    false
+                }
+                TCallFailure::Desynchronized => {
+                    endpoint_call.finish(true);
+                    false
+                }
+                TCallFailure::Remote => {
+                    endpoint_call.finish(true);
+                    true
+                }
+            },
+        };
+
+        if reusable {
+            let mut idle = endpoint.idle.lock().unwrap();
+            if idle.len() < self.max_idle_per_endpoint {
+                idle.push(client);
+            }
+        }
+        res
+    }
+
+    /// run `call` with the call deadline, failing it once the deadline passes
+    async fn within_deadline<T, Fut>(&self, call: Fut) -> crate::Result<T>
+        where
+            Fut: Future<Output = crate::Result<T>>,
+    {
+        let timeout_deadline = self.call_timeout.map(|timeout| Instant::now() + timeout);
+        let deadline = match (timeout_deadline, current_deadline()) {
+            (Some(timeout_deadline), Some(current)) => timeout_deadline.min(current),
+            (Some(deadline), None) | (None, Some(deadline)) => deadline,
+            (None, None) => return call.await,
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match future::timeout(remaining, with_deadline(deadline, call)).await {
+            Ok(res) => res,
+            Err(_) => Err(new_transport_error(TransportErrorKind::TimedOut, "call deadline exceeded")),
+        }
+    }
+
+    async fn refresh_if_due(&self) -> crate::Result<()> {
+        let due = match *self.last_refresh.lock().unwrap() {
+            None => true,
+            Some(last_refresh) => match self.refresh_interval {
+                Some(interval) => last_refresh.elapsed() >= interval,
+                None => false,
+            },
+        };
+        if !due {
+            return Ok(());
+        }
+
+        match self.refresh().await {
+            Ok(()) => Ok(()),
+            // keep serving from the previous endpoint list if there is one
+            Err(e) if !self.endpoints.read().unwrap().is_empty() => {
+                warn!("failed to refresh endpoints: {:?}", e);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// choose an endpoint, other than the one at `exclude`, that is not
+    /// ejected and claim a call on it, returning whether the call is the
+    /// endpoint's half-open probe
+    fn pick(&self, exclude: Option<&str>) -> crate::Result<(Arc<TEndpoint<F::Client>>, bool)> {
+        let endpoints = self.endpoints.read().unwrap();
+        if endpoints.is_empty() {
+            return Err(new_transport_error(TransportErrorKind::NotOpen, "no endpoints to call"));
+        }
+
+        // another caller may claim a half-open endpoint between choosing and
+        // claiming it, so try again a bounded number of times
+        for _ in 0..=endpoints.len() {
+            let available: Vec<&Arc<TEndpoint<F::Client>>> = endpoints
+                .iter()
+                .filter(|endpoint| Some(endpoint.address.as_str()) != exclude)
+                .filter(|endpoint| endpoint.breaker.lock().unwrap().is_available())
+                .collect();
+            if available.is_empty() {
+                break;
+            }
+
+            let chosen = self.choose(&available);
+            let mut breaker = chosen.breaker.lock().unwrap();
+            if breaker.allow_request() {
+                // a breaker that let a call through while half-open gave it
+                // the probe, and it stays half-open until the probe ends
+                let probe = breaker.state() == TCircuitState::HalfOpen;
+                return Ok((chosen.clone(), probe));
+            }
+        }
+
+        Err(new_transport_error(TransportErrorKind::NotOpen, "all endpoints are ejected"))
+    }
+
+    fn choose<'a>(&self, available: &[&'a Arc<TEndpoint<F::Client>>]) -> &'a Arc<TEndpoint<F::Client>> {
+        let len = available.len();
+        match self.strategy {
+            TBalanceStrategy::RoundRobin => available[self.next.fetch_add(1, Ordering::Relaxed) % len],
+            TBalanceStrategy::LeastOutstanding => {
+                // rotate the starting point so ties are spread evenly
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..len)
+                    .map(|i| available[(start + i) % len])
+                    .min_by_key(|endpoint| endpoint.outstanding.load(Ordering::Relaxed))
+                    .unwrap()
+            }
+            TBalanceStrategy::PowerOfTwoChoices => {
+                if len == 1 {
+                    return available[0];
+                }
+                let mut rng = rand::thread_rng();
+                let first = rng.gen_range(0, len);
+                let mut second = rng.gen_range(0, len - 1);
+                if second >= first {
+                    second += 1;
+                }
+                let (first, second) = (available[first], available[second]);
+                if second.outstanding.load(Ordering::Relaxed) < first.outstanding.load(Ordering::Relaxed) {
+                    second
+                } else {
+                    first
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task;
+    use futures::future::{self, FutureExt};
+
+    use crate::errors::{new_application_error, ApplicationErrorKind};
+
+    use super::*;
+
+    /// Factory whose clients are the address they are connected to.
+    struct TAddressFactory;
+
+    #[async_trait]
+    impl TAsyncClientFactory for TAddressFactory {
+        type Client = String;
+
+        async fn create(&self, address: &str) -> crate::Result<String> {
+            Ok(address.to_owned())
+        }
+    }
+
+    fn connection_reset() -> crate::Error {
+        new_transport_error(TransportErrorKind::Unknown, "connection reset")
+    }
+
+    fn address_of(client: &TBalancedClient<TAddressFactory>) -> crate::Result<String> {
+        task::block_on(client.call(|address: &mut String| {
+            let address = address.clone();
+            async move { Ok(address) }.boxed()
+        }))
+    }
+
+    #[test]
+    fn must_eject_failing_endpoint() {
+        let client = TBalancedClient::with_addresses(["a", "b"], TAddressFactory)
+            .with_ejection(2, Duration::from_secs(60));
+
+        for _ in 0..4 {
+            let _ = task::block_on(client.call(|address: &mut String| {
+                let res = if address == "a" { Err(connection_reset()) } else { Ok(()) };
+                async move { res }.boxed()
+            }));
+        }
+
+        let endpoints = client.endpoints();
+        assert_eq!(endpoints[0].state, TCircuitState::Open);
+        assert_eq!(endpoints[0].idle_connections, 0);
+        assert_eq!(endpoints[1].state, TCircuitState::Closed);
+        for _ in 0..3 {
+            assert_eq!(assert_success!(address_of(&client)), "b");
+        }
+    }
+
+    #[test]
+    fn must_release_half_open_probe_only_from_probe_call() {
+        let client = TBalancedClient::with_addresses(["a"], TAddressFactory)
+            .with_ejection(1, Duration::from_millis(0));
+
+        // a call let through while the endpoint is healthy
+        let mut before_ejection = Box::pin(client.call(|_: &mut String| future::pending::<crate::Result<()>>().boxed()));
+        assert!(before_ejection.as_mut().now_or_never().is_none());
+
+        let failed = task::block_on(client.call(|_: &mut String| async { Err::<(), _>(connection_reset()) }.boxed()));
+        assert!(failed.is_err());
+        assert_eq!(client.endpoints()[0].state, TCircuitState::HalfOpen);
+
+        let mut probe = Box::pin(client.call(|_: &mut String| future::pending::<crate::Result<()>>().boxed()));
+        assert!(probe.as_mut().now_or_never().is_none());
+
+        // cancelling the older call leaves the probe in progress
+        drop(before_ejection);
+        assert!(address_of(&client).is_err());
+
+        // cancelling the probe lets another call probe the endpoint
+        drop(probe);
+        assert_eq!(assert_success!(address_of(&client)), "a");
+        assert_eq!(client.endpoints()[0].state, TCircuitState::Closed);
+        assert_eq!(client.endpoints()[0].outstanding, 0);
+    }
+
+    #[test]
+    fn must_hedge_slow_call_to_another_endpoint() {
+        let client = TBalancedClient::with_addresses(["a", "b"], TAddressFactory)
+            .with_hedge_policy(THedgePolicy::new().initial_delay(Duration::from_millis(10)));
+
+        let res = task::block_on(client.call_hedged(|address: &mut String| {
+            let address = address.clone();
+            async move {
+                if address == "a" {
+                    future::pending::<()>().await;
+                }
+                Ok(address)
+            }.boxed()
+        }));
+        assert_eq!(assert_success!(res), "b");
+
+        // the slow call was cancelled and its connection closed
+        let endpoints = client.endpoints();
+        assert_eq!((endpoints[0].outstanding, endpoints[0].idle_connections), (0, 0));
+        assert_eq!((endpoints[1].outstanding, endpoints[1].idle_connections), (0, 1));
+    }
+
+    #[test]
+    fn must_use_other_answer_when_hedged_call_fails() {
+        let client = TBalancedClient::with_addresses(["a", "b"], TAddressFactory)
+            .with_hedge_policy(THedgePolicy::new().initial_delay(Duration::from_millis(5)));
+
+        let res = task::block_on(client.call_hedged(|address: &mut String| {
+            let address = address.clone();
+            async move {
+                if address == "a" {
+                    task::sleep(Duration::from_millis(20)).await;
+                    Err(new_application_error(ApplicationErrorKind::InternalError, "failed"))
+                } else {
+                    task::sleep(Duration::from_millis(50)).await;
+                    Ok(address)
+                }
+            }.boxed()
+        }));
+        assert_eq!(assert_success!(res), "b");
+    }
+
+    #[test]
+    fn must_fail_call_at_deadline() {
+        let client = TBalancedClient::with_addresses(["a"], TAddressFactory)
+            .with_call_timeout(Duration::from_millis(10));
+
+        let res = task::block_on(client.call(|_: &mut String| future::pending::<crate::Result<()>>().boxed()));
+        match res {
+            Err(crate::Error::Transport(ref e)) => assert_eq!(e.kind, TransportErrorKind::TimedOut),
+            res => panic!("unexpected result {:?}", res),
+        }
+        assert_eq!(client.endpoints()[0].outstanding, 0);
+    }
+
+    #[test]
+    fn must_pass_earliest_deadline_to_call() {
+        let client = TBalancedClient::with_addresses(["a"], TAddressFactory)
+            .with_call_timeout(Duration::from_secs(60));
+        let deadline = Instant::now() + Duration::from_secs(1);
+
+        let seen = task::block_on(with_deadline(deadline, client.call(|_: &mut String| {
+            let seen = current_deadline();
+            async move { Ok(seen) }.boxed()
+        })));
+        assert_eq!(assert_success!(seen), Some(deadline));
+
+        let seen = task::block_on(client.call(|_: &mut String| {
+            let seen = current_deadline();
+            async move { Ok(seen) }.boxed()
+        }));
+        let seen = assert_success!(seen).unwrap();
+        assert!(seen > deadline && seen <= Instant::now() + Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Decides when a hedged call sends its duplicate request.
+///
+/// The duplicate is sent once the first request has been outstanding for
+/// longer than `percentile` of recent successful calls took, clamped to at
+/// least `min_delay`. Until `min_samples` latencies have been recorded the
+/// delay is `initial_delay`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use async_thrift::client::hedge::THedgePolicy;
+///
+/// // hedge calls slower than 99% of the last 500 calls
+/// let policy = THedgePolicy::new()
+///     .percentile(0.99)
+///     .window(500)
+///     .min_delay(Duration::from_millis(2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct THedgePolicy {
+    percentile: f64,
+    window: usize,
+    min_samples: usize,
+    initial_delay: Duration,
+    min_delay: Duration,
+}
+
+impl THedgePolicy {
+    /// Create a `THedgePolicy` that hedges at the 95th percentile of the
+    /// last 1000 calls, waiting 50ms until 20 calls have been seen and at
+    /// least 1ms after that.
+    pub fn new() -> THedgePolicy {
+        THedgePolicy {
+            percentile: 0.95,
+            window: 1000,
+            min_samples: 20,
+            initial_delay: Duration::from_millis(50),
+            min_delay: Duration::from_millis(1),
+        }
+    }
+
+    /// Set the percentile, between 0 and 1, of recent latencies to wait
+    /// before hedging.
+    pub fn percentile(mut self, percentile: f64) -> THedgePolicy {
+        self.percentile = percentile.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the number of recent latencies the percentile is taken over.
+    pub fn window(mut self, window: usize) -> THedgePolicy {
+        assert!(window > 0, "latency window must hold at least one call");
+        self.window = window;
+        self
+    }
+
+    /// Set the number of latencies needed before the percentile is used.
+    pub fn min_samples(mut self, min_samples: usize) -> THedgePolicy {
+        self.min_samples = min_samples;
+        self
+    }
+
+    /// Set the delay used until enough latencies have been recorded.
+    pub fn initial_delay(mut self, delay: Duration) -> THedgePolicy {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the shortest delay before hedging.
+    pub fn min_delay(mut self, delay: Duration) -> THedgePolicy {
+        self.min_delay = delay;
+        self
+    }
+}
+
+impl Default for THedgePolicy {
+    fn default() -> Self {
+        THedgePolicy::new()
+    }
+}
+
+/// Latencies of the most recent successful calls.
+#[derive(Debug)]
+pub(crate) struct TLatencyWindow {
+    samples: VecDeque<Duration>,
+}
+
+impl TLatencyWindow {
+    pub(crate) fn new() -> TLatencyWindow {
+        TLatencyWindow { samples: VecDeque::new() }
+    }
+
+    /// Record the latency of a successful call, forgetting the oldest one
+    /// once `policy.window` latencies are held.
+    pub(crate) fn record(&mut self, latency: Duration, policy: &THedgePolicy) {
+        while self.samples.len() >= policy.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    /// Return how long to wait before hedging a call under `policy`.
+    pub(crate) fn hedge_delay(&self, policy: &THedgePolicy) -> Duration {
+        if self.samples.is_empty() || self.samples.len() < policy.min_samples {
+            return policy.initial_delay;
+        }
+
+        let mut samples: Vec<Duration> = self.samples.iter().cloned().collect();
+        let index = ((samples.len() - 1) as f64 * policy.percentile).round() as usize;
+        let (_, delay, _) = samples.select_nth_unstable(index);
+        (*delay).max(policy.min_delay)
+    }
+}
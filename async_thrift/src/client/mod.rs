@@ -0,0 +1,175 @@
+//! Building blocks for resilient clients.
+//!
+//! Generated clients own a single connection and give up on the first
+//! error. The types in this module wrap them: a `TAsyncClientFactory`
+//! knows how to open a new client for an address, and wrappers such as
+//! `retry::TRetryingClient` use it to replace clients whose connection
+//! broke.
+
+use std::time::Duration;
+
+use async_std::{future, net::TcpStream};
+use async_trait::async_trait;
+
+use crate::errors::{new_transport_error, ApplicationErrorKind, TransportErrorKind};
+use crate::protocol::{TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory};
+use crate::transport::async_socket::TAsyncTcpChannel;
+use crate::transport::{TAsyncIoChannel, TAsyncReadTransportFactory, TAsyncWriteTransportFactory};
+
+pub mod balance;
+pub mod hedge;
+pub mod retry;
+
+/// Opens new clients.
+#[async_trait]
+pub trait TAsyncClientFactory {
+    /// Type of client created, usually a generated client.
+    type Client: Send;
+
+    /// Open a new client connected to `address`.
+    async fn create(&self, address: &str) -> crate::Result<Self::Client>;
+}
+
+/// Effect of a failed call on the client that made it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum TCallFailure {
+    /// The connection broke or its stream is corrupt; the server may be
+    /// unhealthy.
+    Connection,
+    /// The server answered, but the reply did not match the call so the
+    /// connection can no longer be trusted.
+    Desynchronized,
+    /// The server answered with an error; the connection is still usable.
+    Remote,
+}
+
+impl TCallFailure {
+    pub(crate) fn of(err: &crate::Error) -> TCallFailure {
+        match err {
+            crate::Error::Transport(_) | crate::Error::Protocol(_) => TCallFailure::Connection,
+            crate::Error::Application(ref app_err) => match app_err.kind {
+                ApplicationErrorKind::BadSequenceId
+                | ApplicationErrorKind::WrongMethodName
+                | ApplicationErrorKind::InvalidMessageType => TCallFailure::Desynchronized,
+                _ => TCallFailure::Remote,
+            },
+            crate::Error::User(_) => TCallFailure::Remote,
+        }
+    }
+}
+
+/// `TAsyncClientFactory` that opens a TCP connection and builds a client on
+/// top of it.
+///
+/// The connection is wrapped in transports and protocols created by the
+/// given factories, and `new_client` turns the resulting protocol pair into
+/// a client. `new_client` is a plain function, such as a generated client's
+/// `new`; implement `TAsyncClientFactory` directly when building a client
+/// needs more state.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_thrift::client::TAsyncTcpClientFactory;
+/// use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+/// use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+///
+/// let factory = TAsyncTcpClientFactory::new(
+///     TAsyncBufferedReadTransportFactory::new(),
+///     TAsyncBinaryInputProtocolFactory::new(),
+///     TAsyncBufferedWriteTransportFactory::new(),
+///     TAsyncBinaryOutputProtocolFactory::new(),
+///     |i_prot, o_prot| (i_prot, o_prot), // e.g. `CalculatorSyncClient::new`
+/// );
+/// ```
+pub struct TAsyncTcpClientFactory<RTF, IPF, WTF, OPF, C> {
+    r_trans_factory: RTF,
+    i_proto_factory: IPF,
+    w_trans_factory: WTF,
+    o_proto_factory: OPF,
+    new_client: fn(Box<dyn TAsyncInputProtocol + Send>, Box<dyn TAsyncOutputProtocol + Send>) -> C,
+    connect_timeout: Option<Duration>,
+    nodelay: bool,
+}
+
+impl<RTF, IPF, WTF, OPF, C> TAsyncTcpClientFactory<RTF, IPF, WTF, OPF, C>
+    where
+        RTF: TAsyncReadTransportFactory,
+        IPF: TAsyncInputProtocolFactory,
+        WTF: TAsyncWriteTransportFactory,
+        OPF: TAsyncOutputProtocolFactory,
+{
+    /// Create a `TAsyncTcpClientFactory`.
+    pub fn new(
+        read_transport_factory: RTF,
+        input_protocol_factory: IPF,
+        write_transport_factory: WTF,
+        output_protocol_factory: OPF,
+        new_client: fn(Box<dyn TAsyncInputProtocol + Send>, Box<dyn TAsyncOutputProtocol + Send>) -> C,
+    ) -> TAsyncTcpClientFactory<RTF, IPF, WTF, OPF, C> {
+        TAsyncTcpClientFactory {
+            r_trans_factory: read_transport_factory,
+            i_proto_factory: input_protocol_factory,
+            w_trans_factory: write_transport_factory,
+            o_proto_factory: output_protocol_factory,
+            new_client,
+            connect_timeout: None,
+            nodelay: false,
+        }
+    }
+
+    /// Fail connection attempts that take longer than `timeout`.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> TAsyncTcpClientFactory<RTF, IPF, WTF, OPF, C> {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set `TCP_NODELAY` on new connections.
+    pub fn with_nodelay(mut self, nodelay: bool) -> TAsyncTcpClientFactory<RTF, IPF, WTF, OPF, C> {
+        self.nodelay = nodelay;
+        self
+    }
+
+    async fn connect(&self, address: &str) -> crate::Result<TcpStream> {
+        match self.connect_timeout {
+            Some(timeout) => match future::timeout(timeout, TcpStream::connect(address)).await {
+                Ok(stream) => Ok(stream?),
+                Err(_) => Err(new_transport_error(
+                    TransportErrorKind::TimedOut,
+                    format!("timed out connecting to {}", address),
+                )),
+            },
+            None => Ok(TcpStream::connect(address).await?),
+        }
+    }
+}
+
+#[async_trait]
+impl<RTF, IPF, WTF, OPF, C> TAsyncClientFactory for TAsyncTcpClientFactory<RTF, IPF, WTF, OPF, C>
+    where
+        RTF: TAsyncReadTransportFactory + Send + Sync,
+        IPF: TAsyncInputProtocolFactory + Send + Sync,
+        WTF: TAsyncWriteTransportFactory + Send + Sync,
+        OPF: TAsyncOutputProtocolFactory + Send + Sync,
+        C: Send,
+{
+    type Client = C;
+
+    async fn create(&self, address: &str) -> crate::Result<C> {
+        let stream = self.connect(address).await?;
+        if self.nodelay {
+            stream.set_nodelay(true)?;
+        }
+
+        let channel = TAsyncTcpChannel::with_stream(stream);
+        let (r_chan, w_chan) = channel.split()?;
+
+        let r_tran = self.r_trans_factory.create(Box::new(r_chan));
+        let i_prot = self.i_proto_factory.create(r_tran);
+
+        let w_tran = self.w_trans_factory.create(Box::new(w_chan));
+        let o_prot = self.o_proto_factory.create(w_tran);
+
+        Ok((self.new_client)(i_prot, o_prot))
+    }
+}
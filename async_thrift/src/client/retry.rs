@@ -0,0 +1,516 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use async_std::task;
+use futures::future::BoxFuture;
+use rand::Rng;
+
+use crate::errors::{new_transport_error, TransportErrorKind};
+
+use super::{TAsyncClientFactory, TCallFailure};
+
+/// Decides which failed calls are retried and how long to wait in between.
+///
+/// Only calls that failed with a `TransportError` are retried. A call whose
+/// connection could not even be opened never reached the server and is
+/// always retried; any other call is retried only if its method is marked
+/// idempotent. Calls that failed with an `ApplicationError` or a user
+/// exception are never retried.
+///
+/// The delay before retry `n` is `initial_backoff * multiplier^(n - 1)`,
+/// capped at `max_backoff` and then reduced by a random fraction of up to
+/// `jitter`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use async_thrift::client::retry::TRetryPolicy;
+///
+/// let policy = TRetryPolicy::new()
+///     .max_attempts(4)
+///     .backoff(Duration::from_millis(20), Duration::from_secs(1))
+///     .idempotent("ping")
+///     .idempotent("get");
+///
+/// assert!(policy.is_idempotent("get"));
+/// assert!(!policy.is_idempotent("put"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TRetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    idempotent_methods: HashSet<String>,
+    all_idempotent: bool,
+}
+
+impl TRetryPolicy {
+    /// Create a `TRetryPolicy` that makes up to 3 attempts, backing off
+    /// from 50ms up to 2s with 20% jitter, and treats no method as
+    /// idempotent.
+    pub fn new() -> TRetryPolicy {
+        TRetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            multiplier: 2.0,
+            jitter: 0.2,
+            idempotent_methods: HashSet::new(),
+            all_idempotent: false,
+        }
+    }
+
+    /// Set the maximum number of attempts per call, including the first.
+    pub fn max_attempts(mut self, max_attempts: u32) -> TRetryPolicy {
+        assert!(max_attempts > 0, "at least one attempt is required");
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry and the cap on later delays.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> TRetryPolicy {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set the factor applied to the delay after every retry.
+    pub fn multiplier(mut self, multiplier: f64) -> TRetryPolicy {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Set the largest fraction, between 0 and 1, randomly taken off each
+    /// delay.
+    pub fn jitter(mut self, jitter: f64) -> TRetryPolicy {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Mark `method` as safe to retry after it may have reached the server.
+    pub fn idempotent<S: Into<String>>(mut self, method: S) -> TRetryPolicy {
+        self.idempotent_methods.insert(method.into());
+        self
+    }
+
+    /// Treat every method as idempotent.
+    pub fn all_idempotent(mut self, all_idempotent: bool) -> TRetryPolicy {
+        self.all_idempotent = all_idempotent;
+        self
+    }
+
+    /// Return `true` if `method` may be retried after it was sent.
+    pub fn is_idempotent(&self, method: &str) -> bool {
+        self.all_idempotent || self.idempotent_methods.contains(method)
+    }
+
+    /// Maximum number of attempts per call.
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Return the delay before retry number `retry`, starting at 1.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let delay = delay.min(self.max_backoff.as_secs_f64());
+        let jitter = if self.jitter > 0.0 {
+            rand::thread_rng().gen_range(0.0, self.jitter)
+        } else {
+            0.0
+        };
+        Duration::from_secs_f64(delay * (1.0 - jitter))
+    }
+}
+
+impl Default for TRetryPolicy {
+    fn default() -> Self {
+        TRetryPolicy::new()
+    }
+}
+
+/// State of a `TCircuitBreaker`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TCircuitState {
+    /// Calls go through.
+    Closed,
+    /// Calls fail immediately.
+    Open,
+    /// The reset timeout has passed; one probe call is let through to
+    /// decide whether to close or re-open the breaker.
+    HalfOpen,
+}
+
+/// Stops calls to a server after consecutive failures.
+///
+/// The breaker opens after `failure_threshold` consecutive failures and
+/// rejects calls until `reset_timeout` has passed. It then lets a single
+/// probe through: success closes the breaker, failure opens it again.
+#[derive(Clone, Debug)]
+pub struct TCircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+impl TCircuitBreaker {
+    /// Create a closed `TCircuitBreaker`.
+    pub fn new(failure_threshold: u32, reset_timeout: Duration) -> TCircuitBreaker {
+        assert!(failure_threshold > 0, "failure threshold must be a positive integer");
+        TCircuitBreaker {
+            failure_threshold,
+            reset_timeout,
+            consecutive_failures: 0,
+            opened_at: None,
+            probing: false,
+        }
+    }
+
+    /// Current state of the breaker.
+    pub fn state(&self) -> TCircuitState {
+        match self.opened_at {
+            None => TCircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() >= self.reset_timeout => TCircuitState::HalfOpen,
+            Some(_) => TCircuitState::Open,
+        }
+    }
+
+    /// Return `true` if `allow_request` would let a call through now,
+    /// without claiming the half-open probe.
+    pub fn is_available(&self) -> bool {
+        match self.state() {
+            TCircuitState::Closed => true,
+            TCircuitState::Open => false,
+            TCircuitState::HalfOpen => !self.probing,
+        }
+    }
+
+    /// Return `true` if a call may be made now.
+    ///
+    /// In the half-open state only the first caller is allowed through
+    /// until its outcome is recorded.
+    pub fn allow_request(&mut self) -> bool {
+        match self.state() {
+            TCircuitState::Closed => true,
+            TCircuitState::Open => false,
+            TCircuitState::HalfOpen if self.probing => false,
+            TCircuitState::HalfOpen => {
+                self.probing = true;
+                true
+            }
+        }
+    }
+
+    /// Forget a call let through by `allow_request` whose outcome will never
+    /// be known, e.g. because it was cancelled.
+    pub fn cancel_request(&mut self) {
+        self.probing = false;
+    }
+
+    /// Record a call that reached a healthy server.
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probing = false;
+    }
+
+    /// Record a call that failed because the server or the connection to it
+    /// is unhealthy.
+    pub fn record_failure(&mut self) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.opened_at.is_some() || self.consecutive_failures >= self.failure_threshold {
+            // a failed probe re-opens the breaker for another timeout
+            self.opened_at = Some(Instant::now());
+            self.probing = false;
+        }
+    }
+}
+
+impl Default for TCircuitBreaker {
+    /// A breaker that opens after 5 consecutive failures for 10 seconds.
+    fn default() -> Self {
+        TCircuitBreaker::new(5, Duration::from_secs(10))
+    }
+}
+
+/// Client wrapper that reconnects, retries and stops calling a failing
+/// server.
+///
+/// Each call is passed as a closure over the wrapped client. When a call
+/// fails with a `TransportError` or `ProtocolError` the client is dropped
+/// and a new one is created by the factory on the next attempt; the call is
+/// retried according to the `TRetryPolicy`. Those failures also count
+/// towards the `TCircuitBreaker`; once it opens, calls fail immediately with
+/// a `TransportErrorKind::NotOpen` error.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_thrift::client::retry::{TRetryingClient, TRetryPolicy};
+/// use async_thrift::client::TAsyncTcpClientFactory;
+/// use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+/// use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+///
+/// # async_std::task::block_on(async {
+/// let factory = TAsyncTcpClientFactory::new(
+///     TAsyncBufferedReadTransportFactory::new(),
+///     TAsyncBinaryInputProtocolFactory::new(),
+///     TAsyncBufferedWriteTransportFactory::new(),
+///     TAsyncBinaryOutputProtocolFactory::new(),
+///     |i_prot, o_prot| (i_prot, o_prot), // e.g. `CalculatorSyncClient::new`
+/// );
+/// let mut client = TRetryingClient::new("127.0.0.1:9090", factory)
+///     .with_retry_policy(TRetryPolicy::new().idempotent("ping"));
+///
+/// // with a generated client: client.call("ping", |c| c.ping()).await?;
+/// client.call("ping", |(_, o_prot)| o_prot.flush()).await?;
+/// # Ok::<(), async_thrift::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct TRetryingClient<F>
+    where
+        F: TAsyncClientFactory,
+{
+    address: String,
+    factory: F,
+    policy: TRetryPolicy,
+    breaker: TCircuitBreaker,
+    client: Option<F::Client>,
+}
+
+impl<F> TRetryingClient<F>
+    where
+        F: TAsyncClientFactory + Send + Sync,
+{
+    /// Create a `TRetryingClient` that uses `factory` to connect to
+    /// `address`, with the default retry policy and circuit breaker.
+    ///
+    /// No connection is opened until the first call.
+    pub fn new<S: Into<String>>(address: S, factory: F) -> TRetryingClient<F> {
+        TRetryingClient {
+            address: address.into(),
+            factory,
+            policy: TRetryPolicy::new(),
+            breaker: TCircuitBreaker::default(),
+            client: None,
+        }
+    }
+
+    /// Use `policy` to decide which calls to retry.
+    pub fn with_retry_policy(mut self, policy: TRetryPolicy) -> TRetryingClient<F> {
+        self.policy = policy;
+        self
+    }
+
+    /// Use `breaker` to stop calling a failing server.
+    pub fn with_circuit_breaker(mut self, breaker: TCircuitBreaker) -> TRetryingClient<F> {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Current state of the circuit breaker.
+    pub fn circuit_state(&self) -> TCircuitState {
+        self.breaker.state()
+    }
+
+    /// Drop the current connection, if any. The next call opens a new one.
+    pub fn disconnect(&mut self) {
+        self.client = None;
+    }
+
+    /// Make a call to `method` by running `call` on the wrapped client,
+    /// retrying it as allowed by the retry policy.
+    ///
+    /// `method` must be the Thrift method name invoked by `call`; it is
+    /// used to look up whether the call is idempotent.
+    ///
+    /// The returned future may be dropped before it completes, e.g. by a
+    /// timeout. The connection it was using is then dropped, since its
+    /// stream may hold half a call, and the next call opens a new one.
+    pub async fn call<T, C>(&mut self, method: &str, mut call: C) -> crate::Result<T>
+        where
+            C: for<'c> FnMut(&'c mut F::Client) -> BoxFuture<'c, crate::Result<T>>,
+    {
+        let idempotent = self.policy.is_idempotent(method);
+        let mut last_err = None;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            if !self.breaker.allow_request() {
+                return Err(last_err.unwrap_or_else(|| {
+                    new_transport_error(
+                        TransportErrorKind::NotOpen,
+                        format!("circuit breaker for {} is open", self.address),
+                    )
+                }));
+            }
+
+            let outcome = TAttemptOutcome::new(&mut self.breaker);
+
+            // the client is taken out while in use and only put back once
+            // the call left its stream in a usable state
+            let connected = match self.client.take() {
+                Some(client) => Ok(client),
+                None => self.factory.create(&self.address).await,
+            };
+            let (res, client, sent) = match connected {
+                Ok(mut client) => (call(&mut client).await, Some(client), true),
+                Err(e) => (Err(e), None, false),
+            };
+
+            let err = match res {
+                Ok(value) => {
+                    self.client = client;
+                    outcome.finish(true);
+                    return Ok(value);
+                }
+                Err(err) => err,
+            };
+
+            let retryable = match TCallFailure::of(&err) {
+                TCallFailure::Connection => {
+                    outcome.finish(false);
+                    // a call that never left this side is always safe to
+                    // retry; a broken protocol stream is never retried
+                    match err {
+                        crate::Error::Transport(_) => !sent || idempotent,
+                        _ => false,
+                    }
+                }
+                TCallFailure::Desynchronized => {
+                    outcome.finish(true);
+                    false
+                }
+                TCallFailure::Remote => {
+                    self.client = client;
+                    outcome.finish(true);
+                    false
+                }
+            };
+
+            if !retryable || attempt >= self.policy.max_attempts {
+                return Err(err);
+            }
+
+            let delay = self.policy.backoff_for(attempt);
+            debug!("retrying {} on {} in {:?} after error: {:?}", method, self.address, delay, err);
+            last_err = Some(err);
+            task::sleep(delay).await;
+        }
+    }
+}
+
+/// Reports the outcome of one attempt to the circuit breaker. An attempt
+/// dropped before its outcome is known, because the call was cancelled,
+/// releases the breaker's half-open probe so that it can be probed again.
+struct TAttemptOutcome<'a> {
+    breaker: &'a mut TCircuitBreaker,
+    finished: bool,
+}
+
+impl<'a> TAttemptOutcome<'a> {
+    fn new(breaker: &'a mut TCircuitBreaker) -> TAttemptOutcome<'a> {
+        TAttemptOutcome { breaker, finished: false }
+    }
+
+    fn finish(mut self, healthy: bool) {
+        if healthy {
+            self.breaker.record_success();
+        } else {
+            self.breaker.record_failure();
+        }
+        self.finished = true;
+    }
+}
+
+impl<'a> Drop for TAttemptOutcome<'a> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.breaker.cancel_request();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use async_std::task;
+    use async_trait::async_trait;
+    use futures::future::{self, FutureExt};
+
+    use crate::errors::{new_transport_error, TransportErrorKind};
+
+    use super::*;
+
+    /// Factory whose clients are numbered in the order they are created.
+    struct TCountingFactory(AtomicUsize);
+
+    #[async_trait]
+    impl TAsyncClientFactory for TCountingFactory {
+        type Client = usize;
+
+        async fn create(&self, _address: &str) -> crate::Result<usize> {
+            Ok(self.0.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    fn new_client() -> TRetryingClient<TCountingFactory> {
+        TRetryingClient::new("127.0.0.1:9090", TCountingFactory(AtomicUsize::new(0)))
+    }
+
+    fn client_id(client: &mut TRetryingClient<TCountingFactory>) -> crate::Result<usize> {
+        task::block_on(client.call("ping", |c: &mut usize| {
+            let id = *c;
+            async move { Ok(id) }.boxed()
+        }))
+    }
+
+    #[test]
+    fn must_reuse_client_after_successful_call() {
+        let mut client = new_client();
+
+        assert_eq!(assert_success!(client_id(&mut client)), 0);
+        assert_eq!(assert_success!(client_id(&mut client)), 0);
+    }
+
+    #[test]
+    fn must_drop_client_of_cancelled_call() {
+        let mut client = new_client();
+        assert_eq!(assert_success!(client_id(&mut client)), 0);
+
+        let cancelled = client
+            .call("ping", |_: &mut usize| future::pending::<crate::Result<()>>().boxed())
+            .now_or_never();
+        assert!(cancelled.is_none());
+
+        assert_eq!(assert_success!(client_id(&mut client)), 1);
+    }
+
+    #[test]
+    fn must_release_half_open_probe_of_cancelled_call() {
+        let mut client = new_client().with_circuit_breaker(TCircuitBreaker::new(1, Duration::from_millis(0)));
+
+        let failed = task::block_on(client.call("ping", |_: &mut usize| {
+            async { Err::<(), _>(new_transport_error(TransportErrorKind::Unknown, "connection reset")) }.boxed()
+        }));
+        assert!(failed.is_err());
+        assert_eq!(client.circuit_state(), TCircuitState::HalfOpen);
+
+        // the probe is cancelled before its outcome is known
+        let cancelled = client
+            .call("ping", |_: &mut usize| future::pending::<crate::Result<()>>().boxed())
+            .now_or_never();
+        assert!(cancelled.is_none());
+
+        assert_success!(client_id(&mut client));
+        assert_eq!(client.circuit_state(), TCircuitState::Closed);
+    }
+}
@@ -24,7 +24,8 @@
 //! 2. protocol
 //! 3. transport
 //! 4. server
-//! 5. autogen
+//! 5. client
+//! 6. autogen
 //!
 //! The modules are layered as shown in the diagram below. The `autogen'd`
 //! layer is generated by the Thrift compiler's Rust plugin. It uses the
@@ -61,6 +62,8 @@ extern crate threadpool;
 
 // Re-export ordered-float, since it is used by the generator
 pub use ordered_float::OrderedFloat as OrderedFloat;
+// Re-export bytes for binary fields read and written as `Bytes`
+pub use bytes::Bytes;
 
 pub use crate::autogen::*;
 pub use crate::errors::*;
@@ -76,6 +79,16 @@ macro_rules! assert_success {
     }};
 }
 
+/// Emit a `tracing` event at trace level when the `tracing` feature is
+/// enabled; expands to nothing otherwise.
+macro_rules! trace_event {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)+);
+    };
+}
+
+pub mod client;
 pub mod protocol;
 pub mod server;
 pub mod transport;
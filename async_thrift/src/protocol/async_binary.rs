@@ -1,11 +1,13 @@
 use std::convert::{From, TryFrom};
 use async_trait::async_trait;
-use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use byteorder::{BigEndian, ByteOrder};
+use bytes::Bytes;
 
-use crate::errors::{Error, ProtocolError, ProtocolErrorKind};
-use crate::transport::{TAsyncReadTransport, TAsyncWriteTransport};
+use crate::errors::{new_protocol_error, Error, ProtocolError, ProtocolErrorKind};
+use crate::transport::{read_exact, write_all, TAsyncReadTransport, TAsyncWriteTransport};
 
-use super::{TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory};
+use super::{TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory, TProtocolFuture};
+use super::DEFAULT_MAX_MESSAGE_SIZE;
 use super::{
     TFieldIdentifier, TListIdentifier,
     TMapIdentifier, TMessageIdentifier,
@@ -21,15 +23,14 @@ pub struct TAsyncBinaryInputProtocol<T>
         T: TAsyncReadTransport,
 {
     strict: bool,
+    max_message_size: usize,
     pub transport: T,
     // FIXME: shouldn't be public
-    buf1: [u8; 1],
-    buf2: [u8; 2],
-    buf4: [u8; 4],
-    buf8: [u8; 8],
+    // holds a value while it is read from the transport
+    buf: [u8; 8],
 }
 
-impl<'a, T> TAsyncBinaryInputProtocol<T>
+impl<T> TAsyncBinaryInputProtocol<T>
     where
         T: TAsyncReadTransport,
 {
@@ -40,13 +41,19 @@ impl<'a, T> TAsyncBinaryInputProtocol<T>
     pub fn new(transport: T, strict: bool) -> TAsyncBinaryInputProtocol<T> {
         TAsyncBinaryInputProtocol {
             strict,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
             transport,
-            buf1: [0; 1],
-            buf2: [0; 2],
-            buf4: [0; 4],
-            buf8: [0; 8],
+            buf: [0; 8],
         }
     }
+
+    /// Fail with a `SizeLimit` error when a string, binary value or
+    /// container is larger than `max_message_size` bytes or elements,
+    /// before anything is allocated for it. The default is 100 MiB.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> TAsyncBinaryInputProtocol<T> {
+        self.max_message_size = max_message_size;
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -57,10 +64,8 @@ pub struct TAsyncBinaryOutputProtocol<T>
     strict: bool,
     pub transport: T,
     // FIXME: do not make public; only public for testing!
-    buf1: [u8; 1],
-    buf2: [u8; 2],
-    buf4: [u8; 4],
-    buf8: [u8; 8],
+    // holds a value while it is written to the transport
+    buf: [u8; 8],
 }
 
 
@@ -76,28 +81,38 @@ impl<T> TAsyncBinaryOutputProtocol<T>
         TAsyncBinaryOutputProtocol {
             strict,
             transport,
-            buf1: [0; 1],
-            buf2: [0; 2],
-            buf4: [0; 4],
-            buf8: [0; 8],
+            buf: [0; 8],
         }
     }
 }
 
 /// Factory for creating instances of `TBinaryInputProtocol`.
 #[derive(Default)]
-pub struct TAsyncBinaryInputProtocolFactory;
+pub struct TAsyncBinaryInputProtocolFactory {
+    max_message_size: Option<usize>,
+}
 
 impl TAsyncBinaryInputProtocolFactory {
     /// Create a `TBinaryInputProtocolFactory`.
     pub fn new() -> TAsyncBinaryInputProtocolFactory {
-        TAsyncBinaryInputProtocolFactory {}
+        TAsyncBinaryInputProtocolFactory::default()
+    }
+
+    /// Create protocols that reject values larger than `max_message_size`.
+    /// See `TAsyncBinaryInputProtocol::with_max_message_size`.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> TAsyncBinaryInputProtocolFactory {
+        self.max_message_size = Some(max_message_size);
+        self
     }
 }
 
 impl TAsyncInputProtocolFactory for TAsyncBinaryInputProtocolFactory {
     fn create(&self, transport: Box<dyn TAsyncReadTransport + Send>) -> Box<dyn TAsyncInputProtocol + Send> {
-        Box::new(TAsyncBinaryInputProtocol::new(transport, true))
+        let protocol = TAsyncBinaryInputProtocol::new(transport, true);
+        match self.max_message_size {
+            Some(max_message_size) => Box::new(protocol.with_max_message_size(max_message_size)),
+            None => Box::new(protocol),
+        }
     }
 }
 
@@ -118,179 +133,272 @@ impl TAsyncOutputProtocolFactory for TAsyncBinaryOutputProtocolFactory {
     }
 }
 
-#[async_trait]
+impl<T> TAsyncBinaryInputProtocol<T>
+    where
+        T: TAsyncReadTransport + std::marker::Send,
+{
+    // Decode the next `size` bytes with `decode`, straight from the
+    // transport's buffer when it holds them.
+    fn read_fixed<V, F>(&mut self, size: usize, decode: F) -> TProtocolFuture<'_, V>
+        where
+            F: Fn(&[u8]) -> crate::Result<V> + Send + 'static,
+    {
+        if let Some(bytes) = self.transport.buffered().get(..size) {
+            let res = decode(bytes);
+            self.transport.consume(size);
+            return TProtocolFuture::ready(res);
+        }
+        TProtocolFuture::boxed(async move {
+            read_exact(&mut self.transport, &mut self.buf[..size]).await?;
+            decode(&self.buf[..size])
+        })
+    }
+}
+
 impl<T> TAsyncInputProtocol for TAsyncBinaryInputProtocol<T>
     where
         T: TAsyncReadTransport + std::marker::Send,
 {
     #[cfg_attr(feature = "cargo-clippy", allow(collapsible_if))]
-    async fn read_message_begin(&mut self) -> crate::Result<TMessageIdentifier> {
-        self.transport.read(&mut self.buf4).await?;
-
-        // the thrift version header is intentionally negative
-        // so the first check we'll do is see if the sign bit is set
-        // and if so - assume it's the protocol-version header
-        if self.buf4[0] >= 8 {
-            // apparently we got a protocol-version header - check
-            // it, and if it matches, read the rest of the fields
-            if self.buf4[0..2] != [0x80, 0x01] {
-                Err(crate::errors::Error::Protocol(ProtocolError {
-                    kind: ProtocolErrorKind::BadVersion,
-                    message: format!("received bad version: {:?}", &self.buf4[0..2]),
-                }))
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        TProtocolFuture::boxed(async move {
+            read_exact(&mut self.transport, &mut self.buf[..4]).await?;
+
+            // the thrift version header is intentionally negative
+            // so the first check we'll do is see if the sign bit is set
+            // and if so - assume it's the protocol-version header
+            if self.buf[0] >= 8 {
+                // apparently we got a protocol-version header - check
+                // it, and if it matches, read the rest of the fields
+                if self.buf[0..2] != [0x80, 0x01] {
+                    Err(crate::errors::Error::Protocol(ProtocolError {
+                        kind: ProtocolErrorKind::BadVersion,
+                        message: format!("received bad version: {:?}", &self.buf[0..2]),
+                    }))
+                } else {
+                    let message_type: TMessageType = TryFrom::try_from(self.buf[3])?;
+                    let name = self.read_string().await?;
+                    let sequence_number = self.read_i32().await?;
+                    Ok(TMessageIdentifier::new(name, message_type, sequence_number))
+                }
             } else {
-                let message_type: TMessageType = TryFrom::try_from(self.buf4[3])?;
-                let name = self.read_string().await?;
-                let sequence_number = self.read_i32().await?;
-                Ok(TMessageIdentifier::new(name, message_type, sequence_number))
+                // apparently we didn't get a protocol-version header,
+                // which happens if the sender is not using the strict protocol
+                if self.strict {
+                    // we're in strict mode however, and that always
+                    // requires the protocol-version header to be written first
+                    Err(crate::Error::Protocol(ProtocolError {
+                        kind: ProtocolErrorKind::BadVersion,
+                        message: format!("received bad version: {:?}", &self.buf[0..2]),
+                    }))
+                } else {
+                    // in the non-strict version the first message field
+                    // is the message name. strings (byte arrays) are length-prefixed,
+                    // so we've just read the length in the first 4 bytes
+                    let name_size = BigEndian::read_i32(&self.buf[..4]);
+                    let mut name_buf: Vec<u8> = vec![0; checked_size(name_size, self.max_message_size)?];
+                    read_exact(&mut self.transport, &mut name_buf).await?;
+                    let name = String::from_utf8(name_buf)?;
+
+                    // read the rest of the fields
+                    let message_type: TMessageType = self.read_byte().await.and_then(TryFrom::try_from)?;
+                    let sequence_number = self.read_i32().await?;
+                    Ok(TMessageIdentifier::new(name, message_type, sequence_number))
+                }
             }
-        } else {
-            // apparently we didn't get a protocol-version header,
-            // which happens if the sender is not using the strict protocol
-            if self.strict {
-                // we're in strict mode however, and that always
-                // requires the protocol-version header to be written first
-                Err(crate::Error::Protocol(ProtocolError {
-                    kind: ProtocolErrorKind::BadVersion,
-                    message: format!("received bad version: {:?}", &self.buf4[0..2]),
-                }))
-            } else {
-                // in the non-strict version the first message field
-                // is the message name. strings (byte arrays) are length-prefixed,
-                // so we've just read the length in the first 4 bytes
-                let name_size = BigEndian::read_i32(&self.buf4) as usize;
-                let mut name_buf: Vec<u8> = vec![0; name_size];
-                self.transport.read(&mut name_buf).await?;
-                let name = String::from_utf8(name_buf);
-
-                // read the rest of the fields
-                let message_type: TMessageType = self.read_byte().await.and_then(TryFrom::try_from)?;
-                let sequence_number = self.read_i32().await?;
-                Ok(TMessageIdentifier::new(name.unwrap(), message_type, sequence_number))
-            }
-        }
-    }
-
-    async fn read_message_end(&mut self) -> crate::Result<()> {
-        Ok(())
+        })
     }
 
-    async fn read_struct_begin(&mut self) -> crate::Result<Option<TStructIdentifier>> {
-        Ok(None)
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn read_struct_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        TProtocolFuture::ready(Ok(None))
     }
 
-    async fn read_field_begin(&mut self) -> crate::Result<TFieldIdentifier> {
-        let field_type_byte = self.read_byte().await?;
-        let field_type = field_type_from_u8(field_type_byte)?;
-        let id = match field_type {
-            TType::Stop => Ok(0),
-            _ => self.read_i16().await,
-        }?;
-        Ok(TFieldIdentifier::new::<Option<String>, String, i16>(
-            None, field_type, id,
-        ))
-    }
-
-    async fn read_field_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn read_bool(&mut self) -> crate::Result<bool> {
-        let b = self.read_i8().await?;
-        match b {
-            0 => Ok(false),
-            _ => Ok(true),
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        // a stop field has no id
+        let buffered = self.transport.buffered();
+        if let Some(&field_type_byte) = buffered.first() {
+            let field_type = match field_type_from_u8(field_type_byte) {
+                Ok(field_type) => field_type,
+                Err(e) => return TProtocolFuture::ready(Err(e)),
+            };
+            let size = if field_type == TType::Stop { 1 } else { 3 };
+            if let Some(bytes) = buffered.get(..size) {
+                let id = if size == 1 { 0 } else { BigEndian::read_i16(&bytes[1..]) };
+                self.transport.consume(size);
+                return TProtocolFuture::ready(Ok(TFieldIdentifier::new::<Option<String>, String, i16>(
+                    None, field_type, id,
+                )));
+            }
         }
+        TProtocolFuture::boxed(async move {
+            let field_type_byte = self.read_byte().await?;
+            let field_type = field_type_from_u8(field_type_byte)?;
+            let id = match field_type {
+                TType::Stop => Ok(0),
+                _ => self.read_i16().await,
+            }?;
+            Ok(TFieldIdentifier::new::<Option<String>, String, i16>(
+                None, field_type, id,
+            ))
+        })
+    }
+
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        self.read_fixed(1, |b| Ok(b[0] != 0))
+    }
+
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        let buffered = self.transport.buffered();
+        if buffered.len() >= 4 {
+            let num_bytes = match checked_size(BigEndian::read_i32(buffered), self.max_message_size) {
+                Ok(num_bytes) => num_bytes,
+                Err(e) => return TProtocolFuture::ready(Err(e)),
+            };
+            if let Some(bytes) = buffered.get(4..4 + num_bytes) {
+                let bytes = bytes.to_vec();
+                self.transport.consume(4 + num_bytes);
+                return TProtocolFuture::ready(Ok(bytes));
+            }
+        }
+        TProtocolFuture::boxed(async move {
+            let num_bytes = self.read_i32().await?;
+            let mut buf = vec![0u8; checked_size(num_bytes, self.max_message_size)?];
+            read_exact(&mut self.transport, &mut buf).await?;
+            Ok(buf)
+        })
+    }
+
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        let buffered = self.transport.buffered();
+        if buffered.len() >= 4 {
+            let num_bytes = match checked_size(BigEndian::read_i32(buffered), self.max_message_size) {
+                Ok(num_bytes) => num_bytes,
+                Err(e) => return TProtocolFuture::ready(Err(e)),
+            };
+            if buffered.len() >= 4 + num_bytes {
+                self.transport.consume(4);
+                if let Some(bytes) = self.transport.consume_bytes(num_bytes) {
+                    return TProtocolFuture::ready(Ok(bytes));
+                }
+            }
+        }
+        self.read_bytes().map(|bytes| bytes.map(Bytes::from))
     }
 
-    async fn read_bytes(&mut self) -> crate::Result<Vec<u8>> {
-        let num_bytes = self.read_i32().await? as usize;
-        let mut buf = vec![0u8; num_bytes];
-        self.transport
-            .read(&mut buf).await
-            .map(|_| buf)
-            .map_err(From::from)
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        self.read_fixed(1, |b| Ok(b[0] as i8))
     }
 
-    async fn read_i8(&mut self) -> crate::Result<i8> {
-        self.transport.read(&mut self.buf1).await;
-
-        Ok(self.buf1[0] as i8)
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        self.read_fixed(2, |b| Ok(BigEndian::read_i16(b)))
     }
 
-    async fn read_i16(&mut self) -> crate::Result<i16> {
-        self.transport.read(&mut self.buf2).await;
-
-        Ok(BigEndian::read_i16(&mut self.buf2))
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        self.read_fixed(4, |b| Ok(BigEndian::read_i32(b)))
     }
 
-    async fn read_i32(&mut self) -> crate::Result<i32> {
-        self.transport.read(&mut self.buf4).await;
-
-        Ok(BigEndian::read_i32(&mut self.buf4))
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        self.read_fixed(8, |b| Ok(BigEndian::read_i64(b)))
     }
 
-    async fn read_i64(&mut self) -> crate::Result<i64> {
-        self.transport.read(&mut self.buf8).await;
-
-        Ok(BigEndian::read_i64(&mut self.buf8))
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        self.read_fixed(8, |b| Ok(BigEndian::read_f64(b)))
     }
 
-    async fn read_double(&mut self) -> crate::Result<f64> {
-        self.transport.read(&mut self.buf8).await;
-
-        Ok(BigEndian::read_f64(&mut self.buf8))
-    }
-
-    async fn read_string(&mut self) -> crate::Result<String> {
-        let bytes = self.read_bytes().await?;
-        let res = String::from_utf8(bytes);
-
-        Ok(res.unwrap())
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        self.read_bytes().map(|bytes| Ok(String::from_utf8(bytes?)?))
     }
 
-    async fn read_list_begin(&mut self) -> crate::Result<TListIdentifier> {
-        let element_type: TType = self.read_byte().await.and_then(field_type_from_u8)?;
-        let size = self.read_i32().await?;
-        Ok(TListIdentifier::new(element_type, size))
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        let max_size = self.max_message_size;
+        self.read_fixed(5, move |b| {
+            let element_type = field_type_from_u8(b[0])?;
+            let size = BigEndian::read_i32(&b[1..]);
+            checked_size(size, max_size)?;
+            Ok(TListIdentifier::new(element_type, size))
+        })
     }
 
-    async fn read_list_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn read_set_begin(&mut self) -> crate::Result<TSetIdentifier> {
-        let element_type: TType = self.read_byte().await.and_then(field_type_from_u8)?;
-        let size = self.read_i32().await?;
-        Ok(TSetIdentifier::new(element_type, size))
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        let max_size = self.max_message_size;
+        self.read_fixed(5, move |b| {
+            let element_type = field_type_from_u8(b[0])?;
+            let size = BigEndian::read_i32(&b[1..]);
+            checked_size(size, max_size)?;
+            Ok(TSetIdentifier::new(element_type, size))
+        })
     }
 
-    async fn read_set_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn read_map_begin(&mut self) -> crate::Result<TMapIdentifier> {
-        let key_type: TType = self.read_byte().await.and_then(field_type_from_u8)?;
-        let value_type: TType = self.read_byte().await.and_then(field_type_from_u8)?;
-        let size = self.read_i32().await?;
-        Ok(TMapIdentifier::new(key_type, value_type, size))
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        let max_size = self.max_message_size;
+        self.read_fixed(6, move |b| {
+            let key_type = field_type_from_u8(b[0])?;
+            let value_type = field_type_from_u8(b[1])?;
+            let size = BigEndian::read_i32(&b[2..]);
+            checked_size(size, max_size)?;
+            Ok(TMapIdentifier::new(key_type, value_type, size))
+        })
     }
 
-    async fn read_map_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
     // utility
     //
 
-    async fn read_byte(&mut self) -> crate::Result<u8> {
-        self.transport.read(&mut self.buf1).await;
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        self.read_fixed(1, |b| Ok(b[0]))
+    }
+}
+
+impl<T> TAsyncBinaryOutputProtocol<T>
+    where
+        T: TAsyncWriteTransport + std::marker::Send,
+{
+    // Write `size` bytes encoded by `encode`, straight into the transport's
+    // buffer when it has room.
+    fn write_fixed<F>(&mut self, size: usize, encode: F) -> TProtocolFuture<'_, ()>
+        where
+            F: FnOnce(&mut [u8]),
+    {
+        encode(&mut self.buf[..size]);
+        if self.transport.write_buffered(&self.buf[..size]) {
+            return TProtocolFuture::ready(Ok(()));
+        }
+        TProtocolFuture::boxed(async move {
+            write_all(&mut self.transport, &self.buf[..size]).await
+        })
+    }
 
-        Ok(self.buf1[0])
+    // Write `b` as it is, without a length prefix.
+    fn write_raw<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        if self.transport.write_buffered(b) {
+            return TProtocolFuture::ready(Ok(()));
+        }
+        TProtocolFuture::boxed(async move {
+            write_all(&mut self.transport, b).await
+        })
     }
 }
 
@@ -299,160 +407,177 @@ impl<T> TAsyncOutputProtocol for TAsyncBinaryOutputProtocol<T>
     where
         T: TAsyncWriteTransport + std::marker::Send,
 {
-    async fn write_message_begin(&mut self, identifier: &TMessageIdentifier) -> crate::Result<()> {
-        if self.strict {
-            let message_type: u8 = identifier.message_type.into();
-            let header = BINARY_PROTOCOL_VERSION_1 | (message_type as u32);
-            // write u32
-            let mut wtr = Vec::new();
-            wtr.write_u32::<BigEndian>(header).unwrap();
-            self.transport.write(&wtr).await;
-            //
-            self.write_string(&identifier.name).await?;
-            self.write_i32(identifier.sequence_number).await
-        } else {
-            self.write_string(&identifier.name).await?;
-            self.write_byte(identifier.message_type.into()).await?;
-            self.write_i32(identifier.sequence_number).await
-        }
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            if self.strict {
+                let message_type: u8 = identifier.message_type.into();
+                let header = BINARY_PROTOCOL_VERSION_1 | (message_type as u32);
+                self.write_fixed(4, |b| BigEndian::write_u32(b, header)).await?;
+                self.write_string(&identifier.name).await?;
+                self.write_i32(identifier.sequence_number).await
+            } else {
+                self.write_string(&identifier.name).await?;
+                self.write_byte(identifier.message_type.into()).await?;
+                self.write_i32(identifier.sequence_number).await
+            }
+        })
     }
 
-    async fn write_message_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn write_struct_begin(&mut self, _: &TStructIdentifier) -> crate::Result<()> {
-        Ok(())
+    fn write_struct_begin<'a>(&'a mut self, _: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn write_struct_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn write_field_begin(&mut self, identifier: &TFieldIdentifier) -> crate::Result<()> {
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
         if identifier.id.is_none() && identifier.field_type != TType::Stop {
-            return Err(crate::Error::Protocol(ProtocolError {
+            return TProtocolFuture::ready(Err(crate::Error::Protocol(ProtocolError {
                 kind: ProtocolErrorKind::Unknown,
                 message: format!(
                     "cannot write identifier {:?} without sequence number",
                     &identifier
                 ),
-            }));
+            })));
         }
 
-        self.write_byte(field_type_to_u8(identifier.field_type)).await?;
+        let field_type = field_type_to_u8(identifier.field_type);
         if let Some(id) = identifier.id {
-            self.write_i16(id).await
+            self.write_fixed(3, |b| {
+                b[0] = field_type;
+                BigEndian::write_i16(&mut b[1..], id);
+            })
         } else {
-            Ok(())
+            self.write_byte(field_type)
         }
     }
 
-    async fn write_field_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn write_field_stop(&mut self) -> crate::Result<()> {
-        self.write_byte(field_type_to_u8(TType::Stop)).await
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        self.write_byte(field_type_to_u8(TType::Stop))
     }
 
-    async fn write_bool(&mut self, b: bool) -> crate::Result<()> {
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
         if b {
-            self.write_i8(1).await
+            self.write_i8(1)
         } else {
-            self.write_i8(0).await
+            self.write_i8(0)
         }
     }
 
-    async fn write_bytes(&mut self, b: &[u8]) -> crate::Result<()> {
-        self.write_i32(b.len() as i32).await?;
-        self.transport.write(b).await;
-
-        Ok(())
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        BigEndian::write_i32(&mut self.buf[..4], b.len() as i32);
+        if !self.transport.write_buffered(&self.buf[..4]) {
+            return TProtocolFuture::boxed(async move {
+                write_all(&mut self.transport, &self.buf[..4]).await?;
+                write_all(&mut self.transport, b).await
+            });
+        }
+        self.write_raw(b)
     }
 
-    async fn write_i8(&mut self, i: i8) -> crate::Result<()> {
-        self.transport.write(&[i as u8; 1]).await;
-
-        Ok(())
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        BigEndian::write_i32(&mut self.buf[..4], b.len() as i32);
+        if !self.transport.write_buffered(&self.buf[..4]) {
+            return self.write_bytes(b);
+        }
+        if self.transport.write_shared(b) {
+            return TProtocolFuture::ready(Ok(()));
+        }
+        self.write_raw(b)
     }
 
-    async fn write_i16(&mut self, i: i16) -> crate::Result<()> {
-        BigEndian::write_i16(&mut self.buf2, i);
-        self.transport.write(&self.buf2).await;
-        Ok(())
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.write_fixed(1, |b| b[0] = i as u8)
     }
 
-    async fn write_i32(&mut self, i: i32) -> crate::Result<()> {
-        BigEndian::write_i32(&mut self.buf4, i);
-        self.transport.write(&self.buf4).await;
-
-        Ok(())
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.write_fixed(2, |b| BigEndian::write_i16(b, i))
     }
 
-    async fn write_i64(&mut self, i: i64) -> crate::Result<()> {
-        BigEndian::write_i64(&mut self.buf8, i);
-        self.transport.write(&self.buf8).await;
-
-        Ok(())
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.write_fixed(4, |b| BigEndian::write_i32(b, i))
     }
 
-    async fn write_double(&mut self, d: f64) -> crate::Result<()> {
-        BigEndian::write_f64(&mut self.buf8, d);
-        self.transport.write(&self.buf8).await;
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.write_fixed(8, |b| BigEndian::write_i64(b, i))
+    }
 
-        Ok(())
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        self.write_fixed(8, |b| BigEndian::write_f64(b, d))
     }
 
-    async fn write_string(&mut self, s: &str) -> crate::Result<()> {
-        self.write_bytes(s.as_bytes()).await
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        self.write_bytes(s.as_bytes())
     }
 
-    async fn write_list_begin(&mut self, identifier: &TListIdentifier) -> crate::Result<()> {
-        self.write_byte(field_type_to_u8(identifier.element_type)).await?;
-        self.write_i32(identifier.size).await
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        let element_type = field_type_to_u8(identifier.element_type);
+        let size = identifier.size;
+        self.write_fixed(5, |b| {
+            b[0] = element_type;
+            BigEndian::write_i32(&mut b[1..], size);
+        })
     }
 
-    async fn write_list_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn write_set_begin(&mut self, identifier: &TSetIdentifier) -> crate::Result<()> {
-        self.write_byte(field_type_to_u8(identifier.element_type)).await?;
-        self.write_i32(identifier.size).await
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        let element_type = field_type_to_u8(identifier.element_type);
+        let size = identifier.size;
+        self.write_fixed(5, |b| {
+            b[0] = element_type;
+            BigEndian::write_i32(&mut b[1..], size);
+        })
     }
 
-    async fn write_set_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
-    async fn write_map_begin(&mut self, identifier: &TMapIdentifier) -> crate::Result<()> {
-        let key_type = identifier
-            .key_type
-            .expect("map identifier to write should contain key type");
-        self.write_byte(field_type_to_u8(key_type)).await?;
-        let val_type = identifier
-            .value_type
-            .expect("map identifier to write should contain value type");
-        self.write_byte(field_type_to_u8(val_type)).await?;
-        self.write_i32(identifier.size).await
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        let (key_type, val_type) = match (identifier.key_type, identifier.value_type) {
+            (Some(key_type), Some(val_type)) => (key_type, val_type),
+            _ => return TProtocolFuture::ready(Err(new_protocol_error(
+                ProtocolErrorKind::InvalidData,
+                format!("cannot write map identifier {:?} without key or value type", identifier),
+            ))),
+        };
+        let size = identifier.size;
+        self.write_fixed(6, |b| {
+            b[0] = field_type_to_u8(key_type);
+            b[1] = field_type_to_u8(val_type);
+            BigEndian::write_i32(&mut b[2..], size);
+        })
     }
 
-    async fn write_map_end(&mut self) -> crate::Result<()> {
-        Ok(())
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
     }
 
     async fn flush(&mut self) -> crate::Result<()> {
         self.transport.flush().await.map_err(From::from)
     }
 
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.transport.flush_coalesced().await.map_err(From::from)
+    }
+
     // utility
     //
 
-    async fn write_byte(&mut self, b: u8) -> crate::Result<()> {
-        self.transport.write(&[b; 1]).await;
-
-        Ok(())
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        self.write_fixed(1, |buf| buf[0] = b)
     }
 }
 
@@ -498,4 +623,326 @@ fn field_type_from_u8(b: u8) -> crate::Result<TType> {
             message: format!("cannot convert {} to TType", unkn),
         }))
     }
-}
\ No newline at end of file
+}
+/// Convert a size read from the wire, rejecting negative sizes and sizes
+/// over `max_size`.
+fn checked_size(size: i32, max_size: usize) -> crate::Result<usize> {
+    if size < 0 {
+        Err(new_protocol_error(
+            ProtocolErrorKind::NegativeSize,
+            format!("received negative size {}", size),
+        ))
+    } else if size as usize > max_size {
+        Err(new_protocol_error(
+            ProtocolErrorKind::SizeLimit,
+            format!("received size {} larger than the maximum of {}", size, max_size),
+        ))
+    } else {
+        Ok(size as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp;
+    use std::collections::VecDeque;
+    use std::io;
+
+    use bytes::Buf;
+    use async_std::task::block_on;
+
+    use crate::transport::async_mem::TAsyncBufferChannel;
+    use crate::transport::{AsyncRead, AsyncWrite};
+
+    use super::*;
+
+    /// Reader that buffers `chunks` one at a time, so that values crossing
+    /// from one chunk to the next are decoded on the slow path.
+    struct TChunkedReader {
+        buffer: Bytes,
+        chunks: VecDeque<Bytes>,
+        // whether `buffered` shows the buffer to the protocol
+        exposed: bool,
+    }
+
+    impl TChunkedReader {
+        fn new(bytes: &[u8], boundary: usize, exposed: bool) -> TChunkedReader {
+            let mut chunks = VecDeque::new();
+            chunks.push_back(Bytes::copy_from_slice(&bytes[boundary..]));
+            TChunkedReader {
+                buffer: Bytes::copy_from_slice(&bytes[..boundary]),
+                chunks,
+                exposed,
+            }
+        }
+
+        fn is_exhausted(&self) -> bool {
+            self.buffer.is_empty() && self.chunks.iter().all(|chunk| chunk.is_empty())
+        }
+    }
+
+    #[async_trait]
+    impl AsyncRead for TChunkedReader {
+        async fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
+            if self.buffer.is_empty() {
+                self.buffer = self.chunks.pop_front().unwrap_or_default();
+            }
+            let nread = cmp::min(b.len(), self.buffer.len());
+            b[..nread].copy_from_slice(&self.buffer[..nread]);
+            self.buffer.advance(nread);
+            Ok(nread)
+        }
+
+        fn buffered(&self) -> &[u8] {
+            if self.exposed { &self.buffer } else { &[] }
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.buffer.advance(amt);
+        }
+    }
+
+    /// Writer whose buffer takes `room` bytes before writes go through
+    /// `write`.
+    struct TBoundedWriter {
+        written: Vec<u8>,
+        room: usize,
+    }
+
+    #[async_trait]
+    impl AsyncWrite for TBoundedWriter {
+        async fn write(&mut self, b: &[u8]) -> io::Result<usize> {
+            self.written.extend_from_slice(b);
+            Ok(b.len())
+        }
+
+        async fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write_buffered(&mut self, b: &[u8]) -> bool {
+            if b.len() > self.room {
+                return false;
+            }
+            self.room -= b.len();
+            self.written.extend_from_slice(b);
+            true
+        }
+    }
+
+    /// Writer that queues every `Bytes` given to `write_shared`.
+    #[derive(Default)]
+    struct TSharingWriter {
+        written: Vec<u8>,
+        shared: Vec<Bytes>,
+    }
+
+    #[async_trait]
+    impl AsyncWrite for TSharingWriter {
+        async fn write(&mut self, b: &[u8]) -> io::Result<usize> {
+            self.written.extend_from_slice(b);
+            Ok(b.len())
+        }
+
+        async fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write_buffered(&mut self, b: &[u8]) -> bool {
+            self.written.extend_from_slice(b);
+            true
+        }
+
+        fn write_shared(&mut self, b: &Bytes) -> bool {
+            self.written.extend_from_slice(b);
+            self.shared.push(b.clone());
+            true
+        }
+    }
+
+    fn field(name: &str, field_type: TType, id: i16) -> TFieldIdentifier {
+        TFieldIdentifier::new(name, field_type, id)
+    }
+
+    async fn write_values(o: &mut (dyn TAsyncOutputProtocol + Send)) -> crate::Result<()> {
+        o.write_message_begin(&TMessageIdentifier::new("values", TMessageType::Call, 7)).await?;
+        o.write_struct_begin(&TStructIdentifier::new("values")).await?;
+        o.write_field_begin(&field("i64", TType::I64, 1)).await?;
+        o.write_i64(-1_234_567_890_123).await?;
+        o.write_field_begin(&field("double", TType::Double, 2)).await?;
+        o.write_double(3.25).await?;
+        o.write_field_begin(&field("bool", TType::Bool, 3)).await?;
+        o.write_bool(true).await?;
+        o.write_field_begin(&field("i16", TType::I16, 4)).await?;
+        o.write_i16(-7).await?;
+        o.write_field_begin(&field("i8", TType::I08, 5)).await?;
+        o.write_i8(-3).await?;
+        o.write_field_begin(&field("string", TType::String, 6)).await?;
+        o.write_string("héllo").await?;
+        o.write_field_begin(&field("binary", TType::String, 7)).await?;
+        o.write_binary(&Bytes::from_static(b"\x00\x01binary")).await?;
+        o.write_field_begin(&field("list", TType::List, 8)).await?;
+        o.write_list_begin(&TListIdentifier::new(TType::I32, 2)).await?;
+        o.write_i32(1).await?;
+        o.write_i32(-2).await?;
+        o.write_list_end().await?;
+        o.write_field_begin(&field("map", TType::Map, 9)).await?;
+        o.write_map_begin(&TMapIdentifier::new(TType::String, TType::I32, 1)).await?;
+        o.write_string("key").await?;
+        o.write_i32(i32::MAX).await?;
+        o.write_map_end().await?;
+        o.write_field_begin(&field("set", TType::Set, 10)).await?;
+        o.write_set_begin(&TSetIdentifier::new(TType::I64, 0)).await?;
+        o.write_set_end().await?;
+        o.write_field_stop().await?;
+        o.write_struct_end().await?;
+        o.write_message_end().await?;
+        o.flush().await
+    }
+
+    async fn read_values(i: &mut (dyn TAsyncInputProtocol + Send)) -> crate::Result<()> {
+        async fn read_field(i: &mut (dyn TAsyncInputProtocol + Send), field_type: TType, id: i16) -> crate::Result<()> {
+            let field_ident = i.read_field_begin().await?;
+            assert_eq!((field_ident.field_type, field_ident.id), (field_type, Some(id)));
+            Ok(())
+        }
+
+        assert_eq!(i.read_message_begin().await?, TMessageIdentifier::new("values", TMessageType::Call, 7));
+        i.read_struct_begin().await?;
+        read_field(i, TType::I64, 1).await?;
+        assert_eq!(i.read_i64().await?, -1_234_567_890_123);
+        read_field(i, TType::Double, 2).await?;
+        assert_eq!(i.read_double().await?, 3.25);
+        read_field(i, TType::Bool, 3).await?;
+        assert!(i.read_bool().await?);
+        read_field(i, TType::I16, 4).await?;
+        assert_eq!(i.read_i16().await?, -7);
+        read_field(i, TType::I08, 5).await?;
+        assert_eq!(i.read_i8().await?, -3);
+        read_field(i, TType::String, 6).await?;
+        assert_eq!(i.read_string().await?, "héllo");
+        read_field(i, TType::String, 7).await?;
+        assert_eq!(i.read_binary().await?, Bytes::from_static(b"\x00\x01binary"));
+        read_field(i, TType::List, 8).await?;
+        assert_eq!(i.read_list_begin().await?, TListIdentifier::new(TType::I32, 2));
+        assert_eq!(i.read_i32().await?, 1);
+        assert_eq!(i.read_i32().await?, -2);
+        i.read_list_end().await?;
+        read_field(i, TType::Map, 9).await?;
+        assert_eq!(i.read_map_begin().await?, TMapIdentifier::new(TType::String, TType::I32, 1));
+        assert_eq!(i.read_string().await?, "key");
+        assert_eq!(i.read_i32().await?, i32::MAX);
+        i.read_map_end().await?;
+        read_field(i, TType::Set, 10).await?;
+        assert_eq!(i.read_set_begin().await?, TSetIdentifier::new(TType::I64, 0));
+        i.read_set_end().await?;
+        assert_eq!(i.read_field_begin().await?.field_type, TType::Stop);
+        i.read_struct_end().await?;
+        i.read_message_end().await
+    }
+
+    fn encode(room: usize) -> Vec<u8> {
+        let mut o = TAsyncBinaryOutputProtocol::new(TBoundedWriter { written: Vec::new(), room }, true);
+        block_on(write_values(&mut o)).unwrap();
+        o.transport.written
+    }
+
+    #[test]
+    fn must_write_same_bytes_on_fast_and_slow_path() {
+        // with no room every value is written on the slow path
+        let expected = encode(0);
+        for room in 1..=expected.len() {
+            assert_eq!(encode(room), expected, "buffer room {}", room);
+        }
+    }
+
+    #[test]
+    fn must_read_values_split_at_any_buffer_boundary() {
+        let bytes = encode(usize::MAX);
+
+        let mut i = TAsyncBinaryInputProtocol::new(TChunkedReader::new(&bytes, 0, false), true);
+        block_on(read_values(&mut i)).unwrap();
+        assert!(i.transport.is_exhausted());
+
+        for boundary in 0..=bytes.len() {
+            let mut i = TAsyncBinaryInputProtocol::new(TChunkedReader::new(&bytes, boundary, true), true);
+            block_on(read_values(&mut i)).unwrap_or_else(|e| panic!("boundary {}: {:?}", boundary, e));
+            assert!(i.transport.is_exhausted(), "boundary {}", boundary);
+        }
+    }
+
+    #[test]
+    fn must_reject_map_without_types() {
+        let mut o = TAsyncBinaryOutputProtocol::new(TBoundedWriter { written: Vec::new(), room: usize::MAX }, true);
+        match block_on(o.write_map_begin(&TMapIdentifier::new(None, None, 0))) {
+            Err(crate::Error::Protocol(e)) => assert_eq!(e.kind, ProtocolErrorKind::InvalidData),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    fn must_reject_sizes_over_max_message_size() {
+        fn error_kind<V: std::fmt::Debug>(res: crate::Result<V>) -> ProtocolErrorKind {
+            match res {
+                Err(crate::Error::Protocol(e)) => e.kind,
+                res => panic!("unexpected result {:?}", res),
+            }
+        }
+
+        // a string claiming almost 2 GiB is rejected before its buffer is
+        // allocated, whether or not its length is decoded from the buffer
+        let bytes = [0x7F, 0xFF, 0xFF, 0xFF, b'a'];
+        for exposed in [false, true].iter() {
+            let mut i = TAsyncBinaryInputProtocol::new(TChunkedReader::new(&bytes, bytes.len(), *exposed), true);
+            assert_eq!(error_kind(block_on(i.read_bytes())), ProtocolErrorKind::SizeLimit);
+        }
+
+        let input = |bytes: &[u8]| {
+            TAsyncBinaryInputProtocol::new(TChunkedReader::new(bytes, bytes.len(), true), true).with_max_message_size(3)
+        };
+        assert_eq!(block_on(input(&[0, 0, 0, 3, b'a', b'b', b'c']).read_string()).unwrap(), "abc");
+        assert_eq!(error_kind(block_on(input(&[0, 0, 0, 4, b'a', b'b', b'c', b'd']).read_binary())), ProtocolErrorKind::SizeLimit);
+        assert_eq!(error_kind(block_on(input(&[8, 0, 0, 0, 4]).read_list_begin())), ProtocolErrorKind::SizeLimit);
+        assert_eq!(error_kind(block_on(input(&[8, 0, 0, 0, 4]).read_set_begin())), ProtocolErrorKind::SizeLimit);
+        assert_eq!(error_kind(block_on(input(&[8, 8, 0, 0, 0, 4]).read_map_begin())), ProtocolErrorKind::SizeLimit);
+        assert_eq!(error_kind(block_on(input(&[8, 0xFF, 0xFF, 0xFF, 0xFF]).read_list_begin())), ProtocolErrorKind::NegativeSize);
+    }
+
+    #[test]
+    fn must_read_binary_as_slice_of_bytes_buffer() {
+        let bytes = Bytes::from_static(&[0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o', 0, 0, 0, 2, b'h', b'i']);
+
+        let mut i = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes.clone()), true);
+        let binary = block_on(i.read_binary()).unwrap();
+        assert_eq!(binary, Bytes::from_static(b"hello"));
+        assert_eq!(binary.as_ptr(), bytes[4..].as_ptr());
+
+        // readers with a plain buffer hand out a copy
+        let mut i = TAsyncBinaryInputProtocol::new(TChunkedReader::new(&bytes, bytes.len(), true), true);
+        let binary = block_on(i.read_binary()).unwrap();
+        assert_eq!(binary, Bytes::from_static(b"hello"));
+        assert_ne!(binary.as_ptr(), bytes[4..].as_ptr());
+        assert_eq!(block_on(i.read_binary()).unwrap(), Bytes::from_static(b"hi"));
+        assert!(i.transport.is_exhausted());
+    }
+
+    #[test]
+    fn must_queue_binary_without_copying_where_writer_allows() {
+        let payload = Bytes::from(vec![7u8; 64]);
+        let mut expected = vec![0, 0, 0, 64];
+        expected.extend_from_slice(&payload);
+
+        let mut o = TAsyncBinaryOutputProtocol::new(TSharingWriter::default(), true);
+        block_on(o.write_binary(&payload)).unwrap();
+        assert_eq!(o.transport.written, expected);
+        assert_eq!(o.transport.shared.len(), 1);
+        assert_eq!(o.transport.shared[0].as_ptr(), payload.as_ptr());
+
+        // without room in the writer's buffer the payload is written through
+        for room in [0, 4].iter() {
+            let mut o = TAsyncBinaryOutputProtocol::new(TBoundedWriter { written: Vec::new(), room: *room }, true);
+            block_on(o.write_binary(&payload)).unwrap();
+            assert_eq!(o.transport.written, expected, "buffer room {}", room);
+        }
+    }
+}
@@ -0,0 +1,819 @@
+//! Protocol wrappers that print the messages passing through them.
+//!
+//! `TAsyncDebugInputProtocol` and `TAsyncDebugOutputProtocol` build a
+//! human-readable tree of every message read or written through them, with
+//! the message header followed by each field's id, type and value:
+//!
+//! ```text
+//! read Call add (seqid 3) {
+//!   1: i32 = 7
+//!   2: struct {
+//!     1: string = "hi"
+//!   }
+//!   3: list<i64> (size 2) [
+//!     10
+//!     20
+//!   ]
+//! }
+//! ```
+//!
+//! The tree is handed to a sink once the message is complete, or as far
+//! as it got when a read or write fails. The default sink logs it at debug
+//! level.
+
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use super::{TAsyncInputProtocol, TAsyncOutputProtocol, TProtocolFuture};
+use super::{
+    TFieldIdentifier, TListIdentifier,
+    TMapIdentifier, TMessageIdentifier,
+    TSetIdentifier, TStructIdentifier,
+    TType,
+};
+
+/// Receives the tree of each message.
+pub type TDebugSink = fn(&str);
+
+fn log_tree(tree: &str) {
+    debug!("{}", tree);
+}
+
+/// Input protocol that prints every message it reads.
+///
+/// # Examples
+///
+/// ```
+/// use async_thrift::protocol::async_binary::TAsyncBinaryInputProtocol;
+/// use async_thrift::protocol::async_debug::TAsyncDebugInputProtocol;
+/// use async_thrift::transport::async_mem::TAsyncBufferChannel;
+///
+/// let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::new(), true);
+/// // print messages instead of logging them
+/// let i_prot = TAsyncDebugInputProtocol::with_sink(i_prot, |tree| println!("{}", tree));
+/// ```
+#[derive(Debug)]
+pub struct TAsyncDebugInputProtocol<P>
+    where
+        P: TAsyncInputProtocol,
+{
+    inner: P,
+    tree: TDebugTree,
+}
+
+impl<P> TAsyncDebugInputProtocol<P>
+    where
+        P: TAsyncInputProtocol,
+{
+    /// Create a `TAsyncDebugInputProtocol` that reads messages from `inner`
+    /// and logs them.
+    pub fn new(inner: P) -> TAsyncDebugInputProtocol<P> {
+        TAsyncDebugInputProtocol::with_sink(inner, log_tree)
+    }
+
+    /// Create a `TAsyncDebugInputProtocol` that reads messages from `inner`
+    /// and hands them to `sink`.
+    pub fn with_sink(inner: P, sink: TDebugSink) -> TAsyncDebugInputProtocol<P> {
+        TAsyncDebugInputProtocol { inner, tree: TDebugTree::new("read", sink) }
+    }
+
+    /// Return the wrapped protocol.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+/// Output protocol that prints every message it writes.
+#[derive(Debug)]
+pub struct TAsyncDebugOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol,
+{
+    inner: P,
+    tree: TDebugTree,
+}
+
+impl<P> TAsyncDebugOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol,
+{
+    /// Create a `TAsyncDebugOutputProtocol` that writes messages to `inner`
+    /// and logs them.
+    pub fn new(inner: P) -> TAsyncDebugOutputProtocol<P> {
+        TAsyncDebugOutputProtocol::with_sink(inner, log_tree)
+    }
+
+    /// Create a `TAsyncDebugOutputProtocol` that writes messages to `inner`
+    /// and hands them to `sink`.
+    pub fn with_sink(inner: P, sink: TDebugSink) -> TAsyncDebugOutputProtocol<P> {
+        TAsyncDebugOutputProtocol { inner, tree: TDebugTree::new("write", sink) }
+    }
+
+    /// Return the wrapped protocol.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+/// What the values being read or written belong to.
+#[derive(Debug)]
+enum TDebugFrame {
+    Struct,
+    List,
+    /// Map whose next value is a key if `key` is `None`.
+    Map { key: Option<String> },
+}
+
+/// The tree of the message in progress.
+#[derive(Debug)]
+struct TDebugTree {
+    verb: &'static str,
+    sink: TDebugSink,
+    text: String,
+    frames: Vec<TDebugFrame>,
+    /// Id of the field whose value comes next.
+    field: Option<i16>,
+}
+
+impl TDebugTree {
+    fn new(verb: &'static str, sink: TDebugSink) -> TDebugTree {
+        TDebugTree {
+            verb,
+            sink,
+            text: String::new(),
+            frames: Vec::new(),
+            field: None,
+        }
+    }
+
+    fn message_begin(&mut self, message_ident: &TMessageIdentifier) {
+        self.text.clear();
+        self.frames.clear();
+        self.field = None;
+        let _ = write!(
+            self.text,
+            "{} {} {} (seqid {})",
+            self.verb, message_ident.message_type, message_ident.name, message_ident.sequence_number,
+        );
+    }
+
+    fn message_end(&mut self) {
+        if !self.text.is_empty() {
+            (self.sink)(&self.text);
+            self.text.clear();
+        }
+    }
+
+    fn fail(&mut self, e: &crate::Error) {
+        if !self.text.is_empty() {
+            self.line(&format!("<failed: {:?}>", e));
+            self.message_end();
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        self.text.push('\n');
+        for _ in 0..self.frames.len() {
+            self.text.push_str("  ");
+        }
+        self.text.push_str(line);
+    }
+
+    fn struct_begin(&mut self) {
+        // the outermost struct is the body of the message
+        if self.frames.is_empty() {
+            self.text.push_str(" {");
+            self.frames.push(TDebugFrame::Struct);
+        } else {
+            self.open("struct {".to_owned(), TDebugFrame::Struct);
+        }
+    }
+
+    fn field(&mut self, field_ident: &TFieldIdentifier) {
+        if field_ident.field_type != TType::Stop {
+            self.field = Some(field_ident.id.unwrap_or_default());
+        }
+    }
+
+    fn list_begin(&mut self, kind: &str, element_type: TType, size: i32) {
+        let header = format!("{}<{}> (size {}) [", kind, type_name(element_type), size);
+        self.open(header, TDebugFrame::List);
+    }
+
+    fn map_begin(&mut self, map_ident: &TMapIdentifier) {
+        let header = format!(
+            "map<{}, {}> (size {}) {{",
+            map_ident.key_type.map_or("?", type_name),
+            map_ident.value_type.map_or("?", type_name),
+            map_ident.size,
+        );
+        self.open(header, TDebugFrame::Map { key: None });
+    }
+
+    fn close(&mut self, closer: &str) {
+        self.frames.pop();
+        if self.frames.is_empty() {
+            self.text.push('\n');
+            self.text.push_str(closer);
+        } else {
+            self.line(closer);
+        }
+    }
+
+    /// Start a nested struct or container described by `header`.
+    fn open(&mut self, header: String, frame: TDebugFrame) {
+        let line = match self.label() {
+            Some(label) => format!("{}{}", label, header),
+            None => header,
+        };
+        self.line(&line);
+        self.frames.push(frame);
+    }
+
+    /// Record a value of type `field_type` printed as `value`.
+    fn value(&mut self, field_type: TType, value: String) {
+        if let Some(TDebugFrame::Map { key: key @ None }) = self.frames.last_mut() {
+            *key = Some(value);
+            return;
+        }
+        let line = match (self.frames.last(), self.field.take()) {
+            (Some(TDebugFrame::Struct), Some(id)) => format!("{}: {} = {}", id, type_name(field_type), value),
+            _ => match self.label() {
+                Some(label) => format!("{}{}", label, value),
+                None => value,
+            },
+        };
+        self.line(&line);
+    }
+
+    /// Return what precedes the next value: its field id in a struct and
+    /// its key in a map.
+    fn label(&mut self) -> Option<String> {
+        match self.frames.last_mut() {
+            Some(TDebugFrame::Struct) => self.field.take().map(|id| format!("{}: ", id)),
+            Some(TDebugFrame::Map { key }) => key.take().map(|key| format!("{} => ", key)),
+            _ => None,
+        }
+    }
+}
+
+fn type_name(field_type: TType) -> &'static str {
+    match field_type {
+        TType::Stop => "stop",
+        TType::Void => "void",
+        TType::Bool => "bool",
+        TType::I08 => "i8",
+        TType::Double => "double",
+        TType::I16 => "i16",
+        TType::I32 => "i32",
+        TType::I64 => "i64",
+        TType::String => "string",
+        TType::Utf7 => "utf7",
+        TType::Struct => "struct",
+        TType::Map => "map",
+        TType::Set => "set",
+        TType::List => "list",
+        TType::Utf8 => "utf8",
+        TType::Utf16 => "utf16",
+    }
+}
+
+fn bytes_value(b: &[u8]) -> String {
+    match std::str::from_utf8(b) {
+        Ok(s) => format!("{:?}", s),
+        Err(_) => b.iter().fold("0x".to_owned(), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        }),
+    }
+}
+
+impl<P> TAsyncDebugInputProtocol<P>
+    where
+        P: TAsyncInputProtocol + Send,
+{
+    fn check<T>(&mut self, res: crate::Result<T>) -> crate::Result<T> {
+        if let Err(ref e) = res {
+            self.tree.fail(e);
+        }
+        res
+    }
+}
+
+impl<P> TAsyncInputProtocol for TAsyncDebugInputProtocol<P>
+    where
+        P: TAsyncInputProtocol + Send,
+{
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let message_ident = self.inner.read_message_begin().await?;
+            self.tree.message_begin(&message_ident);
+            Ok(message_ident)
+        })
+    }
+
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_message_end().await;
+            self.tree.message_end();
+            res
+        })
+    }
+
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_struct_begin().await;
+            let struct_ident = self.check(res)?;
+            self.tree.struct_begin();
+            Ok(struct_ident)
+        })
+    }
+
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_struct_end().await;
+            self.check(res)?;
+            self.tree.close("}");
+            Ok(())
+        })
+    }
+
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_field_begin().await;
+            let field_ident = self.check(res)?;
+            self.tree.field(&field_ident);
+            Ok(field_ident)
+        })
+    }
+
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_field_end().await;
+            self.check(res)
+        })
+    }
+
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_bool().await;
+            let b = self.check(res)?;
+            self.tree.value(TType::Bool, b.to_string());
+            Ok(b)
+        })
+    }
+
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_bytes().await;
+            let b = self.check(res)?;
+            self.tree.value(TType::String, bytes_value(&b));
+            Ok(b)
+        })
+    }
+
+    fn read_string_or_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_string_or_binary().await;
+            let b = self.check(res)?;
+            self.tree.value(TType::String, bytes_value(&b));
+            Ok(b)
+        })
+    }
+
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_i8().await;
+            let i = self.check(res)?;
+            self.tree.value(TType::I08, i.to_string());
+            Ok(i)
+        })
+    }
+
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_i16().await;
+            let i = self.check(res)?;
+            self.tree.value(TType::I16, i.to_string());
+            Ok(i)
+        })
+    }
+
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_i32().await;
+            let i = self.check(res)?;
+            self.tree.value(TType::I32, i.to_string());
+            Ok(i)
+        })
+    }
+
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_i64().await;
+            let i = self.check(res)?;
+            self.tree.value(TType::I64, i.to_string());
+            Ok(i)
+        })
+    }
+
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_double().await;
+            let d = self.check(res)?;
+            self.tree.value(TType::Double, format!("{:?}", d));
+            Ok(d)
+        })
+    }
+
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_string().await;
+            let s = self.check(res)?;
+            self.tree.value(TType::String, format!("{:?}", s));
+            Ok(s)
+        })
+    }
+
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_list_begin().await;
+            let list_ident = self.check(res)?;
+            self.tree.list_begin("list", list_ident.element_type, list_ident.size);
+            Ok(list_ident)
+        })
+    }
+
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_list_end().await;
+            self.check(res)?;
+            self.tree.close("]");
+            Ok(())
+        })
+    }
+
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_set_begin().await;
+            let set_ident = self.check(res)?;
+            self.tree.list_begin("set", set_ident.element_type, set_ident.size);
+            Ok(set_ident)
+        })
+    }
+
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_set_end().await;
+            self.check(res)?;
+            self.tree.close("]");
+            Ok(())
+        })
+    }
+
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_map_begin().await;
+            let map_ident = self.check(res)?;
+            self.tree.map_begin(&map_ident);
+            Ok(map_ident)
+        })
+    }
+
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_map_end().await;
+            self.check(res)?;
+            self.tree.close("}");
+            Ok(())
+        })
+    }
+
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_byte().await;
+            self.check(res)
+        })
+    }
+}
+
+impl<P> TAsyncDebugOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol + Send,
+{
+    fn check(&mut self, res: crate::Result<()>) -> crate::Result<()> {
+        if let Err(ref e) = res {
+            self.tree.fail(e);
+        }
+        res
+    }
+}
+
+#[async_trait]
+impl<P> TAsyncOutputProtocol for TAsyncDebugOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol + Send,
+{
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.message_begin(identifier);
+            let res = self.inner.write_message_begin(identifier).await;
+            self.check(res)
+        })
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.write_message_end().await;
+            self.tree.message_end();
+            res
+        })
+    }
+
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.struct_begin();
+            let res = self.inner.write_struct_begin(identifier).await;
+            self.check(res)
+        })
+    }
+
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.close("}");
+            let res = self.inner.write_struct_end().await;
+            self.check(res)
+        })
+    }
+
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.field(identifier);
+            let res = self.inner.write_field_begin(identifier).await;
+            self.check(res)
+        })
+    }
+
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.write_field_end().await;
+            self.check(res)
+        })
+    }
+
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.write_field_stop().await;
+            self.check(res)
+        })
+    }
+
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::Bool, b.to_string());
+            let res = self.inner.write_bool(b).await;
+            self.check(res)
+        })
+    }
+
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::String, bytes_value(b));
+            let res = self.inner.write_bytes(b).await;
+            self.check(res)
+        })
+    }
+
+    fn write_string_or_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::String, bytes_value(b));
+            let res = self.inner.write_string_or_binary(b).await;
+            self.check(res)
+        })
+    }
+
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::I08, i.to_string());
+            let res = self.inner.write_i8(i).await;
+            self.check(res)
+        })
+    }
+
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::I16, i.to_string());
+            let res = self.inner.write_i16(i).await;
+            self.check(res)
+        })
+    }
+
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::I32, i.to_string());
+            let res = self.inner.write_i32(i).await;
+            self.check(res)
+        })
+    }
+
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::I64, i.to_string());
+            let res = self.inner.write_i64(i).await;
+            self.check(res)
+        })
+    }
+
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::Double, format!("{:?}", d));
+            let res = self.inner.write_double(d).await;
+            self.check(res)
+        })
+    }
+
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.value(TType::String, format!("{:?}", s));
+            let res = self.inner.write_string(s).await;
+            self.check(res)
+        })
+    }
+
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.list_begin("list", identifier.element_type, identifier.size);
+            let res = self.inner.write_list_begin(identifier).await;
+            self.check(res)
+        })
+    }
+
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.close("]");
+            let res = self.inner.write_list_end().await;
+            self.check(res)
+        })
+    }
+
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.list_begin("set", identifier.element_type, identifier.size);
+            let res = self.inner.write_set_begin(identifier).await;
+            self.check(res)
+        })
+    }
+
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.close("]");
+            let res = self.inner.write_set_end().await;
+            self.check(res)
+        })
+    }
+
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.map_begin(identifier);
+            let res = self.inner.write_map_begin(identifier).await;
+            self.check(res)
+        })
+    }
+
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.tree.close("}");
+            let res = self.inner.write_map_end().await;
+            self.check(res)
+        })
+    }
+
+    async fn flush(&mut self) -> crate::Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.inner.flush_coalesced().await
+    }
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_byte(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use async_std::task;
+
+    use crate::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+    use crate::protocol::TMessageType;
+    use crate::transport::async_mem::TAsyncBufferChannel;
+
+    use super::*;
+
+    thread_local! {
+        static TREES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record_tree(tree: &str) {
+        TREES.with(|trees| trees.borrow_mut().push(tree.to_owned()));
+    }
+
+    fn recorded_trees() -> Vec<String> {
+        TREES.with(|trees| trees.borrow_mut().drain(..).collect())
+    }
+
+    const TREE: &str = r#" Call add (seqid 3) {
+  1: i32 = 7
+  2: struct {
+    1: string = "hi"
+  }
+  3: list<i64> (size 2) [
+    10
+    20
+  ]
+  4: map<string, bool> (size 1) {
+    "on" => true
+  }
+  5: string = 0xff00
+}"#;
+
+    fn write_add<P: TAsyncOutputProtocol>(o_prot: &mut P) -> crate::Result<()> {
+        task::block_on(async {
+            o_prot.write_message_begin(&TMessageIdentifier::new("add", TMessageType::Call, 3)).await?;
+            o_prot.write_struct_begin(&TStructIdentifier::new("args")).await?;
+            o_prot.write_field_begin(&TFieldIdentifier::new("a", TType::I32, 1)).await?;
+            o_prot.write_i32(7).await?;
+            o_prot.write_field_end().await?;
+            o_prot.write_field_begin(&TFieldIdentifier::new("b", TType::Struct, 2)).await?;
+            o_prot.write_struct_begin(&TStructIdentifier::new("inner")).await?;
+            o_prot.write_field_begin(&TFieldIdentifier::new("s", TType::String, 1)).await?;
+            o_prot.write_string("hi").await?;
+            o_prot.write_field_end().await?;
+            o_prot.write_field_stop().await?;
+            o_prot.write_struct_end().await?;
+            o_prot.write_field_end().await?;
+            o_prot.write_field_begin(&TFieldIdentifier::new("c", TType::List, 3)).await?;
+            o_prot.write_list_begin(&TListIdentifier::new(TType::I64, 2)).await?;
+            o_prot.write_i64(10).await?;
+            o_prot.write_i64(20).await?;
+            o_prot.write_list_end().await?;
+            o_prot.write_field_end().await?;
+            o_prot.write_field_begin(&TFieldIdentifier::new("d", TType::Map, 4)).await?;
+            o_prot.write_map_begin(&TMapIdentifier::new(TType::String, TType::Bool, 1)).await?;
+            o_prot.write_string("on").await?;
+            o_prot.write_bool(true).await?;
+            o_prot.write_map_end().await?;
+            o_prot.write_field_end().await?;
+            o_prot.write_field_begin(&TFieldIdentifier::new("e", TType::String, 5)).await?;
+            o_prot.write_bytes(&[0xff, 0x00]).await?;
+            o_prot.write_field_end().await?;
+            o_prot.write_field_stop().await?;
+            o_prot.write_struct_end().await?;
+            o_prot.write_message_end().await
+        })
+    }
+
+    fn read_add<P: TAsyncInputProtocol>(i_prot: &mut P) -> crate::Result<()> {
+        task::block_on(async {
+            i_prot.read_message_begin().await?;
+            i_prot.skip(TType::Struct).await?;
+            i_prot.read_message_end().await
+        })
+    }
+
+    #[test]
+    fn must_print_tree_of_written_and_read_messages() {
+        let mut o_prot = TAsyncDebugOutputProtocol::with_sink(TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true), record_tree);
+        assert_success!(write_add(&mut o_prot));
+        assert_eq!(recorded_trees(), [format!("write{}", TREE)]);
+
+        let bytes = o_prot.into_inner().transport.take_write_bytes();
+        let mut i_prot = TAsyncDebugInputProtocol::with_sink(TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true), record_tree);
+        assert_success!(read_add(&mut i_prot));
+        assert_eq!(recorded_trees(), [format!("read{}", TREE)]);
+    }
+
+    #[test]
+    fn must_print_partial_tree_when_read_fails() {
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        assert_success!(write_add(&mut o_prot));
+        let mut bytes = o_prot.transport.take_write_bytes();
+        // cut the message inside the list
+        bytes.truncate(bytes.len() - 40);
+
+        let mut i_prot = TAsyncDebugInputProtocol::with_sink(TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true), record_tree);
+        assert!(read_add(&mut i_prot).is_err());
+
+        let trees = recorded_trees();
+        assert_eq!(trees.len(), 1);
+        let (read, failure) = trees[0].split_at(trees[0].find("\n    <failed: ").unwrap());
+        assert!(TREE.starts_with(read.trim_start_matches("read")));
+        assert!(read.ends_with("3: list<i64> (size 2) ["));
+        assert!(failure.ends_with('>'));
+    }
+}
@@ -0,0 +1,1109 @@
+use std::convert::{From, TryFrom};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::errors::{new_protocol_error, ProtocolErrorKind};
+use crate::transport::{read_exact, write_all, TAsyncReadTransport, TAsyncWriteTransport};
+
+use super::{TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory, TProtocolFuture};
+use super::DEFAULT_MAX_MESSAGE_SIZE;
+use super::{
+    TFieldIdentifier, TListIdentifier,
+    TMapIdentifier, TMessageIdentifier,
+    TMessageType, TSetIdentifier,
+    TStructIdentifier, TType,
+};
+
+const JSON_PROTOCOL_VERSION: i64 = 1;
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Read messages encoded in the Thrift JSON protocol.
+///
+/// Whitespace between tokens is skipped, and binary values are accepted
+/// with or without base64 padding.
+#[derive(Debug)]
+pub struct TAsyncJsonInputProtocol<T>
+    where
+        T: TAsyncReadTransport,
+{
+    // objects and arrays the next value is nested in
+    contexts: Vec<TJsonContext>,
+    // byte looked at but not read yet
+    peeked: Option<u8>,
+    max_message_size: usize,
+    pub transport: T,
+    // holds a byte while it is read from the transport
+    buf: [u8; 1],
+}
+
+impl<T> TAsyncJsonInputProtocol<T>
+    where
+        T: TAsyncReadTransport,
+{
+    /// Create a `TAsyncJsonInputProtocol` that reads bytes from `transport`.
+    pub fn new(transport: T) -> TAsyncJsonInputProtocol<T> {
+        TAsyncJsonInputProtocol {
+            contexts: Vec::new(),
+            peeked: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            transport,
+            buf: [0; 1],
+        }
+    }
+
+    /// Fail with a `SizeLimit` error when a container has more than
+    /// `max_message_size` elements, before anything is allocated for it.
+    /// The default is 100 MiB.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> TAsyncJsonInputProtocol<T> {
+        self.max_message_size = max_message_size;
+        self
+    }
+}
+
+/// Write messages in the Thrift JSON protocol.
+///
+/// The output is what the other Thrift libraries write: no whitespace,
+/// numbers quoted when they are object keys and binary values in padded
+/// base64.
+#[derive(Debug)]
+pub struct TAsyncJsonOutputProtocol<T>
+    where
+        T: TAsyncWriteTransport,
+{
+    // objects and arrays the next value is nested in
+    contexts: Vec<TJsonContext>,
+    pub transport: T,
+    // holds the tokens of a call while they are written to the transport
+    buf: Vec<u8>,
+}
+
+impl<T> TAsyncJsonOutputProtocol<T>
+    where
+        T: TAsyncWriteTransport,
+{
+    /// Create a `TAsyncJsonOutputProtocol` that writes bytes to `transport`.
+    pub fn new(transport: T) -> TAsyncJsonOutputProtocol<T> {
+        TAsyncJsonOutputProtocol {
+            contexts: Vec::new(),
+            transport,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Factory for creating instances of `TAsyncJsonInputProtocol`.
+#[derive(Default)]
+pub struct TAsyncJsonInputProtocolFactory {
+    max_message_size: Option<usize>,
+}
+
+impl TAsyncJsonInputProtocolFactory {
+    /// Create a `TAsyncJsonInputProtocolFactory`.
+    pub fn new() -> TAsyncJsonInputProtocolFactory {
+        TAsyncJsonInputProtocolFactory::default()
+    }
+
+    /// Create protocols that reject containers larger than
+    /// `max_message_size`. See `TAsyncJsonInputProtocol::with_max_message_size`.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> TAsyncJsonInputProtocolFactory {
+        self.max_message_size = Some(max_message_size);
+        self
+    }
+}
+
+impl TAsyncInputProtocolFactory for TAsyncJsonInputProtocolFactory {
+    fn create(&self, transport: Box<dyn TAsyncReadTransport + Send>) -> Box<dyn TAsyncInputProtocol + Send> {
+        let protocol = TAsyncJsonInputProtocol::new(transport);
+        match self.max_message_size {
+            Some(max_message_size) => Box::new(protocol.with_max_message_size(max_message_size)),
+            None => Box::new(protocol),
+        }
+    }
+}
+
+/// Factory for creating instances of `TAsyncJsonOutputProtocol`.
+#[derive(Default)]
+pub struct TAsyncJsonOutputProtocolFactory;
+
+impl TAsyncJsonOutputProtocolFactory {
+    /// Create a `TAsyncJsonOutputProtocolFactory`.
+    pub fn new() -> TAsyncJsonOutputProtocolFactory {
+        TAsyncJsonOutputProtocolFactory {}
+    }
+}
+
+impl TAsyncOutputProtocolFactory for TAsyncJsonOutputProtocolFactory {
+    fn create(&self, transport: Box<dyn TAsyncWriteTransport + Send>) -> Box<dyn TAsyncOutputProtocol + Send> {
+        Box::new(TAsyncJsonOutputProtocol::new(transport))
+    }
+}
+
+// A JSON object or array values are read from or written to.
+#[derive(Clone, Copy, Debug)]
+enum TJsonContext {
+    // `first` until the first element
+    Array { first: bool },
+    // keys and values alternate; `key` when the next value is a key
+    Object { first: bool, key: bool },
+}
+
+impl TJsonContext {
+    fn array() -> TJsonContext {
+        TJsonContext::Array { first: true }
+    }
+
+    fn object() -> TJsonContext {
+        TJsonContext::Object { first: true, key: true }
+    }
+
+    // Move past the next value, returning the separator that precedes it
+    // and whether it is an object key.
+    fn next_value(&mut self) -> (Option<u8>, bool) {
+        match self {
+            TJsonContext::Array { first } => {
+                let separator = if *first { None } else { Some(b',') };
+                *first = false;
+                (separator, false)
+            }
+            TJsonContext::Object { first, key } => {
+                let separator = match (*first, *key) {
+                    (true, _) => None,
+                    (false, true) => Some(b','),
+                    (false, false) => Some(b':'),
+                };
+                let is_key = *key;
+                *first = false;
+                *key = !*key;
+                (separator, is_key)
+            }
+        }
+    }
+}
+
+fn next_value(contexts: &mut [TJsonContext]) -> (Option<u8>, bool) {
+    match contexts.last_mut() {
+        Some(context) => context.next_value(),
+        None => (None, false),
+    }
+}
+
+impl<T> TAsyncJsonInputProtocol<T>
+    where
+        T: TAsyncReadTransport + std::marker::Send,
+{
+    async fn next_byte(&mut self) -> crate::Result<u8> {
+        if let Some(b) = self.peeked.take() {
+            return Ok(b);
+        }
+        if let Some(&b) = self.transport.buffered().first() {
+            self.transport.consume(1);
+            return Ok(b);
+        }
+        read_exact(&mut self.transport, &mut self.buf).await?;
+        Ok(self.buf[0])
+    }
+
+    async fn peek_byte(&mut self) -> crate::Result<u8> {
+        let b = self.next_byte().await?;
+        self.peeked = Some(b);
+        Ok(b)
+    }
+
+    // Peek at the next byte that is not whitespace.
+    async fn peek_token(&mut self) -> crate::Result<u8> {
+        loop {
+            match self.peek_byte().await? {
+                b' ' | b'\t' | b'\n' | b'\r' => self.peeked = None,
+                b => return Ok(b),
+            }
+        }
+    }
+
+    async fn expect(&mut self, expected: u8) -> crate::Result<()> {
+        match self.peek_token().await? {
+            b if b == expected => {
+                self.peeked = None;
+                Ok(())
+            }
+            b => Err(new_protocol_error(
+                ProtocolErrorKind::InvalidData,
+                format!("expected {:?} but found {:?}", char::from(expected), char::from(b)),
+            )),
+        }
+    }
+
+    // Read the separator before the next value, and return whether the
+    // value is an object key.
+    async fn begin_value(&mut self) -> crate::Result<bool> {
+        let (separator, is_key) = next_value(&mut self.contexts);
+        if let Some(separator) = separator {
+            self.expect(separator).await?;
+        }
+        Ok(is_key)
+    }
+
+    async fn read_object_begin(&mut self) -> crate::Result<()> {
+        self.begin_value().await?;
+        self.expect(b'{').await?;
+        self.contexts.push(TJsonContext::object());
+        Ok(())
+    }
+
+    async fn read_object_end(&mut self) -> crate::Result<()> {
+        self.contexts.pop();
+        self.expect(b'}').await
+    }
+
+    async fn read_array_begin(&mut self) -> crate::Result<()> {
+        self.begin_value().await?;
+        self.expect(b'[').await?;
+        self.contexts.push(TJsonContext::array());
+        Ok(())
+    }
+
+    async fn read_array_end(&mut self) -> crate::Result<()> {
+        self.contexts.pop();
+        self.expect(b']').await
+    }
+
+    // Read the bytes of a number up to the first byte that cannot be part
+    // of it.
+    async fn read_number_bytes(&mut self) -> crate::Result<String> {
+        let mut number = String::new();
+        loop {
+            match self.peek_byte().await? {
+                b @ b'0'..=b'9' | b @ b'-' | b @ b'+' | b @ b'.' | b @ b'e' | b @ b'E' => {
+                    self.peeked = None;
+                    number.push(char::from(b));
+                }
+                _ => return Ok(number),
+            }
+        }
+    }
+
+    async fn read_integer(&mut self) -> crate::Result<i64> {
+        self.begin_value().await?;
+        // object keys are quoted, but other libraries may quote any number
+        let quoted = self.peek_token().await? == b'"';
+        if quoted {
+            self.peeked = None;
+        }
+        let number = self.read_number_bytes().await?;
+        if quoted {
+            self.expect(b'"').await?;
+        }
+        number.parse().map_err(|_| new_protocol_error(
+            ProtocolErrorKind::InvalidData,
+            format!("cannot parse {:?} as an integer", number),
+        ))
+    }
+
+    async fn read_double_value(&mut self) -> crate::Result<f64> {
+        self.begin_value().await?;
+        let number = if self.peek_token().await? == b'"' {
+            self.peeked = None;
+            let mut number = String::new();
+            loop {
+                match self.next_byte().await? {
+                    b'"' => break number,
+                    b => number.push(char::from(b)),
+                }
+            }
+        } else {
+            self.read_number_bytes().await?
+        };
+        match number.as_str() {
+            "NaN" => Ok(f64::NAN),
+            "Infinity" => Ok(f64::INFINITY),
+            "-Infinity" => Ok(f64::NEG_INFINITY),
+            _ => number.parse().map_err(|_| new_protocol_error(
+                ProtocolErrorKind::InvalidData,
+                format!("cannot parse {:?} as a double", number),
+            )),
+        }
+    }
+
+    async fn read_hex_escape(&mut self) -> crate::Result<u16> {
+        let mut value = 0u16;
+        for _ in 0..4 {
+            let b = self.next_byte().await?;
+            let digit = char::from(b).to_digit(16).ok_or_else(|| new_protocol_error(
+                ProtocolErrorKind::InvalidData,
+                format!("invalid hex digit {:?} in string escape", char::from(b)),
+            ))?;
+            value = (value << 4) | digit as u16;
+        }
+        Ok(value)
+    }
+
+    // Read a quoted string, with its escapes resolved.
+    async fn read_string_bytes(&mut self) -> crate::Result<Vec<u8>> {
+        self.begin_value().await?;
+        self.expect(b'"').await?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next_byte().await? {
+                b'"' => return Ok(bytes),
+                b'\\' => {}
+                b => {
+                    bytes.push(b);
+                    continue;
+                }
+            }
+            let unescaped = match self.next_byte().await? {
+                b'"' => b'"',
+                b'\\' => b'\\',
+                b'/' => b'/',
+                b'b' => 0x08,
+                b'f' => 0x0C,
+                b'n' => b'\n',
+                b'r' => b'\r',
+                b't' => b'\t',
+                b'u' => {
+                    let mut units = vec![self.read_hex_escape().await?];
+                    // characters outside the basic plane are escaped as a
+                    // surrogate pair
+                    if (0xD800..0xDC00).contains(&units[0]) {
+                        self.expect(b'\\').await?;
+                        self.expect(b'u').await?;
+                        units.push(self.read_hex_escape().await?);
+                    }
+                    let c = std::char::decode_utf16(units).next().and_then(Result::ok).ok_or_else(|| {
+                        new_protocol_error(ProtocolErrorKind::InvalidData, "invalid unicode escape in string")
+                    })?;
+                    let mut encoded = [0u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+                    continue;
+                }
+                b => {
+                    return Err(new_protocol_error(
+                        ProtocolErrorKind::InvalidData,
+                        format!("invalid escape {:?} in string", char::from(b)),
+                    ))
+                }
+            };
+            bytes.push(unescaped);
+        }
+    }
+
+    async fn read_type(&mut self) -> crate::Result<TType> {
+        let name = self.read_string_bytes().await?;
+        type_from_name(&name)
+    }
+
+    async fn read_size(&mut self) -> crate::Result<i32> {
+        let size = self.read_integer().await?;
+        let size = i32::try_from(size).ok().filter(|&size| size >= 0).ok_or_else(|| new_protocol_error(
+            ProtocolErrorKind::NegativeSize,
+            format!("invalid size {}", size),
+        ))?;
+        if size as usize > self.max_message_size {
+            return Err(new_protocol_error(
+                ProtocolErrorKind::SizeLimit,
+                format!("received size {} larger than the maximum of {}", size, self.max_message_size),
+            ));
+        }
+        Ok(size)
+    }
+
+    async fn read_integer_as<I: TryFrom<i64>>(&mut self) -> crate::Result<I> {
+        let i = self.read_integer().await?;
+        I::try_from(i).map_err(|_| new_protocol_error(
+            ProtocolErrorKind::InvalidData,
+            format!("{} is out of range", i),
+        ))
+    }
+}
+
+impl<T> TAsyncInputProtocol for TAsyncJsonInputProtocol<T>
+    where
+        T: TAsyncReadTransport + std::marker::Send,
+{
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        TProtocolFuture::boxed(async move {
+            // a failed message may have left contexts behind
+            self.contexts.clear();
+            self.read_array_begin().await?;
+            let version = self.read_integer().await?;
+            if version != JSON_PROTOCOL_VERSION {
+                return Err(new_protocol_error(
+                    ProtocolErrorKind::BadVersion,
+                    format!("received bad version: {}", version),
+                ));
+            }
+            let name = String::from_utf8(self.read_string_bytes().await?)?;
+            let message_type = TMessageType::try_from(self.read_integer_as::<u8>().await?)?;
+            let sequence_number = self.read_integer_as().await?;
+            Ok(TMessageIdentifier::new(name, message_type, sequence_number))
+        })
+    }
+
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(self.read_array_end())
+    }
+
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        TProtocolFuture::boxed(async move {
+            self.read_object_begin().await?;
+            Ok(None)
+        })
+    }
+
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(self.read_object_end())
+    }
+
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        TProtocolFuture::boxed(async move {
+            // the end of the struct stands for the stop field
+            if self.peek_token().await? == b'}' {
+                return Ok(TFieldIdentifier::new::<Option<String>, String, i16>(None, TType::Stop, 0));
+            }
+            let id: i16 = self.read_integer_as().await?;
+            self.read_object_begin().await?;
+            let field_type = self.read_type().await?;
+            Ok(TFieldIdentifier::new::<Option<String>, String, i16>(None, field_type, id))
+        })
+    }
+
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(self.read_object_end())
+    }
+
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        TProtocolFuture::boxed(async move {
+            Ok(self.read_integer().await? != 0)
+        })
+    }
+
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        TProtocolFuture::boxed(async move {
+            let encoded = self.read_string_bytes().await?;
+            decode_base64(&encoded)
+        })
+    }
+
+    fn read_string_or_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        TProtocolFuture::boxed(async move {
+            self.read_string_bytes().await.map(Bytes::from)
+        })
+    }
+
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        TProtocolFuture::boxed(self.read_integer_as())
+    }
+
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        TProtocolFuture::boxed(self.read_integer_as())
+    }
+
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        TProtocolFuture::boxed(self.read_integer_as())
+    }
+
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        TProtocolFuture::boxed(self.read_integer())
+    }
+
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        TProtocolFuture::boxed(self.read_double_value())
+    }
+
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        TProtocolFuture::boxed(async move {
+            String::from_utf8(self.read_string_bytes().await?).map_err(From::from)
+        })
+    }
+
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        TProtocolFuture::boxed(async move {
+            self.read_array_begin().await?;
+            let element_type = self.read_type().await?;
+            let size = self.read_size().await?;
+            Ok(TListIdentifier::new(element_type, size))
+        })
+    }
+
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(self.read_array_end())
+    }
+
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        TProtocolFuture::boxed(async move {
+            self.read_array_begin().await?;
+            let element_type = self.read_type().await?;
+            let size = self.read_size().await?;
+            Ok(TSetIdentifier::new(element_type, size))
+        })
+    }
+
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(self.read_array_end())
+    }
+
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        TProtocolFuture::boxed(async move {
+            self.read_array_begin().await?;
+            let key_type = self.read_type().await?;
+            let value_type = self.read_type().await?;
+            let size = self.read_size().await?;
+            self.read_object_begin().await?;
+            Ok(TMapIdentifier::new(key_type, value_type, size))
+        })
+    }
+
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.read_object_end().await?;
+            self.read_array_end().await
+        })
+    }
+
+    // utility
+    //
+
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        TProtocolFuture::boxed(async move {
+            Ok(self.read_integer_as::<i8>().await? as u8)
+        })
+    }
+}
+
+impl<T> TAsyncJsonOutputProtocol<T>
+    where
+        T: TAsyncWriteTransport + std::marker::Send,
+{
+    // Write the tokens collected in the buffer, straight into the
+    // transport's buffer when it has room.
+    fn write_tokens(&mut self) -> TProtocolFuture<'_, ()> {
+        if self.transport.write_buffered(&self.buf) {
+            return TProtocolFuture::ready(Ok(()));
+        }
+        TProtocolFuture::boxed(async move {
+            write_all(&mut self.transport, &self.buf).await
+        })
+    }
+
+    // Start the tokens of a call with the separator before its first value.
+    fn begin_tokens(&mut self) -> bool {
+        self.buf.clear();
+        self.push_separator()
+    }
+
+    // Add the separator before the next value, and return whether the
+    // value is an object key.
+    fn push_separator(&mut self) -> bool {
+        let (separator, is_key) = next_value(&mut self.contexts);
+        if let Some(separator) = separator {
+            self.buf.push(separator);
+        }
+        is_key
+    }
+
+    fn push_object_begin(&mut self) {
+        self.buf.push(b'{');
+        self.contexts.push(TJsonContext::object());
+    }
+
+    fn push_array_begin(&mut self) {
+        self.buf.push(b'[');
+        self.contexts.push(TJsonContext::array());
+    }
+
+    fn push_end(&mut self, end: u8) {
+        self.contexts.pop();
+        self.buf.push(end);
+    }
+
+    // Add `i`, quoted if it is an object key.
+    fn push_integer(&mut self, i: i64, is_key: bool) {
+        if is_key {
+            self.buf.push(b'"');
+        }
+        self.buf.extend_from_slice(i.to_string().as_bytes());
+        if is_key {
+            self.buf.push(b'"');
+        }
+    }
+
+    fn push_string(&mut self, s: &[u8]) {
+        self.buf.reserve(s.len() + 2);
+        self.buf.push(b'"');
+        for &b in s {
+            match b {
+                b'"' => self.buf.extend_from_slice(b"\\\""),
+                b'\\' => self.buf.extend_from_slice(b"\\\\"),
+                0x08 => self.buf.extend_from_slice(b"\\b"),
+                0x0C => self.buf.extend_from_slice(b"\\f"),
+                b'\n' => self.buf.extend_from_slice(b"\\n"),
+                b'\r' => self.buf.extend_from_slice(b"\\r"),
+                b'\t' => self.buf.extend_from_slice(b"\\t"),
+                b if b < 0x20 => self.buf.extend_from_slice(format!("\\u{:04x}", b).as_bytes()),
+                b => self.buf.push(b),
+            }
+        }
+        self.buf.push(b'"');
+    }
+
+    fn write_integer(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        let is_key = self.begin_tokens();
+        self.push_integer(i, is_key);
+        self.write_tokens()
+    }
+
+    fn write_end(&mut self, end: u8) -> TProtocolFuture<'_, ()> {
+        self.buf.clear();
+        self.push_end(end);
+        self.write_tokens()
+    }
+
+    // Add the type names and size that start a list or a set.
+    fn push_collection_begin(&mut self, element_type: TType, size: i32) -> crate::Result<()> {
+        let element_type = type_name(element_type)?;
+        self.begin_tokens();
+        self.push_array_begin();
+        self.push_separator();
+        self.push_string(element_type);
+        let is_key = self.push_separator();
+        self.push_integer(i64::from(size), is_key);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> TAsyncOutputProtocol for TAsyncJsonOutputProtocol<T>
+    where
+        T: TAsyncWriteTransport + std::marker::Send,
+{
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        self.begin_tokens();
+        self.push_array_begin();
+        self.push_separator();
+        self.push_integer(JSON_PROTOCOL_VERSION, false);
+        self.push_separator();
+        self.push_string(identifier.name.as_bytes());
+        self.push_separator();
+        self.push_integer(i64::from(u8::from(identifier.message_type)), false);
+        self.push_separator();
+        self.push_integer(i64::from(identifier.sequence_number), false);
+        self.write_tokens()
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.write_end(b']')
+    }
+
+    fn write_struct_begin<'a>(&'a mut self, _identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        self.begin_tokens();
+        self.push_object_begin();
+        self.write_tokens()
+    }
+
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.write_end(b'}')
+    }
+
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        let field_id = match identifier.id {
+            Some(field_id) => field_id,
+            None if identifier.field_type == TType::Stop => return self.write_field_stop(),
+            None => return TProtocolFuture::ready(Err(new_protocol_error(
+                ProtocolErrorKind::Unknown,
+                format!("cannot write identifier {:?} without sequence number", identifier),
+            ))),
+        };
+        let field_type = match type_name(identifier.field_type) {
+            Ok(field_type) => field_type,
+            Err(e) => return TProtocolFuture::ready(Err(e)),
+        };
+
+        let is_key = self.begin_tokens();
+        self.push_integer(i64::from(field_id), is_key);
+        self.push_separator();
+        self.push_object_begin();
+        self.push_separator();
+        self.push_string(field_type);
+        self.write_tokens()
+    }
+
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.write_end(b'}')
+    }
+
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        self.write_integer(i64::from(b))
+    }
+
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        self.begin_tokens();
+        self.buf.push(b'"');
+        encode_base64(b, &mut self.buf);
+        self.buf.push(b'"');
+        self.write_tokens()
+    }
+
+    fn write_string_or_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        match std::str::from_utf8(b) {
+            Ok(s) => self.write_string(s),
+            Err(_) => self.write_bytes(b),
+        }
+    }
+
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.write_integer(i64::from(i))
+    }
+
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.write_integer(i64::from(i))
+    }
+
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.write_integer(i64::from(i))
+    }
+
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.write_integer(i)
+    }
+
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        let is_key = self.begin_tokens();
+        // the shortest representation that reads back as `d`
+        let number = format!("{:?}", d);
+        if is_key || !d.is_finite() {
+            let special = if d.is_nan() {
+                "NaN"
+            } else if d == f64::INFINITY {
+                "Infinity"
+            } else if d == f64::NEG_INFINITY {
+                "-Infinity"
+            } else {
+                &number
+            };
+            self.push_string(special.as_bytes());
+        } else {
+            self.buf.extend_from_slice(number.as_bytes());
+        }
+        self.write_tokens()
+    }
+
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        self.begin_tokens();
+        self.push_string(s.as_bytes());
+        self.write_tokens()
+    }
+
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        match self.push_collection_begin(identifier.element_type, identifier.size) {
+            Ok(()) => self.write_tokens(),
+            Err(e) => TProtocolFuture::ready(Err(e)),
+        }
+    }
+
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.write_end(b']')
+    }
+
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        match self.push_collection_begin(identifier.element_type, identifier.size) {
+            Ok(()) => self.write_tokens(),
+            Err(e) => TProtocolFuture::ready(Err(e)),
+        }
+    }
+
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.write_end(b']')
+    }
+
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        // empty maps read from the compact protocol have no key and value
+        // types, which the JSON protocol cannot leave out
+        let known = |field_type: Option<TType>| match field_type {
+            None | Some(TType::Stop) if identifier.size == 0 => Some(TType::I08),
+            field_type => field_type,
+        };
+        let types = match (known(identifier.key_type), known(identifier.value_type)) {
+            (Some(key_type), Some(value_type)) => type_name(key_type).and_then(|k| Ok((k, type_name(value_type)?))),
+            _ => Err(new_protocol_error(
+                ProtocolErrorKind::InvalidData,
+                format!("cannot write map {:?} without key and value types", identifier),
+            )),
+        };
+        let (key_type, value_type) = match types {
+            Ok(types) => types,
+            Err(e) => return TProtocolFuture::ready(Err(e)),
+        };
+
+        self.begin_tokens();
+        self.push_array_begin();
+        self.push_separator();
+        self.push_string(key_type);
+        self.push_separator();
+        self.push_string(value_type);
+        self.push_separator();
+        self.push_integer(i64::from(identifier.size), false);
+        self.push_separator();
+        self.push_object_begin();
+        self.write_tokens()
+    }
+
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.buf.clear();
+        self.push_end(b'}');
+        self.push_end(b']');
+        self.write_tokens()
+    }
+
+    async fn flush(&mut self) -> crate::Result<()> {
+        self.transport.flush().await.map_err(From::from)
+    }
+
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.transport.flush_coalesced().await.map_err(From::from)
+    }
+
+    // utility
+    //
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        self.write_integer(i64::from(b as i8))
+    }
+}
+
+fn type_name(field_type: TType) -> crate::Result<&'static [u8]> {
+    match field_type {
+        TType::Bool => Ok(b"tf"),
+        TType::I08 => Ok(b"i8"),
+        TType::I16 => Ok(b"i16"),
+        TType::I32 => Ok(b"i32"),
+        TType::I64 => Ok(b"i64"),
+        TType::Double => Ok(b"dbl"),
+        TType::String | TType::Utf8 => Ok(b"str"),
+        TType::Struct => Ok(b"rec"),
+        TType::Map => Ok(b"map"),
+        TType::Set => Ok(b"set"),
+        TType::List => Ok(b"lst"),
+        unkn => Err(new_protocol_error(
+            ProtocolErrorKind::InvalidData,
+            format!("cannot convert {:?} to a JSON type name", unkn),
+        )),
+    }
+}
+
+fn type_from_name(name: &[u8]) -> crate::Result<TType> {
+    match name {
+        b"tf" => Ok(TType::Bool),
+        b"i8" => Ok(TType::I08),
+        b"i16" => Ok(TType::I16),
+        b"i32" => Ok(TType::I32),
+        b"i64" => Ok(TType::I64),
+        b"dbl" => Ok(TType::Double),
+        b"str" => Ok(TType::String),
+        b"rec" => Ok(TType::Struct),
+        b"map" => Ok(TType::Map),
+        b"set" => Ok(TType::Set),
+        b"lst" => Ok(TType::List),
+        unkn => Err(new_protocol_error(
+            ProtocolErrorKind::InvalidData,
+            format!("unknown JSON type name {:?}", String::from_utf8_lossy(unkn)),
+        )),
+    }
+}
+
+// Append `bytes` in padded base64 to `buf`.
+fn encode_base64(bytes: &[u8], buf: &mut Vec<u8>) {
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                buf.push(BASE64_ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3F]);
+            } else {
+                buf.push(b'=');
+            }
+        }
+    }
+}
+
+// Decode base64 with or without padding.
+fn decode_base64(encoded: &[u8]) -> crate::Result<Vec<u8>> {
+    let encoded = encoded
+        .strip_suffix(b"==")
+        .or_else(|| encoded.strip_suffix(b"="))
+        .unwrap_or(encoded);
+    if encoded.len() % 4 == 1 {
+        return Err(new_protocol_error(ProtocolErrorKind::InvalidData, "truncated base64 value"));
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3 + 2);
+    for chunk in encoded.chunks(4) {
+        let mut n = 0u32;
+        for (i, &b) in chunk.iter().enumerate() {
+            let digit = BASE64_ALPHABET.iter().position(|&a| a == b).ok_or_else(|| new_protocol_error(
+                ProtocolErrorKind::InvalidData,
+                format!("invalid base64 character {:?}", char::from(b)),
+            ))?;
+            n |= (digit as u32) << (18 - 6 * i);
+        }
+        bytes.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use async_std::task::block_on;
+    use ordered_float::OrderedFloat;
+
+    use crate::protocol::async_value::{read_message, read_value, write_message, write_value, TValue};
+    use crate::transport::async_mem::TAsyncBufferChannel;
+
+    use super::*;
+
+    fn write_json(value: &TValue) -> String {
+        let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+        block_on(write_value(&mut o_prot, value)).unwrap();
+        String::from_utf8(o_prot.transport.take_write_bytes()).unwrap()
+    }
+
+    fn read_json(json: &str, field_type: TType) -> crate::Result<TValue> {
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(json.as_bytes().to_vec()));
+        let value = block_on(read_value(&mut i_prot, field_type))?;
+        assert_eq!(i_prot.transport.remaining(), 0);
+        Ok(value)
+    }
+
+    #[test]
+    fn must_write_message_as_other_libraries_do() {
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::I32(5));
+        fields.insert(2, TValue::String("a\"b\n\u{1}".to_owned()));
+        fields.insert(3, TValue::Map {
+            key_type: Some(TType::I32),
+            value_type: Some(TType::Double),
+            entries: vec![(TValue::I32(1), TValue::Double(OrderedFloat::from(0.5)))],
+        });
+        fields.insert(4, TValue::List { element_type: TType::Bool, elements: vec![TValue::Bool(true)] });
+        fields.insert(5, TValue::Binary(vec![0xFF, 0x00]));
+        let message_ident = TMessageIdentifier::new("ping", TMessageType::Call, 7);
+
+        let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+        block_on(write_message(&mut o_prot, &message_ident, &TValue::Struct(fields))).unwrap();
+
+        assert_eq!(
+            String::from_utf8(o_prot.transport.take_write_bytes()).unwrap(),
+            concat!(
+                r#"[1,"ping",1,7,{"1":{"i32":5},"2":{"str":"a\"b\n\u0001"},"#,
+                r#""3":{"map":["i32","dbl",1,{"1":0.5}]},"4":{"lst":["tf",1,1]},"5":{"str":"/wA="}}]"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn must_read_message_with_whitespace_and_escapes() {
+        let json = r#" [1, "ping", 1, 7, { "1" : { "str" : "\u00e9\ud83d\ude00\/ü" }, "2": {"set": ["i8", 2, 1, -1]} } ] "#;
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(json.as_bytes().to_vec()));
+        let (message_ident, body) = block_on(read_message(&mut i_prot)).unwrap();
+
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::String("é😀/ü".to_owned()));
+        fields.insert(2, TValue::Set { element_type: TType::I08, elements: vec![TValue::I8(1), TValue::I8(-1)] });
+        assert_eq!(message_ident, TMessageIdentifier::new("ping", TMessageType::Call, 7));
+        assert_eq!(body, TValue::Struct(fields));
+    }
+
+    #[test]
+    fn must_round_trip_special_doubles() {
+        let doubles = [f64::INFINITY, f64::NEG_INFINITY, 1e300, -0.0, 0.1];
+        let value = TValue::Map {
+            key_type: Some(TType::Double),
+            value_type: Some(TType::List),
+            entries: vec![(
+                TValue::Double(OrderedFloat::from(2.5)),
+                TValue::List {
+                    element_type: TType::Double,
+                    elements: doubles.iter().map(|&d| TValue::Double(OrderedFloat::from(d))).collect(),
+                },
+            )],
+        };
+
+        let json = write_json(&value);
+        assert_eq!(json, r#"["dbl","lst",1,{"2.5":["dbl",5,"Infinity","-Infinity",1e300,-0.0,0.1]}]"#);
+        assert_eq!(read_json(&json, TType::Map).unwrap(), value);
+
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(br#""NaN""#.to_vec()));
+        assert!(block_on(i_prot.read_double()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn must_read_base64_with_and_without_padding() {
+        for len in 0..5 {
+            let bytes: Vec<u8> = (0..len).map(|i| 0xFC + i as u8).collect();
+            let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+            block_on(o_prot.write_bytes(&bytes)).unwrap();
+            let padded = o_prot.transport.take_write_bytes();
+            assert_eq!(padded.len() % 4, 2);
+
+            let unpadded: Vec<u8> = padded.iter().cloned().filter(|&b| b != b'=').collect();
+            for encoded in &[padded, unpadded] {
+                let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(encoded.clone()));
+                assert_eq!(block_on(i_prot.read_bytes()).unwrap(), bytes);
+            }
+        }
+
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(br#""A=B=""#.to_vec()));
+        assert!(block_on(i_prot.read_bytes()).is_err());
+    }
+
+    #[test]
+    fn must_skip_strings_that_are_not_base64() {
+        let json = r#"{"1":{"str":"not base64 ü"},"2":{"rec":{"1":{"map":["str","str",1,{"k":"v"}]}}}}"#;
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(json.as_bytes().to_vec()));
+        block_on(i_prot.skip(TType::Struct)).unwrap();
+        assert_eq!(i_prot.transport.remaining(), 0);
+    }
+
+    #[test]
+    fn must_reject_bad_version() {
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(br#"[2,"ping",1,7,{}]"#.to_vec()));
+        match block_on(i_prot.read_message_begin()) {
+            Err(crate::Error::Protocol(e)) => assert_eq!(e.kind, ProtocolErrorKind::BadVersion),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    fn must_reject_containers_over_max_message_size() {
+        let input = |json: &[u8]| {
+            TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(json.to_vec())).with_max_message_size(2)
+        };
+        assert_eq!(block_on(input(br#"["i32",2,1,2]"#).read_list_begin()).unwrap().size, 2);
+        for res in [
+            block_on(input(br#"["i32",3,1,2,3]"#).read_list_begin()).map(|_| ()),
+            block_on(input(br#"["i32",2147483647]"#).read_set_begin()).map(|_| ()),
+            block_on(input(br#"["i32","i32",3,{}]"#).read_map_begin()).map(|_| ()),
+        ].iter() {
+            match res {
+                Err(crate::Error::Protocol(e)) => assert_eq!(e.kind, ProtocolErrorKind::SizeLimit),
+                res => panic!("unexpected result {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn must_write_empty_map_without_types() {
+        // as read from the compact protocol
+        let value = TValue::Map { key_type: None, value_type: None, entries: vec![] };
+        assert_eq!(write_json(&value), r#"["i8","i8",0,{}]"#);
+
+        let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+        let map_ident = TMapIdentifier::new(None, TType::I32, 1);
+        assert!(block_on(o_prot.write_map_begin(&map_ident)).is_err());
+    }
+}
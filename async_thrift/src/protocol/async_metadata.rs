@@ -0,0 +1,771 @@
+//! Field-based request metadata.
+//!
+//! Thrift messages have no header section, so request metadata such as
+//! trace context travels as an extra `map<string, string>` field with id
+//! `METADATA_FIELD_ID` at the end of the call's argument struct.
+//! `TAsyncMetadataOutputProtocol` appends that field on the client and
+//! `TAsyncMetadataInputProtocol` strips it on the server. Receivers that
+//! do not use the wrapper skip the field like any other unknown field, so
+//! clients and servers can be upgraded independently.
+//!
+//! Call deadlines travel the same way: a call made inside `with_deadline`
+//! carries the time it has left under `DEADLINE_KEY`, and the server makes
+//! the resulting deadline available through `current_deadline`.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use async_std::task_local;
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{ApplicationError, ApplicationErrorKind};
+
+use super::{TAsyncInputProtocol, TAsyncOutputProtocol, TProtocolFuture};
+use super::{
+    TFieldIdentifier, TListIdentifier,
+    TMapIdentifier, TMessageIdentifier,
+    TMessageType, TSetIdentifier,
+    TStructIdentifier, TType,
+};
+
+/// Id of the argument struct field carrying request metadata.
+pub const METADATA_FIELD_ID: i16 = i16::MAX;
+
+/// Metadata key holding the id of the trace a request belongs to.
+pub const TRACE_ID_KEY: &str = "trace-id";
+
+/// Metadata key holding the id of the caller's span.
+pub const SPAN_ID_KEY: &str = "span-id";
+
+/// Metadata key holding the number of milliseconds the caller is willing to
+/// wait for the reply, measured when the request was sent.
+pub const DEADLINE_KEY: &str = "deadline-ms";
+
+/// Key/value metadata sent along with a request.
+pub type TRequestMetadata = BTreeMap<String, String>;
+
+/// Callback that adds per-call entries to the outgoing metadata.
+pub type TMetadataInjector = Box<dyn Fn(&TMessageIdentifier, &mut TRequestMetadata) + Send + Sync>;
+
+task_local! {
+    static CURRENT_METADATA: RefCell<TRequestMetadata> = RefCell::new(BTreeMap::new());
+    static REQUEST_DEADLINE: Cell<Option<Instant>> = Cell::new(None);
+}
+
+thread_local! {
+    // deadline of the `with_deadline` scope being polled on this thread
+    static SCOPED_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Run `future` with `deadline` as the deadline of every call it makes.
+///
+/// Calls written through a `TAsyncMetadataOutputProtocol` while `future`
+/// runs send the time left until `deadline` with their metadata. The
+/// deadline is only advertised: `future` is not cancelled when it passes.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use async_thrift::protocol::async_metadata::{current_deadline, with_deadline};
+///
+/// # async_std::task::block_on(async {
+/// let deadline = Instant::now() + Duration::from_millis(200);
+/// let seen = with_deadline(deadline, async { current_deadline() }).await;
+/// assert_eq!(seen, Some(deadline));
+/// assert_eq!(current_deadline(), None);
+/// # });
+/// ```
+pub fn with_deadline<F>(deadline: Instant, future: F) -> TDeadlineScope<F>
+    where
+        F: Future,
+{
+    TDeadlineScope {
+        deadline,
+        future: Box::pin(future),
+    }
+}
+
+/// Future returned by `with_deadline`.
+pub struct TDeadlineScope<F> {
+    deadline: Instant,
+    future: Pin<Box<F>>,
+}
+
+impl<F> Future for TDeadlineScope<F>
+    where
+        F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // an enclosing scope with an earlier deadline still applies
+        let deadline = match SCOPED_DEADLINE.with(Cell::get) {
+            Some(outer) if outer < self.deadline => outer,
+            _ => self.deadline,
+        };
+        let outer = SCOPED_DEADLINE.with(|scoped| scoped.replace(Some(deadline)));
+        let res = self.future.as_mut().poll(cx);
+        SCOPED_DEADLINE.with(|scoped| scoped.set(outer));
+        res
+    }
+}
+
+impl<F> fmt::Debug for TDeadlineScope<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TDeadlineScope")
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+/// Return the deadline of the code currently running, if any.
+///
+/// This is the earlier of the deadline set with `with_deadline` and the
+/// deadline sent by the caller of the request this task is processing.
+/// Handlers can use it to give up on work the caller no longer waits for,
+/// and calls they make to other services inherit it.
+pub fn current_deadline() -> Option<Instant> {
+    let scoped = SCOPED_DEADLINE.with(Cell::get);
+    let request = REQUEST_DEADLINE.try_with(Cell::get).unwrap_or(None);
+    match (scoped, request) {
+        (Some(scoped), Some(request)) => Some(scoped.min(request)),
+        (scoped, request) => scoped.or(request),
+    }
+}
+
+fn publish_request_deadline(deadline: Option<Instant>) {
+    let _ = REQUEST_DEADLINE.try_with(|current| current.set(deadline));
+}
+
+/// Return the metadata of the request currently being processed by this
+/// task.
+///
+/// The metadata is published by `TAsyncMetadataInputProtocol` while it
+/// reads the request, so handlers invoked by a processor reading from such
+/// a protocol can call this to inspect it. Return an empty map outside a
+/// task or when the request carried no metadata.
+pub fn current_request_metadata() -> TRequestMetadata {
+    CURRENT_METADATA
+        .try_with(|metadata| metadata.borrow().clone())
+        .unwrap_or_default()
+}
+
+fn publish_request_metadata(metadata: &TRequestMetadata) {
+    let _ = CURRENT_METADATA.try_with(|current| {
+        let mut current = current.borrow_mut();
+        current.clone_from(metadata);
+    });
+}
+
+/// Output protocol wrapper that appends request metadata to every call.
+///
+/// The metadata for a call is the map set with `set_metadata` plus any
+/// entries added by the injector and, when the call is made with a
+/// `current_deadline`, the time left under `DEADLINE_KEY`. Nothing is
+/// written when the resulting map is empty, and replies are never modified.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_thrift::protocol::async_binary::TAsyncBinaryOutputProtocol;
+/// use async_thrift::protocol::async_metadata::{TAsyncMetadataOutputProtocol, TRACE_ID_KEY};
+/// use async_thrift::transport::async_mem::TAsyncBufferChannel;
+///
+/// let o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+/// let mut o_prot = TAsyncMetadataOutputProtocol::new(o_prot)
+///     .with_injector(Box::new(|_, metadata| {
+///         metadata.insert(TRACE_ID_KEY.to_owned(), "4bf92f3577b34da6".to_owned());
+///     }));
+/// o_prot.metadata_mut().insert("tenant".to_owned(), "blue".to_owned());
+/// ```
+pub struct TAsyncMetadataOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol,
+{
+    inner: P,
+    metadata: TRequestMetadata,
+    injector: Option<TMetadataInjector>,
+    // metadata still to be written for the current call
+    pending: Option<TRequestMetadata>,
+    depth: usize,
+}
+
+impl<P> TAsyncMetadataOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol,
+{
+    /// Create a `TAsyncMetadataOutputProtocol` that writes to `inner`.
+    pub fn new(inner: P) -> TAsyncMetadataOutputProtocol<P> {
+        TAsyncMetadataOutputProtocol {
+            inner,
+            metadata: TRequestMetadata::new(),
+            injector: None,
+            pending: None,
+            depth: 0,
+        }
+    }
+
+    /// Call `injector` at the start of every call to add per-call entries.
+    pub fn with_injector(mut self, injector: TMetadataInjector) -> TAsyncMetadataOutputProtocol<P> {
+        self.injector = Some(injector);
+        self
+    }
+
+    /// Metadata sent with every call.
+    pub fn metadata(&self) -> &TRequestMetadata {
+        &self.metadata
+    }
+
+    /// Mutable access to the metadata sent with every call.
+    pub fn metadata_mut(&mut self) -> &mut TRequestMetadata {
+        &mut self.metadata
+    }
+
+    /// Replace the metadata sent with every call.
+    pub fn set_metadata(&mut self, metadata: TRequestMetadata) {
+        self.metadata = metadata;
+    }
+
+    /// Return the wrapped protocol.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    async fn write_metadata_field(&mut self, metadata: &TRequestMetadata) -> crate::Result<()> {
+        self.inner
+            .write_field_begin(&TFieldIdentifier::new("metadata", TType::Map, METADATA_FIELD_ID))
+            .await?;
+        self.inner
+            .write_map_begin(&TMapIdentifier::new(TType::String, TType::String, metadata.len() as i32))
+            .await?;
+        for (key, value) in metadata {
+            self.inner.write_string(key).await?;
+            self.inner.write_string(value).await?;
+        }
+        self.inner.write_map_end().await?;
+        self.inner.write_field_end().await
+    }
+}
+
+impl<P> fmt::Debug for TAsyncMetadataOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TAsyncMetadataOutputProtocol")
+            .field("metadata", &self.metadata)
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<P> TAsyncOutputProtocol for TAsyncMetadataOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol + Send,
+{
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        self.depth = 0;
+        self.pending = match identifier.message_type {
+            TMessageType::Call | TMessageType::OneWay => {
+                let mut metadata = self.metadata.clone();
+                if let Some(ref injector) = self.injector {
+                    injector(identifier, &mut metadata);
+                }
+                if let Some(deadline) = current_deadline() {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    metadata.insert(DEADLINE_KEY.to_owned(), remaining.as_millis().to_string());
+                }
+                if metadata.is_empty() { None } else { Some(metadata) }
+            }
+            _ => None,
+        };
+        self.inner.write_message_begin(identifier)
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.pending = None;
+        self.inner.write_message_end()
+    }
+
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        self.depth += 1;
+        self.inner.write_struct_begin(identifier)
+    }
+
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.depth = self.depth.saturating_sub(1);
+        self.inner.write_struct_end()
+    }
+
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_field_begin(identifier)
+    }
+
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_field_end()
+    }
+
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        // the argument struct is the outermost struct of a call
+        if self.depth != 1 {
+            return self.inner.write_field_stop();
+        }
+        match self.pending.take() {
+            Some(metadata) => TProtocolFuture::boxed(async move {
+                self.write_metadata_field(&metadata).await?;
+                self.inner.write_field_stop().await
+            }),
+            None => self.inner.write_field_stop(),
+        }
+    }
+
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        self.inner.write_bool(b)
+    }
+
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        self.inner.write_bytes(b)
+    }
+
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.inner.write_binary(b)
+    }
+
+    fn write_string_or_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.inner.write_string_or_binary(b)
+    }
+
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i8(i)
+    }
+
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i16(i)
+    }
+
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i32(i)
+    }
+
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i64(i)
+    }
+
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        self.inner.write_double(d)
+    }
+
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        self.inner.write_string(s)
+    }
+
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_list_begin(identifier)
+    }
+
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_list_end()
+    }
+
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_set_begin(identifier)
+    }
+
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_set_end()
+    }
+
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_map_begin(identifier)
+    }
+
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_map_end()
+    }
+
+    async fn flush(&mut self) -> crate::Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.inner.flush_coalesced().await
+    }
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_byte(b)
+    }
+}
+
+/// Input protocol wrapper that extracts request metadata written by
+/// `TAsyncMetadataOutputProtocol`.
+///
+/// The metadata field is consumed by the wrapper, so processors never see
+/// it. The metadata of the message being read is available through
+/// `metadata` and, inside the reading task, through
+/// `current_request_metadata`. With the `tracing` feature enabled, the
+/// `trace_id` and `parent_span_id` fields of the current span are filled
+/// from `TRACE_ID_KEY` and `SPAN_ID_KEY`.
+///
+/// A deadline sent under `DEADLINE_KEY` is made available through
+/// `current_deadline` once the call's arguments have been read. If it has
+/// already passed by then, reading the arguments fails with an
+/// `ApplicationError` so that the handler is not run.
+pub struct TAsyncMetadataInputProtocol<P>
+    where
+        P: TAsyncInputProtocol,
+{
+    inner: P,
+    metadata: TRequestMetadata,
+    deadline: Option<Instant>,
+    message_name: String,
+    depth: usize,
+}
+
+impl<P> TAsyncMetadataInputProtocol<P>
+    where
+        P: TAsyncInputProtocol,
+{
+    /// Create a `TAsyncMetadataInputProtocol` that reads from `inner`.
+    pub fn new(inner: P) -> TAsyncMetadataInputProtocol<P> {
+        TAsyncMetadataInputProtocol {
+            inner,
+            metadata: TRequestMetadata::new(),
+            deadline: None,
+            message_name: String::new(),
+            depth: 0,
+        }
+    }
+
+    /// Metadata of the message being read.
+    pub fn metadata(&self) -> &TRequestMetadata {
+        &self.metadata
+    }
+
+    /// Deadline sent with the message being read.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Return the wrapped protocol.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    async fn read_metadata_field(&mut self) -> crate::Result<()> {
+        let map_ident = self.inner.read_map_begin().await?;
+        let key_type = map_ident.key_type.unwrap_or(TType::String);
+        let value_type = map_ident.value_type.unwrap_or(TType::String);
+        for _ in 0..map_ident.size {
+            if key_type == TType::String && value_type == TType::String {
+                let key = self.inner.read_string().await?;
+                let value = self.inner.read_string().await?;
+                self.metadata.insert(key, value);
+            } else {
+                self.inner.skip(key_type).await?;
+                self.inner.skip(value_type).await?;
+            }
+        }
+        self.inner.read_map_end().await?;
+        self.inner.read_field_end().await?;
+
+        self.deadline = self
+            .metadata
+            .get(DEADLINE_KEY)
+            .and_then(|remaining| remaining.parse().ok())
+            .map(|remaining| Instant::now() + Duration::from_millis(remaining));
+        publish_request_metadata(&self.metadata);
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            if let Some(trace_id) = self.metadata.get(TRACE_ID_KEY) {
+                span.record("trace_id", trace_id.as_str());
+            }
+            if let Some(span_id) = self.metadata.get(SPAN_ID_KEY) {
+                span.record("parent_span_id", span_id.as_str());
+            }
+        }
+        Ok(())
+    }
+
+    /// publish the deadline of a call whose arguments have been read, or
+    /// reject the call if the deadline has passed
+    fn start_deadline(&self) -> crate::Result<()> {
+        publish_request_deadline(self.deadline);
+        match self.deadline {
+            Some(deadline) if deadline <= Instant::now() => Err(crate::Error::Application(ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                format!("deadline of call to {} expired before it was processed", self.message_name),
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<P> fmt::Debug for TAsyncMetadataInputProtocol<P>
+    where
+        P: TAsyncInputProtocol,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TAsyncMetadataInputProtocol")
+            .field("metadata", &self.metadata)
+            .field("deadline", &self.deadline)
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+impl<P> TAsyncInputProtocol for TAsyncMetadataInputProtocol<P>
+    where
+        P: TAsyncInputProtocol + Send,
+{
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let message_ident = self.inner.read_message_begin().await?;
+            self.depth = 0;
+            if !self.metadata.is_empty() {
+                self.metadata.clear();
+                publish_request_metadata(&self.metadata);
+            }
+            if self.deadline.take().is_some() {
+                publish_request_deadline(None);
+            }
+            self.message_name.clone_from(&message_ident.name);
+            Ok(message_ident)
+        })
+    }
+
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_message_end()
+    }
+
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        self.depth += 1;
+        self.inner.read_struct_begin()
+    }
+
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        if self.depth > 1 {
+            self.depth -= 1;
+            return self.inner.read_struct_end();
+        }
+        TProtocolFuture::boxed(async move {
+            self.inner.read_struct_end().await?;
+            self.depth = self.depth.saturating_sub(1);
+            // the arguments have been read once the outermost struct ends
+            if self.depth == 0 && self.deadline.is_some() {
+                self.start_deadline()?;
+            }
+            Ok(())
+        })
+    }
+
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        // the metadata field is a field of the argument struct
+        if self.depth != 1 {
+            return self.inner.read_field_begin();
+        }
+        TProtocolFuture::boxed(async move {
+            loop {
+                let field_ident = self.inner.read_field_begin().await?;
+                if self.depth == 1
+                    && field_ident.id == Some(METADATA_FIELD_ID)
+                    && field_ident.field_type == TType::Map
+                {
+                    self.read_metadata_field().await?;
+                    continue;
+                }
+                return Ok(field_ident);
+            }
+        })
+    }
+
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_field_end()
+    }
+
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        self.inner.read_bool()
+    }
+
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        self.inner.read_bytes()
+    }
+
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.inner.read_binary()
+    }
+
+    fn read_string_or_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.inner.read_string_or_binary()
+    }
+
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        self.inner.read_i8()
+    }
+
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        self.inner.read_i16()
+    }
+
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        self.inner.read_i32()
+    }
+
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        self.inner.read_i64()
+    }
+
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        self.inner.read_double()
+    }
+
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        self.inner.read_string()
+    }
+
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        self.inner.read_list_begin()
+    }
+
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_list_end()
+    }
+
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        self.inner.read_set_begin()
+    }
+
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_set_end()
+    }
+
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        self.inner.read_map_begin()
+    }
+
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_map_end()
+    }
+
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        self.inner.read_byte()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task;
+
+    use crate::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+    use crate::transport::async_mem::TAsyncBufferChannel;
+
+    use super::*;
+
+    /// Write a message whose argument struct holds `seqid`.
+    async fn write_message<P>(o_prot: &mut TAsyncMetadataOutputProtocol<P>, message_type: TMessageType, seqid: i32) -> crate::Result<()>
+        where
+            P: TAsyncOutputProtocol + Send,
+    {
+        o_prot.write_message_begin(&TMessageIdentifier::new("ping", message_type, seqid)).await?;
+        o_prot.write_struct_begin(&TStructIdentifier::new("args")).await?;
+        o_prot.write_field_begin(&TFieldIdentifier::new("value", TType::I32, 1)).await?;
+        o_prot.write_i32(seqid).await?;
+        o_prot.write_field_end().await?;
+        o_prot.write_field_stop().await?;
+        o_prot.write_struct_end().await?;
+        o_prot.write_message_end().await
+    }
+
+    fn encoded_messages(
+        o_prot: TAsyncMetadataOutputProtocol<TAsyncBinaryOutputProtocol<TAsyncBufferChannel>>,
+    ) -> TAsyncMetadataInputProtocol<TAsyncBinaryInputProtocol<TAsyncBufferChannel>> {
+        let bytes = o_prot.into_inner().transport.take_write_bytes();
+        TAsyncMetadataInputProtocol::new(TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true))
+    }
+
+    /// Read a message written by `write_message`, returning its value.
+    async fn read_message<P>(i_prot: &mut TAsyncMetadataInputProtocol<P>) -> crate::Result<i32>
+        where
+            P: TAsyncInputProtocol + Send,
+    {
+        i_prot.read_message_begin().await?;
+        i_prot.read_struct_begin().await?;
+        let field_ident = i_prot.read_field_begin().await?;
+        assert_eq!(field_ident.id, Some(1));
+        let value = i_prot.read_i32().await?;
+        i_prot.read_field_end().await?;
+        // the metadata field is consumed by the wrapper
+        assert_eq!(i_prot.read_field_begin().await?.field_type, TType::Stop);
+        i_prot.read_struct_end().await?;
+        i_prot.read_message_end().await?;
+        Ok(value)
+    }
+
+    fn new_output_protocol() -> TAsyncMetadataOutputProtocol<TAsyncBinaryOutputProtocol<TAsyncBufferChannel>> {
+        TAsyncMetadataOutputProtocol::new(TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true))
+    }
+
+    #[test]
+    fn must_round_trip_metadata_and_trace_context() {
+        let mut o_prot = new_output_protocol().with_injector(Box::new(|message_ident, metadata| {
+            metadata.insert(TRACE_ID_KEY.to_owned(), "4bf92f3577b34da6".to_owned());
+            metadata.insert(SPAN_ID_KEY.to_owned(), message_ident.sequence_number.to_string());
+        }));
+        o_prot.metadata_mut().insert("tenant".to_owned(), "blue".to_owned());
+        assert_success!(task::block_on(write_message(&mut o_prot, TMessageType::Call, 1)));
+        assert_success!(task::block_on(write_message(&mut o_prot, TMessageType::Reply, 2)));
+        let mut i_prot = encoded_messages(o_prot);
+
+        task::block_on(async {
+            assert_eq!(assert_success!(read_message(&mut i_prot).await), 1);
+            let mut expected = TRequestMetadata::new();
+            expected.insert("tenant".to_owned(), "blue".to_owned());
+            expected.insert(TRACE_ID_KEY.to_owned(), "4bf92f3577b34da6".to_owned());
+            expected.insert(SPAN_ID_KEY.to_owned(), "1".to_owned());
+            assert_eq!(i_prot.metadata(), &expected);
+            assert_eq!(current_request_metadata(), expected);
+
+            // replies carry no metadata
+            assert_eq!(assert_success!(read_message(&mut i_prot).await), 2);
+            assert!(i_prot.metadata().is_empty());
+            assert!(current_request_metadata().is_empty());
+        });
+    }
+
+    #[test]
+    fn must_propagate_deadline_with_call() {
+        let mut o_prot = new_output_protocol();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        assert_success!(task::block_on(with_deadline(deadline, write_message(&mut o_prot, TMessageType::Call, 1))));
+        let mut i_prot = encoded_messages(o_prot);
+
+        task::block_on(async {
+            assert_success!(read_message(&mut i_prot).await);
+            let received = i_prot.deadline().unwrap();
+            assert!(received <= Instant::now() + Duration::from_secs(5));
+            assert!(received > Instant::now() + Duration::from_secs(4));
+            assert_eq!(current_deadline(), Some(received));
+        });
+    }
+
+    #[test]
+    fn must_reject_call_whose_deadline_passed() {
+        let mut o_prot = new_output_protocol();
+        o_prot.metadata_mut().insert(DEADLINE_KEY.to_owned(), "0".to_owned());
+        assert_success!(task::block_on(write_message(&mut o_prot, TMessageType::Call, 1)));
+        let mut i_prot = encoded_messages(o_prot);
+
+        match task::block_on(read_message(&mut i_prot)) {
+            Err(crate::Error::Application(ref e)) => assert!(e.message.contains("ping")),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+}
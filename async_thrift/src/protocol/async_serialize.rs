@@ -0,0 +1,258 @@
+//! Serialization of single values to and from bytes.
+//!
+//! Values kept in a cache or a database are encoded on their own, without
+//! a message header and without a connection. `TSerializable` encodes a
+//! value into a `Vec<u8>` and decodes it from a byte slice with an
+//! in-memory channel, so no transport has to be assembled by hand.
+//!
+//! Generated structs already have `write_to_out_protocol` and
+//! `read_from_in_protocol` methods; `impl_serializable!` implements
+//! `TSerializable` for them by forwarding to these methods.
+
+use crate::errors::{new_protocol_error, ProtocolErrorKind};
+use crate::transport::async_mem::TAsyncBufferChannel;
+
+use super::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+use super::async_compact::{TAsyncCompactInputProtocol, TAsyncCompactOutputProtocol};
+use super::async_json::{TAsyncJsonInputProtocol, TAsyncJsonOutputProtocol};
+use super::{TAsyncInputProtocol, TAsyncOutputProtocol, TProtocolFuture};
+
+/// Protocol a value is serialized with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TProtocolKind {
+    /// The strict binary protocol.
+    Binary,
+    /// The compact protocol.
+    Compact,
+    /// The JSON protocol.
+    Json,
+}
+
+/// A value that can be serialized to bytes on its own.
+///
+/// Implement it for generated structs with `impl_serializable!`.
+///
+/// # Examples
+///
+/// ```
+/// use async_thrift::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
+/// use async_thrift::protocol::{TFieldIdentifier, TStructIdentifier, TType};
+/// use async_thrift::protocol::async_serialize::{TProtocolKind, TSerializable};
+///
+/// // as generated by the Thrift compiler
+/// #[derive(Debug, PartialEq)]
+/// struct Point {
+///     x: i32,
+/// }
+///
+/// impl Point {
+///     async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Point> {
+/// #       let mut x = 0;
+/// #       i_prot.read_struct_begin().await?;
+/// #       loop {
+/// #           let field_ident = i_prot.read_field_begin().await?;
+/// #           if field_ident.field_type == TType::Stop {
+/// #               break;
+/// #           }
+/// #           match field_ident.id {
+/// #               Some(1) => x = i_prot.read_i32().await?,
+/// #               _ => i_prot.skip(field_ident.field_type).await?,
+/// #           }
+/// #           i_prot.read_field_end().await?;
+/// #       }
+/// #       i_prot.read_struct_end().await?;
+/// #       Ok(Point { x })
+///         // ...
+///     }
+///
+///     async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+/// #       o_prot.write_struct_begin(&TStructIdentifier::new("Point")).await?;
+/// #       o_prot.write_field_begin(&TFieldIdentifier::new("x", TType::I32, 1)).await?;
+/// #       o_prot.write_i32(self.x).await?;
+/// #       o_prot.write_field_end().await?;
+/// #       o_prot.write_field_stop().await?;
+/// #       o_prot.write_struct_end().await
+///         // ...
+///     }
+/// }
+///
+/// async_thrift::impl_serializable!(Point);
+///
+/// # async_std::task::block_on(async {
+/// let bytes = Point { x: 7 }.to_bytes(TProtocolKind::Binary).await?;
+/// let point = Point::from_bytes(&bytes, TProtocolKind::Binary).await?;
+/// assert_eq!(point, Point { x: 7 });
+/// # Ok::<(), async_thrift::Error>(())
+/// # }).unwrap();
+/// ```
+pub trait TSerializable: Sized + Send + Sync {
+    /// Write the value to `o_prot`.
+    fn write_to_out_protocol<'a>(
+        &'a self,
+        o_prot: &'a mut (dyn TAsyncOutputProtocol + Send),
+    ) -> TProtocolFuture<'a, ()>;
+
+    /// Read a value from `i_prot`.
+    fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> TProtocolFuture<'_, Self>;
+
+    /// Encode the value with `protocol`.
+    fn to_bytes(&self, protocol: TProtocolKind) -> TProtocolFuture<'_, Vec<u8>> {
+        TProtocolFuture::boxed(async move {
+            match protocol {
+                TProtocolKind::Binary => {
+                    let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+                    self.write_to_out_protocol(&mut o_prot).await?;
+                    o_prot.flush().await?;
+                    Ok(o_prot.transport.take_write_bytes())
+                }
+                TProtocolKind::Compact => {
+                    let mut o_prot = TAsyncCompactOutputProtocol::new(TAsyncBufferChannel::new());
+                    self.write_to_out_protocol(&mut o_prot).await?;
+                    o_prot.flush().await?;
+                    Ok(o_prot.transport.take_write_bytes())
+                }
+                TProtocolKind::Json => {
+                    let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+                    self.write_to_out_protocol(&mut o_prot).await?;
+                    o_prot.flush().await?;
+                    Ok(o_prot.transport.take_write_bytes())
+                }
+            }
+        })
+    }
+
+    /// Decode a value encoded with `protocol` from all of `bytes`.
+    ///
+    /// Bytes left after the value are an error, since they mean `bytes`
+    /// does not hold a value of this type.
+    fn from_bytes(bytes: &[u8], protocol: TProtocolKind) -> TProtocolFuture<'static, Self> {
+        let channel = TAsyncBufferChannel::with_readable_bytes(bytes.to_vec());
+        TProtocolFuture::boxed(async move {
+            match protocol {
+                TProtocolKind::Binary => {
+                    let mut i_prot = TAsyncBinaryInputProtocol::new(channel, true);
+                    let value = Self::read_from_in_protocol(&mut i_prot).await?;
+                    check_consumed(&i_prot.transport)?;
+                    Ok(value)
+                }
+                TProtocolKind::Compact => {
+                    let mut i_prot = TAsyncCompactInputProtocol::new(channel);
+                    let value = Self::read_from_in_protocol(&mut i_prot).await?;
+                    check_consumed(&i_prot.transport)?;
+                    Ok(value)
+                }
+                TProtocolKind::Json => {
+                    let mut i_prot = TAsyncJsonInputProtocol::new(channel);
+                    let value = Self::read_from_in_protocol(&mut i_prot).await?;
+                    check_consumed(&i_prot.transport)?;
+                    Ok(value)
+                }
+            }
+        })
+    }
+}
+
+fn check_consumed(channel: &TAsyncBufferChannel) -> crate::Result<()> {
+    match channel.remaining() {
+        0 => Ok(()),
+        remaining => Err(new_protocol_error(
+            ProtocolErrorKind::InvalidData,
+            format!("{} bytes left after the serialized value", remaining),
+        )),
+    }
+}
+
+/// Implement `TSerializable` for generated types by forwarding to their
+/// `write_to_out_protocol` and `read_from_in_protocol` methods.
+///
+/// ```ignore
+/// async_thrift::impl_serializable!(Record, Item);
+/// ```
+#[macro_export]
+macro_rules! impl_serializable {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl $crate::protocol::async_serialize::TSerializable for $t {
+                fn write_to_out_protocol<'a>(
+                    &'a self,
+                    o_prot: &'a mut (dyn $crate::protocol::TAsyncOutputProtocol + Send),
+                ) -> $crate::protocol::TProtocolFuture<'a, ()> {
+                    $crate::protocol::TProtocolFuture::boxed(<$t>::write_to_out_protocol(self, o_prot))
+                }
+
+                fn read_from_in_protocol(
+                    i_prot: &mut (dyn $crate::protocol::TAsyncInputProtocol + Send),
+                ) -> $crate::protocol::TProtocolFuture<'_, Self> {
+                    $crate::protocol::TProtocolFuture::boxed(<$t>::read_from_in_protocol(i_prot))
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use async_std::task::block_on;
+
+    use crate::protocol::async_value::{read_value, write_value, TValue};
+    use crate::protocol::TType;
+
+    use super::*;
+
+    // stands in for a generated struct
+    #[derive(Debug, PartialEq)]
+    struct TRecord(TValue);
+
+    impl TRecord {
+        async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> crate::Result<TRecord> {
+            read_value(i_prot, TType::Struct).await.map(TRecord)
+        }
+
+        async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> crate::Result<()> {
+            write_value(o_prot, &self.0).await
+        }
+    }
+
+    crate::impl_serializable!(TRecord);
+
+    fn record() -> TRecord {
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::String("name".to_owned()));
+        fields.insert(2, TValue::I64(-7));
+        fields.insert(3, TValue::List { element_type: TType::I32, elements: vec![TValue::I32(1), TValue::I32(2)] });
+        TRecord(TValue::Struct(fields))
+    }
+
+    #[test]
+    fn must_round_trip_with_every_protocol() {
+        for &protocol in &[TProtocolKind::Binary, TProtocolKind::Compact, TProtocolKind::Json] {
+            let bytes = block_on(record().to_bytes(protocol)).unwrap();
+            assert_eq!(block_on(TRecord::from_bytes(&bytes, protocol)).unwrap(), record());
+        }
+    }
+
+    #[test]
+    fn must_write_compact_bytes() {
+        let bytes = block_on(record().to_bytes(TProtocolKind::Compact)).unwrap();
+        assert_eq!(bytes, vec![
+            0x18, 0x04, b'n', b'a', b'm', b'e',
+            0x16, 0x0D,
+            0x19, 0x25, 0x02, 0x04,
+            0x00,
+        ]);
+    }
+
+    #[test]
+    fn must_reject_trailing_bytes() {
+        for &protocol in &[TProtocolKind::Binary, TProtocolKind::Compact, TProtocolKind::Json] {
+            let mut bytes = block_on(record().to_bytes(protocol)).unwrap();
+            bytes.push(0);
+            match block_on(TRecord::from_bytes(&bytes, protocol)) {
+                Err(crate::Error::Protocol(e)) => assert_eq!(e.kind, ProtocolErrorKind::InvalidData),
+                res => panic!("unexpected result {:?}", res),
+            }
+        }
+    }
+}
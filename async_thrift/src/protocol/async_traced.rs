@@ -0,0 +1,322 @@
+//! Client call spans for the `tracing` crate.
+//!
+//! Generated clients drive their protocols directly, so calls are traced by
+//! wrapping the client's protocols: `new_traced_client_protocols` returns an
+//! input and output protocol pair that share one `thrift.client.call` span
+//! per call. The span is opened when the call message is written and closed
+//! once its reply has been read, or right after the flush for oneway calls.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tracing::Span;
+
+use super::{TAsyncInputProtocol, TAsyncOutputProtocol, TProtocolFuture};
+use super::{
+    TFieldIdentifier, TListIdentifier,
+    TMapIdentifier, TMessageIdentifier,
+    TMessageType, TSetIdentifier,
+    TStructIdentifier,
+};
+
+/// Span of the call in progress, shared by both halves of a client.
+type TCallSpan = Arc<Mutex<Option<Span>>>;
+
+/// Wrap the protocols of a client so that every call it makes is recorded
+/// in a `thrift.client.call` span with the method name and sequence number.
+///
+/// The call span is a child of the span current when the call starts.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+/// use async_thrift::protocol::async_traced::new_traced_client_protocols;
+/// use async_thrift::transport::async_mem::TAsyncBufferChannel;
+///
+/// let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::new(), true);
+/// let o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+/// let (i_prot, o_prot) = new_traced_client_protocols(i_prot, o_prot);
+/// // hand `i_prot` and `o_prot` to a generated client
+/// ```
+pub fn new_traced_client_protocols<I, O>(
+    input_protocol: I,
+    output_protocol: O,
+) -> (TAsyncTracedInputProtocol<I>, TAsyncTracedOutputProtocol<O>)
+    where
+        I: TAsyncInputProtocol,
+        O: TAsyncOutputProtocol,
+{
+    let call_span = TCallSpan::default();
+    (
+        TAsyncTracedInputProtocol { inner: input_protocol, call_span: call_span.clone() },
+        TAsyncTracedOutputProtocol { inner: output_protocol, call_span, oneway: false },
+    )
+}
+
+/// Reply half of a traced client. See `new_traced_client_protocols`.
+pub struct TAsyncTracedInputProtocol<P>
+    where
+        P: TAsyncInputProtocol,
+{
+    inner: P,
+    call_span: TCallSpan,
+}
+
+/// Call half of a traced client. See `new_traced_client_protocols`.
+pub struct TAsyncTracedOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol,
+{
+    inner: P,
+    call_span: TCallSpan,
+    oneway: bool,
+}
+
+fn current_call_span(call_span: &TCallSpan) -> Option<Span> {
+    call_span.lock().unwrap().clone()
+}
+
+fn end_call_span(call_span: &TCallSpan) {
+    call_span.lock().unwrap().take();
+}
+
+impl<P> TAsyncInputProtocol for TAsyncTracedInputProtocol<P>
+    where
+        P: TAsyncInputProtocol + Send,
+{
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_message_begin().await;
+            if let Some(span) = current_call_span(&self.call_span) {
+                match res {
+                    Ok(ref message_ident) => span.in_scope(|| {
+                        tracing::debug!(message_type = %message_ident.message_type, "reply received")
+                    }),
+                    Err(ref e) => {
+                        span.in_scope(|| tracing::debug!(error = ?e, "failed to read reply"));
+                        end_call_span(&self.call_span);
+                    }
+                }
+            }
+            res
+        })
+    }
+
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_message_end().await;
+            end_call_span(&self.call_span);
+            res
+        })
+    }
+
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        self.inner.read_struct_begin()
+    }
+
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_struct_end()
+    }
+
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        self.inner.read_field_begin()
+    }
+
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_field_end()
+    }
+
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        self.inner.read_bool()
+    }
+
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        self.inner.read_bytes()
+    }
+
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.inner.read_binary()
+    }
+
+    fn read_string_or_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.inner.read_string_or_binary()
+    }
+
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        self.inner.read_i8()
+    }
+
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        self.inner.read_i16()
+    }
+
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        self.inner.read_i32()
+    }
+
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        self.inner.read_i64()
+    }
+
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        self.inner.read_double()
+    }
+
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        self.inner.read_string()
+    }
+
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        self.inner.read_list_begin()
+    }
+
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_list_end()
+    }
+
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        self.inner.read_set_begin()
+    }
+
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_set_end()
+    }
+
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        self.inner.read_map_begin()
+    }
+
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_map_end()
+    }
+
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        self.inner.read_byte()
+    }
+}
+
+#[async_trait]
+impl<P> TAsyncOutputProtocol for TAsyncTracedOutputProtocol<P>
+    where
+        P: TAsyncOutputProtocol + Send,
+{
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        let span = tracing::info_span!(
+            "thrift.client.call",
+            method = %identifier.name,
+            seqid = identifier.sequence_number,
+        );
+        self.oneway = identifier.message_type == TMessageType::OneWay;
+        *self.call_span.lock().unwrap() = Some(span);
+        self.inner.write_message_begin(identifier)
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_message_end()
+    }
+
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_struct_begin(identifier)
+    }
+
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_struct_end()
+    }
+
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_field_begin(identifier)
+    }
+
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_field_end()
+    }
+
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_field_stop()
+    }
+
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        self.inner.write_bool(b)
+    }
+
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        self.inner.write_bytes(b)
+    }
+
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.inner.write_binary(b)
+    }
+
+    fn write_string_or_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.inner.write_string_or_binary(b)
+    }
+
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i8(i)
+    }
+
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i16(i)
+    }
+
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i32(i)
+    }
+
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i64(i)
+    }
+
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        self.inner.write_double(d)
+    }
+
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        self.inner.write_string(s)
+    }
+
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_list_begin(identifier)
+    }
+
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_list_end()
+    }
+
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_set_begin(identifier)
+    }
+
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_set_end()
+    }
+
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        self.inner.write_map_begin(identifier)
+    }
+
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_map_end()
+    }
+
+    async fn flush(&mut self) -> crate::Result<()> {
+        let res = self.inner.flush().await;
+        if let Some(span) = current_call_span(&self.call_span) {
+            match res {
+                Ok(()) => span.in_scope(|| tracing::debug!("request sent")),
+                Err(ref e) => span.in_scope(|| tracing::debug!(error = ?e, "failed to send request")),
+            }
+            // no reply follows a oneway call or a failed send
+            if self.oneway || res.is_err() {
+                end_call_span(&self.call_span);
+            }
+        }
+        res
+    }
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_byte(b)
+    }
+}
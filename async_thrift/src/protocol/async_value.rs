@@ -0,0 +1,655 @@
+//! Schema-less Thrift values.
+//!
+//! `TValue` holds any value that can be sent over a Thrift protocol, so
+//! messages can be decoded, inspected and re-encoded without generated
+//! code. Structs are keyed by field id since field names are not sent on
+//! the wire.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bytes::Bytes;
+use futures::future::{BoxFuture, FutureExt};
+
+use crate::errors::{ProtocolError, ProtocolErrorKind};
+use crate::OrderedFloat;
+
+use super::{visit_till_depth, with_map_types, TAsyncInputProtocol, TAsyncOutputProtocol, TProtocolFuture, TValueVisitor};
+use super::{
+    TFieldIdentifier, TListIdentifier,
+    TMapIdentifier, TMessageIdentifier,
+    TSetIdentifier, TStructIdentifier,
+    TType,
+};
+use super::MAXIMUM_SKIP_DEPTH;
+
+/// A Thrift value decoded without its schema.
+///
+/// Strings and binary fields share a wire type: values of that type are
+/// read as `String` when they are valid UTF-8 and as `Binary` otherwise.
+/// Both are written the same way.
+///
+/// `Display` prints values on one line; the alternate form (`{:#}`) puts
+/// the members of structs and containers on separate, indented lines.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use async_thrift::protocol::async_value::TValue;
+/// use async_thrift::protocol::TType;
+///
+/// let mut fields = BTreeMap::new();
+/// fields.insert(1, TValue::I32(7));
+/// fields.insert(2, TValue::List {
+///     element_type: TType::String,
+///     elements: vec![TValue::String("a".to_owned()), TValue::String("b".to_owned())],
+/// });
+/// let value = TValue::Struct(fields);
+///
+/// assert_eq!(value.ttype(), TType::Struct);
+/// assert_eq!(value.to_string(), r#"{1: 7, 2: ["a", "b"]}"#);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TValue {
+    /// Boolean.
+    Bool(bool),
+    /// Signed 8-bit int.
+    I8(i8),
+    /// Signed 16-bit int.
+    I16(i16),
+    /// Signed 32-bit int.
+    I32(i32),
+    /// Signed 64-bit int.
+    I64(i64),
+    /// Double-precision number.
+    Double(OrderedFloat<f64>),
+    /// UTF-8 string.
+    String(String),
+    /// Bytes that are not valid UTF-8.
+    Binary(Vec<u8>),
+    /// Struct fields by field id.
+    Struct(BTreeMap<i16, TValue>),
+    /// List of elements of type `element_type`.
+    List {
+        element_type: TType,
+        elements: Vec<TValue>,
+    },
+    /// Set of elements of type `element_type`, in wire order.
+    Set {
+        element_type: TType,
+        elements: Vec<TValue>,
+    },
+    /// Map entries, in wire order. Key and value types may be missing for
+    /// empty maps, as read from the compact protocol; they are written as
+    /// `TType::Stop` to protocols that always send them.
+    Map {
+        key_type: Option<TType>,
+        value_type: Option<TType>,
+        entries: Vec<(TValue, TValue)>,
+    },
+}
+
+impl TValue {
+    /// Return the wire type of this value.
+    pub fn ttype(&self) -> TType {
+        match self {
+            TValue::Bool(_) => TType::Bool,
+            TValue::I8(_) => TType::I08,
+            TValue::I16(_) => TType::I16,
+            TValue::I32(_) => TType::I32,
+            TValue::I64(_) => TType::I64,
+            TValue::Double(_) => TType::Double,
+            TValue::String(_) | TValue::Binary(_) => TType::String,
+            TValue::Struct(_) => TType::Struct,
+            TValue::List { .. } => TType::List,
+            TValue::Set { .. } => TType::Set,
+            TValue::Map { .. } => TType::Map,
+        }
+    }
+}
+
+impl fmt::Display for TValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let indent = if f.alternate() { Some(0) } else { None };
+        fmt_value(self, f, indent)
+    }
+}
+
+/// write `value` to `f`, on several lines indented by `indent` levels if
+/// `indent` is set
+fn fmt_value(value: &TValue, f: &mut fmt::Formatter, indent: Option<usize>) -> fmt::Result {
+    match value {
+        TValue::Bool(b) => write!(f, "{}", b),
+        TValue::I8(i) => write!(f, "{}", i),
+        TValue::I16(i) => write!(f, "{}", i),
+        TValue::I32(i) => write!(f, "{}", i),
+        TValue::I64(i) => write!(f, "{}", i),
+        TValue::Double(d) => write!(f, "{:?}", d.into_inner()),
+        TValue::String(s) => write!(f, "{:?}", s),
+        TValue::Binary(b) => {
+            f.write_str("0x")?;
+            b.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+        }
+        TValue::Struct(fields) => fmt_members(f, indent, "{", "}", fields.iter(), |f, (id, value), indent| {
+            write!(f, "{}: ", id)?;
+            fmt_value(value, f, indent)
+        }),
+        TValue::List { elements, .. } => fmt_members(f, indent, "[", "]", elements.iter(), |f, value, indent| {
+            fmt_value(value, f, indent)
+        }),
+        TValue::Set { elements, .. } => fmt_members(f, indent, "set{", "}", elements.iter(), |f, value, indent| {
+            fmt_value(value, f, indent)
+        }),
+        TValue::Map { entries, .. } => fmt_members(f, indent, "map{", "}", entries.iter(), |f, (key, value), indent| {
+            fmt_value(key, f, indent)?;
+            f.write_str(" => ")?;
+            fmt_value(value, f, indent)
+        }),
+    }
+}
+
+/// write the members of a struct or container between `open` and `close`
+fn fmt_members<I, M>(
+    f: &mut fmt::Formatter,
+    indent: Option<usize>,
+    open: &str,
+    close: &str,
+    members: I,
+    mut fmt_member: M,
+) -> fmt::Result
+    where
+        I: ExactSizeIterator,
+        M: FnMut(&mut fmt::Formatter, I::Item, Option<usize>) -> fmt::Result,
+{
+    f.write_str(open)?;
+    if members.len() == 0 {
+        return f.write_str(close);
+    }
+    match indent {
+        None => {
+            for (index, member) in members.enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                fmt_member(f, member, None)?;
+            }
+        }
+        Some(indent) => {
+            for member in members {
+                write!(f, "\n{:width$}", "", width = (indent + 1) * 2)?;
+                fmt_member(f, member, Some(indent + 1))?;
+                f.write_str(",")?;
+            }
+            write!(f, "\n{:width$}", "", width = indent * 2)?;
+        }
+    }
+    f.write_str(close)
+}
+
+/// Read a value of type `field_type` from `i`.
+///
+/// # Examples
+///
+/// ```
+/// use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+/// use async_thrift::protocol::async_value::{read_value, write_value, TValue};
+/// use async_thrift::protocol::TType;
+/// use async_thrift::transport::async_mem::TAsyncBufferChannel;
+///
+/// # async_std::task::block_on(async {
+/// let value = TValue::List { element_type: TType::I64, elements: vec![TValue::I64(1), TValue::I64(2)] };
+///
+/// let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+/// write_value(&mut o_prot, &value).await?;
+///
+/// let bytes = o_prot.transport.take_write_bytes();
+/// let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+/// assert_eq!(read_value(&mut i_prot, TType::List).await?, value);
+/// # Ok::<(), async_thrift::Error>(())
+/// # }).unwrap();
+/// ```
+pub async fn read_value(
+    i: &mut (dyn TAsyncInputProtocol + Send),
+    field_type: TType,
+) -> crate::Result<TValue> {
+    read_value_till_depth(i, field_type, MAXIMUM_SKIP_DEPTH).await
+}
+
+/// Read a value of type `field_type` from `i`, descending at most `depth`
+/// levels into nested structs and containers.
+pub fn read_value_till_depth<'a>(
+    i: &'a mut (dyn TAsyncInputProtocol + Send),
+    field_type: TType,
+    depth: i8,
+) -> BoxFuture<'a, crate::Result<TValue>> {
+    async move {
+        let mut builder = TValueBuilder { open: Vec::new(), value: None };
+        visit_till_depth(i, &mut builder, field_type, depth).await?;
+        builder.value.ok_or_else(|| crate::Error::Protocol(ProtocolError {
+            kind: ProtocolErrorKind::InvalidData,
+            message: format!("no {:?} value read", field_type),
+        }))
+    }.boxed()
+}
+
+/// A struct or container whose members are being read.
+enum TOpenValue {
+    Struct {
+        fields: BTreeMap<i16, TValue>,
+        // id of the field being read
+        id: i16,
+    },
+    List {
+        element_type: TType,
+        elements: Vec<TValue>,
+    },
+    Set {
+        element_type: TType,
+        elements: Vec<TValue>,
+    },
+    Map {
+        key_type: Option<TType>,
+        value_type: Option<TType>,
+        entries: Vec<(TValue, TValue)>,
+        // key of the entry whose value is being read
+        key: Option<TValue>,
+    },
+}
+
+/// Visitor that builds the `TValue` read.
+struct TValueBuilder {
+    // structs and containers enclosing the next value, innermost last
+    open: Vec<TOpenValue>,
+    value: Option<TValue>,
+}
+
+impl TValueBuilder {
+    /// add a complete value to the innermost open struct or container
+    fn add(&mut self, value: TValue) -> TProtocolFuture<'_, ()> {
+        match self.open.last_mut() {
+            None => self.value = Some(value),
+            Some(TOpenValue::Struct { fields, id }) => {
+                fields.insert(*id, value);
+            }
+            Some(TOpenValue::List { elements, .. }) | Some(TOpenValue::Set { elements, .. }) => elements.push(value),
+            Some(TOpenValue::Map { entries, key, .. }) => match key.take() {
+                Some(key) => entries.push((key, value)),
+                None => *key = Some(value),
+            },
+        }
+        TProtocolFuture::ready(Ok(()))
+    }
+
+    /// close the innermost open struct or container and add it as a value
+    fn close(&mut self) -> TProtocolFuture<'_, ()> {
+        let value = match self.open.pop() {
+            Some(TOpenValue::Struct { fields, .. }) => TValue::Struct(fields),
+            Some(TOpenValue::List { element_type, elements }) => TValue::List { element_type, elements },
+            Some(TOpenValue::Set { element_type, elements }) => TValue::Set { element_type, elements },
+            Some(TOpenValue::Map { key_type, value_type, entries, .. }) => TValue::Map { key_type, value_type, entries },
+            None => return TProtocolFuture::ready(Err(crate::Error::Protocol(ProtocolError {
+                kind: ProtocolErrorKind::InvalidData,
+                message: "end of a value that was not begun".to_owned(),
+            }))),
+        };
+        self.add(value)
+    }
+}
+
+impl TValueVisitor for TValueBuilder {
+    fn visit_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        self.add(TValue::Bool(b))
+    }
+    fn visit_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.add(TValue::I8(i))
+    }
+    fn visit_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.add(TValue::I16(i))
+    }
+    fn visit_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.add(TValue::I32(i))
+    }
+    fn visit_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.add(TValue::I64(i))
+    }
+    fn visit_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        self.add(TValue::Double(OrderedFloat::from(d)))
+    }
+    fn visit_binary(&mut self, b: Bytes) -> TProtocolFuture<'_, ()> {
+        self.add(match String::from_utf8(b.to_vec()) {
+            Ok(s) => TValue::String(s),
+            Err(e) => TValue::Binary(e.into_bytes()),
+        })
+    }
+    fn visit_struct_begin<'a>(&'a mut self, _: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        self.open.push(TOpenValue::Struct { fields: BTreeMap::new(), id: 0 });
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.close()
+    }
+    fn visit_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        let field_id = match super::field_id(identifier) {
+            Ok(field_id) => field_id,
+            Err(e) => return TProtocolFuture::ready(Err(e)),
+        };
+        if let Some(TOpenValue::Struct { id, .. }) = self.open.last_mut() {
+            *id = field_id;
+        }
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        self.open.push(TOpenValue::List {
+            element_type: identifier.element_type,
+            elements: Vec::with_capacity(capacity(identifier.size)),
+        });
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.close()
+    }
+    fn visit_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        self.open.push(TOpenValue::Set {
+            element_type: identifier.element_type,
+            elements: Vec::with_capacity(capacity(identifier.size)),
+        });
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.close()
+    }
+    fn visit_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        self.open.push(TOpenValue::Map {
+            key_type: identifier.key_type,
+            value_type: identifier.value_type,
+            entries: Vec::with_capacity(capacity(identifier.size)),
+            key: None,
+        });
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.close()
+    }
+}
+
+// sizes come from the wire, so do not trust them with large allocations
+fn capacity(size: i32) -> usize {
+    size.clamp(0, 1024) as usize
+}
+
+/// Write `value` to `o`.
+///
+/// Fails with an `InvalidData` `ProtocolError` if a container holds an
+/// element whose type does not match the container's element type.
+pub fn write_value<'a>(
+    o: &'a mut (dyn TAsyncOutputProtocol + Send),
+    value: &'a TValue,
+) -> BoxFuture<'a, crate::Result<()>> {
+    async move {
+        match value {
+            TValue::Bool(b) => o.write_bool(*b).await,
+            TValue::I8(i) => o.write_i8(*i).await,
+            TValue::I16(i) => o.write_i16(*i).await,
+            TValue::I32(i) => o.write_i32(*i).await,
+            TValue::I64(i) => o.write_i64(*i).await,
+            TValue::Double(d) => o.write_double(d.into_inner()).await,
+            TValue::String(s) => o.write_string(s).await,
+            TValue::Binary(b) => o.write_bytes(b).await,
+            TValue::Struct(fields) => {
+                o.write_struct_begin(&TStructIdentifier::new("")).await?;
+                for (id, field) in fields {
+                    o.write_field_begin(&TFieldIdentifier::new("", field.ttype(), *id)).await?;
+                    write_value(o, field).await?;
+                    o.write_field_end().await?;
+                }
+                o.write_field_stop().await?;
+                o.write_struct_end().await
+            }
+            TValue::List { element_type, elements } => {
+                check_types(elements.iter(), *element_type)?;
+                o.write_list_begin(&TListIdentifier::new(*element_type, elements.len() as i32)).await?;
+                for element in elements {
+                    write_value(o, element).await?;
+                }
+                o.write_list_end().await
+            }
+            TValue::Set { element_type, elements } => {
+                check_types(elements.iter(), *element_type)?;
+                o.write_set_begin(&TSetIdentifier::new(*element_type, elements.len() as i32)).await?;
+                for element in elements {
+                    write_value(o, element).await?;
+                }
+                o.write_set_end().await
+            }
+            TValue::Map { key_type, value_type, entries } => {
+                if !entries.is_empty() {
+                    match (key_type, value_type) {
+                        (Some(key_type), Some(value_type)) => {
+                            check_types(entries.iter().map(|(key, _)| key), *key_type)?;
+                            check_types(entries.iter().map(|(_, value)| value), *value_type)?;
+                        }
+                        _ => {
+                            return Err(crate::Error::Protocol(ProtocolError {
+                                kind: ProtocolErrorKind::InvalidData,
+                                message: "non-zero sized map without key or value type".to_owned(),
+                            }))
+                        }
+                    }
+                }
+                let map_ident = TMapIdentifier::new(*key_type, *value_type, entries.len() as i32);
+                o.write_map_begin(&with_map_types(&map_ident)).await?;
+                for (key, value) in entries {
+                    write_value(o, key).await?;
+                    write_value(o, value).await?;
+                }
+                o.write_map_end().await
+            }
+        }
+    }.boxed()
+}
+
+fn check_types<'a, I>(values: I, expected: TType) -> crate::Result<()>
+    where
+        I: Iterator<Item = &'a TValue>,
+{
+    for value in values {
+        if value.ttype() != expected {
+            return Err(crate::Error::Protocol(ProtocolError {
+                kind: ProtocolErrorKind::InvalidData,
+                message: format!("expected {:?} element but got {:?}", expected, value.ttype()),
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Read a complete message from `i` and return its header and body.
+pub async fn read_message(
+    i: &mut (dyn TAsyncInputProtocol + Send),
+) -> crate::Result<(TMessageIdentifier, TValue)> {
+    let message_ident = i.read_message_begin().await?;
+    let body = read_value(i, TType::Struct).await?;
+    i.read_message_end().await?;
+    Ok((message_ident, body))
+}
+
+/// Write a message with header `message_ident` and body `body` to `o`.
+///
+/// `o` is not flushed.
+pub async fn write_message(
+    o: &mut (dyn TAsyncOutputProtocol + Send),
+    message_ident: &TMessageIdentifier,
+    body: &TValue,
+) -> crate::Result<()> {
+    o.write_message_begin(message_ident).await?;
+    write_value(o, body).await?;
+    o.write_message_end().await
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task::block_on;
+
+    use crate::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+    use crate::protocol::async_compact::{TAsyncCompactInputProtocol, TAsyncCompactOutputProtocol};
+    use crate::protocol::async_json::{TAsyncJsonInputProtocol, TAsyncJsonOutputProtocol};
+    use crate::protocol::copy_till_depth;
+    use crate::transport::async_mem::TAsyncBufferChannel;
+
+    use super::*;
+
+    // a struct holding a value of every type, nested structs and containers
+    fn every_type() -> TValue {
+        let mut nested = BTreeMap::new();
+        nested.insert(1, TValue::String("héllo".to_owned()));
+        nested.insert(2, TValue::Binary(vec![0xFF, 0x00]));
+
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::Bool(true));
+        fields.insert(2, TValue::I8(-3));
+        fields.insert(3, TValue::I16(i16::MIN));
+        fields.insert(4, TValue::I32(7));
+        fields.insert(5, TValue::I64(-1_234_567_890_123));
+        fields.insert(6, TValue::Double(OrderedFloat::from(3.25)));
+        fields.insert(7, TValue::Struct(nested));
+        fields.insert(8, TValue::List {
+            element_type: TType::Bool,
+            elements: vec![TValue::Bool(false), TValue::Bool(true)],
+        });
+        fields.insert(9, TValue::Set { element_type: TType::I64, elements: vec![TValue::I64(1)] });
+        fields.insert(10, TValue::Map {
+            key_type: Some(TType::String),
+            value_type: Some(TType::List),
+            entries: vec![(
+                TValue::String("key".to_owned()),
+                TValue::List { element_type: TType::I32, elements: vec![] },
+            )],
+        });
+        fields.insert(11, TValue::Struct(BTreeMap::new()));
+        TValue::Struct(fields)
+    }
+
+    fn write_binary(value: &TValue) -> Vec<u8> {
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(write_value(&mut o_prot, value)).unwrap();
+        o_prot.transport.take_write_bytes()
+    }
+
+    fn write_compact(value: &TValue) -> Vec<u8> {
+        let mut o_prot = TAsyncCompactOutputProtocol::new(TAsyncBufferChannel::new());
+        block_on(write_value(&mut o_prot, value)).unwrap();
+        o_prot.transport.take_write_bytes()
+    }
+
+    fn write_json(value: &TValue) -> Vec<u8> {
+        let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+        block_on(write_value(&mut o_prot, value)).unwrap();
+        o_prot.transport.take_write_bytes()
+    }
+
+    #[test]
+    fn must_round_trip_every_type() {
+        let value = every_type();
+
+        let bytes = write_binary(&value);
+        let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+        assert_eq!(block_on(read_value(&mut i_prot, TType::Struct)).unwrap(), value);
+        assert_eq!(i_prot.transport.remaining(), 0);
+
+        let bytes = write_compact(&value);
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+        assert_eq!(block_on(read_value(&mut i_prot, TType::Struct)).unwrap(), value);
+        assert_eq!(i_prot.transport.remaining(), 0);
+
+        let bytes = write_json(&value);
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+        // JSON sends strings and binary alike, so binary comes back as base64
+        let mut expected = value;
+        if let TValue::Struct(ref mut fields) = expected {
+            if let Some(TValue::Struct(nested)) = fields.get_mut(&7) {
+                nested.insert(2, TValue::String("/wA=".to_owned()));
+            }
+        }
+        assert_eq!(block_on(read_value(&mut i_prot, TType::Struct)).unwrap(), expected);
+        assert_eq!(i_prot.transport.remaining(), 0);
+    }
+
+    #[test]
+    fn must_read_empty_map_without_types() {
+        // the compact protocol sends no key and value types for empty maps
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::Map { key_type: None, value_type: None, entries: vec![] });
+        let value = TValue::Struct(fields);
+
+        let bytes = write_compact(&value);
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes.clone()));
+        assert_eq!(block_on(read_value(&mut i_prot, TType::Struct)).unwrap(), value);
+        assert_eq!(write_compact(&value), bytes);
+    }
+
+    #[test]
+    fn must_translate_empty_map_from_compact_to_binary() {
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::Map { key_type: None, value_type: None, entries: vec![] });
+        let bytes = write_compact(&TValue::Struct(fields));
+        // the key and value types become TType::Stop
+        let expected = vec![0x0D, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes.clone()));
+        let value = block_on(read_value(&mut i_prot, TType::Struct)).unwrap();
+        assert_eq!(write_binary(&value), expected);
+
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(copy_till_depth(&mut i_prot, &mut o_prot, TType::Struct, MAXIMUM_SKIP_DEPTH)).unwrap();
+        assert_eq!(o_prot.transport.take_write_bytes(), expected);
+    }
+
+    #[test]
+    fn must_skip_and_copy_what_read_value_reads() {
+        let bytes = write_compact(&every_type());
+
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes.clone()));
+        block_on(i_prot.skip(TType::Struct)).unwrap();
+        assert_eq!(i_prot.transport.remaining(), 0);
+
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(copy_till_depth(&mut i_prot, &mut o_prot, TType::Struct, MAXIMUM_SKIP_DEPTH)).unwrap();
+        assert_eq!(o_prot.transport.take_write_bytes(), write_binary(&every_type()));
+    }
+
+    #[test]
+    fn must_copy_to_json_what_write_value_writes() {
+        let bytes = write_compact(&every_type());
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+        let mut o_prot = TAsyncJsonOutputProtocol::new(TAsyncBufferChannel::new());
+        block_on(copy_till_depth(&mut i_prot, &mut o_prot, TType::Struct, MAXIMUM_SKIP_DEPTH)).unwrap();
+        assert_eq!(o_prot.transport.take_write_bytes(), write_json(&every_type()));
+
+        let mut i_prot = TAsyncJsonInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(write_json(&every_type())));
+        block_on(i_prot.skip(TType::Struct)).unwrap();
+        assert_eq!(i_prot.transport.remaining(), 0);
+    }
+
+    #[test]
+    fn must_stop_reading_at_depth_limit() {
+        let bytes = write_binary(&every_type());
+        let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+        match block_on(read_value_till_depth(&mut i_prot, TType::Struct, 2)) {
+            Err(crate::Error::Protocol(e)) => assert_eq!(e.kind, ProtocolErrorKind::DepthLimit),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    fn must_reject_element_of_wrong_type() {
+        let value = TValue::List { element_type: TType::I32, elements: vec![TValue::I64(1)] };
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        match block_on(write_value(&mut o_prot, &value)) {
+            Err(crate::Error::Protocol(e)) => assert_eq!(e.kind, ProtocolErrorKind::InvalidData),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+}
@@ -1,13 +1,26 @@
 use std::convert::{From, TryFrom};
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::future::{BoxFuture, FutureExt};
 
 use crate::errors::{ProtocolError, ProtocolErrorKind};
 use crate::transport::{TAsyncReadTransport, TAsyncWriteTransport};
 
 pub mod async_binary;
+pub mod async_compact;
+pub mod async_debug;
+pub mod async_json;
+pub mod async_metadata;
+pub mod async_serialize;
+#[cfg(feature = "tracing")]
+pub mod async_traced;
+pub mod async_value;
 
 // Default maximum depth to which `TInputProtocol::skip` will skip a Thrift
 // field. A default is necessary because Thrift structs or collections may
@@ -15,48 +28,138 @@ pub mod async_binary;
 // recursion.
 const MAXIMUM_SKIP_DEPTH: i8 = 64;
 
+// Default largest size an input protocol accepts for a string, binary value
+// or container before allocating anything for it. None of them can be larger
+// than the message, so this bounds what a corrupt or hostile length costs.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 100 * 1024 * 1024;
+
+/// Future returned by the reads and writes of `TAsyncInputProtocol` and
+/// `TAsyncOutputProtocol`.
+///
+/// A value read from or written to bytes the transport already buffers is
+/// `Ready` when the method returns, which costs no allocation. Only calls
+/// that have to wait for the transport box their future, so a message that
+/// fits the transport's buffer is encoded or decoded without a boxed future
+/// per field.
+///
+/// Generated code and other callers simply `.await` it.
+pub enum TProtocolFuture<'a, T> {
+    /// The call completed synchronously.
+    Ready(Option<crate::Result<T>>),
+    /// The call waits for the transport.
+    Pending(BoxFuture<'a, crate::Result<T>>),
+}
+
+impl<'a, T> TProtocolFuture<'a, T> {
+    /// Create a future that completes with `res` without waiting.
+    pub fn ready(res: crate::Result<T>) -> TProtocolFuture<'a, T> {
+        TProtocolFuture::Ready(Some(res))
+    }
+
+    /// Create a future that completes with the result of `future`.
+    pub fn boxed<F>(future: F) -> TProtocolFuture<'a, T>
+        where
+            F: Future<Output = crate::Result<T>> + Send + 'a,
+    {
+        TProtocolFuture::Pending(future.boxed())
+    }
+
+    /// Transform the result with `f` once it is available.
+    pub fn map<U, F>(self, f: F) -> TProtocolFuture<'a, U>
+        where
+            F: FnOnce(crate::Result<T>) -> crate::Result<U> + Send + 'a,
+            T: 'a,
+    {
+        match self {
+            TProtocolFuture::Ready(res) => TProtocolFuture::Ready(res.map(f)),
+            TProtocolFuture::Pending(future) => TProtocolFuture::Pending(FutureExt::map(future, f).boxed()),
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for TProtocolFuture<'a, T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            TProtocolFuture::Ready(_) => f.write_str("TProtocolFuture::Ready"),
+            TProtocolFuture::Pending(_) => f.write_str("TProtocolFuture::Pending"),
+        }
+    }
+}
+
+// the ready result is moved out, never pinned
+impl<'a, T> Unpin for TProtocolFuture<'a, T> {}
+
+impl<'a, T> Future for TProtocolFuture<'a, T> {
+    type Output = crate::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<crate::Result<T>> {
+        match self.get_mut() {
+            TProtocolFuture::Ready(res) => Poll::Ready(res.take().expect("TProtocolFuture polled after completion")),
+            TProtocolFuture::Pending(future) => future.as_mut().poll(cx),
+        }
+    }
+}
+
 #[async_trait]
 pub trait TAsyncInputProtocol: Send {
     /// Read the beginning of a Thrift message.
-    async fn read_message_begin(&mut self) -> crate::Result<TMessageIdentifier>;
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier>;
     /// Read the end of a Thrift message.
-    async fn read_message_end(&mut self) -> crate::Result<()>;
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a Thrift struct.
-    async fn read_struct_begin(&mut self) -> crate::Result<Option<TStructIdentifier>>;
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>>;
     /// Read the end of a Thrift struct.
-    async fn read_struct_end(&mut self) -> crate::Result<()>;
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a Thrift struct field.
-    async fn read_field_begin(&mut self) -> crate::Result<TFieldIdentifier>;
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier>;
     /// Read the end of a Thrift struct field.
-    async fn read_field_end(&mut self) -> crate::Result<()>;
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read a bool.
-    async fn read_bool(&mut self) -> crate::Result<bool>;
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool>;
     /// Read a fixed-length byte array.
-    async fn read_bytes(&mut self) -> crate::Result<Vec<u8>>;
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>>;
+    /// Read a fixed-length byte array as `Bytes`.
+    ///
+    /// Protocols over a transport that keeps whole frames, such as the framed
+    /// transport, return a slice of the received frame without copying. The
+    /// default implementation copies the result of `read_bytes`.
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.read_bytes().map(|bytes| bytes.map(Bytes::from))
+    }
+    /// Read a `TType::String` value that may be a string or binary, as it is
+    /// sent.
+    ///
+    /// Used where values are read without their schema, such as by `skip`.
+    /// The default implementation calls `read_binary`, since most protocols
+    /// send both alike. The JSON protocol returns the unescaped string, in
+    /// which binary values are base64.
+    fn read_string_or_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.read_binary()
+    }
     /// Read a word.
-    async fn read_i8(&mut self) -> crate::Result<i8>;
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8>;
     /// Read a 16-bit signed integer.
-    async fn read_i16(&mut self) -> crate::Result<i16>;
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16>;
     /// Read a 32-bit signed integer.
-    async fn read_i32(&mut self) -> crate::Result<i32>;
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32>;
     /// Read a 64-bit signed integer.
-    async fn read_i64(&mut self) -> crate::Result<i64>;
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64>;
     /// Read a 64-bit float.
-    async fn read_double(&mut self) -> crate::Result<f64>;
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64>;
     /// Read a fixed-length string (not null terminated).
-    async fn read_string(&mut self) -> crate::Result<String>;
+    fn read_string(&mut self) -> TProtocolFuture<'_, String>;
     /// Read the beginning of a list.
-    async fn read_list_begin(&mut self) -> crate::Result<TListIdentifier>;
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier>;
     /// Read the end of a list.
-    async fn read_list_end(&mut self) -> crate::Result<()>;
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a set.
-    async fn read_set_begin(&mut self) -> crate::Result<TSetIdentifier>;
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier>;
     /// Read the end of a set.
-    async fn read_set_end(&mut self) -> crate::Result<()>;
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a map.
-    async fn read_map_begin(&mut self) -> crate::Result<TMapIdentifier>;
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier>;
     /// Read the end of a map.
-    async fn read_map_end(&mut self) -> crate::Result<()>;
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Skip a field with type `field_type` recursively until the default
     /// maximum skip depth is reached.
     async fn skip(&mut self, field_type: TType) -> crate::Result<()> {
@@ -64,65 +167,7 @@ pub trait TAsyncInputProtocol: Send {
     }
     /// Skip a field with type `field_type` recursively up to `depth` levels.
     async fn skip_till_depth(&mut self, field_type: TType, depth: i8) -> crate::Result<()> {
-        if depth == 0 {
-            return Err(crate::Error::Protocol(ProtocolError {
-                kind: ProtocolErrorKind::DepthLimit,
-                message: format!("cannot parse past {:?}", field_type),
-            }));
-        }
-
-        match field_type {
-            TType::Bool => self.read_bool().await.map(|_| ()),
-            TType::I08 => self.read_i8().await.map(|_| ()),
-            TType::I16 => self.read_i16().await.map(|_| ()),
-            TType::I32 => self.read_i32().await.map(|_| ()),
-            TType::I64 => self.read_i64().await.map(|_| ()),
-            TType::Double => self.read_double().await.map(|_| ()),
-            TType::String => self.read_string().await.map(|_| ()),
-            TType::Struct => {
-                self.read_struct_begin().await?;
-                loop {
-                    let field_ident = self.read_field_begin().await?;
-                    if field_ident.field_type == TType::Stop {
-                        break;
-                    }
-                    self.skip_till_depth(field_ident.field_type, depth - 1).await?;
-                }
-                self.read_struct_end().await
-            }
-            TType::List => {
-                let list_ident = self.read_list_begin().await?;
-                for _ in 0..list_ident.size {
-                    self.skip_till_depth(list_ident.element_type, depth - 1).await?;
-                }
-                self.read_list_end().await
-            }
-            TType::Set => {
-                let set_ident = self.read_set_begin().await?;
-                for _ in 0..set_ident.size {
-                    self.skip_till_depth(set_ident.element_type, depth - 1).await?;
-                }
-                self.read_set_end().await
-            }
-            TType::Map => {
-                let map_ident = self.read_map_begin().await?;
-                for _ in 0..map_ident.size {
-                    let key_type = map_ident
-                        .key_type
-                        .expect("non-zero sized map should contain key type");
-                    let val_type = map_ident
-                        .value_type
-                        .expect("non-zero sized map should contain value type");
-                    self.skip_till_depth(key_type, depth - 1).await?;
-                    self.skip_till_depth(val_type, depth - 1).await?;
-                }
-                self.read_map_end().await
-            }
-            u => Err(crate::Error::Protocol(ProtocolError {
-                kind: ProtocolErrorKind::Unknown,
-                message: format!("cannot skip field type {:?}", &u),
-            })),
-        }
+        visit_till_depth(self, &mut TSkipVisitor, field_type, depth).await
     }
 
     // utility (DO NOT USE IN GENERATED CODE!!!!)
@@ -131,56 +176,79 @@ pub trait TAsyncInputProtocol: Send {
     /// Read an unsigned byte.
     ///
     /// This method should **never** be used in generated code.
-    async fn read_byte(&mut self) -> crate::Result<u8>;
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8>;
 }
 
 #[async_trait]
 pub trait TAsyncOutputProtocol: Send {
     /// Write the beginning of a Thrift message.
-    async fn write_message_begin(&mut self, identifier: &TMessageIdentifier) -> crate::Result<()>;
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a Thrift message.
-    async fn write_message_end(&mut self) -> crate::Result<()>;
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a Thrift struct.
-    async fn write_struct_begin(&mut self, identifier: &TStructIdentifier) -> crate::Result<()>;
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a Thrift struct.
-    async fn write_struct_end(&mut self) -> crate::Result<()>;
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a Thrift field.
-    async fn write_field_begin(&mut self, identifier: &TFieldIdentifier) -> crate::Result<()>;
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a Thrift field.
-    async fn write_field_end(&mut self) -> crate::Result<()>;
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write a STOP field indicating that all the fields in a struct have been
     /// written.
-    async fn write_field_stop(&mut self) -> crate::Result<()>;
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write a bool.
-    async fn write_bool(&mut self, b: bool) -> crate::Result<()>;
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()>;
     /// Write a fixed-length byte array.
-    async fn write_bytes(&mut self, b: &[u8]) -> crate::Result<()>;
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()>;
+    /// Write a fixed-length byte array held in `Bytes`.
+    ///
+    /// Transports that queue writes, such as the framed transport, keep a
+    /// reference to large arrays instead of copying them. The default
+    /// implementation calls `write_bytes`.
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.write_bytes(b)
+    }
+    /// Write a value read with `read_string_or_binary`.
+    ///
+    /// The default implementation calls `write_binary`. The JSON protocol
+    /// writes UTF-8 as a string and anything else as binary.
+    fn write_string_or_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.write_binary(b)
+    }
     /// Write an 8-bit signed integer.
-    async fn write_i8(&mut self, i: i8) -> crate::Result<()>;
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()>;
     /// Write a 16-bit signed integer.
-    async fn write_i16(&mut self, i: i16) -> crate::Result<()>;
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()>;
     /// Write a 32-bit signed integer.
-    async fn write_i32(&mut self, i: i32) -> crate::Result<()>;
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()>;
     /// Write a 64-bit signed integer.
-    async fn write_i64(&mut self, i: i64) -> crate::Result<()>;
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()>;
     /// Write a 64-bit float.
-    async fn write_double(&mut self, d: f64) -> crate::Result<()>;
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()>;
     /// Write a fixed-length string.
-    async fn write_string(&mut self, s: &str) -> crate::Result<()>;
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()>;
     /// Write the beginning of a list.
-    async fn write_list_begin(&mut self, identifier: &TListIdentifier) -> crate::Result<()>;
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a list.
-    async fn write_list_end(&mut self) -> crate::Result<()>;
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a set.
-    async fn write_set_begin(&mut self, identifier: &TSetIdentifier) -> crate::Result<()>;
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a set.
-    async fn write_set_end(&mut self) -> crate::Result<()>;
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a map.
-    async fn write_map_begin(&mut self, identifier: &TMapIdentifier) -> crate::Result<()>;
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a map.
-    async fn write_map_end(&mut self) -> crate::Result<()>;
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Flush buffered bytes to the underlying transport.
     async fn flush(&mut self) -> crate::Result<()>;
+    /// Like `flush`, except that the transport may hold the message back
+    /// and send it together with the messages written after it.
+    ///
+    /// Held back messages are sent by the next `flush` at the latest. The
+    /// default implementation flushes.
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.flush().await
+    }
 
     // utility (DO NOT USE IN GENERATED CODE!!!!)
     //
@@ -188,96 +256,103 @@ pub trait TAsyncOutputProtocol: Send {
     /// Write an unsigned byte.
     ///
     /// This method should **never** be used in generated code.
-    async fn write_byte(&mut self, b: u8) -> crate::Result<()>; // FIXME: REMOVE
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()>; // FIXME: REMOVE
 }
 
-#[async_trait]
 impl<P> TAsyncInputProtocol for Box<P>
     where
         P: TAsyncInputProtocol + ?Sized + Send,
 {
-    async fn read_message_begin(&mut self) -> crate::Result<TMessageIdentifier> {
-        (**self).read_message_begin().await
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        (**self).read_message_begin()
     }
 
-    async fn read_message_end(&mut self) -> crate::Result<()> {
-        (**self).read_message_end().await
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_message_end()
     }
 
-    async fn read_struct_begin(&mut self) -> crate::Result<Option<TStructIdentifier>> {
-        (**self).read_struct_begin().await
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        (**self).read_struct_begin()
     }
 
-    async fn read_struct_end(&mut self) -> crate::Result<()> {
-        (**self).read_struct_end().await
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_struct_end()
     }
 
-    async fn read_field_begin(&mut self) -> crate::Result<TFieldIdentifier> {
-        (**self).read_field_begin().await
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        (**self).read_field_begin()
     }
 
-    async fn read_field_end(&mut self) -> crate::Result<()> {
-        (**self).read_field_end().await
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_field_end()
     }
 
-    async fn read_bool(&mut self) -> crate::Result<bool> {
-        (**self).read_bool().await
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        (**self).read_bool()
     }
 
-    async fn read_bytes(&mut self) -> crate::Result<Vec<u8>> {
-        (**self).read_bytes().await
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        (**self).read_bytes()
     }
 
-    async fn read_i8(&mut self) -> crate::Result<i8> {
-        (**self).read_i8().await
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        (**self).read_binary()
     }
 
-    async fn read_i16(&mut self) -> crate::Result<i16> {
-        (**self).read_i16().await
+    fn read_string_or_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        (**self).read_string_or_binary()
     }
 
-    async fn read_i32(&mut self) -> crate::Result<i32> {
-        (**self).read_i32().await
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        (**self).read_i8()
     }
 
-    async fn read_i64(&mut self) -> crate::Result<i64> {
-        (**self).read_i64().await
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        (**self).read_i16()
     }
 
-    async fn read_double(&mut self) -> crate::Result<f64> {
-        (**self).read_double().await
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        (**self).read_i32()
     }
 
-    async fn read_string(&mut self) -> crate::Result<String> {
-        (**self).read_string().await
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        (**self).read_i64()
     }
 
-    async fn read_list_begin(&mut self) -> crate::Result<TListIdentifier> {
-        (**self).read_list_begin().await
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        (**self).read_double()
     }
 
-    async fn read_list_end(&mut self) -> crate::Result<()> {
-        (**self).read_list_end().await
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        (**self).read_string()
     }
 
-    async fn read_set_begin(&mut self) -> crate::Result<TSetIdentifier> {
-        (**self).read_set_begin().await
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        (**self).read_list_begin()
     }
 
-    async fn read_set_end(&mut self) -> crate::Result<()> {
-        (**self).read_set_end().await
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_list_end()
     }
 
-    async fn read_map_begin(&mut self) -> crate::Result<TMapIdentifier> {
-        (**self).read_map_begin().await
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        (**self).read_set_begin()
     }
 
-    async fn read_map_end(&mut self) -> crate::Result<()> {
-        (**self).read_map_end().await
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_set_end()
     }
 
-    async fn read_byte(&mut self) -> crate::Result<u8> {
-        (**self).read_byte().await
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        (**self).read_map_begin()
+    }
+
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_map_end()
+    }
+
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        (**self).read_byte()
     }
 }
 
@@ -286,96 +361,108 @@ impl<P> TAsyncOutputProtocol for Box<P>
     where
         P: TAsyncOutputProtocol + ?Sized + Send,
 {
-    async fn write_message_begin(&mut self, identifier: &TMessageIdentifier) -> crate::Result<()> {
-        (**self).write_message_begin(identifier).await
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_message_begin(identifier)
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_message_end()
+    }
+
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_struct_begin(identifier)
     }
 
-    async fn write_message_end(&mut self) -> crate::Result<()> {
-        (**self).write_message_end().await
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_struct_end()
     }
 
-    async fn write_struct_begin(&mut self, identifier: &TStructIdentifier) -> crate::Result<()> {
-        (**self).write_struct_begin(identifier).await
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_field_begin(identifier)
     }
 
-    async fn write_struct_end(&mut self) -> crate::Result<()> {
-        (**self).write_struct_end().await
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_field_end()
     }
 
-    async fn write_field_begin(&mut self, identifier: &TFieldIdentifier) -> crate::Result<()> {
-        (**self).write_field_begin(identifier).await
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_field_stop()
     }
 
-    async fn write_field_end(&mut self) -> crate::Result<()> {
-        (**self).write_field_end().await
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        (**self).write_bool(b)
     }
 
-    async fn write_field_stop(&mut self) -> crate::Result<()> {
-        (**self).write_field_stop().await
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        (**self).write_bytes(b)
     }
 
-    async fn write_bool(&mut self, b: bool) -> crate::Result<()> {
-        (**self).write_bool(b).await
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        (**self).write_binary(b)
     }
 
-    async fn write_bytes(&mut self, b: &[u8]) -> crate::Result<()> {
-        (**self).write_bytes(b).await
+    fn write_string_or_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        (**self).write_string_or_binary(b)
     }
 
-    async fn write_i8(&mut self, i: i8) -> crate::Result<()> {
-        (**self).write_i8(i).await
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        (**self).write_i8(i)
     }
 
-    async fn write_i16(&mut self, i: i16) -> crate::Result<()> {
-        (**self).write_i16(i).await
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        (**self).write_i16(i)
     }
 
-    async fn write_i32(&mut self, i: i32) -> crate::Result<()> {
-        (**self).write_i32(i).await
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        (**self).write_i32(i)
     }
 
-    async fn write_i64(&mut self, i: i64) -> crate::Result<()> {
-        (**self).write_i64(i).await
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        (**self).write_i64(i)
     }
 
-    async fn write_double(&mut self, d: f64) -> crate::Result<()> {
-        (**self).write_double(d).await
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        (**self).write_double(d)
     }
 
-    async fn write_string(&mut self, s: &str) -> crate::Result<()> {
-        (**self).write_string(s).await
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        (**self).write_string(s)
     }
 
-    async fn write_list_begin(&mut self, identifier: &TListIdentifier) -> crate::Result<()> {
-        (**self).write_list_begin(identifier).await
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_list_begin(identifier)
     }
 
-    async fn write_list_end(&mut self) -> crate::Result<()> {
-        (**self).write_list_end().await
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_list_end()
     }
 
-    async fn write_set_begin(&mut self, identifier: &TSetIdentifier) -> crate::Result<()> {
-        (**self).write_set_begin(identifier).await
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_set_begin(identifier)
     }
 
-    async fn write_set_end(&mut self) -> crate::Result<()> {
-        (**self).write_set_end().await
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_set_end()
     }
 
-    async fn write_map_begin(&mut self, identifier: &TMapIdentifier) -> crate::Result<()> {
-        (**self).write_map_begin(identifier).await
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_map_begin(identifier)
     }
 
-    async fn write_map_end(&mut self) -> crate::Result<()> {
-        (**self).write_map_end().await
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_map_end()
     }
 
     async fn flush(&mut self) -> crate::Result<()> {
         (**self).flush().await
     }
 
-    async fn write_byte(&mut self, b: u8) -> crate::Result<()> {
-        (**self).write_byte(b).await
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        (**self).flush_coalesced().await
+    }
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        (**self).write_byte(b)
     }
 }
 
@@ -565,7 +652,7 @@ pub enum TMessageType {
 }
 
 /// Thrift struct-field types.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum TType {
     /// Indicates that there are no more serialized fields in this Thrift struct.
     Stop,
@@ -710,3 +797,282 @@ pub fn field_id(field_ident: &TFieldIdentifier) -> crate::Result<i16> {
         })
     })
 }
+
+
+/// Read one complete message from `i` and write it to `o`, field by field.
+///
+/// The message does not have to be understood: nested structs, containers
+/// and binary fields are copied as they are encountered. `i` and `o` may
+/// use different protocols, which makes this usable to re-encode messages.
+/// `o` is not flushed.
+///
+/// Return the identifier of the copied message if successful.
+pub async fn copy_message(
+    i: &mut (dyn TAsyncInputProtocol + Send),
+    o: &mut (dyn TAsyncOutputProtocol + Send),
+) -> crate::Result<TMessageIdentifier> {
+    let message_ident = i.read_message_begin().await?;
+    o.write_message_begin(&message_ident).await?;
+    copy_till_depth(i, o, TType::Struct, MAXIMUM_SKIP_DEPTH).await?;
+    i.read_message_end().await?;
+    o.write_message_end().await?;
+    Ok(message_ident)
+}
+
+/// Copy a field with type `field_type` from `i` to `o` recursively up to
+/// `depth` levels.
+pub fn copy_till_depth<'a>(
+    i: &'a mut (dyn TAsyncInputProtocol + Send),
+    o: &'a mut (dyn TAsyncOutputProtocol + Send),
+    field_type: TType,
+    depth: i8,
+) -> BoxFuture<'a, crate::Result<()>> {
+    async move {
+        visit_till_depth(i, &mut TCopyVisitor { o }, field_type, depth).await
+    }.boxed()
+}
+
+/// Receives the parts of a value as `visit_till_depth` reads them.
+///
+/// Every method does nothing by default, so the default visitor skips the
+/// value. Structs end with `visit_struct_end` after their last field; the
+/// stop field is not visited on its own.
+pub(crate) trait TValueVisitor: Send {
+    fn visit_bool(&mut self, _b: bool) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_i8(&mut self, _i: i8) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_i16(&mut self, _i: i16) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_i32(&mut self, _i: i32) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_i64(&mut self, _i: i64) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_double(&mut self, _d: f64) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    /// Strings and binary fields, which share a wire type.
+    fn visit_binary(&mut self, _b: Bytes) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_struct_begin<'a>(&'a mut self, _identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_field_begin<'a>(&'a mut self, _identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_list_begin<'a>(&'a mut self, _identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_set_begin<'a>(&'a mut self, _identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_map_begin<'a>(&'a mut self, _identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+    fn visit_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::ready(Ok(()))
+    }
+}
+
+/// Visitor that skips the value.
+struct TSkipVisitor;
+
+impl TValueVisitor for TSkipVisitor {}
+
+/// Visitor that writes the value to `o`.
+struct TCopyVisitor<'o> {
+    o: &'o mut (dyn TAsyncOutputProtocol + Send),
+}
+
+impl<'o> TValueVisitor for TCopyVisitor<'o> {
+    fn visit_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        self.o.write_bool(b)
+    }
+    fn visit_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.o.write_i8(i)
+    }
+    fn visit_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.o.write_i16(i)
+    }
+    fn visit_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.o.write_i32(i)
+    }
+    fn visit_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.o.write_i64(i)
+    }
+    fn visit_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        self.o.write_double(d)
+    }
+    fn visit_binary(&mut self, b: Bytes) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move { self.o.write_string_or_binary(&b).await })
+    }
+    fn visit_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        self.o.write_struct_begin(identifier)
+    }
+    fn visit_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        TProtocolFuture::boxed(async move {
+            self.o.write_field_stop().await?;
+            self.o.write_struct_end().await
+        })
+    }
+    fn visit_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        self.o.write_field_begin(identifier)
+    }
+    fn visit_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.o.write_field_end()
+    }
+    fn visit_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        self.o.write_list_begin(identifier)
+    }
+    fn visit_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.o.write_list_end()
+    }
+    fn visit_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        self.o.write_set_begin(identifier)
+    }
+    fn visit_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.o.write_set_end()
+    }
+    fn visit_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        if identifier.key_type.is_some() && identifier.value_type.is_some() {
+            return self.o.write_map_begin(identifier);
+        }
+        TProtocolFuture::boxed(async move {
+            self.o.write_map_begin(&with_map_types(identifier)).await
+        })
+    }
+    fn visit_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.o.write_map_end()
+    }
+}
+
+/// Return a copy of `identifier` whose missing key and value types are
+/// `TType::Stop`.
+///
+/// The compact protocol sends no types for empty maps, while the binary
+/// protocol cannot write a map without them. Like the other Thrift
+/// libraries, messages translated between them use `TType::Stop` for both.
+pub(crate) fn with_map_types(identifier: &TMapIdentifier) -> TMapIdentifier {
+    TMapIdentifier::new(
+        identifier.key_type.unwrap_or(TType::Stop),
+        identifier.value_type.unwrap_or(TType::Stop),
+        identifier.size,
+    )
+}
+
+/// Read a field with type `field_type` from `i` recursively up to `depth`
+/// levels, passing its parts to `visitor`.
+///
+/// This is the type dispatch shared by `skip_till_depth`,
+/// `copy_till_depth` and `async_value::read_value_till_depth`.
+pub(crate) fn visit_till_depth<'a, I, V>(
+    i: &'a mut I,
+    visitor: &'a mut V,
+    field_type: TType,
+    depth: i8,
+) -> BoxFuture<'a, crate::Result<()>>
+    where
+        I: TAsyncInputProtocol + ?Sized,
+        V: TValueVisitor + ?Sized,
+{
+    async move {
+        if depth == 0 {
+            return Err(crate::Error::Protocol(ProtocolError {
+                kind: ProtocolErrorKind::DepthLimit,
+                message: format!("cannot parse past {:?}", field_type),
+            }));
+        }
+
+        match field_type {
+            TType::Bool => visitor.visit_bool(i.read_bool().await?).await,
+            TType::I08 => visitor.visit_i8(i.read_i8().await?).await,
+            TType::I16 => visitor.visit_i16(i.read_i16().await?).await,
+            TType::I32 => visitor.visit_i32(i.read_i32().await?).await,
+            TType::I64 => visitor.visit_i64(i.read_i64().await?).await,
+            TType::Double => visitor.visit_double(i.read_double().await?).await,
+            // binary fields share the string type, so do not require UTF-8
+            TType::String => visitor.visit_binary(i.read_string_or_binary().await?).await,
+            TType::Struct => {
+                let struct_ident = i
+                    .read_struct_begin()
+                    .await?
+                    .unwrap_or_else(|| TStructIdentifier::new(""));
+                visitor.visit_struct_begin(&struct_ident).await?;
+                loop {
+                    let field_ident = i.read_field_begin().await?;
+                    if field_ident.field_type == TType::Stop {
+                        break;
+                    }
+                    visitor.visit_field_begin(&field_ident).await?;
+                    visit_till_depth(i, visitor, field_ident.field_type, depth - 1).await?;
+                    i.read_field_end().await?;
+                    visitor.visit_field_end().await?;
+                }
+                i.read_struct_end().await?;
+                visitor.visit_struct_end().await
+            }
+            TType::List => {
+                let list_ident = i.read_list_begin().await?;
+                visitor.visit_list_begin(&list_ident).await?;
+                for _ in 0..list_ident.size {
+                    visit_till_depth(i, visitor, list_ident.element_type, depth - 1).await?;
+                }
+                i.read_list_end().await?;
+                visitor.visit_list_end().await
+            }
+            TType::Set => {
+                let set_ident = i.read_set_begin().await?;
+                visitor.visit_set_begin(&set_ident).await?;
+                for _ in 0..set_ident.size {
+                    visit_till_depth(i, visitor, set_ident.element_type, depth - 1).await?;
+                }
+                i.read_set_end().await?;
+                visitor.visit_set_end().await
+            }
+            TType::Map => {
+                let map_ident = i.read_map_begin().await?;
+                let types = match (map_ident.key_type, map_ident.value_type) {
+                    (Some(key_type), Some(value_type)) => Some((key_type, value_type)),
+                    _ if map_ident.size > 0 => {
+                        return Err(crate::Error::Protocol(ProtocolError {
+                            kind: ProtocolErrorKind::InvalidData,
+                            message: "non-zero sized map without key or value type".to_owned(),
+                        }))
+                    }
+                    _ => None,
+                };
+                visitor.visit_map_begin(&map_ident).await?;
+                if let Some((key_type, value_type)) = types {
+                    for _ in 0..map_ident.size {
+                        visit_till_depth(i, visitor, key_type, depth - 1).await?;
+                        visit_till_depth(i, visitor, value_type, depth - 1).await?;
+                    }
+                }
+                i.read_map_end().await?;
+                visitor.visit_map_end().await
+            }
+            u => Err(crate::Error::Protocol(ProtocolError {
+                kind: ProtocolErrorKind::Unknown,
+                message: format!("cannot read field type {:?}", &u),
+            })),
+        }
+    }.boxed()
+}
@@ -1,20 +1,37 @@
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use async_std::{
-    net::{SocketAddr, TcpListener, TcpStream},
+    net::{TcpListener, TcpStream},
     prelude::*,
+    sync::Mutex,
     task,
 };
-use socket2::{Domain, Socket, Type};
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
+use futures::FutureExt;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
 
 use crate::{ApplicationError, ApplicationErrorKind};
-use crate::errors::TransportErrorKind;
-use crate::protocol::{TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory};
+use crate::errors::{new_protocol_error, new_transport_error, ProtocolErrorKind, TransportErrorKind};
+use crate::protocol::{copy_message, TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory};
+use crate::protocol::{TMessageIdentifier, TMessageType};
+use crate::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+use crate::protocol::async_metadata::TAsyncMetadataInputProtocol;
 use crate::transport::{TAsyncReadTransportFactory, TAsyncWriteTransportFactory};
+use crate::transport::async_mem::TAsyncBufferChannel;
 use crate::transport::async_socket::TAsyncTcpChannel;
 use crate::transport::TAsyncIoChannel;
 
-use super::TAsyncProcessor;
+use super::listener::TAsyncListenerBuilder;
+use super::metrics::{TErrorClass, TServerMetrics};
+use super::observed::{TObservedInputProtocol, TObservedOutputProtocol};
+use super::{handle_process_result, TAsyncProcessor};
 
 pub struct TAsyncServer<PRC, RTF, IPF, WTF, OPF>
     where
@@ -24,10 +41,8 @@ pub struct TAsyncServer<PRC, RTF, IPF, WTF, OPF>
         WTF: TAsyncWriteTransportFactory + 'static,
         OPF: TAsyncOutputProtocolFactory + 'static,
 {
-    r_trans_factory: RTF,
-    i_proto_factory: IPF,
-    w_trans_factory: WTF,
-    o_proto_factory: OPF,
+    factories: Arc<TConnectionFactories<RTF, IPF, WTF, OPF>>,
+    options: TConnectionOptions,
     async_processor: Arc<PRC>,
 }
 
@@ -54,55 +69,204 @@ impl<PRC, RTF, IPF, WTF, OPF> TAsyncServer<PRC, RTF, IPF, WTF, OPF>
         async_processor: PRC,
     ) -> TAsyncServer<PRC, RTF, IPF, WTF, OPF> {
         TAsyncServer {
-            r_trans_factory: read_transport_factory,
-            i_proto_factory: input_protocol_factory,
-            w_trans_factory: write_transport_factory,
-            o_proto_factory: output_protocol_factory,
+            factories: Arc::new(TConnectionFactories {
+                r_trans_factory: read_transport_factory,
+                i_proto_factory: input_protocol_factory,
+                w_trans_factory: write_transport_factory,
+                o_proto_factory: output_protocol_factory,
+            }),
+            options: TConnectionOptions::default(),
             async_processor: Arc::new(async_processor),
         }
     }
+
+    /// Keep reading requests from a connection while earlier requests are
+    /// still being handled, running at most `max_in_flight` of them
+    /// concurrently.
+    ///
+    /// Each request is buffered in memory before it is handed to the
+    /// processor. Replies are written through the connection's output
+    /// protocol as soon as their handler finishes, so they may reach the
+    /// client in a different order than the requests were sent; clients
+    /// match them by sequence number.
+    ///
+    /// While other replies are waiting to be written, a reply is flushed
+    /// with `flush_coalesced`, so that a write transport created with
+    /// `TAsyncFramedWriteTransportFactory::with_coalescing` sends them with
+    /// one write.
+    pub fn with_pipelining(mut self, max_in_flight: usize) -> TAsyncServer<PRC, RTF, IPF, WTF, OPF> {
+        assert!(
+            max_in_flight > 0,
+            "pipelined requests limit must be a positive integer"
+        );
+
+        self.options.max_pipelined_requests = Some(max_in_flight);
+        self
+    }
+
+    /// Extract request metadata sent by `TAsyncMetadataOutputProtocol`
+    /// clients from every call.
+    ///
+    /// Handlers can read the metadata of the call they are serving with
+    /// `async_metadata::current_request_metadata`. With the `tracing`
+    /// feature, the trace context it carries is recorded on the call span.
+    pub fn with_request_metadata(mut self) -> TAsyncServer<PRC, RTF, IPF, WTF, OPF> {
+        self.options.request_metadata = true;
+        self
+    }
+
+    /// Report connection and request events to `metrics`.
+    ///
+    /// See `metrics::TPrometheusMetrics` for a recorder that can be scraped
+    /// by Prometheus.
+    pub fn with_metrics(mut self, metrics: Arc<dyn TServerMetrics>) -> TAsyncServer<PRC, RTF, IPF, WTF, OPF> {
+        self.options.metrics = Some(metrics);
+        self
+    }
+
     /// Listen for incoming connections on `listen_address`.
     ///
-    /// `listen_address` should implement `ToSocketAddrs` trait.
+    /// `listen_address` must be a literal socket address such as
+    /// `127.0.0.1:9090` or `[::1]:9090`. The listener uses default socket
+    /// options; use `listen_with` to configure them.
     ///
     /// Return `()` if successful.
     ///
     /// Return `Err` when the server cannot bind to `listen_address` or there
     /// is an unrecoverable error.
     pub async fn listen(&mut self, listen_address: &str) -> crate::Result<()> {
-        let socket = Socket::new(Domain::ipv4(), Type::stream(), None).unwrap();
-        socket.bind(&listen_address.parse::<SocketAddr>().unwrap().into());
-        socket.listen(1024);
+        self.listen_with(&TAsyncListenerBuilder::new(listen_address)).await
+    }
+
+    /// Listen for incoming connections on a socket created by `builder`.
+    ///
+    /// Return `Err` when the listening socket cannot be created or there
+    /// is an unrecoverable error.
+    pub async fn listen_with(&mut self, builder: &TAsyncListenerBuilder) -> crate::Result<()> {
+        let listener = builder.bind()?;
+        accept_loop(listener, builder, self.factories.clone(), self.options.clone(), self.async_processor.clone()).await
+    }
+
+    /// Listen for incoming connections on `acceptors` sockets bound to the
+    /// same address with `SO_REUSEPORT`.
+    ///
+    /// Each socket gets its own accept loop running on a dedicated thread,
+    /// so the kernel spreads new connections across acceptors instead of
+    /// funnelling them through a single accept loop. Accepted connections
+    /// are served on the shared task pool by the same processor.
+    ///
+    /// Return `Err` when any listening socket cannot be created, or as soon
+    /// as one of the accept loops fails. The other accept loops are stopped
+    /// and their sockets closed before this returns. Dropping the returned
+    /// future stops all accept loops as well.
+    pub async fn listen_reuseport(&mut self, builder: &TAsyncListenerBuilder, acceptors: usize) -> crate::Result<()>
+        where
+            RTF: Send + Sync,
+            IPF: Send + Sync,
+            WTF: Send + Sync,
+            OPF: Send + Sync,
+    {
+        if acceptors == 0 {
+            return Err(new_transport_error(
+                TransportErrorKind::Unknown,
+                "at least one acceptor is required",
+            ));
+        }
 
-        let listener = socket.into_tcp_listener();
-        let async_listener = TcpListener::from(listener);
+        let builder = builder.clone().reuse_port(true);
 
-        let mut incoming = async_listener.incoming();
+        // bind every socket up front so that address errors reach the caller
+        let mut listeners = Vec::with_capacity(acceptors);
+        for _ in 0..acceptors {
+            listeners.push(builder.bind()?);
+        }
 
-        // let mut count = 0;
-        while let Some(stream) = incoming.next().await {
-            // stream is a new tcp connection stream
-            let stream = stream?;
-            // println!("Accepting from: {}", stream.peer_addr()?);
-            // new tcp reader thread
+        let (sender, mut receiver) = mpsc::unbounded();
+        // dropping a stop sender ends the accept loop of its acceptor
+        let mut stop_senders = Vec::with_capacity(acceptors);
+        let mut threads = Vec::with_capacity(acceptors);
+        let mut res = Ok(());
+        for (index, listener) in listeners.into_iter().enumerate() {
+            let builder = builder.clone();
+            let factories = self.factories.clone();
+            let options = self.options.clone();
+            let processor = self.async_processor.clone();
+            let sender = sender.clone();
+            let (stop_sender, stop) = oneshot::channel::<()>();
+            let spawned = thread::Builder::new()
+                .name(format!("thrift-acceptor-{}", index))
+                .spawn(move || {
+                    let res = task::block_on(async {
+                        let accept = Box::pin(accept_loop(listener, &builder, factories, options, processor));
+                        match future::select(accept, stop).await {
+                            Either::Left((res, _)) => res,
+                            Either::Right(_) => Ok(()),
+                        }
+                    });
+                    let _ = sender.unbounded_send(res);
+                });
+            match spawned {
+                Ok(thread) => {
+                    stop_senders.push(stop_sender);
+                    threads.push(thread);
+                }
+                Err(e) => {
+                    res = Err(e.into());
+                    break;
+                }
+            }
+        }
+        drop(sender);
 
-            let (read_protocol, write_protocol) = self.new_protocols_for_connection(stream).await?;
-            task::spawn(handle_incoming_connection_server(
-                self.async_processor.clone(), read_protocol, write_protocol));
-            // count += 1;
-            // println!("{}", count);
+        if res.is_ok() {
+            res = match receiver.next().await {
+                Some(res) => res,
+                None => Err(aborted_listen_loop()),
+            };
         }
 
-        Err(crate::Error::Application(ApplicationError {
-            kind: ApplicationErrorKind::Unknown,
-            message: "aborted listen loop".into(),
-        }))
+        // stop the other acceptors, which report back once their listening
+        // socket is closed
+        drop(stop_senders);
+        while receiver.next().await.is_some() {}
+        for thread in threads {
+            let _ = thread.join();
+        }
+        res
     }
+}
+
+/// Per-connection behaviour of a `TAsyncServer`.
+#[derive(Clone, Default)]
+struct TConnectionOptions {
+    /// Maximum number of requests handled concurrently on one connection,
+    /// or `None` to handle them one after another.
+    max_pipelined_requests: Option<usize>,
+    /// Recorder notified of connection and request events.
+    metrics: Option<Arc<dyn TServerMetrics>>,
+    /// Whether request metadata is extracted from incoming calls.
+    request_metadata: bool,
+}
+
+/// Factories used to build the protocol stack of every accepted connection.
+struct TConnectionFactories<RTF, IPF, WTF, OPF> {
+    r_trans_factory: RTF,
+    i_proto_factory: IPF,
+    w_trans_factory: WTF,
+    o_proto_factory: OPF,
+}
 
+impl<RTF, IPF, WTF, OPF> TConnectionFactories<RTF, IPF, WTF, OPF>
+    where
+        RTF: TAsyncReadTransportFactory,
+        IPF: TAsyncInputProtocolFactory,
+        WTF: TAsyncWriteTransportFactory,
+        OPF: TAsyncOutputProtocolFactory,
+{
     /// build io channel for connection
     /// return input channel and output channel
-    async fn new_protocols_for_connection(
-        &mut self,
+    fn new_protocols_for_connection(
+        &self,
         stream: TcpStream,
     ) -> crate::Result<(Box<dyn TAsyncInputProtocol + Send>, Box<dyn TAsyncOutputProtocol + Send>)> {
         // create the shared tcp stream
@@ -124,27 +288,716 @@ impl<PRC, RTF, IPF, WTF, OPF> TAsyncServer<PRC, RTF, IPF, WTF, OPF>
     }
 }
 
+/// Pause after a failed accept before accepting again.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(10);
+
+/// accept connections from `listener` and spawn a task serving each of them
+async fn accept_loop<PRC, RTF, IPF, WTF, OPF>(
+    listener: TcpListener,
+    builder: &TAsyncListenerBuilder,
+    factories: Arc<TConnectionFactories<RTF, IPF, WTF, OPF>>,
+    options: TConnectionOptions,
+    processor: Arc<PRC>,
+) -> crate::Result<()>
+    where
+        PRC: TAsyncProcessor + Send + Sync + 'static,
+        RTF: TAsyncReadTransportFactory,
+        IPF: TAsyncInputProtocolFactory,
+        WTF: TAsyncWriteTransportFactory,
+        OPF: TAsyncOutputProtocolFactory,
+{
+    let mut incoming = listener.incoming();
+
+    while let Some(stream) = incoming.next().await {
+        // stream is a new tcp connection stream; a connection that fails
+        // to be set up is dropped without stopping the loop
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("failed to accept connection: {:?}", e);
+                // errors such as EMFILE persist for a while, don't spin on them
+                task::sleep(ACCEPT_ERROR_DELAY).await;
+                continue;
+            }
+        };
+        if let Err(e) = builder.configure_stream(&stream) {
+            warn!("failed to configure accepted connection: {:?}", e);
+            continue;
+        }
+
+        #[cfg(feature = "tracing")]
+        let peer_address = stream.peer_addr().ok();
+
+        let (read_protocol, write_protocol) = match factories.new_protocols_for_connection(stream) {
+            Ok(protocols) => protocols,
+            Err(e) => {
+                warn!("failed to create protocols for accepted connection: {:?}", e);
+                continue;
+            }
+        };
+        let processor = processor.clone();
+        let connection_options = options.clone();
+        if let Some(ref metrics) = connection_options.metrics {
+            metrics.connection_opened();
+        }
+        let connection = async move {
+            let options = connection_options;
+            match options.max_pipelined_requests {
+                Some(max_in_flight) => {
+                    handle_pipelined_connection_server(
+                        processor, read_protocol, write_protocol, max_in_flight, &options).await;
+                }
+                None => {
+                    handle_incoming_connection_server(
+                        processor, read_protocol, write_protocol, &options).await;
+                }
+            }
+            if let Some(ref metrics) = options.metrics {
+                metrics.connection_closed();
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let connection = connection.instrument(tracing::info_span!("thrift.connection", peer = ?peer_address));
+        task::spawn(connection);
+    }
+
+    Err(aborted_listen_loop())
+}
+
+fn aborted_listen_loop() -> crate::Error {
+    crate::Error::Application(ApplicationError {
+        kind: ApplicationErrorKind::Unknown,
+        message: "aborted listen loop".into(),
+    })
+}
+
+
+fn is_end_of_file(err: &crate::Error) -> bool {
+    match err {
+        crate::Error::Transport(ref transport_err) => transport_err.kind == TransportErrorKind::EndOfFile,
+        _ => false,
+    }
+}
+
+/// span covering the processing of one request
+#[cfg(feature = "tracing")]
+fn call_span(message_ident: &TMessageIdentifier) -> tracing::Span {
+    tracing::info_span!(
+        "thrift.call",
+        method = %message_ident.name,
+        seqid = message_ident.sequence_number,
+        trace_id = tracing::field::Empty,
+        parent_span_id = tracing::field::Empty,
+    )
+}
 
 /// handle one connection using processor
 async fn handle_incoming_connection_server<PRC>(
     processor: Arc<PRC>,
     i_prot: Box<dyn TAsyncInputProtocol + Send>,
     o_prot: Box<dyn TAsyncOutputProtocol + Send>,
+    options: &TConnectionOptions,
 ) where
     PRC: TAsyncProcessor,
 {
-    let mut i_prot = i_prot;
+    let mut i_prot: Box<dyn TAsyncInputProtocol + Send> = if options.request_metadata {
+        Box::new(TAsyncMetadataInputProtocol::new(i_prot))
+    } else {
+        i_prot
+    };
     let mut o_prot = o_prot;
     loop {
-        match processor.process(&mut *i_prot, &mut *o_prot).await {
+        let res = process_observed_request(&*processor, &mut *i_prot, &mut *o_prot, options.metrics.as_deref()).await;
+        match res {
             Ok(()) => {}
             Err(err) => {
-                match err {
-                    crate::Error::Transport(ref transport_err) if transport_err.kind == TransportErrorKind::EndOfFile => {}
-                    other => warn!("processor completed with error: {:?}", other),
+                if !is_end_of_file(&err) {
+                    warn!("processor completed with error: {:?}", err);
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// process one request from `i_prot`, reporting it to `metrics` and, with
+/// the `tracing` feature, running it in its own span
+///
+/// a panicking handler is answered with an `InternalError` exception; see
+/// `finish_request` for when the connection is kept after a failure
+async fn process_observed_request<PRC>(
+    processor: &PRC,
+    i_prot: &mut (dyn TAsyncInputProtocol + Send),
+    o_prot: &mut (dyn TAsyncOutputProtocol + Send),
+    metrics: Option<&dyn TServerMetrics>,
+) -> crate::Result<()>
+    where
+        PRC: TAsyncProcessor,
+{
+    // read the header up front so that waiting for the next request is not
+    // counted as processing time
+    let message_ident = match i_prot.read_message_begin().await {
+        Ok(message_ident) => message_ident,
+        Err(err) => {
+            // a connection closed between requests is not a failed request
+            if let (Some(metrics), false) = (metrics, is_end_of_file(&err)) {
+                metrics.request_completed("", Duration::default(), Some(TErrorClass::of(&err)));
+            }
+            return Err(err);
+        }
+    };
+    let started = Instant::now();
+    let method = message_ident.name.clone();
+
+    #[cfg(feature = "tracing")]
+    let span = call_span(&message_ident);
+    let mut i = TObservedInputProtocol::new(i_prot, message_ident.clone());
+    let mut o = TObservedOutputProtocol::new(o_prot);
+    let process = async {
+        let res = match AssertUnwindSafe(processor.process(&mut i, &mut o)).catch_unwind().await {
+            Ok(res) => res,
+            Err(panic) => {
+                log_handler_panic(&message_ident, &*panic);
+                Err(handler_panic_error(&message_ident))
+            }
+        };
+        let error = request_error(res.as_ref().err(), o.message_type());
+        (finish_request(&message_ident, res, &mut i, &mut o).await, error)
+    };
+    #[cfg(feature = "tracing")]
+    let process = process.instrument(span);
+    let (res, error) = process.await;
+
+    if let Some(metrics) = metrics {
+        metrics.request_completed(&method, started.elapsed(), error);
+    }
+    res
+}
+
+/// get the connection ready for the next request once `res`, the result of
+/// processing a request, is known
+///
+/// application and user errors, and protocol errors raised after the
+/// arguments were read, are answered with an exception unless the processor
+/// already replied, and arguments the processor did not read are skipped.
+/// transport errors and failures that leave the request or the reply half
+/// read or written are returned, so that the connection is closed
+async fn finish_request(
+    message_ident: &TMessageIdentifier,
+    res: crate::Result<()>,
+    i: &mut TObservedInputProtocol<'_>,
+    o: &mut TObservedOutputProtocol<'_>,
+) -> crate::Result<()> {
+    let err = match res {
+        Ok(()) => {
+            return if i.skip_unread_args().await? {
+                Ok(())
+            } else {
+                Err(new_protocol_error(
+                    ProtocolErrorKind::InvalidData,
+                    format!("arguments of {} were only partly read", message_ident.name),
+                ))
+            };
+        }
+        Err(err @ crate::Error::Transport(_)) => return Err(err),
+        Err(err) => err,
+    };
+
+    if !i.skip_unread_args().await? || o.message_type().is_some() {
+        return Err(err);
+    }
+    warn!("call to {} failed: {:?}", message_ident.name, err);
+    if message_ident.message_type == TMessageType::OneWay {
+        return Ok(());
+    }
+    handle_process_result(message_ident, Err(err), o).await
+}
+
+/// error returned to the caller of a request whose handler panicked
+fn handler_panic_error(message_ident: &TMessageIdentifier) -> crate::Error {
+    crate::Error::Application(ApplicationError::new(
+        ApplicationErrorKind::InternalError,
+        format!("handler for {} panicked", message_ident.name),
+    ))
+}
+
+fn log_handler_panic(message_ident: &TMessageIdentifier, panic: &(dyn Any + Send)) {
+    let reason = if let Some(reason) = panic.downcast_ref::<&str>() {
+        reason
+    } else if let Some(reason) = panic.downcast_ref::<String>() {
+        reason.as_str()
+    } else {
+        "unknown cause"
+    };
+    error!("handler for {} (seqid {}) panicked: {}", message_ident.name, message_ident.sequence_number, reason);
+}
+
+/// classify the outcome of a request from the processor result and the type
+/// of the reply it wrote
+fn request_error(err: Option<&crate::Error>, reply_type: Option<TMessageType>) -> Option<TErrorClass> {
+    match err {
+        Some(err) => Some(TErrorClass::of(err)),
+        None if reply_type == Some(TMessageType::Exception) => Some(TErrorClass::Application),
+        None => None,
+    }
+}
+
+/// handle one connection using processor, running up to `max_in_flight`
+/// requests concurrently
+async fn handle_pipelined_connection_server<PRC>(
+    processor: Arc<PRC>,
+    i_prot: Box<dyn TAsyncInputProtocol + Send>,
+    o_prot: Box<dyn TAsyncOutputProtocol + Send>,
+    max_in_flight: usize,
+    options: &TConnectionOptions,
+) where
+    PRC: TAsyncProcessor + Send + Sync + 'static,
+{
+    let mut i_prot = i_prot;
+    // replies from concurrent handlers are serialized through this writer
+    let writer = Arc::new(TReplyWriter {
+        o_prot: Mutex::new(o_prot),
+        waiting: AtomicUsize::new(0),
+    });
+    let (done_sender, mut done_receiver) = mpsc::unbounded::<()>();
+    let mut in_flight = 0;
+
+    loop {
+        // completions queue up in the channel, so this only blocks while
+        // `max_in_flight` handlers are actually running
+        while in_flight >= max_in_flight {
+            // we hold a sender, so the stream never ends here
+            done_receiver.next().await;
+            in_flight -= 1;
+        }
+
+        let (message_ident, request) = match read_buffered_request(&mut *i_prot).await {
+            Ok(request) => request,
+            Err(err) => {
+                if !is_end_of_file(&err) {
+                    warn!("failed to read pipelined request: {:?}", err);
+                }
+                break;
+            }
+        };
+
+        in_flight += 1;
+        let processor = processor.clone();
+        let writer = writer.clone();
+        let done_sender = done_sender.clone();
+        let metrics = options.metrics.clone();
+        let request_metadata = options.request_metadata;
+        let started = Instant::now();
+        #[cfg(feature = "tracing")]
+        let span = call_span(&message_ident);
+        let request = async move {
+            let res = process_buffered_request(&*processor, &message_ident, request, &writer, request_metadata).await;
+            if let Some(metrics) = metrics {
+                let error = match res {
+                    Ok(error) => error,
+                    Err(ref err) => Some(TErrorClass::of(err)),
+                };
+                metrics.request_completed(&message_ident.name, started.elapsed(), error);
+            }
+            if let Err(err) = res {
+                warn!("failed to write pipelined reply: {:?}", err);
+            }
+            let _ = done_sender.unbounded_send(());
+        };
+        #[cfg(feature = "tracing")]
+        let request = request.instrument(span);
+        task::spawn(request);
+    }
+}
+
+/// read one request message from `i_prot` into an in-memory binary buffer
+async fn read_buffered_request(
+    i_prot: &mut (dyn TAsyncInputProtocol + Send),
+) -> crate::Result<(TMessageIdentifier, Vec<u8>)> {
+    let mut request = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+    let message_ident = copy_message(i_prot, &mut request).await?;
+    Ok((message_ident, request.transport.take_write_bytes()))
+}
+
+/// output of a pipelined connection, shared by the handlers of its requests
+struct TReplyWriter {
+    o_prot: Mutex<Box<dyn TAsyncOutputProtocol + Send>>,
+    // replies waiting for `o_prot`
+    waiting: AtomicUsize,
+}
+
+impl TReplyWriter {
+    /// write `reply`, a message in the binary protocol, to the connection
+    ///
+    /// while other replies wait for the connection the reply is only
+    /// flushed with `flush_coalesced`, so that a transport coalescing
+    /// frames sends them together once the last of them is written
+    async fn write_reply(&self, reply: Vec<u8>) -> crate::Result<TMessageIdentifier> {
+        let mut reply = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(reply), true);
+        self.waiting.fetch_add(1, Ordering::AcqRel);
+        let mut o_prot = self.o_prot.lock().await;
+        self.waiting.fetch_sub(1, Ordering::AcqRel);
+
+        let res = copy_message(&mut reply, &mut **o_prot).await;
+        // a failed reply is flushed too, so that none held back before it
+        // stays unsent
+        let flushed = if res.is_ok() && self.waiting.load(Ordering::Acquire) > 0 {
+            o_prot.flush_coalesced().await
+        } else {
+            o_prot.flush().await
+        };
+        let reply_ident = res?;
+        flushed?;
+        Ok(reply_ident)
+    }
+}
+
+/// process a request buffered by `read_buffered_request` and write the reply,
+/// if any, to the shared connection writer
+///
+/// the request is read from memory, so a failed or panicking processor only
+/// affects its own call: the failure is answered with an exception instead of
+/// the reply
+///
+/// return the class of error the request failed with, if any, or `Err` if
+/// the reply could not be written to the connection
+async fn process_buffered_request<PRC>(
+    processor: &PRC,
+    message_ident: &TMessageIdentifier,
+    request: Vec<u8>,
+    writer: &TReplyWriter,
+    request_metadata: bool,
+) -> crate::Result<Option<TErrorClass>>
+    where
+        PRC: TAsyncProcessor,
+{
+    let mut i = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(request), true);
+    let mut o = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+    let outcome = if request_metadata {
+        AssertUnwindSafe(processor.process(&mut TAsyncMetadataInputProtocol::new(i), &mut o)).catch_unwind().await
+    } else {
+        AssertUnwindSafe(processor.process(&mut i, &mut o)).catch_unwind().await
+    };
+    let res = match outcome {
+        Ok(res) => res,
+        Err(panic) => {
+            log_handler_panic(message_ident, &*panic);
+            Err(handler_panic_error(message_ident))
+        }
+    };
+    let error = match res {
+        Ok(()) => None,
+        Err(err) => {
+            warn!("call to {} failed: {:?}", message_ident.name, err);
+            let error = TErrorClass::of(&err);
+            // replace whatever part of the reply was written
+            o = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+            if message_ident.message_type != TMessageType::OneWay {
+                handle_process_result(message_ident, Err(err), &mut o).await?;
+            }
+            Some(error)
+        }
+    };
+
+    // oneway calls produce no reply
+    let reply = o.transport.take_write_bytes();
+    if reply.is_empty() {
+        return Ok(error);
+    }
+
+    let reply_ident = writer.write_reply(reply).await?;
+    Ok(error.or_else(|| request_error(None, Some(reply_ident.message_type))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Mutex as StdMutex;
+
+    use async_trait::async_trait;
+
+    use crate::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+    use crate::protocol::async_compact::{TAsyncCompactInputProtocol, TAsyncCompactOutputProtocol};
+    use crate::protocol::{TFieldIdentifier, TMapIdentifier, TStructIdentifier, TType};
+    use crate::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+    use crate::transport::AsyncWrite;
+
+    use super::*;
+
+    /// Connection output that can be inspected while the server writes to it.
+    #[derive(Clone, Default)]
+    struct TSharedSink {
+        bytes: Arc<StdMutex<Vec<u8>>>,
+        flushes: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl AsyncWrite for TSharedSink {
+        async fn write(&mut self, b: &[u8]) -> io::Result<usize> {
+            self.bytes.lock().unwrap().extend_from_slice(b);
+            Ok(b.len())
+        }
+
+        async fn flush(&mut self) -> io::Result<()> {
+            self.flushes.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    /// Processor replying with the delay it was asked to wait, in ms, after
+    /// waiting it. Calls to `panic` panic instead.
+    struct TDelayProcessor;
+
+    #[async_trait]
+    impl TAsyncProcessor for TDelayProcessor {
+        async fn process(&self, i: &mut (dyn TAsyncInputProtocol + Send), o: &mut (dyn TAsyncOutputProtocol + Send)) -> crate::Result<()> {
+            let message_ident = i.read_message_begin().await?;
+            i.read_struct_begin().await?;
+            i.read_field_begin().await?;
+            let delay = i.read_i32().await?;
+            i.read_field_end().await?;
+            loop {
+                let field_ident = i.read_field_begin().await?;
+                if field_ident.field_type == TType::Stop {
+                    break;
+                }
+                i.skip(field_ident.field_type).await?;
+                i.read_field_end().await?;
+            }
+            i.read_struct_end().await?;
+            i.read_message_end().await?;
+            if message_ident.name == "panic" {
+                panic!("handler failed");
+            }
+
+            task::sleep(Duration::from_millis(delay as u64)).await;
+            let reply_ident = TMessageIdentifier::new(message_ident.name.as_str(), TMessageType::Reply, message_ident.sequence_number);
+            o.write_message_begin(&reply_ident).await?;
+            o.write_struct_begin(&TStructIdentifier::new("result")).await?;
+            o.write_field_begin(&TFieldIdentifier::new("success", TType::I32, 0)).await?;
+            o.write_i32(delay).await?;
+            o.write_field_end().await?;
+            o.write_field_stop().await?;
+            o.write_struct_end().await?;
+            o.write_message_end().await?;
+            o.flush().await
+        }
+    }
+
+    fn request(method: &str, seqid: i32, delay: i32) -> Vec<u8> {
+        task::block_on(async {
+            let mut o = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+            o.write_message_begin(&TMessageIdentifier::new(method, TMessageType::Call, seqid)).await?;
+            o.write_struct_begin(&TStructIdentifier::new("args")).await?;
+            o.write_field_begin(&TFieldIdentifier::new("delay", TType::I32, 1)).await?;
+            o.write_i32(delay).await?;
+            o.write_field_end().await?;
+            o.write_field_stop().await?;
+            o.write_struct_end().await?;
+            o.write_message_end().await?;
+            Ok::<_, crate::Error>(o.transport.take_write_bytes())
+        }).unwrap()
+    }
+
+    /// Read every reply in `bytes` as its seqid, type and returned delay.
+    fn replies(bytes: Vec<u8>) -> Vec<(i32, TMessageType, Option<i32>)> {
+        task::block_on(async {
+            let mut i = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+            let mut replies = Vec::new();
+            while i.transport.remaining() > 0 {
+                let message_ident = i.read_message_begin().await?;
+                let delay = if message_ident.message_type == TMessageType::Reply {
+                    i.read_struct_begin().await?;
+                    i.read_field_begin().await?;
+                    let delay = i.read_i32().await?;
+                    i.read_field_end().await?;
+                    i.read_field_begin().await?;
+                    i.read_struct_end().await?;
+                    Some(delay)
+                } else {
+                    i.skip(TType::Struct).await?;
+                    None
+                };
+                i.read_message_end().await?;
+                replies.push((message_ident.sequence_number, message_ident.message_type, delay));
+            }
+            Ok::<_, crate::Error>(replies)
+        }).unwrap()
+    }
+
+    #[test]
+    fn must_answer_panicking_handler_and_keep_connection() {
+        let mut requests = request("fast", 1, 0);
+        requests.extend(request("panic", 2, 0));
+        requests.extend(request("fast", 3, 5));
+        let sink = TSharedSink::default();
+
+        task::block_on(async {
+            let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(requests), true);
+            let o_prot = TAsyncBinaryOutputProtocol::new(sink.clone(), true);
+            let options = TConnectionOptions::default();
+            handle_incoming_connection_server(Arc::new(TDelayProcessor), Box::new(i_prot), Box::new(o_prot), &options).await;
+        });
+
+        assert_eq!(replies(sink.bytes.lock().unwrap().clone()), vec![
+            (1, TMessageType::Reply, Some(0)),
+            (2, TMessageType::Exception, None),
+            (3, TMessageType::Reply, Some(5)),
+        ]);
+    }
+
+    #[test]
+    fn must_match_pipelined_replies_by_seqid() {
+        let mut requests = request("slow", 1, 200);
+        requests.extend(request("fast", 2, 0));
+        requests.extend(request("panic", 3, 0));
+        requests.extend(request("fast", 4, 10));
+        let sink = TSharedSink::default();
+
+        task::block_on(async {
+            let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(requests), true);
+            let o_prot = TAsyncBinaryOutputProtocol::new(sink.clone(), true);
+            let options = TConnectionOptions::default();
+            handle_pipelined_connection_server(Arc::new(TDelayProcessor), Box::new(i_prot), Box::new(o_prot), 4, &options).await;
+
+            // the connection is read to the end before the handlers finish
+            for _ in 0..500 {
+                if sink.flushes.load(Ordering::SeqCst) == 4 {
+                    break;
+                }
+                task::sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        let replies = replies(sink.bytes.lock().unwrap().clone());
+        assert_eq!(replies.len(), 4);
+        // the slow call is answered last, after the calls sent behind it
+        assert_eq!(replies[3], (1, TMessageType::Reply, Some(200)));
+        for (seqid, message_type, delay) in replies {
+            match seqid {
+                1 => assert_eq!(delay, Some(200)),
+                2 => assert_eq!(delay, Some(0)),
+                3 => assert_eq!(message_type, TMessageType::Exception),
+                4 => assert_eq!(delay, Some(10)),
+                _ => panic!("unexpected seqid {}", seqid),
+            }
+        }
+    }
+
+    #[test]
+    fn must_pipeline_compact_requests_with_empty_maps() {
+        // the compact protocol sends no key and value types for the empty
+        // map, which the request buffered in the binary protocol needs
+        let requests = task::block_on(async {
+            let mut o = TAsyncCompactOutputProtocol::new(TAsyncBufferChannel::new());
+            for seqid in 1..=2 {
+                o.write_message_begin(&TMessageIdentifier::new("fast", TMessageType::Call, seqid)).await?;
+                o.write_struct_begin(&TStructIdentifier::new("args")).await?;
+                o.write_field_begin(&TFieldIdentifier::new("delay", TType::I32, 1)).await?;
+                o.write_i32(0).await?;
+                o.write_field_end().await?;
+                o.write_field_begin(&TFieldIdentifier::new("labels", TType::Map, 2)).await?;
+                o.write_map_begin(&TMapIdentifier::new(TType::String, TType::String, 0)).await?;
+                o.write_map_end().await?;
+                o.write_field_end().await?;
+                o.write_field_stop().await?;
+                o.write_struct_end().await?;
+                o.write_message_end().await?;
+            }
+            Ok::<_, crate::Error>(o.transport.take_write_bytes())
+        }).unwrap();
+        let sink = TSharedSink::default();
+
+        let replies = task::block_on(async {
+            let i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(requests));
+            let o_prot = TAsyncCompactOutputProtocol::new(sink.clone());
+            let options = TConnectionOptions::default();
+            handle_pipelined_connection_server(Arc::new(TDelayProcessor), Box::new(i_prot), Box::new(o_prot), 4, &options).await;
+            for _ in 0..500 {
+                if sink.flushes.load(Ordering::SeqCst) == 2 {
+                    break;
                 }
+                task::sleep(Duration::from_millis(10)).await;
+            }
+
+            let bytes = sink.bytes.lock().unwrap().clone();
+            let mut i = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+            let mut replies = Vec::new();
+            while i.transport.remaining() > 0 {
+                let message_ident = i.read_message_begin().await?;
+                i.skip(TType::Struct).await?;
+                i.read_message_end().await?;
+                replies.push((message_ident.sequence_number, message_ident.message_type));
+            }
+            replies.sort_by_key(|(seqid, _)| *seqid);
+            Ok::<_, crate::Error>(replies)
+        }).unwrap();
+
+        assert_eq!(replies, vec![(1, TMessageType::Reply), (2, TMessageType::Reply)]);
+    }
+
+    #[test]
+    fn must_require_an_acceptor() {
+        let mut server = new_server();
+        let builder = TAsyncListenerBuilder::new("127.0.0.1:0");
+        assert!(task::block_on(server.listen_reuseport(&builder, 0)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn must_close_every_reuseport_socket_when_stopped() {
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let builder = TAsyncListenerBuilder::new(address.as_str());
+        let mut server = new_server();
+
+        let reply = task::block_on(async {
+            let serve = Box::pin(server.listen_reuseport(&builder, 2));
+            let call = Box::pin(async {
+                let mut stream = loop {
+                    match TcpStream::connect(address.as_str()).await {
+                        Ok(stream) => break stream,
+                        Err(_) => task::sleep(Duration::from_millis(10)).await,
+                    }
+                };
+                stream.write_all(&request("fast", 1, 0)).await?;
+                stream.shutdown(std::net::Shutdown::Write)?;
+                let mut reply = Vec::new();
+                stream.read_to_end(&mut reply).await?;
+                Ok::<_, io::Error>(reply)
+            });
+            // dropping the server future stops its acceptors
+            match future::select(serve, call).await {
+                Either::Left((res, _)) => panic!("server stopped: {:?}", res),
+                Either::Right((reply, _)) => reply.unwrap(),
+            }
+        });
+        assert_eq!(replies(reply), vec![(1, TMessageType::Reply, Some(0))]);
+
+        // the address is free again once every acceptor closed its socket
+        let mut rebound = std::net::TcpListener::bind(address.as_str());
+        for _ in 0..100 {
+            if rebound.is_ok() {
                 break;
             }
+            thread::sleep(Duration::from_millis(10));
+            rebound = std::net::TcpListener::bind(address.as_str());
         }
+        assert!(rebound.is_ok());
     }
-}
\ No newline at end of file
+
+    fn new_server() -> TAsyncServer<
+        TDelayProcessor,
+        TAsyncBufferedReadTransportFactory,
+        TAsyncBinaryInputProtocolFactory,
+        TAsyncBufferedWriteTransportFactory,
+        TAsyncBinaryOutputProtocolFactory,
+    > {
+        TAsyncServer::new(
+            TAsyncBufferedReadTransportFactory::new(),
+            TAsyncBinaryInputProtocolFactory::new(),
+            TAsyncBufferedWriteTransportFactory::new(),
+            TAsyncBinaryOutputProtocolFactory::new(),
+            TDelayProcessor,
+        )
+    }
+}
@@ -0,0 +1,155 @@
+//! Abandoning requests whose caller has stopped waiting.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use async_std::task;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+
+use crate::errors::{ApplicationError, ApplicationErrorKind};
+use crate::protocol::async_metadata::current_deadline;
+use crate::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
+use crate::protocol::{TMessageIdentifier, TMessageType};
+
+use super::observed::{TObservedInputProtocol, TObservedOutputProtocol};
+use super::TAsyncProcessor;
+
+/// `TAsyncProcessor` wrapper that stops processing a request once the
+/// deadline sent by its caller has passed.
+///
+/// Deadlines are read from request metadata, so the server must be built
+/// with `TAsyncServer::with_request_metadata`. A request whose deadline
+/// passes while its handler is still running is abandoned: the handler
+/// future is dropped and the caller receives an `ApplicationError` instead
+/// of the reply. Once the handler has started writing its reply the request
+/// is left to finish.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+/// use async_thrift::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
+/// use async_thrift::server::asynced::TAsyncServer;
+/// use async_thrift::server::deadline::TAsyncDeadlineProcessor;
+/// use async_thrift::server::TAsyncProcessor;
+/// use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+///
+/// # struct Processor;
+/// # #[async_trait::async_trait]
+/// # impl TAsyncProcessor for Processor {
+/// #     async fn process(&self, _: &mut (dyn TAsyncInputProtocol + Send), _: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # let processor = Processor;
+/// // `processor` is e.g. a generated `CalculatorSyncProcessor`
+/// let mut server = TAsyncServer::new(
+///     TAsyncBufferedReadTransportFactory::new(),
+///     TAsyncBinaryInputProtocolFactory::new(),
+///     TAsyncBufferedWriteTransportFactory::new(),
+///     TAsyncBinaryOutputProtocolFactory::new(),
+///     TAsyncDeadlineProcessor::new(processor),
+/// ).with_request_metadata();
+///
+/// async_std::task::block_on(server.listen("127.0.0.1:9090")).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TAsyncDeadlineProcessor<P> {
+    inner: P,
+}
+
+impl<P> TAsyncDeadlineProcessor<P>
+    where
+        P: TAsyncProcessor,
+{
+    /// Create a `TAsyncDeadlineProcessor` that processes requests with
+    /// `inner`.
+    pub fn new(inner: P) -> TAsyncDeadlineProcessor<P> {
+        TAsyncDeadlineProcessor { inner }
+    }
+
+    /// Return the wrapped processor.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<P> TAsyncProcessor for TAsyncDeadlineProcessor<P>
+    where
+        P: TAsyncProcessor + Send + Sync,
+{
+    async fn process(&self, i: &mut (dyn TAsyncInputProtocol + Send), o: &mut (dyn TAsyncOutputProtocol + Send)) -> crate::Result<()> {
+        let message_ident = i.read_message_begin().await?;
+        let reply_started = AtomicBool::new(false);
+        let outcome = {
+            let mut i = TObservedInputProtocol::new(i, message_ident.clone());
+            let mut o = TObservedOutputProtocol::with_reply_flag(o, &reply_started);
+            TDeadlineGuard {
+                process: self.inner.process(&mut i, &mut o),
+                reply_started: &reply_started,
+                timer: None,
+            }.await
+        };
+
+        match outcome {
+            Some(res) => res,
+            None => {
+                debug!("abandoned call to {} after its deadline", message_ident.name);
+                let err = ApplicationError::new(
+                    ApplicationErrorKind::Unknown,
+                    format!("deadline of call to {} expired while it was processed", message_ident.name),
+                );
+                let reply_ident = TMessageIdentifier::new(
+                    message_ident.name,
+                    TMessageType::Exception,
+                    message_ident.sequence_number,
+                );
+                o.write_message_begin(&reply_ident).await?;
+                crate::Error::write_application_error_to_out_protocol(&err, o).await?;
+                o.write_message_end().await?;
+                o.flush().await
+            }
+        }
+    }
+}
+
+/// Runs `process` until it completes, or resolves to `None` if the current
+/// deadline passes before a reply has been started.
+struct TDeadlineGuard<'a> {
+    process: BoxFuture<'a, crate::Result<()>>,
+    reply_started: &'a AtomicBool,
+    // armed once the deadline is known, i.e. after the arguments are read
+    timer: Option<BoxFuture<'static, ()>>,
+}
+
+impl<'a> Future for TDeadlineGuard<'a> {
+    type Output = Option<crate::Result<()>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Poll::Ready(res) = this.process.as_mut().poll(cx) {
+            return Poll::Ready(Some(res));
+        }
+        if this.timer.is_none() {
+            if let Some(deadline) = current_deadline() {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                this.timer = Some(Box::pin(task::sleep(remaining)));
+            }
+        }
+        if let Some(ref mut timer) = this.timer {
+            if timer.as_mut().poll(cx).is_ready() {
+                if !this.reply_started.load(Ordering::Acquire) {
+                    return Poll::Ready(None);
+                }
+                // the reply is on its way; never poll the finished timer again
+                this.timer = Some(Box::pin(futures::future::pending()));
+            }
+        }
+        Poll::Pending
+    }
+}
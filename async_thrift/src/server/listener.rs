@@ -0,0 +1,230 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use async_std::net::{TcpListener, TcpStream};
+use socket2::{Domain, Socket, Type};
+
+use crate::errors::{new_transport_error, TransportErrorKind};
+
+/// Default length of the pending connection queue.
+const DEFAULT_BACKLOG: i32 = 1024;
+
+/// Builds the listening socket used by `TAsyncServer`.
+///
+/// The address family is picked from `address`: an IPv4 address creates an
+/// IPv4 socket and an IPv6 address creates an IPv6 socket. Binding to `[::]`
+/// with `only_v6(false)` accepts both IPv4 and IPv6 clients on one socket.
+///
+/// Socket options set here are applied to the listening socket and are
+/// inherited by accepted connections; `nodelay` is additionally applied to
+/// every accepted stream.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use async_thrift::server::listener::TAsyncListenerBuilder;
+///
+/// let builder = TAsyncListenerBuilder::new("[::]:9090")
+///     .only_v6(false)
+///     .backlog(4096)
+///     .reuse_port(true)
+///     .nodelay(true)
+///     .keepalive(Some(Duration::from_secs(60)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TAsyncListenerBuilder {
+    address: String,
+    backlog: i32,
+    only_v6: Option<bool>,
+    reuse_address: bool,
+    reuse_port: bool,
+    nodelay: bool,
+    keepalive: Option<Duration>,
+    send_buffer_size: Option<usize>,
+    recv_buffer_size: Option<usize>,
+}
+
+impl TAsyncListenerBuilder {
+    /// Create a `TAsyncListenerBuilder` that binds to `address`.
+    ///
+    /// `address` must be a literal socket address such as `127.0.0.1:9090`
+    /// or `[::1]:9090`.
+    pub fn new<S: Into<String>>(address: S) -> TAsyncListenerBuilder {
+        TAsyncListenerBuilder {
+            address: address.into(),
+            backlog: DEFAULT_BACKLOG,
+            only_v6: None,
+            reuse_address: true,
+            reuse_port: false,
+            nodelay: false,
+            keepalive: None,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+        }
+    }
+
+    /// Address the listener binds to.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Set the maximum length of the pending connection queue.
+    pub fn backlog(mut self, backlog: i32) -> TAsyncListenerBuilder {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Restrict an IPv6 listener to IPv6 clients (`true`) or make it a
+    /// dual-stack listener that also accepts IPv4 clients (`false`).
+    ///
+    /// Ignored for IPv4 addresses. When unset the OS default applies.
+    pub fn only_v6(mut self, only_v6: bool) -> TAsyncListenerBuilder {
+        self.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Set `SO_REUSEADDR` on the listening socket. Enabled by default.
+    pub fn reuse_address(mut self, reuse: bool) -> TAsyncListenerBuilder {
+        self.reuse_address = reuse;
+        self
+    }
+
+    /// Set `SO_REUSEPORT` on the listening socket, allowing several sockets
+    /// to bind to the same address.
+    pub fn reuse_port(mut self, reuse: bool) -> TAsyncListenerBuilder {
+        self.reuse_port = reuse;
+        self
+    }
+
+    /// Set `TCP_NODELAY` on accepted connections.
+    pub fn nodelay(mut self, nodelay: bool) -> TAsyncListenerBuilder {
+        self.nodelay = nodelay;
+        self
+    }
+
+    /// Enable TCP keepalive with the given idle time, or disable it with
+    /// `None`.
+    pub fn keepalive(mut self, keepalive: Option<Duration>) -> TAsyncListenerBuilder {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Set `SO_SNDBUF` to `size` bytes.
+    pub fn send_buffer_size(mut self, size: usize) -> TAsyncListenerBuilder {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set `SO_RCVBUF` to `size` bytes.
+    pub fn recv_buffer_size(mut self, size: usize) -> TAsyncListenerBuilder {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Create, configure, bind and listen on a socket.
+    ///
+    /// Return `Err` if the address cannot be parsed or if any socket call
+    /// fails.
+    pub fn bind(&self) -> crate::Result<TcpListener> {
+        let address = self.address.parse::<SocketAddr>().map_err(|e| {
+            new_transport_error(
+                TransportErrorKind::Unknown,
+                format!("invalid listen address {}: {}", self.address, e),
+            )
+        })?;
+
+        let domain = if address.is_ipv6() { Domain::ipv6() } else { Domain::ipv4() };
+        let socket = Socket::new(domain, Type::stream(), None)?;
+
+        if let (true, Some(only_v6)) = (address.is_ipv6(), self.only_v6) {
+            socket.set_only_v6(only_v6)?;
+        }
+        socket.set_reuse_address(self.reuse_address)?;
+        if self.reuse_port {
+            set_reuse_port(&socket)?;
+        }
+        if self.nodelay {
+            socket.set_nodelay(true)?;
+        }
+        if self.keepalive.is_some() {
+            socket.set_keepalive(self.keepalive)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+
+        socket.bind(&address.into())?;
+        socket.listen(self.backlog)?;
+
+        Ok(TcpListener::from(socket.into_tcp_listener()))
+    }
+
+    /// Apply per-connection options to an accepted `stream`.
+    pub fn configure_stream(&self, stream: &TcpStream) -> crate::Result<()> {
+        if self.nodelay {
+            stream.set_nodelay(true)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn set_reuse_port(socket: &Socket) -> crate::Result<()> {
+    socket.set_reuse_port(true).map_err(From::from)
+}
+
+#[cfg(not(unix))]
+fn set_reuse_port(_: &Socket) -> crate::Result<()> {
+    Err(new_transport_error(
+        TransportErrorKind::Unknown,
+        "SO_REUSEPORT is not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::prelude::*;
+    use async_std::task;
+
+    use super::*;
+
+    #[test]
+    fn must_set_nodelay_on_accepted_connections() {
+        let builder = TAsyncListenerBuilder::new("127.0.0.1:0")
+            .backlog(16)
+            .nodelay(true)
+            .keepalive(Some(Duration::from_secs(60)))
+            .send_buffer_size(65536)
+            .recv_buffer_size(65536);
+        let listener = assert_success!(builder.bind());
+        let address = listener.local_addr().unwrap();
+
+        task::block_on(async {
+            let _client = TcpStream::connect(address).await.unwrap();
+            let stream = listener.incoming().next().await.unwrap().unwrap();
+            assert_success!(builder.configure_stream(&stream));
+            assert!(stream.nodelay().unwrap());
+        });
+    }
+
+    #[test]
+    fn must_reject_invalid_address() {
+        assert!(TAsyncListenerBuilder::new("localhost:9090").bind().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn must_share_address_only_with_reuse_port() {
+        let builder = TAsyncListenerBuilder::new("127.0.0.1:0").reuse_port(true);
+        let first = assert_success!(builder.bind());
+        let address = first.local_addr().unwrap().to_string();
+
+        let shared = TAsyncListenerBuilder::new(address.as_str()).reuse_port(true);
+        assert_success!(shared.bind());
+        assert!(TAsyncListenerBuilder::new(address.as_str()).bind().is_err());
+    }
+}
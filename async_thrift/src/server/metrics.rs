@@ -0,0 +1,430 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as FmtWrite;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_std::{
+    io,
+    prelude::*,
+    net::{TcpListener, TcpStream},
+    task,
+};
+
+use crate::{ApplicationError, ApplicationErrorKind};
+
+/// Upper bounds, in seconds, of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 14] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05,
+    0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Largest scrape request head accepted by the HTTP endpoint.
+const MAX_SCRAPE_REQUEST_SIZE: usize = 8192;
+
+/// Default time a scrape connection is given to send its request head.
+const SCRAPE_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause after a failed accept before accepting again.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(10);
+
+/// Default number of distinct method names counted separately.
+const MAX_METHODS: usize = 256;
+
+/// Method label of the requests whose method name is not counted separately.
+const OTHER_METHODS: &str = "unknown";
+
+/// Class of a failed request, one per `Error` variant.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TErrorClass {
+    /// A `crate::Error::Transport`.
+    Transport,
+    /// A `crate::Error::Protocol`.
+    Protocol,
+    /// A `crate::Error::Application`, or an exception reply written by the
+    /// processor.
+    Application,
+    /// A `crate::Error::User`.
+    User,
+}
+
+impl TErrorClass {
+    const ALL: [TErrorClass; 4] = [
+        TErrorClass::Transport,
+        TErrorClass::Protocol,
+        TErrorClass::Application,
+        TErrorClass::User,
+    ];
+
+    /// Return the class of `err`.
+    pub fn of(err: &crate::Error) -> TErrorClass {
+        match err {
+            crate::Error::Transport(_) => TErrorClass::Transport,
+            crate::Error::Protocol(_) => TErrorClass::Protocol,
+            crate::Error::Application(_) => TErrorClass::Application,
+            crate::Error::User(_) => TErrorClass::User,
+        }
+    }
+
+    /// Lower-case name used as a metric label value.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TErrorClass::Transport => "transport",
+            TErrorClass::Protocol => "protocol",
+            TErrorClass::Application => "application",
+            TErrorClass::User => "user",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Receives events from a `TAsyncServer`.
+///
+/// Implementations are shared by every connection task and acceptor
+/// thread, so they must be cheap and must not block.
+pub trait TServerMetrics: Send + Sync {
+    /// A connection was accepted.
+    fn connection_opened(&self);
+
+    /// A connection was closed, either by the client or after an error.
+    fn connection_closed(&self);
+
+    /// A request for `method` finished after `elapsed`, measured from the
+    /// moment its message header was read.
+    ///
+    /// `error` is `None` if a regular reply (or no reply, for oneway
+    /// calls) was written. `method` is empty if the request failed before
+    /// its message header could be read.
+    fn request_completed(&self, method: &str, elapsed: Duration, error: Option<TErrorClass>);
+}
+
+/// `TServerMetrics` implementation that keeps counters in memory and
+/// renders them in the Prometheus text exposition format.
+///
+/// Method names are read off the wire before the processor sees them, so
+/// a client can send any number of them. Only the first 256 distinct names
+/// get their own counters; requests for later names are counted under
+/// `method="unknown"`. See `with_max_methods`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use async_std::task;
+/// use async_thrift::server::metrics::TPrometheusMetrics;
+///
+/// let metrics = Arc::new(TPrometheusMetrics::new());
+///
+/// // scrape http://127.0.0.1:9100/metrics
+/// task::spawn(metrics.clone().serve("127.0.0.1:9100"));
+///
+/// // hand `metrics` to `TAsyncServer::with_metrics`
+/// ```
+#[derive(Debug)]
+pub struct TPrometheusMetrics {
+    active_connections: AtomicI64,
+    accepted_connections: AtomicU64,
+    closed_connections: AtomicU64,
+    methods: Mutex<BTreeMap<String, TMethodStats>>,
+    max_methods: usize,
+    scrape_timeout: Duration,
+}
+
+/// Counters kept for each method name.
+#[derive(Debug, Default)]
+struct TMethodStats {
+    requests: u64,
+    errors: [u64; 4],
+    // one counter per entry of `LATENCY_BUCKETS`, not cumulative
+    buckets: [u64; 14],
+    latency_sum: f64,
+}
+
+impl TPrometheusMetrics {
+    /// Create a `TPrometheusMetrics` with all counters at zero.
+    pub fn new() -> TPrometheusMetrics {
+        TPrometheusMetrics {
+            active_connections: AtomicI64::new(0),
+            accepted_connections: AtomicU64::new(0),
+            closed_connections: AtomicU64::new(0),
+            methods: Mutex::new(BTreeMap::new()),
+            max_methods: MAX_METHODS,
+            scrape_timeout: SCRAPE_READ_TIMEOUT,
+        }
+    }
+
+    /// Count at most `max_methods` distinct method names separately.
+    pub fn with_max_methods(mut self, max_methods: usize) -> TPrometheusMetrics {
+        self.max_methods = max_methods;
+        self
+    }
+
+    /// Close scrape connections that have not sent their request within
+    /// `timeout`, instead of the default 5 seconds.
+    pub fn with_scrape_timeout(mut self, timeout: Duration) -> TPrometheusMetrics {
+        self.scrape_timeout = timeout;
+        self
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        write_header(&mut out, "thrift_server_active_connections", "gauge", "Connections currently open.");
+        let _ = writeln!(out, "thrift_server_active_connections {}", self.active_connections.load(Ordering::Relaxed));
+        write_header(&mut out, "thrift_server_connections_accepted_total", "counter", "Connections accepted.");
+        let _ = writeln!(out, "thrift_server_connections_accepted_total {}", self.accepted_connections.load(Ordering::Relaxed));
+        write_header(&mut out, "thrift_server_connections_closed_total", "counter", "Connections closed.");
+        let _ = writeln!(out, "thrift_server_connections_closed_total {}", self.closed_connections.load(Ordering::Relaxed));
+
+        let methods = self.methods.lock().unwrap();
+
+        write_header(&mut out, "thrift_server_requests_total", "counter", "Requests handled, by method.");
+        for (method, stats) in methods.iter() {
+            let _ = writeln!(out, "thrift_server_requests_total{{method=\"{}\"}} {}", escape_label(method), stats.requests);
+        }
+
+        write_header(&mut out, "thrift_server_errors_total", "counter", "Failed requests, by method and error class.");
+        for (method, stats) in methods.iter() {
+            for class in TErrorClass::ALL.iter() {
+                let count = stats.errors[class.index()];
+                if count > 0 {
+                    let _ = writeln!(
+                        out,
+                        "thrift_server_errors_total{{method=\"{}\",kind=\"{}\"}} {}",
+                        escape_label(method), class.as_str(), count
+                    );
+                }
+            }
+        }
+
+        write_header(&mut out, "thrift_server_request_duration_seconds", "histogram", "Request latency, by method.");
+        for (method, stats) in methods.iter() {
+            let method = escape_label(method);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(stats.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "thrift_server_request_duration_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, bound, cumulative
+                );
+            }
+            let _ = writeln!(out, "thrift_server_request_duration_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}", method, stats.requests);
+            let _ = writeln!(out, "thrift_server_request_duration_seconds_sum{{method=\"{}\"}} {}", method, stats.latency_sum);
+            let _ = writeln!(out, "thrift_server_request_duration_seconds_count{{method=\"{}\"}} {}", method, stats.requests);
+        }
+
+        out
+    }
+
+    /// Serve `render()` over HTTP at `GET /metrics` on `listen_address`.
+    ///
+    /// This is a minimal endpoint meant for a local Prometheus scraper:
+    /// each connection gets one response and is then closed. Connections
+    /// that do not send a request in time are closed without one. Failed
+    /// accepts are logged and do not stop the endpoint.
+    ///
+    /// Return `Err` when the endpoint cannot bind to `listen_address`.
+    pub async fn serve(self: Arc<Self>, listen_address: &str) -> crate::Result<()> {
+        let listener = TcpListener::bind(listen_address).await?;
+        let mut incoming = listener.incoming();
+
+        while let Some(stream) = incoming.next().await {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("failed to accept metrics connection: {:?}", e);
+                    // errors such as EMFILE persist for a while, don't spin on them
+                    task::sleep(ACCEPT_ERROR_DELAY).await;
+                    continue;
+                }
+            };
+            let metrics = self.clone();
+            task::spawn(async move {
+                if let Err(e) = metrics.handle_scrape(stream).await {
+                    warn!("failed to serve metrics scrape: {:?}", e);
+                }
+            });
+        }
+
+        Err(crate::Error::Application(ApplicationError {
+            kind: ApplicationErrorKind::Unknown,
+            message: "aborted metrics listen loop".into(),
+        }))
+    }
+
+    async fn handle_scrape(&self, mut stream: TcpStream) -> crate::Result<()> {
+        // the whole head has to arrive in time, however slowly it is sent
+        let head = io::timeout(self.scrape_timeout, read_scrape_head(&mut stream)).await?;
+
+        let head = String::from_utf8_lossy(&head);
+        let mut request_line = head.lines().next().unwrap_or("").split_whitespace();
+        let method = request_line.next().unwrap_or("");
+        let path = request_line.next().unwrap_or("");
+
+        let (status, content_type, body) = if method == "GET" && (path == "/metrics" || path.starts_with("/metrics?")) {
+            ("200 OK", "text/plain; version=0.0.4", self.render())
+        } else {
+            ("404 Not Found", "text/plain", "not found\n".to_owned())
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status, content_type, body.len(), body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.flush().await?;
+        Ok(())
+    }
+}
+
+impl TServerMetrics for TPrometheusMetrics {
+    fn connection_opened(&self) {
+        self.accepted_connections.fetch_add(1, Ordering::Relaxed);
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn connection_closed(&self) {
+        self.closed_connections.fetch_add(1, Ordering::Relaxed);
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn request_completed(&self, method: &str, elapsed: Duration, error: Option<TErrorClass>) {
+        let seconds = elapsed.as_secs_f64();
+
+        let mut methods = self.methods.lock().unwrap();
+        let method = if methods.contains_key(method) || methods.len() < self.max_methods {
+            method
+        } else {
+            OTHER_METHODS
+        };
+        // avoid allocating the key for methods that were already seen
+        if !methods.contains_key(method) {
+            methods.insert(method.to_owned(), TMethodStats::default());
+        }
+        let stats = methods.get_mut(method).unwrap();
+
+        stats.requests += 1;
+        stats.latency_sum += seconds;
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            stats.buckets[bucket] += 1;
+        }
+        if let Some(class) = error {
+            stats.errors[class.index()] += 1;
+        }
+    }
+}
+
+impl Default for TPrometheusMetrics {
+    fn default() -> Self {
+        TPrometheusMetrics::new()
+    }
+}
+
+async fn read_scrape_head(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.len() > MAX_SCRAPE_REQUEST_SIZE {
+            break;
+        }
+    }
+    Ok(head)
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn must_count_requests_by_method() {
+        let metrics = TPrometheusMetrics::new();
+        metrics.request_completed("ping", Duration::from_millis(1), None);
+        metrics.request_completed("ping", Duration::from_millis(1), Some(TErrorClass::User));
+
+        let out = metrics.render();
+        assert!(out.contains("thrift_server_requests_total{method=\"ping\"} 2\n"));
+        assert!(out.contains("thrift_server_errors_total{method=\"ping\",kind=\"user\"} 1\n"));
+    }
+
+    #[test]
+    fn must_fold_methods_beyond_limit_into_unknown() {
+        let metrics = TPrometheusMetrics::new().with_max_methods(2);
+        for method in &["ping", "echo", "random_1", "random_2", "ping"] {
+            metrics.request_completed(method, Duration::from_millis(1), None);
+        }
+
+        assert_eq!(metrics.methods.lock().unwrap().len(), 3);
+        let out = metrics.render();
+        assert!(out.contains("thrift_server_requests_total{method=\"ping\"} 2\n"));
+        assert!(out.contains("thrift_server_requests_total{method=\"echo\"} 1\n"));
+        assert!(out.contains("thrift_server_requests_total{method=\"unknown\"} 2\n"));
+        assert!(!out.contains("random_1"));
+    }
+
+    fn scrape(address: &str, request: &[u8]) -> String {
+        task::block_on(async {
+            let mut stream = TcpStream::connect(address).await?;
+            stream.write_all(request).await?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await?;
+            Ok::<_, io::Error>(response)
+        }).unwrap()
+    }
+
+    #[test]
+    fn must_close_scrape_connections_that_send_nothing() {
+        let address = task::block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().to_string()
+        });
+        let metrics = Arc::new(TPrometheusMetrics::new().with_scrape_timeout(Duration::from_millis(100)));
+        metrics.request_completed("ping", Duration::from_millis(1), None);
+        task::spawn({
+            let address = address.clone();
+            async move { metrics.serve(&address).await }
+        });
+
+        let idle = task::block_on(async {
+            // the endpoint binds in the background
+            let mut stream = loop {
+                match TcpStream::connect(&address).await {
+                    Ok(stream) => break stream,
+                    Err(_) => task::sleep(Duration::from_millis(10)).await,
+                }
+            };
+            let mut response = Vec::new();
+            io::timeout(Duration::from_secs(10), stream.read_to_end(&mut response)).await.map(|_| response)
+        });
+        assert_eq!(idle.unwrap(), b"");
+
+        // the endpoint still serves other scrapes
+        let response = scrape(&address, b"GET /metrics HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("thrift_server_requests_total{method=\"ping\"} 1\n"));
+        assert!(scrape(&address, b"GET /other HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
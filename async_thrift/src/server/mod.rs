@@ -5,6 +5,10 @@ use crate::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
 use crate::protocol::{TMessageIdentifier, TMessageType};
 
 pub mod asynced;
+pub mod deadline;
+pub mod listener;
+pub mod metrics;
+mod observed;
 
 #[async_trait]
 pub trait TAsyncProcessor {
@@ -0,0 +1,120 @@
+use std::cmp;
+use std::io;
+use std::mem;
+
+use async_trait::async_trait;
+use bytes::{Buf, Bytes};
+
+use super::{AsyncRead, AsyncWrite};
+
+/// In-memory read/write channel.
+///
+/// Reads are serviced from a byte buffer supplied up front or through
+/// `set_readable_bytes`; a read on an exhausted buffer returns `0`. All
+/// writes are appended to a separate, growable write buffer whose contents
+/// can be inspected with `write_bytes` or taken with `take_write_bytes`.
+///
+/// The read buffer is kept in `Bytes`, so binary fields read with
+/// `TAsyncInputProtocol::read_binary` are slices of it.
+///
+/// # Examples
+///
+/// ```
+/// use async_thrift::transport::async_mem::TAsyncBufferChannel;
+/// use async_thrift::transport::{AsyncRead, AsyncWrite};
+///
+/// # async_std::task::block_on(async {
+/// let mut c = TAsyncBufferChannel::with_readable_bytes(vec![1, 2, 3]);
+///
+/// let mut buf = [0u8; 3];
+/// c.read(&mut buf).await?;
+///
+/// c.write(&buf).await?;
+/// assert_eq!(c.write_bytes(), &[1, 2, 3]);
+/// # Ok::<(), std::io::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct TAsyncBufferChannel {
+    // unread part of the read buffer
+    read_buf: Bytes,
+    write_buf: Vec<u8>,
+}
+
+impl TAsyncBufferChannel {
+    /// Create a `TAsyncBufferChannel` with empty read and write buffers.
+    pub fn new() -> TAsyncBufferChannel {
+        TAsyncBufferChannel::default()
+    }
+
+    /// Create a `TAsyncBufferChannel` whose reads are serviced from `bytes`.
+    pub fn with_readable_bytes<B: Into<Bytes>>(bytes: B) -> TAsyncBufferChannel {
+        TAsyncBufferChannel {
+            read_buf: bytes.into(),
+            write_buf: Vec::new(),
+        }
+    }
+
+    /// Replace the read buffer with `bytes` and rewind to its start.
+    pub fn set_readable_bytes<B: Into<Bytes>>(&mut self, bytes: B) {
+        self.read_buf = bytes.into();
+    }
+
+    /// Number of bytes that have not been read yet.
+    pub fn remaining(&self) -> usize {
+        self.read_buf.len()
+    }
+
+    /// Bytes written to the channel so far.
+    pub fn write_bytes(&self) -> &[u8] {
+        &self.write_buf
+    }
+
+    /// Take the bytes written to the channel so far, leaving the write
+    /// buffer empty.
+    pub fn take_write_bytes(&mut self) -> Vec<u8> {
+        mem::take(&mut self.write_buf)
+    }
+}
+
+#[async_trait]
+impl AsyncRead for TAsyncBufferChannel {
+    async fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
+        let nread = cmp::min(b.len(), self.remaining());
+        b[..nread].copy_from_slice(&self.read_buf[..nread]);
+        self.read_buf.advance(nread);
+        Ok(nread)
+    }
+
+    fn buffered(&self) -> &[u8] {
+        &self.read_buf
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.read_buf.advance(cmp::min(self.read_buf.len(), amt));
+    }
+
+    fn consume_bytes(&mut self, amt: usize) -> Option<Bytes> {
+        if amt > self.read_buf.len() {
+            return None;
+        }
+        Some(self.read_buf.split_to(amt))
+    }
+}
+
+#[async_trait]
+impl AsyncWrite for TAsyncBufferChannel {
+    async fn write(&mut self, b: &[u8]) -> io::Result<usize> {
+        self.write_buf.extend_from_slice(b);
+        Ok(b.len())
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_buffered(&mut self, b: &[u8]) -> bool {
+        self.write_buf.extend_from_slice(b);
+        true
+    }
+}
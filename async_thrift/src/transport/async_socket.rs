@@ -1,5 +1,5 @@
 use std::io;
-use std::io::ErrorKind;
+use std::io::{ErrorKind, IoSlice};
 
 use async_std::{
     net::{Shutdown, TcpStream},
@@ -87,5 +87,16 @@ impl AsyncWrite for TAsyncTcpChannel {
             ))
         }
     }
+
+    async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if let Some(ref mut s) = self.stream {
+            s.write_vectored(bufs).await
+        } else {
+            Err(io::Error::new(
+                ErrorKind::NotConnected,
+                "tcp endpoint not connected",
+            ))
+        }
+    }
 }
 
@@ -0,0 +1,250 @@
+//! Buffers shared by the transports of many connections.
+//!
+//! Transports take their read and write buffers from a `TBufferPool` when
+//! they have bytes to hold and give them back once the bytes are used up,
+//! so that a connection waiting for its next message holds no buffer.
+//! Buffers are kept in size classes, and both the number of buffers kept per
+//! class and their total size are capped, so that the memory taken by a
+//! burst of large messages is freed afterwards.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::BytesMut;
+
+/// Default size classes in bytes.
+const SIZE_CLASSES: [usize; 5] = [4 * 1024, 16 * 1024, 64 * 1024, 256 * 1024, 1024 * 1024];
+
+/// Default cap on the number of buffers kept per size class.
+const MAX_BUFFERS: usize = 1024;
+
+/// Default cap on the total capacity of the buffers kept.
+const MAX_RETAINED_BYTES: usize = 32 * 1024 * 1024;
+
+/// Pool of buffers in size classes.
+///
+/// `take` returns a buffer of the smallest class that fits the requested
+/// size, and `release` keeps a buffer for later use unless a cap is reached.
+/// Requests larger than the largest class are allocated as asked and freed
+/// on release.
+///
+/// The transports use `TBufferPool::shared` unless they are given a pool
+/// with `with_buffer_pool`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use async_thrift::transport::async_framed::TAsyncFramedReadTransportFactory;
+/// use async_thrift::transport::buffer_pool::TBufferPool;
+///
+/// let pool = TBufferPool::new()
+///     .with_size_classes(&[4096, 65536])
+///     .with_max_retained_bytes(8 * 1024 * 1024);
+/// let factory = TAsyncFramedReadTransportFactory::new().with_buffer_pool(Arc::new(pool));
+/// ```
+pub struct TBufferPool {
+    classes: Vec<TSizeClass>,
+    max_buffers: usize,
+    max_retained_bytes: usize,
+    retained_bytes: AtomicUsize,
+}
+
+struct TSizeClass {
+    size: usize,
+    free: Mutex<Vec<BytesMut>>,
+}
+
+impl TBufferPool {
+    /// Create a `TBufferPool` with size classes from 4 KiB to 1 MiB that
+    /// keeps up to 1024 buffers per class and 32 MiB in total.
+    pub fn new() -> TBufferPool {
+        TBufferPool {
+            classes: SIZE_CLASSES.iter().map(|&size| TSizeClass::new(size)).collect(),
+            max_buffers: MAX_BUFFERS,
+            max_retained_bytes: MAX_RETAINED_BYTES,
+            retained_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pool shared by all transports not given a pool of their own.
+    pub fn shared() -> Arc<TBufferPool> {
+        static SHARED: OnceLock<Arc<TBufferPool>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(TBufferPool::new())).clone()
+    }
+
+    /// Use size classes of `sizes` bytes instead of the default ones.
+    pub fn with_size_classes(mut self, sizes: &[usize]) -> TBufferPool {
+        assert!(
+            !sizes.is_empty() && sizes.iter().all(|&size| size > 0),
+            "size classes must be positive integers"
+        );
+
+        let mut sizes = sizes.to_vec();
+        sizes.sort_unstable();
+        sizes.dedup();
+        self.classes = sizes.into_iter().map(TSizeClass::new).collect();
+        self
+    }
+
+    /// Keep at most `max_buffers` buffers per size class.
+    pub fn with_max_buffers(mut self, max_buffers: usize) -> TBufferPool {
+        self.max_buffers = max_buffers;
+        self
+    }
+
+    /// Keep buffers with a total capacity of at most `max_retained_bytes`.
+    pub fn with_max_retained_bytes(mut self, max_retained_bytes: usize) -> TBufferPool {
+        self.max_retained_bytes = max_retained_bytes;
+        self
+    }
+
+    /// Take an empty buffer with a capacity of at least `size` bytes.
+    pub fn take(&self, size: usize) -> BytesMut {
+        match self.classes.iter().find(|class| class.size >= size) {
+            Some(class) => {
+                if let Some(buf) = class.free.lock().unwrap().pop() {
+                    self.retained_bytes.fetch_sub(buf.capacity(), Ordering::Relaxed);
+                    return buf;
+                }
+                BytesMut::with_capacity(class.size)
+            }
+            None => BytesMut::with_capacity(size),
+        }
+    }
+
+    /// Give `buf` back to the pool, which keeps it unless a cap is reached.
+    ///
+    /// Buffers that `Bytes` were split off should not be released: only the
+    /// space after the split part can be reused.
+    pub fn release(&self, mut buf: BytesMut) {
+        buf.clear();
+        let capacity = buf.capacity();
+        // a buffer is kept in the largest class it can serve, unless it has
+        // grown well beyond that class or is larger than every class
+        let class = match self.classes.iter().rev().find(|class| class.size <= capacity) {
+            Some(class) if capacity <= 2 * class.size && !self.is_oversized(capacity) => class,
+            _ => return,
+        };
+
+        let mut free = class.free.lock().unwrap();
+        if free.len() >= self.max_buffers {
+            return;
+        }
+        if self.retained_bytes.fetch_add(capacity, Ordering::Relaxed) + capacity > self.max_retained_bytes {
+            self.retained_bytes.fetch_sub(capacity, Ordering::Relaxed);
+            return;
+        }
+        free.push(buf);
+    }
+
+    // whether `capacity` is beyond the largest size class
+    fn is_oversized(&self, capacity: usize) -> bool {
+        self.classes.last().is_none_or(|class| capacity > class.size)
+    }
+
+    /// Total capacity of the buffers the pool keeps.
+    pub fn retained_bytes(&self) -> usize {
+        self.retained_bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for TBufferPool {
+    fn default() -> Self {
+        TBufferPool::new()
+    }
+}
+
+impl fmt::Debug for TBufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TBufferPool")
+            .field("size_classes", &self.classes.iter().map(|class| class.size).collect::<Vec<_>>())
+            .field("max_buffers", &self.max_buffers)
+            .field("max_retained_bytes", &self.max_retained_bytes)
+            .field("retained_bytes", &self.retained_bytes())
+            .finish()
+    }
+}
+
+impl TSizeClass {
+    fn new(size: usize) -> TSizeClass {
+        TSizeClass {
+            size,
+            free: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_take_smallest_size_class_that_fits() {
+        let pool = TBufferPool::new().with_size_classes(&[16384, 4096]);
+        assert_eq!(pool.take(1).capacity(), 4096);
+        assert_eq!(pool.take(4096).capacity(), 4096);
+        assert_eq!(pool.take(4097).capacity(), 16384);
+        assert!(pool.take(20000).capacity() >= 20000);
+    }
+
+    #[test]
+    fn must_reuse_released_buffer() {
+        let pool = TBufferPool::new();
+        let mut buf = pool.take(100);
+        buf.extend_from_slice(b"used");
+        pool.release(buf);
+        assert_eq!(pool.retained_bytes(), 4096);
+
+        let buf = pool.take(100);
+        assert!(buf.is_empty());
+        assert_eq!(buf.capacity(), 4096);
+        assert_eq!(pool.retained_bytes(), 0);
+    }
+
+    #[test]
+    fn must_keep_at_most_max_buffers_per_class() {
+        let pool = TBufferPool::new().with_size_classes(&[4096, 16384]).with_max_buffers(2);
+        let bufs: Vec<_> = (0..3).map(|_| pool.take(4096)).collect();
+        for buf in bufs {
+            pool.release(buf);
+        }
+        assert_eq!(pool.retained_bytes(), 2 * 4096);
+
+        // the cap is per class
+        pool.release(pool.take(16384));
+        assert_eq!(pool.retained_bytes(), 2 * 4096 + 16384);
+    }
+
+    #[test]
+    fn must_keep_at_most_max_retained_bytes() {
+        let pool = TBufferPool::new().with_size_classes(&[4096, 16384]).with_max_retained_bytes(20000);
+        let (small, large, other_large) = (pool.take(4096), pool.take(16384), pool.take(16384));
+        pool.release(large);
+        pool.release(other_large);
+        assert_eq!(pool.retained_bytes(), 16384);
+        pool.release(small);
+        assert_eq!(pool.retained_bytes(), 16384);
+
+        pool.take(16384);
+        assert_eq!(pool.retained_bytes(), 0);
+    }
+
+    #[test]
+    fn must_free_buffers_outside_size_classes() {
+        let pool = TBufferPool::new().with_size_classes(&[4096, 65536]);
+        // larger than the largest class
+        pool.release(pool.take(100_000));
+        // smaller than the smallest class
+        pool.release(BytesMut::with_capacity(1024));
+        // grown well beyond its class
+        pool.release(BytesMut::with_capacity(3 * 4096));
+        assert_eq!(pool.retained_bytes(), 0);
+
+        // grown a little, kept in the class it can serve
+        pool.release(BytesMut::with_capacity(4096 + 100));
+        assert_eq!(pool.retained_bytes(), 4096 + 100);
+    }
+}
@@ -1,15 +1,41 @@
 use std::io;
+use std::io::IoSlice;
 use std::ops::{Deref, DerefMut};
 
 use async_trait::async_trait;
+use bytes::Bytes;
 
 pub mod async_buffered;
 pub mod async_framed;
+pub mod async_mem;
 pub mod async_socket;
+pub mod buffer_pool;
 
 #[async_trait]
 pub trait AsyncRead {
     async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Bytes the reader already holds, which can be taken without waiting.
+    ///
+    /// Protocols decode from these directly and only `read` when they run
+    /// out. Readers without a buffer return an empty slice.
+    fn buffered(&self) -> &[u8] {
+        &[]
+    }
+
+    /// Mark the first `amt` bytes of `buffered` as read.
+    fn consume(&mut self, _amt: usize) {}
+
+    /// Take the first `amt` bytes of `buffered` as `Bytes`, or return
+    /// `None` if fewer are buffered.
+    ///
+    /// Readers that keep their buffer in `Bytes` return a slice of it
+    /// without copying.
+    fn consume_bytes(&mut self, amt: usize) -> Option<Bytes> {
+        let bytes = Bytes::copy_from_slice(self.buffered().get(..amt)?);
+        self.consume(amt);
+        Some(bytes)
+    }
 }
 
 #[async_trait]
@@ -17,6 +43,44 @@ pub trait AsyncWrite {
     async fn write(&mut self, buf: &[u8]) -> io::Result<usize>;
 
     async fn flush(&mut self) -> io::Result<()>;
+
+    /// Write from `bufs` in order with a single write where the writer
+    /// supports it, and return the number of bytes written.
+    ///
+    /// As with `write`, fewer bytes than `bufs` hold may be written. The
+    /// default implementation writes from the first non-empty buffer only.
+    async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        match bufs.iter().find(|buf| !buf.is_empty()) {
+            Some(buf) => self.write(buf).await,
+            None => Ok(0),
+        }
+    }
+
+    /// Like `flush`, except that the writer may hold back what was written
+    /// so far and send it together with what is written next.
+    ///
+    /// Held back bytes are sent by the next `flush` at the latest. The
+    /// default implementation flushes.
+    async fn flush_coalesced(&mut self) -> io::Result<()> {
+        self.flush().await
+    }
+
+    /// Append all of `buf` to the writer's buffer if it fits without
+    /// waiting, and return whether it did.
+    ///
+    /// Nothing is written when `false` is returned. Writers without a
+    /// buffer always return `false`.
+    fn write_buffered(&mut self, _buf: &[u8]) -> bool {
+        false
+    }
+
+    /// Queue `buf` to be sent after the bytes written so far without
+    /// copying it, and return whether it was queued.
+    ///
+    /// Nothing is written when `false` is returned.
+    fn write_shared(&mut self, _buf: &Bytes) -> bool {
+        false
+    }
 }
 
 #[async_trait]
@@ -24,6 +88,15 @@ impl<R: AsyncRead + ?Sized + Send> AsyncRead for Box<R> {
     async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         (**self).read(buf).await
     }
+    fn buffered(&self) -> &[u8] {
+        (**self).buffered()
+    }
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+    fn consume_bytes(&mut self, amt: usize) -> Option<Bytes> {
+        (**self).consume_bytes(amt)
+    }
 }
 
 #[async_trait]
@@ -34,6 +107,18 @@ impl<R: AsyncWrite + ?Sized + Send> AsyncWrite for Box<R> {
     async fn flush(&mut self) -> io::Result<()> {
         (**self).flush().await
     }
+    async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (**self).write_vectored(bufs).await
+    }
+    async fn flush_coalesced(&mut self) -> io::Result<()> {
+        (**self).flush_coalesced().await
+    }
+    fn write_buffered(&mut self, buf: &[u8]) -> bool {
+        (**self).write_buffered(buf)
+    }
+    fn write_shared(&mut self, buf: &Bytes) -> bool {
+        (**self).write_shared(buf)
+    }
 }
 
 /// Identifies a transport used by a `TAsyncInputProtocol` to receive bytes.
@@ -46,8 +131,52 @@ pub trait TAsyncWriteTransport: AsyncWrite {}
 
 impl<T> TAsyncReadTransport for T where T: AsyncRead {}
 
+/// Fill `buf` from `transport`.
+///
+/// Return an `EndOfFile` `TransportError` if the transport ends before
+/// `buf` is full.
+pub(crate) async fn read_exact<T>(transport: &mut T, buf: &mut [u8]) -> crate::Result<()>
+    where
+        T: AsyncRead + ?Sized + Send,
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        let nread = transport.read(&mut buf[filled..]).await?;
+        if nread == 0 {
+            return Err(crate::errors::new_transport_error(
+                crate::errors::TransportErrorKind::EndOfFile,
+                format!("expected {} bytes but only {} were available", buf.len(), filled),
+            ));
+        }
+        filled += nread;
+    }
+    Ok(())
+}
+
 impl<T> TAsyncWriteTransport for T where T: AsyncWrite {}
 
+/// Write all of `buf` to `transport`.
+///
+/// Return a `TransportError` if the transport stops accepting bytes before
+/// all of `buf` is written.
+pub(crate) async fn write_all<T>(transport: &mut T, buf: &[u8]) -> crate::Result<()>
+    where
+        T: AsyncWrite + ?Sized + Send,
+{
+    let mut written = 0;
+    while written < buf.len() {
+        let nwrite = transport.write(&buf[written..]).await?;
+        if nwrite == 0 {
+            return Err(crate::errors::new_transport_error(
+                crate::errors::TransportErrorKind::Unknown,
+                format!("only {} of {} bytes could be written", written, buf.len()),
+            ));
+        }
+        written += nwrite;
+    }
+    Ok(())
+}
+
 /// Helper type used by a server to create `TAsyncReadTransport` instances for
 /// accepted client connections.
 pub trait TAsyncReadTransportFactory {
@@ -141,6 +270,18 @@ impl<C> AsyncRead for AsyncReadHalf<C>
     async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.handle.read(buf).await
     }
+
+    fn buffered(&self) -> &[u8] {
+        self.handle.buffered()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.handle.consume(amt)
+    }
+
+    fn consume_bytes(&mut self, amt: usize) -> Option<Bytes> {
+        self.handle.consume_bytes(amt)
+    }
 }
 
 #[async_trait]
@@ -155,6 +296,22 @@ impl<C> AsyncWrite for AsyncWriteHalf<C>
     async fn flush(&mut self) -> io::Result<()> {
         self.handle.flush().await
     }
+
+    async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.handle.write_vectored(bufs).await
+    }
+
+    async fn flush_coalesced(&mut self) -> io::Result<()> {
+        self.handle.flush_coalesced().await
+    }
+
+    fn write_buffered(&mut self, buf: &[u8]) -> bool {
+        self.handle.write_buffered(buf)
+    }
+
+    fn write_shared(&mut self, buf: &Bytes) -> bool {
+        self.handle.write_shared(buf)
+    }
 }
 
 impl<C> Deref for AsyncReadHalf<C>
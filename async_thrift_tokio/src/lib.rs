@@ -61,6 +61,8 @@ extern crate threadpool;
 
 // Re-export ordered-float, since it is used by the generator
 pub use ordered_float::OrderedFloat as OrderedFloat;
+// Re-export bytes for binary fields read and written as `Bytes`
+pub use bytes::Bytes;
 
 pub use crate::autogen::*;
 pub use crate::errors::*;
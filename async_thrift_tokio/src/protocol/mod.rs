@@ -1,8 +1,13 @@
 use std::convert::{From, TryFrom};
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::future::{BoxFuture, FutureExt};
 
 use crate::errors::{ProtocolError, ProtocolErrorKind};
 use crate::transport::{TAsyncReadTransport, TAsyncWriteTransport};
@@ -15,48 +20,128 @@ pub mod async_binary;
 // recursion.
 const MAXIMUM_SKIP_DEPTH: i8 = 64;
 
+// Default largest size an input protocol accepts for a string, binary value
+// or container before allocating anything for it. None of them can be larger
+// than the message, so this bounds what a corrupt or hostile length costs.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 100 * 1024 * 1024;
+
+/// Future returned by the reads and writes of `TAsyncInputProtocol` and
+/// `TAsyncOutputProtocol`.
+///
+/// A value read from or written to bytes the transport already buffers is
+/// `Ready` when the method returns, which costs no allocation. Only calls
+/// that have to wait for the transport box their future, so a message that
+/// fits the transport's buffer is encoded or decoded without a boxed future
+/// per field.
+///
+/// Generated code and other callers simply `.await` it.
+pub enum TProtocolFuture<'a, T> {
+    /// The call completed synchronously.
+    Ready(Option<crate::Result<T>>),
+    /// The call waits for the transport.
+    Pending(BoxFuture<'a, crate::Result<T>>),
+}
+
+impl<'a, T> TProtocolFuture<'a, T> {
+    /// Create a future that completes with `res` without waiting.
+    pub fn ready(res: crate::Result<T>) -> TProtocolFuture<'a, T> {
+        TProtocolFuture::Ready(Some(res))
+    }
+
+    /// Create a future that completes with the result of `future`.
+    pub fn boxed<F>(future: F) -> TProtocolFuture<'a, T>
+        where
+            F: Future<Output = crate::Result<T>> + Send + 'a,
+    {
+        TProtocolFuture::Pending(future.boxed())
+    }
+
+    /// Transform the result with `f` once it is available.
+    pub fn map<U, F>(self, f: F) -> TProtocolFuture<'a, U>
+        where
+            F: FnOnce(crate::Result<T>) -> crate::Result<U> + Send + 'a,
+            T: 'a,
+    {
+        match self {
+            TProtocolFuture::Ready(res) => TProtocolFuture::Ready(res.map(f)),
+            TProtocolFuture::Pending(future) => TProtocolFuture::Pending(FutureExt::map(future, f).boxed()),
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for TProtocolFuture<'a, T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            TProtocolFuture::Ready(_) => f.write_str("TProtocolFuture::Ready"),
+            TProtocolFuture::Pending(_) => f.write_str("TProtocolFuture::Pending"),
+        }
+    }
+}
+
+// the ready result is moved out, never pinned
+impl<'a, T> Unpin for TProtocolFuture<'a, T> {}
+
+impl<'a, T> Future for TProtocolFuture<'a, T> {
+    type Output = crate::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<crate::Result<T>> {
+        match self.get_mut() {
+            TProtocolFuture::Ready(res) => Poll::Ready(res.take().expect("TProtocolFuture polled after completion")),
+            TProtocolFuture::Pending(future) => future.as_mut().poll(cx),
+        }
+    }
+}
+
 #[async_trait]
 pub trait TAsyncInputProtocol: Send {
     /// Read the beginning of a Thrift message.
-    async fn read_message_begin(&mut self) -> crate::Result<TMessageIdentifier>;
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier>;
     /// Read the end of a Thrift message.
-    async fn read_message_end(&mut self) -> crate::Result<()>;
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a Thrift struct.
-    async fn read_struct_begin(&mut self) -> crate::Result<Option<TStructIdentifier>>;
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>>;
     /// Read the end of a Thrift struct.
-    async fn read_struct_end(&mut self) -> crate::Result<()>;
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a Thrift struct field.
-    async fn read_field_begin(&mut self) -> crate::Result<TFieldIdentifier>;
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier>;
     /// Read the end of a Thrift struct field.
-    async fn read_field_end(&mut self) -> crate::Result<()>;
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read a bool.
-    async fn read_bool(&mut self) -> crate::Result<bool>;
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool>;
     /// Read a fixed-length byte array.
-    async fn read_bytes(&mut self) -> crate::Result<Vec<u8>>;
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>>;
+    /// Read a fixed-length byte array as `Bytes`.
+    ///
+    /// Protocols over a transport that keeps whole frames, such as the framed
+    /// transport, return a slice of the received frame without copying. The
+    /// default implementation copies the result of `read_bytes`.
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.read_bytes().map(|bytes| bytes.map(Bytes::from))
+    }
     /// Read a word.
-    async fn read_i8(&mut self) -> crate::Result<i8>;
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8>;
     /// Read a 16-bit signed integer.
-    async fn read_i16(&mut self) -> crate::Result<i16>;
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16>;
     /// Read a 32-bit signed integer.
-    async fn read_i32(&mut self) -> crate::Result<i32>;
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32>;
     /// Read a 64-bit signed integer.
-    async fn read_i64(&mut self) -> crate::Result<i64>;
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64>;
     /// Read a 64-bit float.
-    async fn read_double(&mut self) -> crate::Result<f64>;
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64>;
     /// Read a fixed-length string (not null terminated).
-    async fn read_string(&mut self) -> crate::Result<String>;
+    fn read_string(&mut self) -> TProtocolFuture<'_, String>;
     /// Read the beginning of a list.
-    async fn read_list_begin(&mut self) -> crate::Result<TListIdentifier>;
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier>;
     /// Read the end of a list.
-    async fn read_list_end(&mut self) -> crate::Result<()>;
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a set.
-    async fn read_set_begin(&mut self) -> crate::Result<TSetIdentifier>;
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier>;
     /// Read the end of a set.
-    async fn read_set_end(&mut self) -> crate::Result<()>;
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Read the beginning of a map.
-    async fn read_map_begin(&mut self) -> crate::Result<TMapIdentifier>;
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier>;
     /// Read the end of a map.
-    async fn read_map_end(&mut self) -> crate::Result<()>;
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Skip a field with type `field_type` recursively until the default
     /// maximum skip depth is reached.
     async fn skip(&mut self, field_type: TType) -> crate::Result<()> {
@@ -131,56 +216,72 @@ pub trait TAsyncInputProtocol: Send {
     /// Read an unsigned byte.
     ///
     /// This method should **never** be used in generated code.
-    async fn read_byte(&mut self) -> crate::Result<u8>;
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8>;
 }
 
 #[async_trait]
 pub trait TAsyncOutputProtocol: Send {
     /// Write the beginning of a Thrift message.
-    async fn write_message_begin(&mut self, identifier: &TMessageIdentifier) -> crate::Result<()>;
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a Thrift message.
-    async fn write_message_end(&mut self) -> crate::Result<()>;
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a Thrift struct.
-    async fn write_struct_begin(&mut self, identifier: &TStructIdentifier) -> crate::Result<()>;
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a Thrift struct.
-    async fn write_struct_end(&mut self) -> crate::Result<()>;
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a Thrift field.
-    async fn write_field_begin(&mut self, identifier: &TFieldIdentifier) -> crate::Result<()>;
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a Thrift field.
-    async fn write_field_end(&mut self) -> crate::Result<()>;
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write a STOP field indicating that all the fields in a struct have been
     /// written.
-    async fn write_field_stop(&mut self) -> crate::Result<()>;
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write a bool.
-    async fn write_bool(&mut self, b: bool) -> crate::Result<()>;
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()>;
     /// Write a fixed-length byte array.
-    async fn write_bytes(&mut self, b: &[u8]) -> crate::Result<()>;
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()>;
+    /// Write a fixed-length byte array held in `Bytes`.
+    ///
+    /// Transports that queue writes, such as the framed transport, keep a
+    /// reference to large arrays instead of copying them. The default
+    /// implementation calls `write_bytes`.
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        self.write_bytes(b)
+    }
     /// Write an 8-bit signed integer.
-    async fn write_i8(&mut self, i: i8) -> crate::Result<()>;
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()>;
     /// Write a 16-bit signed integer.
-    async fn write_i16(&mut self, i: i16) -> crate::Result<()>;
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()>;
     /// Write a 32-bit signed integer.
-    async fn write_i32(&mut self, i: i32) -> crate::Result<()>;
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()>;
     /// Write a 64-bit signed integer.
-    async fn write_i64(&mut self, i: i64) -> crate::Result<()>;
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()>;
     /// Write a 64-bit float.
-    async fn write_double(&mut self, d: f64) -> crate::Result<()>;
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()>;
     /// Write a fixed-length string.
-    async fn write_string(&mut self, s: &str) -> crate::Result<()>;
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()>;
     /// Write the beginning of a list.
-    async fn write_list_begin(&mut self, identifier: &TListIdentifier) -> crate::Result<()>;
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a list.
-    async fn write_list_end(&mut self) -> crate::Result<()>;
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a set.
-    async fn write_set_begin(&mut self, identifier: &TSetIdentifier) -> crate::Result<()>;
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a set.
-    async fn write_set_end(&mut self) -> crate::Result<()>;
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Write the beginning of a map.
-    async fn write_map_begin(&mut self, identifier: &TMapIdentifier) -> crate::Result<()>;
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()>;
     /// Write the end of a map.
-    async fn write_map_end(&mut self) -> crate::Result<()>;
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()>;
     /// Flush buffered bytes to the underlying transport.
     async fn flush(&mut self) -> crate::Result<()>;
+    /// Like `flush`, except that the transport may hold the message back
+    /// and send it together with the messages written after it.
+    ///
+    /// Held back messages are sent by the next `flush` at the latest. The
+    /// default implementation flushes.
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.flush().await
+    }
 
     // utility (DO NOT USE IN GENERATED CODE!!!!)
     //
@@ -188,96 +289,99 @@ pub trait TAsyncOutputProtocol: Send {
     /// Write an unsigned byte.
     ///
     /// This method should **never** be used in generated code.
-    async fn write_byte(&mut self, b: u8) -> crate::Result<()>; // FIXME: REMOVE
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()>; // FIXME: REMOVE
 }
 
-#[async_trait]
 impl<P> TAsyncInputProtocol for Box<P>
     where
         P: TAsyncInputProtocol + ?Sized + Send,
 {
-    async fn read_message_begin(&mut self) -> crate::Result<TMessageIdentifier> {
-        (**self).read_message_begin().await
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        (**self).read_message_begin()
+    }
+
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_message_end()
     }
 
-    async fn read_message_end(&mut self) -> crate::Result<()> {
-        (**self).read_message_end().await
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        (**self).read_struct_begin()
     }
 
-    async fn read_struct_begin(&mut self) -> crate::Result<Option<TStructIdentifier>> {
-        (**self).read_struct_begin().await
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_struct_end()
     }
 
-    async fn read_struct_end(&mut self) -> crate::Result<()> {
-        (**self).read_struct_end().await
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        (**self).read_field_begin()
     }
 
-    async fn read_field_begin(&mut self) -> crate::Result<TFieldIdentifier> {
-        (**self).read_field_begin().await
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_field_end()
     }
 
-    async fn read_field_end(&mut self) -> crate::Result<()> {
-        (**self).read_field_end().await
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        (**self).read_bool()
     }
 
-    async fn read_bool(&mut self) -> crate::Result<bool> {
-        (**self).read_bool().await
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        (**self).read_bytes()
     }
 
-    async fn read_bytes(&mut self) -> crate::Result<Vec<u8>> {
-        (**self).read_bytes().await
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        (**self).read_binary()
     }
 
-    async fn read_i8(&mut self) -> crate::Result<i8> {
-        (**self).read_i8().await
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        (**self).read_i8()
     }
 
-    async fn read_i16(&mut self) -> crate::Result<i16> {
-        (**self).read_i16().await
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        (**self).read_i16()
     }
 
-    async fn read_i32(&mut self) -> crate::Result<i32> {
-        (**self).read_i32().await
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        (**self).read_i32()
     }
 
-    async fn read_i64(&mut self) -> crate::Result<i64> {
-        (**self).read_i64().await
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        (**self).read_i64()
     }
 
-    async fn read_double(&mut self) -> crate::Result<f64> {
-        (**self).read_double().await
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        (**self).read_double()
     }
 
-    async fn read_string(&mut self) -> crate::Result<String> {
-        (**self).read_string().await
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        (**self).read_string()
     }
 
-    async fn read_list_begin(&mut self) -> crate::Result<TListIdentifier> {
-        (**self).read_list_begin().await
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        (**self).read_list_begin()
     }
 
-    async fn read_list_end(&mut self) -> crate::Result<()> {
-        (**self).read_list_end().await
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_list_end()
     }
 
-    async fn read_set_begin(&mut self) -> crate::Result<TSetIdentifier> {
-        (**self).read_set_begin().await
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        (**self).read_set_begin()
     }
 
-    async fn read_set_end(&mut self) -> crate::Result<()> {
-        (**self).read_set_end().await
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_set_end()
     }
 
-    async fn read_map_begin(&mut self) -> crate::Result<TMapIdentifier> {
-        (**self).read_map_begin().await
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        (**self).read_map_begin()
     }
 
-    async fn read_map_end(&mut self) -> crate::Result<()> {
-        (**self).read_map_end().await
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).read_map_end()
     }
 
-    async fn read_byte(&mut self) -> crate::Result<u8> {
-        (**self).read_byte().await
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        (**self).read_byte()
     }
 }
 
@@ -286,96 +390,104 @@ impl<P> TAsyncOutputProtocol for Box<P>
     where
         P: TAsyncOutputProtocol + ?Sized + Send,
 {
-    async fn write_message_begin(&mut self, identifier: &TMessageIdentifier) -> crate::Result<()> {
-        (**self).write_message_begin(identifier).await
+    fn write_message_begin<'a>(&'a mut self, identifier: &'a TMessageIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_message_begin(identifier)
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_message_end()
     }
 
-    async fn write_message_end(&mut self) -> crate::Result<()> {
-        (**self).write_message_end().await
+    fn write_struct_begin<'a>(&'a mut self, identifier: &'a TStructIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_struct_begin(identifier)
     }
 
-    async fn write_struct_begin(&mut self, identifier: &TStructIdentifier) -> crate::Result<()> {
-        (**self).write_struct_begin(identifier).await
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_struct_end()
     }
 
-    async fn write_struct_end(&mut self) -> crate::Result<()> {
-        (**self).write_struct_end().await
+    fn write_field_begin<'a>(&'a mut self, identifier: &'a TFieldIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_field_begin(identifier)
     }
 
-    async fn write_field_begin(&mut self, identifier: &TFieldIdentifier) -> crate::Result<()> {
-        (**self).write_field_begin(identifier).await
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_field_end()
     }
 
-    async fn write_field_end(&mut self) -> crate::Result<()> {
-        (**self).write_field_end().await
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_field_stop()
     }
 
-    async fn write_field_stop(&mut self) -> crate::Result<()> {
-        (**self).write_field_stop().await
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        (**self).write_bool(b)
     }
 
-    async fn write_bool(&mut self, b: bool) -> crate::Result<()> {
-        (**self).write_bool(b).await
+    fn write_bytes<'a>(&'a mut self, b: &'a [u8]) -> TProtocolFuture<'a, ()> {
+        (**self).write_bytes(b)
     }
 
-    async fn write_bytes(&mut self, b: &[u8]) -> crate::Result<()> {
-        (**self).write_bytes(b).await
+    fn write_binary<'a>(&'a mut self, b: &'a Bytes) -> TProtocolFuture<'a, ()> {
+        (**self).write_binary(b)
     }
 
-    async fn write_i8(&mut self, i: i8) -> crate::Result<()> {
-        (**self).write_i8(i).await
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        (**self).write_i8(i)
     }
 
-    async fn write_i16(&mut self, i: i16) -> crate::Result<()> {
-        (**self).write_i16(i).await
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        (**self).write_i16(i)
     }
 
-    async fn write_i32(&mut self, i: i32) -> crate::Result<()> {
-        (**self).write_i32(i).await
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        (**self).write_i32(i)
     }
 
-    async fn write_i64(&mut self, i: i64) -> crate::Result<()> {
-        (**self).write_i64(i).await
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        (**self).write_i64(i)
     }
 
-    async fn write_double(&mut self, d: f64) -> crate::Result<()> {
-        (**self).write_double(d).await
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        (**self).write_double(d)
     }
 
-    async fn write_string(&mut self, s: &str) -> crate::Result<()> {
-        (**self).write_string(s).await
+    fn write_string<'a>(&'a mut self, s: &'a str) -> TProtocolFuture<'a, ()> {
+        (**self).write_string(s)
     }
 
-    async fn write_list_begin(&mut self, identifier: &TListIdentifier) -> crate::Result<()> {
-        (**self).write_list_begin(identifier).await
+    fn write_list_begin<'a>(&'a mut self, identifier: &'a TListIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_list_begin(identifier)
     }
 
-    async fn write_list_end(&mut self) -> crate::Result<()> {
-        (**self).write_list_end().await
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_list_end()
     }
 
-    async fn write_set_begin(&mut self, identifier: &TSetIdentifier) -> crate::Result<()> {
-        (**self).write_set_begin(identifier).await
+    fn write_set_begin<'a>(&'a mut self, identifier: &'a TSetIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_set_begin(identifier)
     }
 
-    async fn write_set_end(&mut self) -> crate::Result<()> {
-        (**self).write_set_end().await
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_set_end()
     }
 
-    async fn write_map_begin(&mut self, identifier: &TMapIdentifier) -> crate::Result<()> {
-        (**self).write_map_begin(identifier).await
+    fn write_map_begin<'a>(&'a mut self, identifier: &'a TMapIdentifier) -> TProtocolFuture<'a, ()> {
+        (**self).write_map_begin(identifier)
     }
 
-    async fn write_map_end(&mut self) -> crate::Result<()> {
-        (**self).write_map_end().await
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        (**self).write_map_end()
     }
 
     async fn flush(&mut self) -> crate::Result<()> {
         (**self).flush().await
     }
 
-    async fn write_byte(&mut self, b: u8) -> crate::Result<()> {
-        (**self).write_byte(b).await
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        (**self).flush_coalesced().await
+    }
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        (**self).write_byte(b)
     }
 }
 
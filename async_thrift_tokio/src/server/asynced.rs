@@ -1,20 +1,31 @@
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+use std::time::Duration;
+
+use futures::FutureExt;
 
 use tokio::{
     net::{TcpListener, TcpStream, ToSocketAddrs},
     prelude::*,
     task,
+    time,
 };
 use tokio::stream::StreamExt;
 
 use crate::{ApplicationError, ApplicationErrorKind};
-use crate::errors::TransportErrorKind;
+use crate::errors::{new_protocol_error, ProtocolErrorKind, TransportErrorKind};
 use crate::protocol::{TAsyncInputProtocol, TAsyncInputProtocolFactory, TAsyncOutputProtocol, TAsyncOutputProtocolFactory};
+use crate::protocol::{TMessageIdentifier, TMessageType};
 use crate::transport::{TAsyncReadTransportFactory, TAsyncWriteTransportFactory};
 use crate::transport::async_socket::TAsyncTcpChannel;
 use crate::transport::TAsyncIoChannel;
 
-use super::TAsyncProcessor;
+use super::observed::{TObservedInputProtocol, TObservedOutputProtocol};
+use super::{handle_process_result, TAsyncProcessor};
+
+/// Pause after a failed accept before accepting again.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(10);
 
 pub struct TAsyncServer<PRC, RTF, IPF, WTF, OPF>
     where
@@ -29,6 +40,7 @@ pub struct TAsyncServer<PRC, RTF, IPF, WTF, OPF>
     w_trans_factory: WTF,
     o_proto_factory: OPF,
     async_processor: Arc<PRC>,
+    nodelay: bool,
 }
 
 impl<PRC, RTF, IPF, WTF, OPF> TAsyncServer<PRC, RTF, IPF, WTF, OPF>
@@ -59,8 +71,17 @@ impl<PRC, RTF, IPF, WTF, OPF> TAsyncServer<PRC, RTF, IPF, WTF, OPF>
             w_trans_factory: write_transport_factory,
             o_proto_factory: output_protocol_factory,
             async_processor: Arc::new(async_processor),
+            nodelay: false,
         }
     }
+
+    /// Set `TCP_NODELAY` on accepted connections, so that replies written
+    /// in several pieces are not held back waiting for an ack.
+    pub fn with_nodelay(mut self, nodelay: bool) -> TAsyncServer<PRC, RTF, IPF, WTF, OPF> {
+        self.nodelay = nodelay;
+        self
+    }
+
     /// Listen for incoming connections on `listen_address`.
     ///
     /// `listen_address` should implement `ToSocketAddrs` trait.
@@ -74,11 +95,27 @@ impl<PRC, RTF, IPF, WTF, OPF> TAsyncServer<PRC, RTF, IPF, WTF, OPF>
         while let Some(stream) = listener.next().await {
             match stream {
                 Ok(stream) => {
-                    let (read_protocol, write_protocol) = self.new_protocols_for_connection(stream).await?;
+                    if self.nodelay {
+                        if let Err(e) = stream.set_nodelay(true) {
+                            warn!("failed to set TCP_NODELAY on accepted connection: {:?}", e);
+                            continue;
+                        }
+                    }
+                    let (read_protocol, write_protocol) = match self.new_protocols_for_connection(stream).await {
+                        Ok(protocols) => protocols,
+                        Err(e) => {
+                            warn!("failed to create protocols for accepted connection: {:?}", e);
+                            continue;
+                        }
+                    };
                     task::spawn(handle_incoming_connection_server(
                         self.async_processor.clone(), read_protocol, write_protocol));
                 }
-                Err(e) => { /* connection failed */ }
+                Err(e) => {
+                    warn!("failed to accept connection: {:?}", e);
+                    // errors such as EMFILE persist for a while, don't spin on them
+                    time::delay_for(ACCEPT_ERROR_DELAY).await;
+                }
             }
         }
 
@@ -126,7 +163,7 @@ async fn handle_incoming_connection_server<PRC>(
     let mut i_prot = i_prot;
     let mut o_prot = o_prot;
     loop {
-        match processor.process(&mut *i_prot, &mut *o_prot).await {
+        match process_request(&*processor, &mut *i_prot, &mut *o_prot).await {
             Ok(()) => {}
             Err(err) => {
                 match err {
@@ -137,4 +174,267 @@ async fn handle_incoming_connection_server<PRC>(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// process one request from `i_prot`
+///
+/// a panicking handler is answered with an `InternalError` exception; see
+/// `finish_request` for when the connection is kept after a failure
+async fn process_request<PRC>(
+    processor: &PRC,
+    i_prot: &mut (dyn TAsyncInputProtocol + Send),
+    o_prot: &mut (dyn TAsyncOutputProtocol + Send),
+) -> crate::Result<()>
+    where
+        PRC: TAsyncProcessor,
+{
+    let message_ident = i_prot.read_message_begin().await?;
+    let mut i = TObservedInputProtocol::new(i_prot, message_ident.clone());
+    let mut o = TObservedOutputProtocol::new(o_prot);
+    let res = match AssertUnwindSafe(processor.process(&mut i, &mut o)).catch_unwind().await {
+        Ok(res) => res,
+        Err(panic) => {
+            log_handler_panic(&message_ident, &*panic);
+            Err(handler_panic_error(&message_ident))
+        }
+    };
+    finish_request(&message_ident, res, &mut i, &mut o).await
+}
+
+/// get the connection ready for the next request once `res`, the result of
+/// processing a request, is known
+///
+/// application and user errors, and protocol errors raised after the
+/// arguments were read, are answered with an exception unless the processor
+/// already replied, and arguments the processor did not read are skipped.
+/// transport errors and failures that leave the request or the reply half
+/// read or written are returned, so that the connection is closed
+async fn finish_request(
+    message_ident: &TMessageIdentifier,
+    res: crate::Result<()>,
+    i: &mut TObservedInputProtocol<'_>,
+    o: &mut TObservedOutputProtocol<'_>,
+) -> crate::Result<()> {
+    let err = match res {
+        Ok(()) => {
+            return if i.skip_unread_args().await? {
+                Ok(())
+            } else {
+                Err(new_protocol_error(
+                    ProtocolErrorKind::InvalidData,
+                    format!("arguments of {} were only partly read", message_ident.name),
+                ))
+            };
+        }
+        Err(err @ crate::Error::Transport(_)) => return Err(err),
+        Err(err) => err,
+    };
+
+    if !i.skip_unread_args().await? || o.message_type().is_some() {
+        return Err(err);
+    }
+    warn!("call to {} failed: {:?}", message_ident.name, err);
+    if message_ident.message_type == TMessageType::OneWay {
+        return Ok(());
+    }
+    handle_process_result(message_ident, Err(err), o).await
+}
+
+/// error returned to the caller of a request whose handler panicked
+fn handler_panic_error(message_ident: &TMessageIdentifier) -> crate::Error {
+    crate::Error::Application(ApplicationError::new(
+        ApplicationErrorKind::InternalError,
+        format!("handler for {} panicked", message_ident.name),
+    ))
+}
+
+fn log_handler_panic(message_ident: &TMessageIdentifier, panic: &(dyn Any + Send)) {
+    let reason = if let Some(reason) = panic.downcast_ref::<&str>() {
+        reason
+    } else if let Some(reason) = panic.downcast_ref::<String>() {
+        reason.as_str()
+    } else {
+        "unknown cause"
+    };
+    error!("handler for {} (seqid {}) panicked: {}", message_ident.name, message_ident.sequence_number, reason);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp;
+    use std::io;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use bytes::{Buf, Bytes};
+    use futures::executor::block_on;
+
+    use crate::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+    use crate::protocol::{TFieldIdentifier, TStructIdentifier, TType};
+    use crate::transport::{AsyncRead, AsyncWrite};
+
+    use super::*;
+
+    /// Channel reading from a byte buffer.
+    struct TBytesChannel(Bytes);
+
+    #[async_trait]
+    impl AsyncRead for TBytesChannel {
+        async fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
+            let nread = cmp::min(b.len(), self.0.len());
+            b[..nread].copy_from_slice(&self.0[..nread]);
+            self.0.advance(nread);
+            Ok(nread)
+        }
+    }
+
+    /// Connection output that can be inspected after the server wrote to it.
+    #[derive(Clone, Default)]
+    struct TSharedSink(Arc<Mutex<Vec<u8>>>);
+
+    #[async_trait]
+    impl AsyncWrite for TSharedSink {
+        async fn write(&mut self, b: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(b);
+            Ok(b.len())
+        }
+
+        async fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Processor echoing the `i32` argument of each call. Calls to `panic`
+    /// panic after reading their arguments, calls to `panic_early` before.
+    /// Calls to `fail` fail after reading their arguments, calls to
+    /// `partial` after reading part of them, and calls to `unread` reply 0
+    /// without reading them.
+    struct TEchoProcessor;
+
+    #[async_trait]
+    impl TAsyncProcessor for TEchoProcessor {
+        async fn process(&self, i: &mut (dyn TAsyncInputProtocol + Send), o: &mut (dyn TAsyncOutputProtocol + Send)) -> crate::Result<()> {
+            let message_ident = i.read_message_begin().await?;
+            let value = match message_ident.name.as_str() {
+                "panic_early" => panic!("handler failed before reading its arguments"),
+                "unread" => 0,
+                name => {
+                    i.read_struct_begin().await?;
+                    if name == "partial" {
+                        return Err(crate::Error::Application(ApplicationError::new(ApplicationErrorKind::Unknown, "failed")));
+                    }
+                    i.read_field_begin().await?;
+                    let value = i.read_i32().await?;
+                    i.read_field_end().await?;
+                    i.read_field_begin().await?;
+                    i.read_struct_end().await?;
+                    i.read_message_end().await?;
+                    value
+                }
+            };
+            match message_ident.name.as_str() {
+                "panic" => panic!("handler failed"),
+                "fail" => return Err(crate::Error::Application(ApplicationError::new(ApplicationErrorKind::Unknown, "failed"))),
+                _ => {}
+            }
+
+            let reply_ident = TMessageIdentifier::new(message_ident.name.as_str(), TMessageType::Reply, message_ident.sequence_number);
+            o.write_message_begin(&reply_ident).await?;
+            o.write_struct_begin(&TStructIdentifier::new("result")).await?;
+            o.write_field_begin(&TFieldIdentifier::new("success", TType::I32, 0)).await?;
+            o.write_i32(value).await?;
+            o.write_field_end().await?;
+            o.write_field_stop().await?;
+            o.write_struct_end().await?;
+            o.write_message_end().await?;
+            o.flush().await
+        }
+    }
+
+    fn requests(calls: &[(&str, i32)]) -> Vec<u8> {
+        let sink = TSharedSink::default();
+        let mut o = TAsyncBinaryOutputProtocol::new(sink.clone(), true);
+        block_on(async {
+            for (seqid, (method, value)) in calls.iter().enumerate() {
+                o.write_message_begin(&TMessageIdentifier::new(*method, TMessageType::Call, seqid as i32 + 1)).await?;
+                o.write_struct_begin(&TStructIdentifier::new("args")).await?;
+                o.write_field_begin(&TFieldIdentifier::new("value", TType::I32, 1)).await?;
+                o.write_i32(*value).await?;
+                o.write_field_end().await?;
+                o.write_field_stop().await?;
+                o.write_struct_end().await?;
+                o.write_message_end().await?;
+            }
+            o.flush().await
+        }).unwrap();
+        let bytes = sink.0.lock().unwrap().clone();
+        bytes
+    }
+
+    /// Serve `requests` on one connection and read every reply as its
+    /// seqid and either the returned value or the exception.
+    fn serve(requests: Vec<u8>) -> Vec<(i32, Result<i32, ApplicationError>)> {
+        let sink = TSharedSink::default();
+        let i_prot = TAsyncBinaryInputProtocol::new(TBytesChannel(Bytes::from(requests)), true);
+        let o_prot = TAsyncBinaryOutputProtocol::new(sink.clone(), true);
+        block_on(handle_incoming_connection_server(Arc::new(TEchoProcessor), Box::new(i_prot), Box::new(o_prot)));
+
+        let bytes = Bytes::from(sink.0.lock().unwrap().clone());
+        let mut i = TAsyncBinaryInputProtocol::new(TBytesChannel(bytes), true);
+        block_on(async {
+            let mut replies = Vec::new();
+            while !i.transport.0.is_empty() {
+                let message_ident = i.read_message_begin().await?;
+                let reply = if message_ident.message_type == TMessageType::Exception {
+                    Err(crate::Error::read_application_error_from_in_protocol(&mut i).await?)
+                } else {
+                    i.read_struct_begin().await?;
+                    i.read_field_begin().await?;
+                    let value = i.read_i32().await?;
+                    i.read_field_end().await?;
+                    i.read_field_begin().await?;
+                    i.read_struct_end().await?;
+                    Ok(value)
+                };
+                i.read_message_end().await?;
+                replies.push((message_ident.sequence_number, reply));
+            }
+            Ok::<_, crate::Error>(replies)
+        }).unwrap()
+    }
+
+    #[test]
+    fn must_answer_panicking_handler_and_keep_connection() {
+        let replies = serve(requests(&[("echo", 1), ("panic", 2), ("panic_early", 3), ("echo", 4)]));
+
+        assert_eq!(replies.len(), 4);
+        assert_eq!(replies[0], (1, Ok(1)));
+        for (seqid, method) in [(2, "panic"), (3, "panic_early")].iter() {
+            let (reply_seqid, reply) = &replies[*seqid as usize - 1];
+            assert_eq!(reply_seqid, seqid);
+            let err = reply.as_ref().unwrap_err();
+            assert_eq!(err.kind, ApplicationErrorKind::InternalError);
+            assert_eq!(err.message, format!("handler for {} panicked", method));
+        }
+        assert_eq!(replies[3], (4, Ok(4)));
+    }
+
+    #[test]
+    fn must_keep_connection_after_recoverable_errors() {
+        let replies = serve(requests(&[("echo", 1), ("fail", 2), ("unread", 3), ("echo", 4)]));
+
+        assert_eq!(replies.len(), 4);
+        assert_eq!(replies[0], (1, Ok(1)));
+        assert_eq!(replies[1], (2, Err(ApplicationError::new(ApplicationErrorKind::Unknown, "failed"))));
+        // the unread arguments are skipped to get to the next request
+        assert_eq!(replies[2], (3, Ok(0)));
+        assert_eq!(replies[3], (4, Ok(4)));
+    }
+
+    #[test]
+    fn must_close_connection_when_arguments_were_partly_read() {
+        let replies = serve(requests(&[("echo", 1), ("partial", 2), ("echo", 3)]));
+
+        assert_eq!(replies, vec![(1, Ok(1))]);
+    }
+}
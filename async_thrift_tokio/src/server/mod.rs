@@ -5,6 +5,7 @@ use crate::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
 use crate::protocol::{TMessageIdentifier, TMessageType};
 
 pub mod asynced;
+mod observed;
 
 #[async_trait]
 pub trait TAsyncProcessor {
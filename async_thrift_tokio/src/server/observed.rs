@@ -0,0 +1,307 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol, TProtocolFuture};
+use crate::protocol::{
+    TFieldIdentifier, TListIdentifier,
+    TMapIdentifier, TMessageIdentifier,
+    TMessageType, TSetIdentifier,
+    TStructIdentifier, TType,
+};
+
+/// How much of a request's argument struct has been read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum TArgsProgress {
+    NotStarted,
+    Reading,
+    Finished,
+}
+
+/// Input protocol wrapper for a request whose message header has already
+/// been read from `inner`.
+///
+/// The first `read_message_begin` returns that header instead of reading
+/// from `inner`; all other calls are forwarded. The wrapper follows the
+/// request's argument struct so that arguments the processor did not read
+/// can be skipped.
+pub(crate) struct TObservedInputProtocol<'a> {
+    inner: &'a mut (dyn TAsyncInputProtocol + Send),
+    message: Option<TMessageIdentifier>,
+    args: TArgsProgress,
+    depth: usize,
+}
+
+impl<'a> TObservedInputProtocol<'a> {
+    pub(crate) fn new(
+        inner: &'a mut (dyn TAsyncInputProtocol + Send),
+        message_ident: TMessageIdentifier,
+    ) -> TObservedInputProtocol<'a> {
+        TObservedInputProtocol {
+            inner,
+            message: Some(message_ident),
+            args: TArgsProgress::NotStarted,
+            depth: 0,
+        }
+    }
+
+    /// Skip the arguments if the processor did not read them, leaving the
+    /// input at the start of the next message.
+    ///
+    /// Return `false` if the arguments were only partly read, in which case
+    /// the position of the input is unknown.
+    pub(crate) async fn skip_unread_args(&mut self) -> crate::Result<bool> {
+        match self.args {
+            TArgsProgress::NotStarted => {
+                self.skip(TType::Struct).await?;
+                Ok(true)
+            }
+            TArgsProgress::Reading => Ok(false),
+            TArgsProgress::Finished => Ok(true),
+        }
+    }
+}
+
+impl<'a> TAsyncInputProtocol for TObservedInputProtocol<'a> {
+    fn read_message_begin(&mut self) -> TProtocolFuture<'_, TMessageIdentifier> {
+        match self.message.take() {
+            Some(message_ident) => TProtocolFuture::ready(Ok(message_ident)),
+            None => self.inner.read_message_begin(),
+        }
+    }
+
+    fn read_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_message_end()
+    }
+
+    fn read_struct_begin(&mut self) -> TProtocolFuture<'_, Option<TStructIdentifier>> {
+        // only the outermost struct changes the progress of the arguments
+        if self.depth > 0 {
+            self.depth += 1;
+            return self.inner.read_struct_begin();
+        }
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_struct_begin().await;
+            if res.is_ok() {
+                if self.depth == 0 && self.args == TArgsProgress::NotStarted {
+                    self.args = TArgsProgress::Reading;
+                }
+                self.depth += 1;
+            }
+            res
+        })
+    }
+
+    fn read_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        if self.depth > 1 {
+            self.depth -= 1;
+            return self.inner.read_struct_end();
+        }
+        TProtocolFuture::boxed(async move {
+            let res = self.inner.read_struct_end().await;
+            if res.is_ok() {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 && self.args == TArgsProgress::Reading {
+                    self.args = TArgsProgress::Finished;
+                }
+            }
+            res
+        })
+    }
+
+    fn read_field_begin(&mut self) -> TProtocolFuture<'_, TFieldIdentifier> {
+        self.inner.read_field_begin()
+    }
+
+    fn read_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_field_end()
+    }
+
+    fn read_bool(&mut self) -> TProtocolFuture<'_, bool> {
+        self.inner.read_bool()
+    }
+
+    fn read_bytes(&mut self) -> TProtocolFuture<'_, Vec<u8>> {
+        self.inner.read_bytes()
+    }
+
+    fn read_binary(&mut self) -> TProtocolFuture<'_, Bytes> {
+        self.inner.read_binary()
+    }
+
+    fn read_i8(&mut self) -> TProtocolFuture<'_, i8> {
+        self.inner.read_i8()
+    }
+
+    fn read_i16(&mut self) -> TProtocolFuture<'_, i16> {
+        self.inner.read_i16()
+    }
+
+    fn read_i32(&mut self) -> TProtocolFuture<'_, i32> {
+        self.inner.read_i32()
+    }
+
+    fn read_i64(&mut self) -> TProtocolFuture<'_, i64> {
+        self.inner.read_i64()
+    }
+
+    fn read_double(&mut self) -> TProtocolFuture<'_, f64> {
+        self.inner.read_double()
+    }
+
+    fn read_string(&mut self) -> TProtocolFuture<'_, String> {
+        self.inner.read_string()
+    }
+
+    fn read_list_begin(&mut self) -> TProtocolFuture<'_, TListIdentifier> {
+        self.inner.read_list_begin()
+    }
+
+    fn read_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_list_end()
+    }
+
+    fn read_set_begin(&mut self) -> TProtocolFuture<'_, TSetIdentifier> {
+        self.inner.read_set_begin()
+    }
+
+    fn read_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_set_end()
+    }
+
+    fn read_map_begin(&mut self) -> TProtocolFuture<'_, TMapIdentifier> {
+        self.inner.read_map_begin()
+    }
+
+    fn read_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.read_map_end()
+    }
+
+    fn read_byte(&mut self) -> TProtocolFuture<'_, u8> {
+        self.inner.read_byte()
+    }
+}
+
+/// Output protocol wrapper that remembers the type of the last message
+/// written through it.
+pub(crate) struct TObservedOutputProtocol<'a> {
+    inner: &'a mut (dyn TAsyncOutputProtocol + Send),
+    message_type: Option<TMessageType>,
+}
+
+impl<'a> TObservedOutputProtocol<'a> {
+    pub(crate) fn new(inner: &'a mut (dyn TAsyncOutputProtocol + Send)) -> TObservedOutputProtocol<'a> {
+        TObservedOutputProtocol {
+            inner,
+            message_type: None,
+        }
+    }
+
+    /// Type of the last message written, if any.
+    pub(crate) fn message_type(&self) -> Option<TMessageType> {
+        self.message_type
+    }
+}
+
+#[async_trait]
+impl<'a> TAsyncOutputProtocol for TObservedOutputProtocol<'a> {
+    fn write_message_begin<'b>(&'b mut self, identifier: &'b TMessageIdentifier) -> TProtocolFuture<'b, ()> {
+        self.message_type = Some(identifier.message_type);
+        self.inner.write_message_begin(identifier)
+    }
+
+    fn write_message_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_message_end()
+    }
+
+    fn write_struct_begin<'b>(&'b mut self, identifier: &'b TStructIdentifier) -> TProtocolFuture<'b, ()> {
+        self.inner.write_struct_begin(identifier)
+    }
+
+    fn write_struct_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_struct_end()
+    }
+
+    fn write_field_begin<'b>(&'b mut self, identifier: &'b TFieldIdentifier) -> TProtocolFuture<'b, ()> {
+        self.inner.write_field_begin(identifier)
+    }
+
+    fn write_field_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_field_end()
+    }
+
+    fn write_field_stop(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_field_stop()
+    }
+
+    fn write_bool(&mut self, b: bool) -> TProtocolFuture<'_, ()> {
+        self.inner.write_bool(b)
+    }
+
+    fn write_bytes<'b>(&'b mut self, b: &'b [u8]) -> TProtocolFuture<'b, ()> {
+        self.inner.write_bytes(b)
+    }
+
+    fn write_binary<'b>(&'b mut self, b: &'b Bytes) -> TProtocolFuture<'b, ()> {
+        self.inner.write_binary(b)
+    }
+
+    fn write_i8(&mut self, i: i8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i8(i)
+    }
+
+    fn write_i16(&mut self, i: i16) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i16(i)
+    }
+
+    fn write_i32(&mut self, i: i32) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i32(i)
+    }
+
+    fn write_i64(&mut self, i: i64) -> TProtocolFuture<'_, ()> {
+        self.inner.write_i64(i)
+    }
+
+    fn write_double(&mut self, d: f64) -> TProtocolFuture<'_, ()> {
+        self.inner.write_double(d)
+    }
+
+    fn write_string<'b>(&'b mut self, s: &'b str) -> TProtocolFuture<'b, ()> {
+        self.inner.write_string(s)
+    }
+
+    fn write_list_begin<'b>(&'b mut self, identifier: &'b TListIdentifier) -> TProtocolFuture<'b, ()> {
+        self.inner.write_list_begin(identifier)
+    }
+
+    fn write_list_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_list_end()
+    }
+
+    fn write_set_begin<'b>(&'b mut self, identifier: &'b TSetIdentifier) -> TProtocolFuture<'b, ()> {
+        self.inner.write_set_begin(identifier)
+    }
+
+    fn write_set_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_set_end()
+    }
+
+    fn write_map_begin<'b>(&'b mut self, identifier: &'b TMapIdentifier) -> TProtocolFuture<'b, ()> {
+        self.inner.write_map_begin(identifier)
+    }
+
+    fn write_map_end(&mut self) -> TProtocolFuture<'_, ()> {
+        self.inner.write_map_end()
+    }
+
+    async fn flush(&mut self) -> crate::Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn flush_coalesced(&mut self) -> crate::Result<()> {
+        self.inner.flush_coalesced().await
+    }
+
+    fn write_byte(&mut self, b: u8) -> TProtocolFuture<'_, ()> {
+        self.inner.write_byte(b)
+    }
+}
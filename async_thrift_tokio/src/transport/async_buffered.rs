@@ -1,8 +1,12 @@
 use std::cmp;
 use std::io;
+use std::mem;
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::BytesMut;
 
+use super::buffer_pool::TBufferPool;
 use super::{AsyncRead, AsyncWrite, TAsyncReadTransport, TAsyncReadTransportFactory, TAsyncWriteTransport, TAsyncWriteTransportFactory};
 
 /// Default capacity of the read buffer in bytes.
@@ -12,14 +16,25 @@ const READ_CAPACITY: usize = 1024;
 const WRITE_CAPACITY: usize = 1024;
 
 
+/// Transport that reads through a buffer.
+///
+/// The buffer is taken from a `TBufferPool` when bytes are first read into
+/// it. With `with_idle_release` it is given back whenever it is drained, so
+/// that a connection waiting for a message holds no buffer.
 #[derive(Debug)]
 pub struct TAsyncBufferedReadTransport<C>
     where
         C: AsyncRead,
 {
-    buf: Box<[u8]>,
+    buf: BytesMut,
     pos: usize,
     cap: usize,
+    read_capacity: usize,
+    // whether the buffer is given back whenever it is drained
+    idle_release: bool,
+    // whether the buffer was given back and nothing was read since
+    idle: bool,
+    pool: Arc<TBufferPool>,
     chan: C,
 }
 
@@ -38,26 +53,47 @@ impl<C> TAsyncBufferedReadTransport<C>
     /// `write_capacity` that wraps the given `TIoChannel`.
     pub fn with_capacity(read_capacity: usize, channel: C) -> TAsyncBufferedReadTransport<C> {
         TAsyncBufferedReadTransport {
-            buf: vec![0; read_capacity].into_boxed_slice(),
+            buf: BytesMut::new(),
             pos: 0,
             cap: 0,
+            read_capacity,
+            idle_release: false,
+            idle: true,
+            pool: TBufferPool::shared(),
             chan: channel,
         }
     }
 
+    /// Take the read buffer from `pool` instead of the shared pool.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncBufferedReadTransport<C> {
+        self.pool = pool;
+        self
+    }
+
+    /// Give the read buffer back to the pool whenever all bytes read into
+    /// it are consumed.
+    ///
+    /// A drained transport then reads the first bytes of the next message
+    /// straight into the caller's buffer, which costs one more read per
+    /// message than reading them through the buffer.
+    pub fn with_idle_release(mut self) -> TAsyncBufferedReadTransport<C> {
+        self.idle_release = true;
+        self
+    }
+
     async fn get_bytes(&mut self) -> io::Result<&[u8]> {
         if self.cap - self.pos == 0 {
+            if self.buf.is_empty() {
+                self.buf = self.pool.take(self.read_capacity);
+                self.buf.resize(self.read_capacity, 0);
+                self.idle = false;
+            }
             self.pos = 0;
             self.cap = self.chan.read(&mut self.buf).await?;
         }
 
         Ok(&self.buf[self.pos..self.cap])
     }
-
-    fn consume(&mut self, consumed: usize) {
-        // TODO: was a bug here += <-- test somehow
-        self.pos = cmp::min(self.cap, self.pos + consumed);
-    }
 }
 
 #[async_trait]
@@ -66,6 +102,11 @@ impl<C> AsyncRead for TAsyncBufferedReadTransport<C>
         C: AsyncRead + Send,
 {
     async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.idle_release && self.idle && !buf.is_empty() {
+            self.idle = false;
+            return self.chan.read(buf).await;
+        }
+
         let mut bytes_read = 0;
 
         loop {
@@ -87,16 +128,36 @@ impl<C> AsyncRead for TAsyncBufferedReadTransport<C>
 
         Ok(bytes_read)
     }
+
+    fn buffered(&self) -> &[u8] {
+        &self.buf[self.pos..self.cap]
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // TODO: was a bug here += <-- test somehow
+        self.pos = cmp::min(self.cap, self.pos + amt);
+        if self.idle_release && self.pos == self.cap && self.cap > 0 {
+            self.pool.release(mem::take(&mut self.buf));
+            self.pos = 0;
+            self.cap = 0;
+            self.idle = true;
+        }
+    }
 }
 
 
+/// Transport that writes through a buffer.
+///
+/// The buffer is taken from a `TBufferPool` on the first write and given
+/// back after each flush.
 #[derive(Debug)]
 pub struct TAsyncBufferedWriteTransport<C>
     where
         C: AsyncWrite,
 {
-    buf: Vec<u8>,
+    buf: BytesMut,
     cap: usize,
+    pool: Arc<TBufferPool>,
     channel: C,
 }
 
@@ -120,11 +181,25 @@ impl<C> TAsyncBufferedWriteTransport<C>
         );
 
         TAsyncBufferedWriteTransport {
-            buf: Vec::with_capacity(write_capacity),
+            buf: BytesMut::new(),
             cap: write_capacity,
+            pool: TBufferPool::shared(),
             channel,
         }
     }
+
+    /// Take the write buffer from `pool` instead of the shared pool.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncBufferedWriteTransport<C> {
+        self.pool = pool;
+        self
+    }
+
+    fn buf_mut(&mut self) -> &mut BytesMut {
+        if self.buf.capacity() == 0 {
+            self.buf = self.pool.take(self.cap);
+        }
+        &mut self.buf
+    }
 }
 
 #[async_trait]
@@ -148,7 +223,7 @@ impl<C> AsyncWrite for TAsyncBufferedWriteTransport<C>
 
             let avail_bytes = avail_bytes;
 
-            self.buf.extend_from_slice(&buf[..avail_bytes]);
+            self.buf_mut().extend_from_slice(&buf[..avail_bytes]);
             assert!(self.buf.len() <= self.cap, "copy overflowed buffer");
 
             Ok(avail_bytes)
@@ -158,43 +233,150 @@ impl<C> AsyncWrite for TAsyncBufferedWriteTransport<C>
     }
 
     async fn flush(&mut self) -> io::Result<()> {
-        self.channel.write(&self.buf).await?;
+        // the channel may accept fewer bytes than it is given
+        let mut written = 0;
+        while written < self.buf.len() {
+            let nwrite = self.channel.write(&self.buf[written..]).await?;
+            if nwrite == 0 {
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "channel stopped accepting bytes"));
+            }
+            written += nwrite;
+        }
         self.channel.flush().await?;
-        self.buf.clear();
+        if self.buf.capacity() > 0 {
+            self.pool.release(mem::take(&mut self.buf));
+        }
         Ok(())
     }
+
+    fn write_buffered(&mut self, buf: &[u8]) -> bool {
+        if buf.len() > self.cap - self.buf.len() {
+            return false;
+        }
+        self.buf_mut().extend_from_slice(buf);
+        true
+    }
 }
 
 /// Factory for creating instances of `TAsyncBufferedReadTransport`.
 #[derive(Default)]
-pub struct TAsyncBufferedReadTransportFactory;
+pub struct TAsyncBufferedReadTransportFactory {
+    buffer_pool: Option<Arc<TBufferPool>>,
+    idle_release: bool,
+}
 
 impl TAsyncBufferedReadTransportFactory {
     pub fn new() -> TAsyncBufferedReadTransportFactory {
-        TAsyncBufferedReadTransportFactory {}
+        TAsyncBufferedReadTransportFactory::default()
+    }
+
+    /// Create transports that take their buffers from `pool`.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncBufferedReadTransportFactory {
+        self.buffer_pool = Some(pool);
+        self
+    }
+
+    /// Create transports that give their buffer back whenever it is drained.
+    /// See `TAsyncBufferedReadTransport::with_idle_release`.
+    pub fn with_idle_release(mut self) -> TAsyncBufferedReadTransportFactory {
+        self.idle_release = true;
+        self
     }
 }
 
 impl TAsyncReadTransportFactory for TAsyncBufferedReadTransportFactory {
     /// Create a `TAsyncBufferedReadTransport`.
     fn create(&self, channel: Box<dyn AsyncRead + Send>) -> Box<dyn TAsyncReadTransport + Send> {
-        Box::new(TAsyncBufferedReadTransport::new(channel))
+        let mut transport = TAsyncBufferedReadTransport::new(channel);
+        if let Some(ref pool) = self.buffer_pool {
+            transport = transport.with_buffer_pool(pool.clone());
+        }
+        if self.idle_release {
+            transport = transport.with_idle_release();
+        }
+        Box::new(transport)
     }
 }
 
 /// Factory for creating instances of `TAsyncBufferedWriteTransport`.
 #[derive(Default)]
-pub struct TAsyncBufferedWriteTransportFactory;
+pub struct TAsyncBufferedWriteTransportFactory {
+    buffer_pool: Option<Arc<TBufferPool>>,
+}
 
 impl TAsyncBufferedWriteTransportFactory {
     pub fn new() -> TAsyncBufferedWriteTransportFactory {
-        TAsyncBufferedWriteTransportFactory {}
+        TAsyncBufferedWriteTransportFactory::default()
+    }
+
+    /// Create transports that take their buffers from `pool`.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncBufferedWriteTransportFactory {
+        self.buffer_pool = Some(pool);
+        self
     }
 }
 
 impl TAsyncWriteTransportFactory for TAsyncBufferedWriteTransportFactory {
     /// Create a `TAsyncBufferedWriteTransport`.
     fn create(&self, channel: Box<dyn AsyncWrite + Send>) -> Box<dyn TAsyncWriteTransport + Send> {
-        Box::new(TAsyncBufferedWriteTransport::new(channel))
+        let transport = TAsyncBufferedWriteTransport::new(channel);
+        match self.buffer_pool {
+            Some(ref pool) => Box::new(transport.with_buffer_pool(pool.clone())),
+            None => Box::new(transport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// Channel counting the reads it is asked for.
+    struct TCountingChannel {
+        bytes: Vec<u8>,
+        reads: usize,
+    }
+
+    #[async_trait]
+    impl AsyncRead for TCountingChannel {
+        async fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            let nread = cmp::min(b.len(), self.bytes.len());
+            b[..nread].copy_from_slice(&self.bytes[..nread]);
+            self.bytes.drain(..nread);
+            Ok(nread)
+        }
+    }
+
+    /// Read `bytes` as four-byte values, returning the reads made on the
+    /// channel and the bytes left in `pool`.
+    fn read_in_fours(bytes: Vec<u8>, idle_release: bool) -> (usize, usize) {
+        let pool = Arc::new(TBufferPool::new());
+        let len = bytes.len();
+        let mut transport = TAsyncBufferedReadTransport::new(TCountingChannel { bytes, reads: 0 })
+            .with_buffer_pool(pool.clone());
+        if idle_release {
+            transport = transport.with_idle_release();
+        }
+
+        let mut buf = [0u8; 4];
+        for _ in 0..len / 4 {
+            assert_eq!(block_on(transport.read(&mut buf)).unwrap(), 4);
+        }
+        (transport.chan.reads, pool.retained_bytes())
+    }
+
+    #[test]
+    fn must_keep_read_buffer_by_default() {
+        assert_eq!(read_in_fours(vec![1; 16], false), (1, 0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn must_release_drained_read_buffer_with_idle_release() {
+        // the first bytes are read straight into the caller's buffer, and
+        // the drained buffer is kept in the 4 KiB size class
+        assert_eq!(read_in_fours(vec![1; 16], true), (2, 4096));
+    }
+}
@@ -1,20 +1,36 @@
 use std::cmp;
+use std::convert::TryFrom;
 use std::io;
-use std::io::Cursor;
+use std::io::{Cursor, IoSlice};
+use std::iter;
+use std::mem;
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::{BigEndian, ReadBytesExt};
+use bytes::{Buf, Bytes, BytesMut};
 
 use crate::transport::{AsyncRead, AsyncWrite};
 
+use super::buffer_pool::TBufferPool;
 use super::{TAsyncReadTransport, TAsyncReadTransportFactory, TAsyncWriteTransport, TAsyncWriteTransportFactory};
 
-/// Default capacity of the read buffer in bytes.
+/// Default smallest read buffer in bytes.
 const READ_CAPACITY: usize = 4096;
 
+/// Default largest frame the read transport accepts, in bytes. Other Thrift
+/// implementations use the same limit.
+const DEFAULT_MAX_FRAME_SIZE: usize = 16_384_000;
+
 /// Default capacity of the write buffer in bytes.
 const WRITE_CAPACITY: usize = 4096;
 
+/// Size from which `write_shared` queues bytes instead of copying them.
+const SHARED_WRITE_MIN: usize = 1024;
+
+/// Most buffers handed to one vectored write of the channel.
+const MAX_WRITE_PARTS: usize = 64;
+
 /// Transport that reads framed messages.
 ///
 /// A `TAsyncFramedReadTransport` maintains a fixed-size internal read buffer.
@@ -24,6 +40,14 @@ const WRITE_CAPACITY: usize = 4096;
 /// until it is exhausted, at which point the next full message is read
 /// from the wrapped channel.
 ///
+/// The buffer of a frame is taken from a `TBufferPool` and given back once
+/// the frame has been read, so that a connection waiting for its next
+/// message holds no buffer.
+///
+/// Binary fields read with `TAsyncInputProtocol::read_binary` are slices of
+/// the frame rather than copies. The buffer of such a frame is not given
+/// back, it is freed once the last slice is dropped.
+///
 /// # Examples
 ///
 /// Create and use a `TAsyncFramedReadTransport`.
@@ -44,9 +68,14 @@ pub struct TAsyncFramedReadTransport<C>
     where
         C: AsyncRead,
 {
-    buf: Vec<u8>,
+    // current frame, read up to `pos`
+    frame: BytesMut,
     pos: usize,
-    cap: usize,
+    // unread part of the current frame once a part was taken as `Bytes`
+    shared: Option<Bytes>,
+    read_capacity: usize,
+    max_frame_size: usize,
+    pool: Arc<TBufferPool>,
     chan: C,
 }
 
@@ -64,12 +93,41 @@ impl<C> TAsyncFramedReadTransport<C>
     /// of size `read_capacity` that wraps the given `TIoChannel`.
     pub fn with_capacity(read_capacity: usize, channel: C) -> TAsyncFramedReadTransport<C> {
         TAsyncFramedReadTransport {
-            buf: vec![0; read_capacity], // FIXME: do I actually have to do this?
+            frame: BytesMut::new(),
             pos: 0,
-            cap: 0,
+            shared: None,
+            read_capacity,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            pool: TBufferPool::shared(),
             chan: channel,
         }
     }
+
+    /// Take frame buffers from `pool` instead of the shared pool.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncFramedReadTransport<C> {
+        self.pool = pool;
+        self
+    }
+
+    /// Fail reads of frames larger than `max_frame_size` bytes with an
+    /// `InvalidData` error instead of buffering them. The default is
+    /// 16384000 bytes.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> TAsyncFramedReadTransport<C> {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    // Give the frame buffer back once the whole frame has been read.
+    fn release_read_frame(&mut self) {
+        match self.shared {
+            Some(ref shared) if shared.is_empty() => self.shared = None,
+            None if self.pos == self.frame.len() && self.frame.capacity() > 0 => {
+                self.pool.release(mem::take(&mut self.frame));
+                self.pos = 0;
+            }
+            _ => {}
+        }
+    }
 }
 
 #[async_trait]
@@ -78,26 +136,95 @@ impl<C> AsyncRead for TAsyncFramedReadTransport<C>
         C: AsyncRead + std::marker::Send
 {
     async fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
-        if self.cap - self.pos == 0 {
+        if self.buffered().is_empty() {
             let mut buf = [0; 4];
-            self.chan.read(&mut buf).await;
+            if !fill(&mut self.chan, &mut buf).await? {
+                return Ok(0);
+            }
             let mut rdr = Cursor::new(buf);
-            let message_size = rdr.read_i32::<BigEndian>().unwrap() as usize;
-
-            let buf_capacity = cmp::max(message_size, READ_CAPACITY);
-            self.buf.resize(buf_capacity, 0);
+            let message_size = rdr.read_i32::<BigEndian>().unwrap();
+            // the size is checked before a buffer of that size is taken
+            let message_size = match usize::try_from(message_size) {
+                Ok(size) if size <= self.max_frame_size => size,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("frame size {} is not within 0..={}", message_size, self.max_frame_size),
+                    ));
+                }
+            };
 
-            self.chan.read(&mut self.buf[..message_size]).await;
-            self.cap = message_size as usize;
+            let mut frame = self.pool.take(cmp::max(message_size, self.read_capacity));
+            frame.resize(message_size, 0);
+            if !fill(&mut self.chan, &mut frame[..]).await? {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed inside a frame"));
+            }
+            self.frame = frame;
             self.pos = 0;
         }
 
-        let nread = cmp::min(b.len(), self.cap - self.pos);
-        b[..nread].clone_from_slice(&self.buf[self.pos..self.pos + nread]);
-        self.pos += nread;
+        let bytes = self.buffered();
+        let nread = cmp::min(b.len(), bytes.len());
+        b[..nread].clone_from_slice(&bytes[..nread]);
+        self.consume(nread);
 
         Ok(nread)
     }
+
+    fn buffered(&self) -> &[u8] {
+        match self.shared {
+            Some(ref shared) => shared,
+            None => &self.frame[self.pos..],
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self.shared {
+            Some(ref mut shared) => shared.advance(cmp::min(shared.len(), amt)),
+            None => self.pos = cmp::min(self.frame.len(), self.pos + amt),
+        }
+        self.release_read_frame();
+    }
+
+    fn consume_bytes(&mut self, amt: usize) -> Option<Bytes> {
+        if amt > self.buffered().len() {
+            return None;
+        }
+        if self.shared.is_none() {
+            // from here on the frame is read from `Bytes`, whose slices may
+            // outlive the frame
+            let mut shared = self.frame.split().freeze();
+            shared.advance(self.pos);
+            self.frame = BytesMut::new();
+            self.pos = 0;
+            self.shared = Some(shared);
+        }
+        let bytes = self.shared.as_mut().map(|shared| shared.split_to(amt));
+        self.release_read_frame();
+        bytes
+    }
+}
+
+/// Read into all of `buf`, a frame header or body, which may take several
+/// reads from the channel.
+///
+/// Return `false` if the channel ends before the first byte.
+async fn fill<C>(chan: &mut C, buf: &mut [u8]) -> io::Result<bool>
+    where
+        C: AsyncRead + Send
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        let nread = chan.read(&mut buf[filled..]).await?;
+        if nread == 0 {
+            if filled == 0 {
+                return Ok(false);
+            }
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed inside a frame"));
+        }
+        filled += nread;
+    }
+    Ok(true)
 }
 
 /// Transport that writes framed messages.
@@ -106,7 +233,19 @@ impl<C> AsyncRead for TAsyncFramedReadTransport<C>
 /// writes are made to this buffer and are sent to the wrapped channel only
 /// when `TAsyncFramedWriteTransport::flush()` is called. On a flush a fixed-length
 /// header with a count of the buffered bytes is written, followed by the bytes
-/// themselves.
+/// themselves. Header and bytes are sent with a single vectored write where
+/// the channel supports it.
+///
+/// The write buffer is taken from a `TBufferPool` on the first write and
+/// given back once the frames in it have been sent.
+///
+/// Large `Bytes` given to `write_shared`, such as binary fields written
+/// with `TAsyncOutputProtocol::write_binary`, are sent from where they are
+/// instead of being copied into the buffer.
+///
+/// With `with_coalescing`, `flush_coalesced` only completes the frame and
+/// holds it back, so that the frames of several messages are sent with one
+/// write.
 ///
 /// # Examples
 ///
@@ -129,7 +268,18 @@ pub struct TAsyncFramedWriteTransport<C>
     where
         C: AsyncWrite,
 {
-    buf: Vec<u8>,
+    // complete frames followed by the current frame, each behind its header
+    buf: BytesMut,
+    // where the header of the current frame starts in `buf`
+    frame_start: Option<usize>,
+    frame_size: usize,
+    // parts sent before the bytes of `buf` at their offset
+    shared: Vec<(usize, Bytes)>,
+    // size of the complete frames
+    queued: usize,
+    write_capacity: usize,
+    pool: Arc<TBufferPool>,
+    coalesce_limit: Option<usize>,
     channel: C,
 }
 
@@ -147,10 +297,101 @@ impl<C> TAsyncFramedWriteTransport<C>
     /// of size `write_capacity` that wraps the given `TIoChannel`.
     pub fn with_capacity(write_capacity: usize, channel: C) -> TAsyncFramedWriteTransport<C> {
         TAsyncFramedWriteTransport {
-            buf: Vec::with_capacity(write_capacity),
+            buf: BytesMut::new(),
+            frame_start: None,
+            frame_size: 0,
+            shared: Vec::new(),
+            queued: 0,
+            write_capacity,
+            pool: TBufferPool::shared(),
+            coalesce_limit: None,
             channel,
         }
     }
+
+    /// Take write buffers from `pool` instead of the shared pool.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncFramedWriteTransport<C> {
+        self.pool = pool;
+        self
+    }
+
+    /// Hold back the frames completed by `flush_coalesced` until they add up
+    /// to `max_bytes` or `flush` is called.
+    pub fn with_coalescing(mut self, max_bytes: usize) -> TAsyncFramedWriteTransport<C> {
+        self.coalesce_limit = Some(max_bytes);
+        self
+    }
+
+    // Leave room for the header if a frame is not started yet.
+    fn begin_frame(&mut self) {
+        if self.frame_start.is_some() {
+            return;
+        }
+        if self.buf.capacity() == 0 {
+            self.buf = self.pool.take(self.write_capacity);
+        }
+        self.frame_start = Some(self.buf.len());
+        self.buf.extend_from_slice(&[0; 4]);
+    }
+
+    // Fill in the header of the current frame, making it complete.
+    fn end_frame(&mut self) -> io::Result<()> {
+        let frame_start = match self.frame_start.take() {
+            Some(frame_start) => frame_start,
+            None => return Ok(()),
+        };
+        let frame_size = mem::take(&mut self.frame_size);
+        if frame_size == 0 {
+            self.buf.truncate(frame_start);
+            return Ok(());
+        }
+        if frame_size > i32::MAX as usize {
+            self.buf.truncate(frame_start);
+            self.shared.retain(|&(offset, _)| offset <= frame_start);
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("frame of {} bytes exceeds the maximum frame size", frame_size),
+            ));
+        }
+
+        self.buf[frame_start..frame_start + 4].copy_from_slice(&(frame_size as i32).to_be_bytes());
+        self.queued += 4 + frame_size;
+        Ok(())
+    }
+
+    // Send all complete frames.
+    async fn send_frames(&mut self) -> io::Result<()> {
+        let res = if self.queued > 0 {
+            let mut parts = Vec::with_capacity(2 * self.shared.len() + 1);
+            let mut pos = 0;
+            for (offset, bytes) in &self.shared {
+                if *offset > pos {
+                    parts.push(&self.buf[pos..*offset]);
+                }
+                parts.push(&bytes[..]);
+                pos = *offset;
+            }
+            if pos < self.buf.len() {
+                parts.push(&self.buf[pos..]);
+            }
+            write_all_vectored(&mut self.channel, &parts).await
+        } else {
+            Ok(())
+        };
+        // frames are not sent again after a failed write, which leaves the
+        // channel in an unknown state
+        let sent = self.queued > 0;
+        self.shared.clear();
+        self.queued = 0;
+        if self.buf.capacity() > 0 {
+            self.pool.release(mem::take(&mut self.buf));
+        }
+        res?;
+        if sent {
+            self.channel.flush().await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -159,74 +400,235 @@ impl<C> AsyncWrite for TAsyncFramedWriteTransport<C>
         C: AsyncWrite + std::marker::Send
 {
     async fn write(&mut self, b: &[u8]) -> io::Result<usize> {
-        let current_capacity = self.buf.capacity();
-        let available_space = current_capacity - self.buf.len();
-        if b.len() > available_space {
-            let additional_space = cmp::max(b.len() - available_space, current_capacity);
-            self.buf.reserve(additional_space);
-        }
-
-        self.buf.extend_from_slice(b);
+        self.write_buffered(b);
         Ok(b.len())
     }
 
     async fn flush(&mut self) -> io::Result<()> {
-        let message_size = self.buf.len();
-
-        if let 0 = message_size {
-            return Ok(());
-        } else {
-            let mut wtr = Vec::new();
-            wtr.write_i32::<BigEndian>(message_size as i32).unwrap();
+        self.end_frame()?;
+        self.send_frames().await
+    }
 
-            self.channel.write(&wtr).await;
+    async fn flush_coalesced(&mut self) -> io::Result<()> {
+        self.end_frame()?;
+        match self.coalesce_limit {
+            Some(max_bytes) if self.queued < max_bytes => Ok(()),
+            _ => self.send_frames().await,
         }
+    }
 
-        // will spin if the underlying channel can't be written to
-        let mut byte_index = 0;
-        while byte_index < message_size {
-            let nwrite = self.channel.write(&self.buf[byte_index..message_size]).await?;
-            byte_index = cmp::min(byte_index + nwrite, message_size);
-        }
+    // the frame buffer grows as needed
+    fn write_buffered(&mut self, b: &[u8]) -> bool {
+        self.begin_frame();
+        self.buf.extend_from_slice(b);
+        self.frame_size += b.len();
+        true
+    }
 
-        let buf_capacity = cmp::min(self.buf.capacity(), WRITE_CAPACITY);
-        self.buf.resize(buf_capacity, 0);
-        self.buf.clear();
+    fn write_shared(&mut self, b: &Bytes) -> bool {
+        // small values are cheaper to copy than to queue
+        if b.len() < SHARED_WRITE_MIN {
+            return false;
+        }
+        self.begin_frame();
+        self.shared.push((self.buf.len(), b.clone()));
+        self.frame_size += b.len();
+        true
+    }
+}
 
-        self.channel.flush().await
+/// Write all of `parts` to `channel` in order, with as few vectored writes
+/// as the channel allows.
+async fn write_all_vectored<C>(channel: &mut C, parts: &[&[u8]]) -> io::Result<()>
+    where
+        C: AsyncWrite + Send
+{
+    let mut parts = parts;
+    // bytes of `parts[0]` already written
+    let mut pos = 0;
+    while !parts.is_empty() {
+        let bufs = iter::once(&parts[0][pos..])
+            .chain(parts[1..].iter().copied())
+            .take(MAX_WRITE_PARTS)
+            .map(IoSlice::new)
+            .collect::<Vec<_>>();
+        let nwrite = channel.write_vectored(&bufs).await?;
+        if nwrite == 0 {
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "channel stopped accepting bytes"));
+        }
+        // a partial write may end anywhere, also inside a part
+        pos += nwrite;
+        while let Some(part) = parts.first() {
+            if pos < part.len() {
+                break;
+            }
+            pos -= part.len();
+            parts = &parts[1..];
+        }
     }
+    Ok(())
 }
 
 /// Factory for creating instances of `TAsyncFramedReadTransport`.
 #[derive(Default)]
-pub struct TAsyncFramedReadTransportFactory;
+pub struct TAsyncFramedReadTransportFactory {
+    max_frame_size: Option<usize>,
+    buffer_pool: Option<Arc<TBufferPool>>,
+}
 
 impl TAsyncFramedReadTransportFactory {
     pub fn new() -> TAsyncFramedReadTransportFactory {
-        TAsyncFramedReadTransportFactory {}
+        TAsyncFramedReadTransportFactory::default()
+    }
+
+    /// Create transports that reject frames larger than `max_frame_size`
+    /// bytes. See `TAsyncFramedReadTransport::with_max_frame_size`.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> TAsyncFramedReadTransportFactory {
+        self.max_frame_size = Some(max_frame_size);
+        self
+    }
+
+    /// Create transports that take frame buffers from `pool`.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncFramedReadTransportFactory {
+        self.buffer_pool = Some(pool);
+        self
     }
 }
 
 impl TAsyncReadTransportFactory for TAsyncFramedReadTransportFactory {
     /// Create a `TAsyncFramedReadTransport`.
     fn create(&self, channel: Box<dyn AsyncRead + Send>) -> Box<dyn TAsyncReadTransport + Send> {
-        Box::new(TAsyncFramedReadTransport::new(channel))
+        let mut transport = TAsyncFramedReadTransport::new(channel);
+        if let Some(max_frame_size) = self.max_frame_size {
+            transport = transport.with_max_frame_size(max_frame_size);
+        }
+        if let Some(ref pool) = self.buffer_pool {
+            transport = transport.with_buffer_pool(pool.clone());
+        }
+        Box::new(transport)
     }
 }
 
 /// Factory for creating instances of `TAsyncFramedWriteTransport`.
 #[derive(Default)]
-pub struct TAsyncFramedWriteTransportFactory;
+pub struct TAsyncFramedWriteTransportFactory {
+    coalesce_limit: Option<usize>,
+    buffer_pool: Option<Arc<TBufferPool>>,
+}
 
 impl TAsyncFramedWriteTransportFactory {
     pub fn new() -> TAsyncFramedWriteTransportFactory {
-        TAsyncFramedWriteTransportFactory {}
+        TAsyncFramedWriteTransportFactory::default()
+    }
+
+    /// Create transports that coalesce frames up to `max_bytes`. See
+    /// `TAsyncFramedWriteTransport::with_coalescing`.
+    pub fn with_coalescing(mut self, max_bytes: usize) -> TAsyncFramedWriteTransportFactory {
+        self.coalesce_limit = Some(max_bytes);
+        self
+    }
+
+    /// Create transports that take write buffers from `pool`.
+    pub fn with_buffer_pool(mut self, pool: Arc<TBufferPool>) -> TAsyncFramedWriteTransportFactory {
+        self.buffer_pool = Some(pool);
+        self
     }
 }
 
 impl TAsyncWriteTransportFactory for TAsyncFramedWriteTransportFactory {
     /// Create a `TAsyncFramedWriteTransport`.
     fn create(&self, channel: Box<dyn AsyncWrite + Send>) -> Box<dyn TAsyncWriteTransport + Send> {
-        Box::new(TAsyncFramedWriteTransport::new(channel))
+        let mut transport = TAsyncFramedWriteTransport::new(channel);
+        if let Some(max_bytes) = self.coalesce_limit {
+            transport = transport.with_coalescing(max_bytes);
+        }
+        if let Some(ref pool) = self.buffer_pool {
+            transport = transport.with_buffer_pool(pool.clone());
+        }
+        Box::new(transport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// Channel reading from a byte buffer.
+    struct TBytesChannel(Bytes);
+
+    #[async_trait]
+    impl AsyncRead for TBytesChannel {
+        async fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
+            let nread = cmp::min(b.len(), self.0.len());
+            b[..nread].copy_from_slice(&self.0[..nread]);
+            self.0.advance(nread);
+            Ok(nread)
+        }
     }
-}
\ No newline at end of file
+
+    fn framed(frames: &[&[u8]], pool: &Arc<TBufferPool>) -> TAsyncFramedReadTransport<TBytesChannel> {
+        let mut bytes = Vec::new();
+        for frame in frames {
+            bytes.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(frame);
+        }
+        TAsyncFramedReadTransport::new(TBytesChannel(Bytes::from(bytes))).with_buffer_pool(pool.clone())
+    }
+
+    #[test]
+    fn must_reject_frames_over_max_size() {
+        let pool = Arc::new(TBufferPool::new());
+        let mut t = framed(&[b"four", b"fives"], &pool).with_max_frame_size(4);
+
+        let mut buf = [0u8; 8];
+        assert_eq!(block_on(t.read(&mut buf)).unwrap(), 4);
+        let err = block_on(t.read(&mut buf)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "frame size 5 is not within 0..=4");
+        // no buffer was taken for the rejected frame
+        assert_eq!(pool.retained_bytes(), READ_CAPACITY);
+    }
+
+    #[test]
+    fn must_reject_negative_frame_size() {
+        // read as a u32 this would be a frame of almost 4 GiB
+        let mut t = TAsyncFramedReadTransport::new(TBytesChannel(Bytes::from_static(&[0xFF, 0xFF, 0xFF, 0xFE, 0x00])));
+
+        let err = block_on(t.read(&mut [0u8; 1])).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), format!("frame size -2 is not within 0..={}", DEFAULT_MAX_FRAME_SIZE));
+    }
+
+    #[test]
+    fn must_release_read_frame_to_pool() {
+        let pool = Arc::new(TBufferPool::new());
+        let mut t = framed(&[b"first", b"second"], &pool);
+
+        let mut buf = [0u8; 5];
+        assert_eq!(block_on(t.read(&mut buf)).unwrap(), 5);
+        assert_eq!(&buf, b"first");
+        assert_eq!(pool.retained_bytes(), READ_CAPACITY);
+
+        // the next frame takes the released buffer
+        assert_eq!(block_on(t.read(&mut buf)).unwrap(), 5);
+        assert_eq!(pool.retained_bytes(), 0);
+        assert_eq!(block_on(t.read(&mut buf)).unwrap(), 1);
+        assert_eq!(pool.retained_bytes(), READ_CAPACITY);
+    }
+
+    #[test]
+    fn must_keep_shared_frame_out_of_pool() {
+        let pool = Arc::new(TBufferPool::new());
+        let mut t = framed(&[b"header:payload"], &pool);
+
+        let mut buf = [0u8; 7];
+        assert_eq!(block_on(t.read(&mut buf)).unwrap(), 7);
+        let payload = t.consume_bytes(7).unwrap();
+        assert_eq!(&payload[..], b"payload");
+        assert!(t.buffered().is_empty());
+        // the slice still points into the frame
+        assert_eq!(pool.retained_bytes(), 0);
+    }
+}
@@ -1,7 +1,8 @@
 use std::io;
-use std::io::ErrorKind;
+use std::io::{ErrorKind, IoSlice};
 
 use async_trait::async_trait;
+use bytes::Buf;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{
     tcp::{OwnedReadHalf, OwnedWriteHalf},
@@ -51,6 +52,10 @@ impl AsyncWrite for OwnedWriteHalf {
     async fn flush(&mut self) -> io::Result<()> {
         tokio::io::AsyncWriteExt::flush(self).await
     }
+
+    async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.write_buf(&mut IoSlices::new(bufs)).await
+    }
 }
 
 impl TAsyncIoChannel for TAsyncTcpChannel {
@@ -104,5 +109,71 @@ impl AsyncWrite for TAsyncTcpChannel {
             ))
         }
     }
+
+    async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if let Some(ref mut s) = self.stream {
+            s.write_buf(&mut IoSlices::new(bufs)).await
+        } else {
+            Err(io::Error::new(
+                ErrorKind::NotConnected,
+                "tcp endpoint not connected",
+            ))
+        }
+    }
 }
 
+/// `Buf` over the buffers of a vectored write, which tokio sends with a
+/// single `writev`.
+struct IoSlices<'a, 'b> {
+    bufs: &'a [IoSlice<'b>],
+    // bytes of `bufs[0]` already taken
+    pos: usize,
+}
+
+impl<'a, 'b> IoSlices<'a, 'b> {
+    fn new(bufs: &'a [IoSlice<'b>]) -> IoSlices<'a, 'b> {
+        let mut slices = IoSlices { bufs, pos: 0 };
+        slices.advance(0);
+        slices
+    }
+}
+
+impl<'a, 'b> Buf for IoSlices<'a, 'b> {
+    fn remaining(&self) -> usize {
+        self.bufs.iter().map(|buf| buf.len()).sum::<usize>() - self.pos
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self.bufs.first() {
+            Some(buf) => &buf[self.pos..],
+            None => &[],
+        }
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.pos += cnt;
+        // also skips empty buffers, so that `bytes` is only empty at the end
+        while let Some(buf) = self.bufs.first() {
+            if self.pos < buf.len() {
+                break;
+            }
+            self.pos -= buf.len();
+            self.bufs = &self.bufs[1..];
+        }
+    }
+
+    fn bytes_vectored<'c>(&'c self, dst: &mut [IoSlice<'c>]) -> usize {
+        let mut n = 0;
+        for (i, buf) in self.bufs.iter().enumerate() {
+            if n == dst.len() {
+                break;
+            }
+            let buf: &[u8] = if i == 0 { &buf[self.pos..] } else { buf };
+            if !buf.is_empty() {
+                dst[n] = IoSlice::new(buf);
+                n += 1;
+            }
+        }
+        n
+    }
+}
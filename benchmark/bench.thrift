@@ -0,0 +1,23 @@
+namespace rs bench
+
+struct Item {
+    1: i64 id,
+    2: string name,
+    3: double score,
+    4: list<i32> tags,
+}
+
+struct Record {
+    1: i64 id,
+    2: string name,
+    3: Item head,
+    4: list<Item> items,
+    5: map<string, Item> index,
+    6: set<string> labels,
+}
+
+service Benchmark {
+    void ping(),
+    binary echo(1: binary payload),
+    Record echo_record(1: Record record),
+}
@@ -6,15 +6,16 @@ export ASYNC_STD_THREAD_COUNT=$CPU_NUM;
 echo "SET ASYNC_STD_THREAD_COUNT = $ASYNC_STD_THREAD_COUNT";
 
 ## config parameter
-RUN_CLIENT=true;
-RUN_SERVER=true;
-RUN_SYNC=false;
-RUN_ASYNC=false;
-RUN_ASYNC_TOKIO=true;
-THREAD_NUM=32;
-LOOP_NUM=1000;
+RUNTIME=async-std,tokio,sync;
+CONCURRENCY=32;
+WARMUP=2;
+DURATION=10;
 ADDR=127.0.0.1:9090;
+ACCEPTOR_NUM=1;
 ##
 
-cargo run --color=always --release --package benchmark --bin benchmark \
- $RUN_CLIENT $RUN_SERVER $RUN_SYNC $RUN_ASYNC $RUN_ASYNC_TOKIO $THREAD_NUM $LOOP_NUM $ADDR
+# see `benchmark --help` for the other flags, or run a scenario file with
+# --scenario scenarios/ping.json
+cargo run --color=always --release --package benchmark --bin benchmark -- \
+ --runtime $RUNTIME --concurrency $CONCURRENCY --warmup $WARMUP --duration $DURATION \
+ --addr $ADDR --acceptors $ACCEPTOR_NUM "$@"
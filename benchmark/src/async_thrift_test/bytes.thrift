@@ -1,3 +0,0 @@
-service LongMessageTest{
-    list<i8> echo(1: list<i8> input);
-}
\ No newline at end of file
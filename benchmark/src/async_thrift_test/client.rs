@@ -1,64 +1,92 @@
-// only for test!!!
-//
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
 
-use std::io::Error;
-use std::time::{SystemTime, UNIX_EPOCH};
+use async_std::net::TcpStream;
+use async_std::task;
 
-use async_std::{
-    net::{TcpListener, TcpStream, ToSocketAddrs},
-    task,
-};
-use async_std::sync::Receiver;
-use thrift::transport::TTcpChannel;
-use time::Duration;
-
-use async_thrift::protocol::{TFieldIdentifier, TType};
+use async_thrift::OrderedFloat;
 use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
-use async_thrift::protocol::TAsyncOutputProtocol;
-use async_thrift::transport::{AsyncReadHalf, AsyncWrite, AsyncWriteHalf, TAsyncIoChannel};
+use async_thrift::transport::TAsyncIoChannel;
 use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransport, TAsyncBufferedWriteTransport};
 use async_thrift::transport::async_framed::{TAsyncFramedReadTransport, TAsyncFramedWriteTransport};
 use async_thrift::transport::async_socket::TAsyncTcpChannel;
-use crate::async_thrift_test::echo::{LongMessageTestSyncClient,TLongMessageTestSyncClient};
-use crate::async_thrift_test::tutorial::{CalculatorSyncClient, TCalculatorSyncClient};
-
-pub type Result<T> = std::result::Result<T, Error>;
-
 
-pub async fn run_client(addr: String, loop_num: i32, receiver: Receiver<Vec<i8>>) -> async_thrift::Result<(Box<Vec<i64>>)> {
-    // time
-    // let start = time::now();
+use crate::async_thrift_test::bench::{BenchmarkSyncClient, Item, Record, TBenchmarkSyncClient};
+use crate::mix::{Method, MethodPicker};
+use crate::pacer::Pacer;
+use crate::scenario::{Scenario, Transport};
 
-    let mut stream = TcpStream::connect(addr.as_str()).await?;
+/// Call the server on the connection at `index` until the scenario ends.
+///
+/// Returns the latencies in ns of the calls started after the warmup.
+pub async fn run_client(addr: String, scenario: Scenario, start: Instant, index: usize) -> async_thrift::Result<Vec<i64>> {
+    let stream = TcpStream::connect(addr.as_str()).await?;
+    // calls are written in pieces, do not hold them back for an ack
+    stream.set_nodelay(true)?;
 
     let mut c = TAsyncTcpChannel::with_stream(stream);
 
-    let (i_chan, o_chan) = c.split().unwrap();
+    let (i_chan, o_chan) = c.split()?;
 
-    let i_prot = TAsyncBinaryInputProtocol::new(
-        TAsyncBufferedReadTransport::new(i_chan), true,
-    );
-    let o_prot = TAsyncBinaryOutputProtocol::new(
-        TAsyncBufferedWriteTransport::new(o_chan), true,
-    );
+    // the protocol is always binary, the only one async_thrift implements
+    let time_array = match scenario.transport {
+        Transport::Buffered => {
+            let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferedReadTransport::new(i_chan), true);
+            let o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferedWriteTransport::new(o_chan), true);
+            run_calls(&mut BenchmarkSyncClient::new(i_prot, o_prot), &scenario, start, index).await?
+        }
+        Transport::Framed => {
+            let i_prot = TAsyncBinaryInputProtocol::new(TAsyncFramedReadTransport::new(i_chan), true);
+            let o_prot = TAsyncBinaryOutputProtocol::new(TAsyncFramedWriteTransport::new(o_chan), true);
+            run_calls(&mut BenchmarkSyncClient::new(i_prot, o_prot), &scenario, start, index).await?
+        }
+    };
 
-    let mut client = CalculatorSyncClient::new(i_prot, o_prot);
+    c.close();
 
-    let mut time_array = Vec::with_capacity(loop_num as usize);
+    Ok(time_array)
+}
+
+async fn run_calls<C: TBenchmarkSyncClient + Send>(client: &mut C, scenario: &Scenario, start: Instant, index: usize) -> async_thrift::Result<Vec<i64>> {
+    let (measure_from, end) = scenario.phases(start);
+    let payload = vec![0u8; scenario.payload_size];
+    let record = record(scenario.record_items);
+    let mut picker = MethodPicker::new(scenario.mix());
+    let mut time_array = Vec::new();
+    let mut pacer = Pacer::new(scenario, start, index);
 
     loop {
-        let vec = receiver.recv().await.unwrap();
-        if vec.len() >= 1 {
-            let before = time::Instant::now();
-            client.ping().await?;
-            let end = time::Instant::now();
-            time_array.push((end - before).num_nanoseconds().unwrap());
-        } else {
+        let intended = pacer.next_send();
+        if intended >= end {
             break;
         }
+        let now = Instant::now();
+        if intended > now {
+            task::sleep(intended - now).await;
+        }
+        match picker.next_method() {
+            Method::Ping => client.ping().await?,
+            Method::Echo => {
+                client.echo(payload.clone()).await?;
+            }
+            Method::EchoRecord => {
+                client.echo_record(record.clone()).await?;
+            }
+        }
+        if intended >= measure_from {
+            time_array.push(intended.elapsed().as_nanos() as i64);
+        }
     }
 
-    c.close();
+    Ok(time_array)
+}
 
-    Ok((Box::new(time_array)))
-}
\ No newline at end of file
+/// The record echoed by `echo_record`, with `items` items, map entries and
+/// labels.
+fn record(items: usize) -> Record {
+    let item = |i: usize| Item::new(i as i64, format!("item {}", i), OrderedFloat::from(i as f64), (0..8).collect::<Vec<i32>>());
+    Record::new(0, "record".to_owned(), item(0),
+                (0..items).map(item).collect::<Vec<_>>(),
+                (0..items).map(|i| (format!("item {}", i), item(i))).collect::<BTreeMap<_, _>>(),
+                (0..items).map(|i| format!("label {}", i)).collect::<BTreeSet<_>>())
+}
@@ -1,5 +1,3 @@
 pub mod server;
 pub mod client;
-pub mod tutorial;
-pub mod echo;
-
+pub mod bench;
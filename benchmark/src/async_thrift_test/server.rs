@@ -1,39 +1,57 @@
-use async_std::{net::ToSocketAddrs, task, io};
 use async_trait::async_trait;
 
 use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
 use async_thrift::server;
-use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransport, TAsyncBufferedWriteTransportFactory};
+use async_thrift::server::listener::TAsyncListenerBuilder;
+use async_thrift::transport::{TAsyncReadTransportFactory, TAsyncWriteTransportFactory};
+use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
 use async_thrift::transport::async_framed::{TAsyncFramedReadTransportFactory, TAsyncFramedWriteTransportFactory};
 
-use crate::async_thrift_test::tutorial::{CalculatorSyncHandler, CalculatorSyncProcessor};
-use crate::async_thrift_test::echo::{LongMessageTestSyncProcessor, LongMessageTestSyncHandler};
+use crate::async_thrift_test::bench::{BenchmarkSyncHandler, BenchmarkSyncProcessor, Record};
+use crate::scenario::{Scenario, Transport};
 
-pub async fn run_server(addr: String) {
-    let processor = CalculatorSyncProcessor::new(PartHandler {});
-    let r_trans_factory = TAsyncBufferedReadTransportFactory::new();
-    let w_trans_factory = TAsyncBufferedWriteTransportFactory::new();
+pub async fn run_server(addr: String, scenario: Scenario) -> async_thrift::Result<()> {
+    match scenario.transport {
+        Transport::Buffered => serve(addr, TAsyncBufferedReadTransportFactory::new(),
+                                     TAsyncBufferedWriteTransportFactory::new(), scenario.acceptors).await,
+        Transport::Framed => serve(addr, TAsyncFramedReadTransportFactory::new(),
+                                   TAsyncFramedWriteTransportFactory::new(), scenario.acceptors).await,
+    }
+}
+
+// the server accepts on `acceptor_num` SO_REUSEPORT listeners when it is greater than 1
+async fn serve<RTF, WTF>(addr: String, r_trans_factory: RTF, w_trans_factory: WTF, acceptor_num: usize) -> async_thrift::Result<()>
+    where
+        RTF: TAsyncReadTransportFactory + Send + Sync + 'static,
+        WTF: TAsyncWriteTransportFactory + Send + Sync + 'static,
+{
+    let processor = BenchmarkSyncProcessor::new(PartHandler {});
     let i_proto_factory = TAsyncBinaryInputProtocolFactory::new();
     let o_proto_factory = TAsyncBinaryOutputProtocolFactory::new();
     let mut s = server::asynced::TAsyncServer::new(r_trans_factory, i_proto_factory, w_trans_factory, o_proto_factory, processor);
 
-    s.listen(addr.as_str()).await;
+    // replies are written in pieces, do not hold them back for an ack
+    let builder = TAsyncListenerBuilder::new(addr.as_str()).nodelay(true);
+    if acceptor_num > 1 {
+        s.listen_reuseport(&builder, acceptor_num).await
+    } else {
+        s.listen_with(&builder).await
+    }
 }
 
 struct PartHandler {}
 
 #[async_trait]
-impl CalculatorSyncHandler for PartHandler {
+impl BenchmarkSyncHandler for PartHandler {
     async fn handle_ping(&self) -> async_thrift::Result<()> {
         Ok(())
     }
+
+    async fn handle_echo(&self, payload: Vec<u8>) -> async_thrift::Result<Vec<u8>> {
+        Ok(payload)
+    }
+
+    async fn handle_echo_record(&self, record: Record) -> async_thrift::Result<Record> {
+        Ok(record)
+    }
 }
-// impl LongMessageTestSyncHandler for PartHandler {
-//     // async fn handle_ping(&self) -> async_thrift::Result<()> {
-//     //     Ok(())
-//     // }
-//
-//     async fn handle_echo(&self, input: Vec<i8>) -> async_thrift::Result<Vec<i8>> {
-//         Ok(input)
-//     }
-// }
@@ -0,0 +1,938 @@
+// Autogenerated by Thrift Compiler ()
+// DO NOT EDIT UNLESS YOU ARE SURE THAT YOU KNOW WHAT YOU ARE DOING
+
+#![allow(unused_imports)]
+#![allow(unused_extern_crates)]
+#![cfg_attr(feature = "cargo-clippy", allow(too_many_arguments, type_complexity))]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate async_thrift_tokio;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::{From, TryFrom};
+use std::default::Default;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use async_thrift_tokio::{ApplicationError, ApplicationErrorKind, ProtocolError, ProtocolErrorKind, TThriftClient};
+use async_thrift_tokio::protocol::{TFieldIdentifier, TListIdentifier, TMapIdentifier, TMessageIdentifier, TMessageType, TAsyncInputProtocol, TAsyncOutputProtocol, TSetIdentifier, TStructIdentifier, TType};
+use async_thrift_tokio::protocol::field_id;
+use async_thrift_tokio::protocol::verify_expected_message_type;
+use async_thrift_tokio::protocol::verify_expected_sequence_number;
+use async_thrift_tokio::protocol::verify_expected_service_call;
+use async_thrift_tokio::protocol::verify_required_field_exists;
+use async_trait::async_trait;
+use async_thrift_tokio::server::TAsyncProcessor;
+use async_thrift_tokio::OrderedFloat;
+
+//
+// Item
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Item {
+  pub id: Option<i64>,
+  pub name: Option<String>,
+  pub score: Option<OrderedFloat<f64>>,
+  pub tags: Option<Vec<i32>>,
+}
+
+impl Item {
+  pub fn new<F1, F2, F3, F4>(id: F1, name: F2, score: F3, tags: F4) -> Item where F1: Into<Option<i64>>, F2: Into<Option<String>>, F3: Into<Option<OrderedFloat<f64>>>, F4: Into<Option<Vec<i32>>> {
+    Item {
+      id: id.into(),
+      name: name.into(),
+      score: score.into(),
+      tags: tags.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<Item> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i64> = Some(0);
+    let mut f_2: Option<String> = Some("".to_owned());
+    let mut f_3: Option<OrderedFloat<f64>> = Some(OrderedFloat::from(0.0));
+    let mut f_4: Option<Vec<i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i64().await?;
+          f_1 = Some(val);
+        },
+        2 => {
+          let val = i_prot.read_string().await?;
+          f_2 = Some(val);
+        },
+        3 => {
+          let val = OrderedFloat::from(i_prot.read_double().await?);
+          f_3 = Some(val);
+        },
+        4 => {
+          let list_ident = i_prot.read_list_begin().await?;
+          let mut val: Vec<i32> = Vec::with_capacity(list_ident.size as usize);
+          for _ in 0..list_ident.size {
+            let list_elem_0 = i_prot.read_i32().await?;
+            val.push(list_elem_0);
+          }
+          i_prot.read_list_end().await?;
+          f_4 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Item {
+      id: f_1,
+      name: f_2,
+      score: f_3,
+      tags: f_4,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("Item");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.id {
+      o_prot.write_field_begin(&TFieldIdentifier::new("id", TType::I64, 1)).await?;
+      o_prot.write_i64(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.name {
+      o_prot.write_field_begin(&TFieldIdentifier::new("name", TType::String, 2)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(fld_var) = self.score {
+      o_prot.write_field_begin(&TFieldIdentifier::new("score", TType::Double, 3)).await?;
+      o_prot.write_double(fld_var.into()).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.tags {
+      o_prot.write_field_begin(&TFieldIdentifier::new("tags", TType::List, 4)).await?;
+      o_prot.write_list_begin(&TListIdentifier::new(TType::I32, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        o_prot.write_i32(*e).await?;
+      }
+      o_prot.write_list_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+impl Default for Item {
+  fn default() -> Self {
+    Item{
+      id: Some(0),
+      name: Some("".to_owned()),
+      score: Some(OrderedFloat::from(0.0)),
+      tags: None,
+    }
+  }
+}
+
+//
+// Record
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Record {
+  pub id: Option<i64>,
+  pub name: Option<String>,
+  pub head: Option<Item>,
+  pub items: Option<Vec<Item>>,
+  pub index: Option<BTreeMap<String, Item>>,
+  pub labels: Option<BTreeSet<String>>,
+}
+
+impl Record {
+  pub fn new<F1, F2, F3, F4, F5, F6>(id: F1, name: F2, head: F3, items: F4, index: F5, labels: F6) -> Record where F1: Into<Option<i64>>, F2: Into<Option<String>>, F3: Into<Option<Item>>, F4: Into<Option<Vec<Item>>>, F5: Into<Option<BTreeMap<String, Item>>>, F6: Into<Option<BTreeSet<String>>> {
+    Record {
+      id: id.into(),
+      name: name.into(),
+      head: head.into(),
+      items: items.into(),
+      index: index.into(),
+      labels: labels.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<Record> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i64> = Some(0);
+    let mut f_2: Option<String> = Some("".to_owned());
+    let mut f_3: Option<Item> = None;
+    let mut f_4: Option<Vec<Item>> = None;
+    let mut f_5: Option<BTreeMap<String, Item>> = None;
+    let mut f_6: Option<BTreeSet<String>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i64().await?;
+          f_1 = Some(val);
+        },
+        2 => {
+          let val = i_prot.read_string().await?;
+          f_2 = Some(val);
+        },
+        3 => {
+          let val = Item::read_from_in_protocol(i_prot).await?;
+          f_3 = Some(val);
+        },
+        4 => {
+          let list_ident = i_prot.read_list_begin().await?;
+          let mut val: Vec<Item> = Vec::with_capacity(list_ident.size as usize);
+          for _ in 0..list_ident.size {
+            let list_elem_1 = Item::read_from_in_protocol(i_prot).await?;
+            val.push(list_elem_1);
+          }
+          i_prot.read_list_end().await?;
+          f_4 = Some(val);
+        },
+        5 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<String, Item> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_2 = i_prot.read_string().await?;
+            let map_val_3 = Item::read_from_in_protocol(i_prot).await?;
+            val.insert(map_key_2, map_val_3);
+          }
+          i_prot.read_map_end().await?;
+          f_5 = Some(val);
+        },
+        6 => {
+          let set_ident = i_prot.read_set_begin().await?;
+          let mut val: BTreeSet<String> = BTreeSet::new();
+          for _ in 0..set_ident.size {
+            let set_elem_4 = i_prot.read_string().await?;
+            val.insert(set_elem_4);
+          }
+          i_prot.read_set_end().await?;
+          f_6 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Record {
+      id: f_1,
+      name: f_2,
+      head: f_3,
+      items: f_4,
+      index: f_5,
+      labels: f_6,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("Record");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.id {
+      o_prot.write_field_begin(&TFieldIdentifier::new("id", TType::I64, 1)).await?;
+      o_prot.write_i64(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.name {
+      o_prot.write_field_begin(&TFieldIdentifier::new("name", TType::String, 2)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.head {
+      o_prot.write_field_begin(&TFieldIdentifier::new("head", TType::Struct, 3)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.items {
+      o_prot.write_field_begin(&TFieldIdentifier::new("items", TType::List, 4)).await?;
+      o_prot.write_list_begin(&TListIdentifier::new(TType::Struct, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        e.write_to_out_protocol(o_prot).await?;
+      }
+      o_prot.write_list_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.index {
+      o_prot.write_field_begin(&TFieldIdentifier::new("index", TType::Map, 5)).await?;
+      o_prot.write_map_begin(&TMapIdentifier::new(TType::String, TType::Struct, fld_var.len() as i32)).await?;
+      for (k, v) in fld_var {
+        o_prot.write_string(k).await?;
+        v.write_to_out_protocol(o_prot).await?;
+      }
+      o_prot.write_map_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.labels {
+      o_prot.write_field_begin(&TFieldIdentifier::new("labels", TType::Set, 6)).await?;
+      o_prot.write_set_begin(&TSetIdentifier::new(TType::String, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        o_prot.write_string(e).await?;
+      }
+      o_prot.write_set_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+impl Default for Record {
+  fn default() -> Self {
+    Record{
+      id: Some(0),
+      name: Some("".to_owned()),
+      head: None,
+      items: None,
+      index: None,
+      labels: None,
+    }
+  }
+}
+
+//
+// Benchmark service client
+//
+
+#[async_trait]
+pub trait TBenchmarkSyncClient {
+  async fn ping(&mut self) -> async_thrift_tokio::Result<()>;
+  async fn echo(&mut self, payload: Vec<u8>) -> async_thrift_tokio::Result<Vec<u8>>;
+  async fn echo_record(&mut self, record: Record) -> async_thrift_tokio::Result<Record>;
+}
+
+pub trait TBenchmarkSyncClientMarker {}
+
+pub struct BenchmarkSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {
+  _i_prot: IP,
+  _o_prot: OP,
+  _sequence_number: i32,
+}
+
+impl <IP, OP> BenchmarkSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {
+  pub fn new(input_protocol: IP, output_protocol: OP) -> BenchmarkSyncClient<IP, OP> {
+    BenchmarkSyncClient { _i_prot: input_protocol, _o_prot: output_protocol, _sequence_number: 0 }
+  }
+}
+
+impl <IP, OP> TThriftClient for BenchmarkSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {
+  fn i_prot_mut(&mut self) -> &mut (dyn TAsyncInputProtocol + Send) { &mut self._i_prot }
+  fn o_prot_mut(&mut self) -> &mut (dyn TAsyncOutputProtocol + Send) { &mut self._o_prot }
+  fn sequence_number(&self) -> i32 { self._sequence_number }
+  fn increment_sequence_number(&mut self) -> i32 { self._sequence_number += 1; self._sequence_number }
+}
+
+impl <IP, OP> TBenchmarkSyncClientMarker for BenchmarkSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {}
+
+#[async_trait]
+impl <C: TThriftClient + TBenchmarkSyncClientMarker+ Send> TBenchmarkSyncClient for C {
+  async fn ping(&mut self) -> async_thrift_tokio::Result<()> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("ping", TMessageType::Call, self.sequence_number());
+        let call_args = BenchmarkPingArgs {  };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("ping", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift_tokio::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift_tokio::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = BenchmarkPingResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn echo(&mut self, payload: Vec<u8>) -> async_thrift_tokio::Result<Vec<u8>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("echo", TMessageType::Call, self.sequence_number());
+        let call_args = BenchmarkEchoArgs { payload: payload };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("echo", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift_tokio::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift_tokio::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = BenchmarkEchoResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn echo_record(&mut self, record: Record) -> async_thrift_tokio::Result<Record> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("echo_record", TMessageType::Call, self.sequence_number());
+        let call_args = BenchmarkEchoRecordArgs { record: record };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("echo_record", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift_tokio::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift_tokio::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = BenchmarkEchoRecordResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+}
+
+//
+// Benchmark service processor
+//
+
+#[async_trait]
+pub trait BenchmarkSyncHandler {
+  async fn handle_ping(&self) -> async_thrift_tokio::Result<()>;
+  async fn handle_echo(&self, payload: Vec<u8>) -> async_thrift_tokio::Result<Vec<u8>>;
+  async fn handle_echo_record(&self, record: Record) -> async_thrift_tokio::Result<Record>;
+}
+
+pub struct BenchmarkSyncProcessor<H: BenchmarkSyncHandler> {
+  handler: H,
+}
+
+impl <H: BenchmarkSyncHandler> BenchmarkSyncProcessor<H> {
+  pub fn new(handler: H) -> BenchmarkSyncProcessor<H> {
+    BenchmarkSyncProcessor {
+      handler,
+    }
+  }
+  async fn process_ping(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    TBenchmarkProcessFunctions::process_ping(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_echo(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    TBenchmarkProcessFunctions::process_echo(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_echo_record(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    TBenchmarkProcessFunctions::process_echo_record(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+}
+
+pub struct TBenchmarkProcessFunctions;
+
+impl TBenchmarkProcessFunctions {
+  pub async fn process_ping<H: BenchmarkSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    BenchmarkPingArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_ping().await {
+      Ok(_) => {
+        let message_ident = TMessageIdentifier::new("ping", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = BenchmarkPingResult {  };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift_tokio::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("ping", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift_tokio::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("ping", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift_tokio::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_echo<H: BenchmarkSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let args = BenchmarkEchoArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_echo(args.payload).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("echo", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = BenchmarkEchoResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift_tokio::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("echo", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift_tokio::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("echo", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift_tokio::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_echo_record<H: BenchmarkSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let args = BenchmarkEchoRecordArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_echo_record(args.record).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("echo_record", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = BenchmarkEchoRecordResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift_tokio::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("echo_record", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift_tokio::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("echo_record", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift_tokio::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+}
+
+#[async_trait]
+impl <H: BenchmarkSyncHandler + Send + Sync> TAsyncProcessor for BenchmarkSyncProcessor<H> {
+  async fn process(&self, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let message_ident = i_prot.read_message_begin().await?;
+    let res = match &*message_ident.name {
+      "ping" => {
+        self.process_ping(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "echo" => {
+        self.process_echo(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "echo_record" => {
+        self.process_echo_record(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      method => {
+        Err(
+          async_thrift_tokio::Error::Application(
+            ApplicationError::new(
+              ApplicationErrorKind::UnknownMethod,
+              format!("unknown method {}", method)
+            )
+          )
+        )
+      },
+    };
+    async_thrift_tokio::server::handle_process_result(&message_ident, res, o_prot).await
+  }
+}
+
+//
+// BenchmarkPingArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct BenchmarkPingArgs {
+}
+
+impl BenchmarkPingArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<BenchmarkPingArgs> {
+    i_prot.read_struct_begin().await?;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = BenchmarkPingArgs {
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("ping_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// BenchmarkPingResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct BenchmarkPingResult {
+}
+
+impl BenchmarkPingResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<BenchmarkPingResult> {
+    i_prot.read_struct_begin().await?;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = BenchmarkPingResult {};
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("BenchmarkPingResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift_tokio::Result<()> {
+    Ok(())
+  }
+}
+
+//
+// BenchmarkEchoArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct BenchmarkEchoArgs {
+  payload: Vec<u8>,
+}
+
+impl BenchmarkEchoArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<BenchmarkEchoArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Vec<u8>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_bytes().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("BenchmarkEchoArgs.payload", &f_1)?;
+    let ret = BenchmarkEchoArgs {
+      payload: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("echo_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("payload", TType::String, 1)).await?;
+    o_prot.write_bytes(&self.payload).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// BenchmarkEchoResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct BenchmarkEchoResult {
+  result_value: Option<Vec<u8>>,
+}
+
+impl BenchmarkEchoResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<BenchmarkEchoResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<Vec<u8>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_bytes().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = BenchmarkEchoResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("BenchmarkEchoResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::String, 0)).await?;
+      o_prot.write_bytes(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift_tokio::Result<Vec<u8>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift_tokio::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for BenchmarkEcho"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// BenchmarkEchoRecordArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct BenchmarkEchoRecordArgs {
+  record: Record,
+}
+
+impl BenchmarkEchoRecordArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<BenchmarkEchoRecordArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Record> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = Record::read_from_in_protocol(i_prot).await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("BenchmarkEchoRecordArgs.record", &f_1)?;
+    let ret = BenchmarkEchoRecordArgs {
+      record: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("echo_record_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("record", TType::Struct, 1)).await?;
+    self.record.write_to_out_protocol(o_prot).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// BenchmarkEchoRecordResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct BenchmarkEchoRecordResult {
+  result_value: Option<Record>,
+}
+
+impl BenchmarkEchoRecordResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift_tokio::Result<BenchmarkEchoRecordResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<Record> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = Record::read_from_in_protocol(i_prot).await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = BenchmarkEchoRecordResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift_tokio::Result<()> {
+    let struct_ident = TStructIdentifier::new("BenchmarkEchoRecordResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Struct, 0)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift_tokio::Result<Record> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift_tokio::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for BenchmarkEchoRecord"
+          )
+        )
+      )
+    }
+  }
+}
+
@@ -1,62 +1,92 @@
-// only for test!!!
-//
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
 
-use std::io::Error;
-use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::TcpStream;
+use tokio::time::delay_until;
 
-use time::Duration;
-use tokio::{
-    net::{TcpStream, ToSocketAddrs},
-    task,
-};
-
-use async_thrift_tokio::protocol::{TFieldIdentifier, TType};
-use async_thrift_tokio::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
+use async_thrift_tokio::OrderedFloat;
 use async_thrift_tokio::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
-use async_thrift_tokio::transport::{AsyncReadHalf, AsyncWrite, AsyncWriteHalf, TAsyncIoChannel};
+use async_thrift_tokio::transport::TAsyncIoChannel;
 use async_thrift_tokio::transport::async_buffered::{TAsyncBufferedReadTransport, TAsyncBufferedWriteTransport};
 use async_thrift_tokio::transport::async_framed::{TAsyncFramedReadTransport, TAsyncFramedWriteTransport};
 use async_thrift_tokio::transport::async_socket::TAsyncTcpChannel;
 
-use crate::async_thrift_test_tokio::tutorial::{CalculatorSyncClient, TCalculatorSyncClient};
-use async_std::sync::Receiver;
-pub type Result<T> = std::result::Result<T, Error>;
-
+use crate::async_thrift_test_tokio::bench::{BenchmarkSyncClient, Item, Record, TBenchmarkSyncClient};
+use crate::mix::{Method, MethodPicker};
+use crate::pacer::Pacer;
+use crate::scenario::{Scenario, Transport};
 
-pub async fn run_client(addr: String, loop_num: i32, receiver: Receiver<i32>) -> async_thrift_tokio::Result<Box<Vec<i64>>> {
-    // time
-    // let start = time::now();
+/// Call the server on the connection at `index` until the scenario ends.
+///
+/// Returns the latencies in ns of the calls started after the warmup.
+pub async fn run_client(addr: String, scenario: Scenario, start: Instant, index: usize) -> async_thrift_tokio::Result<Vec<i64>> {
     let stream = TcpStream::connect(addr.as_str()).await?;
+    // calls are written in pieces, do not hold them back for an ack
+    stream.set_nodelay(true)?;
 
     let mut c = TAsyncTcpChannel::with_stream(stream);
 
-    let (i_chan, o_chan) = c.split().unwrap();
+    let (i_chan, o_chan) = c.split()?;
 
-    let i_prot = TAsyncBinaryInputProtocol::new(
-        TAsyncBufferedReadTransport::new(i_chan), true,
-    );
-    let o_prot = TAsyncBinaryOutputProtocol::new(
-        TAsyncBufferedWriteTransport::new(o_chan), true,
-    );
+    // the protocol is always binary, the only one async_thrift_tokio implements
+    let time_array = match scenario.transport {
+        Transport::Buffered => {
+            let i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferedReadTransport::new(i_chan), true);
+            let o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferedWriteTransport::new(o_chan), true);
+            run_calls(&mut BenchmarkSyncClient::new(i_prot, o_prot), &scenario, start, index).await?
+        }
+        Transport::Framed => {
+            let i_prot = TAsyncBinaryInputProtocol::new(TAsyncFramedReadTransport::new(i_chan), true);
+            let o_prot = TAsyncBinaryOutputProtocol::new(TAsyncFramedWriteTransport::new(o_chan), true);
+            run_calls(&mut BenchmarkSyncClient::new(i_prot, o_prot), &scenario, start, index).await?
+        }
+    };
 
-    let mut client = CalculatorSyncClient::new(i_prot, o_prot);
+    c.close();
+
+    Ok(time_array)
+}
 
-    let mut time_array = Vec::with_capacity(loop_num as usize);
+async fn run_calls<C: TBenchmarkSyncClient + Send>(client: &mut C, scenario: &Scenario, start: Instant, index: usize) -> async_thrift_tokio::Result<Vec<i64>> {
+    let (measure_from, end) = scenario.phases(start);
+    let payload = vec![0u8; scenario.payload_size];
+    let record = record(scenario.record_items);
+    let mut picker = MethodPicker::new(scenario.mix());
+    let mut time_array = Vec::new();
+    let mut pacer = Pacer::new(scenario, start, index);
 
     loop {
-        let x = receiver.recv().await.unwrap();
-        if x == 1 {
-            let before = time::Instant::now();
-            client.ping().await?;
-            let end = time::Instant::now();
-            time_array.push((end - before).num_nanoseconds().unwrap());
-        }
-        else {
+        let intended = pacer.next_send();
+        if intended >= end {
             break;
         }
+        let now = Instant::now();
+        if intended > now {
+            delay_until(tokio::time::Instant::from_std(intended)).await;
+        }
+        match picker.next_method() {
+            Method::Ping => client.ping().await?,
+            Method::Echo => {
+                client.echo(payload.clone()).await?;
+            }
+            Method::EchoRecord => {
+                client.echo_record(record.clone()).await?;
+            }
+        }
+        if intended >= measure_from {
+            time_array.push(intended.elapsed().as_nanos() as i64);
+        }
     }
 
-    c.close();
+    Ok(time_array)
+}
 
-    Ok((Box::new(time_array)))
+/// The record echoed by `echo_record`, with `items` items, map entries and
+/// labels.
+fn record(items: usize) -> Record {
+    let item = |i: usize| Item::new(i as i64, format!("item {}", i), OrderedFloat::from(i as f64), (0..8).collect::<Vec<i32>>());
+    Record::new(0, "record".to_owned(), item(0),
+                (0..items).map(item).collect::<Vec<_>>(),
+                (0..items).map(|i| (format!("item {}", i), item(i))).collect::<BTreeMap<_, _>>(),
+                (0..items).map(|i| format!("label {}", i)).collect::<BTreeSet<_>>())
 }
@@ -1,3 +1,3 @@
 pub mod server;
 pub mod client;
-pub mod tutorial;
+pub mod bench;
@@ -1,34 +1,50 @@
-use std::io::Error;
-
-use async_std::{
-    net::ToSocketAddrs,
-    task,
-};
 use async_trait::async_trait;
 
-use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
-use async_thrift::server;
-use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransport, TAsyncBufferedWriteTransportFactory};
-use async_thrift::transport::async_framed::{TAsyncFramedReadTransportFactory, TAsyncFramedWriteTransportFactory};
+use async_thrift_tokio::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+use async_thrift_tokio::server;
+use async_thrift_tokio::transport::{TAsyncReadTransportFactory, TAsyncWriteTransportFactory};
+use async_thrift_tokio::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+use async_thrift_tokio::transport::async_framed::{TAsyncFramedReadTransportFactory, TAsyncFramedWriteTransportFactory};
+
+use crate::async_thrift_test_tokio::bench::{BenchmarkSyncHandler, BenchmarkSyncProcessor, Record};
+use crate::scenario::{Scenario, Transport};
 
-use crate::async_thrift_test::tutorial::{CalculatorSyncHandler, CalculatorSyncProcessor};
+pub async fn run_server(addr: String, scenario: Scenario) -> async_thrift_tokio::Result<()> {
+    match scenario.transport {
+        Transport::Buffered => serve(addr, TAsyncBufferedReadTransportFactory::new(),
+                                     TAsyncBufferedWriteTransportFactory::new()).await,
+        Transport::Framed => serve(addr, TAsyncFramedReadTransportFactory::new(),
+                                   TAsyncFramedWriteTransportFactory::new()).await,
+    }
+}
 
-pub async fn run_server(addr: String) {
-    let processor = CalculatorSyncProcessor::new(PartHandler {});
-    let r_trans_factory = TAsyncBufferedReadTransportFactory::new();
-    let w_trans_factory = TAsyncBufferedWriteTransportFactory::new();
+async fn serve<RTF, WTF>(addr: String, r_trans_factory: RTF, w_trans_factory: WTF) -> async_thrift_tokio::Result<()>
+    where
+        RTF: TAsyncReadTransportFactory + 'static,
+        WTF: TAsyncWriteTransportFactory + 'static,
+{
+    let processor = BenchmarkSyncProcessor::new(PartHandler {});
     let i_proto_factory = TAsyncBinaryInputProtocolFactory::new();
     let o_proto_factory = TAsyncBinaryOutputProtocolFactory::new();
-    let mut s = server::asynced::TAsyncServer::new(r_trans_factory, i_proto_factory, w_trans_factory, o_proto_factory, processor);
+    let mut s = server::asynced::TAsyncServer::new(r_trans_factory, i_proto_factory, w_trans_factory, o_proto_factory, processor)
+        .with_nodelay(true);
 
-    s.listen(addr.as_str()).await;
+    s.listen(addr.as_str()).await
 }
 
 struct PartHandler {}
 
 #[async_trait]
-impl CalculatorSyncHandler for PartHandler {
-    async fn handle_ping(&self) -> async_thrift::Result<()> {
+impl BenchmarkSyncHandler for PartHandler {
+    async fn handle_ping(&self) -> async_thrift_tokio::Result<()> {
         Ok(())
     }
+
+    async fn handle_echo(&self, payload: Vec<u8>) -> async_thrift_tokio::Result<Vec<u8>> {
+        Ok(payload)
+    }
+
+    async fn handle_echo_record(&self, record: Record) -> async_thrift_tokio::Result<Record> {
+        Ok(record)
+    }
 }
@@ -3,22 +3,19 @@
 //#[global_allocator]
 //static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
-use std::cell::RefCell;
 use std::fs::File;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::process;
 use std::thread;
-use std::time::Duration;
+use std::time::Instant;
 
-use async_std::io::Error;
-use async_std::sync::channel;
 use async_std::task;
-use futures::future::*;
-use thrift::transport::TTcpChannel;
+use futures::future::join_all;
+use structopt::StructOpt;
 
-// async use
-use async_thrift::server;
-
-use crate::util::{handle_time, parse_args};
+use crate::mix::Mix;
+use crate::scenario::{Arrival, Protocol, Runtime, Scenario, Transport};
+use crate::util::Report;
 
 mod async_thrift_test;
 
@@ -26,306 +23,254 @@ mod async_thrift_test;
 mod sync_thrift_test;
 mod async_thrift_test_tokio;
 
+mod mix;
+mod pacer;
+mod scenario;
 
 // util
 mod util;
 
-// const
-const CONFIG_LOCATION: usize = 0;
-const SYNC_LOCATION: usize = 1;
-const ASYNC_LOCATION: usize = 2;
-const ASYNC_TOKIO_LOCATION: usize = 3;
-
-
-// const
-const RUN_CLIENT: usize = 0;
-const RUN_SERVER: usize = 1;
-const RUN_SYNC: usize = 2;
-const RUN_ASYNC: usize = 3;
-const RUN_ASYNC_TOKIO: usize = 4;
-const THREAD_NUM: usize = 5;
-const LOOP_NUM: usize = 6;
-const ADDR: usize = 7;
-
-// print format
-const PRINT_CSV: bool = false;
-
+/// Benchmark the async-std, tokio and sync thrift rpc implementations.
 ///
-const DEFAULT_RUN_CLIENT: &str = "true";
-const DEFAULT_RUN_SERVER: &str = "true";
-const DEFAULT_RUN_SYNC: &str = "true";
-const DEFAULT_RUN_ASYNC: &str = "true";
-const DEFAULT_RUN_ASYNC_TOKIO: &str = "true";
-const DEFAULT_THREAD_NUM: &str = "100";
-const DEFAULT_LOOP_NUM: &str = "1000";
-const DEFAULT_ADDR: &str = "127.0.0.1:9090";
-
-// run sync server and client
-fn run_sync_both(output: &mut Vec<String>, args: Arc<Vec<String>>) {
-    println!("begin sync benchmark...");
-
-    if args[RUN_SERVER] == String::from("true") {
-        // print config
-        output[CONFIG_LOCATION] = util::format_config(args[THREAD_NUM].parse::<i32>().unwrap(),
-                                                      args[LOOP_NUM].parse::<i32>().unwrap());
-        // start server
-        let addr = Clone::clone(&args[ADDR]);
-
-        let server = thread::spawn(move || sync_thrift_test::server::run(addr.as_str()));
-        // we need to wait the server to run
-        thread::sleep(Duration::from_secs(2));
-
-        if args[RUN_CLIENT] != String::from("true") {
-            println!("server is online");
-            server.join();
-
-            return;
-        }
-    }
-
-    if args[RUN_CLIENT] == String::from("true") {
-        // time clock start here
-        let start = time::Instant::now();
-
-        // build client thread
-        let mut list = Vec::new();
-
-
-        for _i in 0..args[THREAD_NUM].parse::<i32>().unwrap() {
-            // to ensure tcp sync queue is enough
-            let stream = std::net::TcpStream::connect(args[ADDR].as_str()).unwrap();
-            // build client
-            let loop_num = args[LOOP_NUM].parse::<i32>().unwrap();
-            //
-            list.push(thread::spawn(move || sync_thrift_test::client::run(stream,
-                                                                          loop_num)));
-        }
+/// Scenarios come from a scenario file, or from the scenario flags with one
+/// scenario per runtime. The server of the scenario at index i listens on the
+/// port of --addr plus i.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "benchmark")]
+struct Opt {
+    /// JSON file with an array of scenarios, replacing the scenario flags.
+    #[structopt(long, short)]
+    scenario: Option<PathBuf>,
+
+    /// Runtimes to benchmark: async-std, tokio or sync.
+    #[structopt(long, default_value = "async-std,tokio,sync", use_delimiter = true)]
+    runtime: Vec<Runtime>,
+
+    /// Transport: buffered or framed.
+    #[structopt(long, default_value = "buffered")]
+    transport: Transport,
+
+    /// Protocol: binary, or compact for the sync runtime.
+    #[structopt(long, default_value = "binary")]
+    protocol: Protocol,
+
+    /// Bytes echoed by every echo call.
+    #[structopt(long, default_value = "0")]
+    payload_size: usize,
+
+    /// Items of the record echoed by every echo_record call.
+    #[structopt(long, default_value = "10")]
+    record_items: usize,
+
+    /// Weights of the methods called, e.g. "ping=1,echo=3,echo_record=1".
+    /// Without it every call is an echo with --payload-size and a ping
+    /// without.
+    #[structopt(long)]
+    mix: Option<Mix>,
+
+    /// Connections, each with one call in flight.
+    #[structopt(long, short, default_value = "100")]
+    concurrency: usize,
+
+    /// Target calls per second over all connections, 0 sends every call as
+    /// soon as the previous one returns.
+    #[structopt(long, default_value = "0")]
+    rate: u64,
+
+    /// Arrival of the calls at --rate: fixed or poisson.
+    #[structopt(long, default_value = "fixed")]
+    arrival: Arrival,
+
+    /// Seconds of unmeasured calls before the measurement.
+    #[structopt(long, default_value = "2")]
+    warmup: u64,
+
+    /// Seconds of measured calls.
+    #[structopt(long, short, default_value = "10")]
+    duration: u64,
+
+    /// SO_REUSEPORT acceptors of the async-std server.
+    #[structopt(long, default_value = "1")]
+    acceptors: usize,
+
+    /// Address of the server of the first scenario.
+    #[structopt(long, default_value = "127.0.0.1:9090")]
+    addr: String,
+
+    /// Only run the servers, until the process is killed.
+    #[structopt(long, conflicts_with = "client-only")]
+    server_only: bool,
+
+    /// Only run the clients, against servers started with --server-only.
+    #[structopt(long)]
+    client_only: bool,
+
+    /// Print the result tables as csv instead of markdown.
+    #[structopt(long)]
+    csv: bool,
+
+    /// Also write the results as JSON to this file.
+    #[structopt(long)]
+    json: Option<PathBuf>,
+}
 
-        // to collect time result from client
-        let mut res = Vec::new();
-        for task in list {
-            res.push(task.join().unwrap().unwrap());
+impl Opt {
+    fn scenarios(&self) -> Result<Vec<Scenario>, String> {
+        if let Some(ref path) = self.scenario {
+            return scenario::load(path);
         }
-
-        // time clock end here;
-        let end = time::Instant::now();
-
-        // handle raw time result to statistic
-        let time_statistic = handle_time(res);
-        output[SYNC_LOCATION] = util::format_result(String::from("sync"),
-                                                    args[THREAD_NUM].parse::<i64>().unwrap() * args[LOOP_NUM].parse::<i64>().unwrap(),
-                                                    (end - start).whole_milliseconds() as i64,
-                                                    time_statistic[0], time_statistic[1],
-                                                    time_statistic[2], time_statistic[3],
-                                                    time_statistic[4], time_statistic[5],
-                                                    time_statistic[6]);
+        Ok(self.runtime.iter().map(|&runtime| Scenario {
+            name: String::new(),
+            runtime,
+            transport: self.transport,
+            protocol: self.protocol,
+            payload_size: self.payload_size,
+            record_items: self.record_items,
+            mix: self.mix.unwrap_or_default(),
+            concurrency: self.concurrency,
+            rate: self.rate,
+            arrival: self.arrival,
+            warmup_secs: self.warmup,
+            duration_secs: self.duration,
+            acceptors: if runtime == Runtime::AsyncStd { self.acceptors } else { 1 },
+        }).collect())
     }
-
-    println!("sync finished!");
 }
 
-// run async server and client
-async fn run_async_both(output: &mut Vec<String>, args: Arc<Vec<String>>) {
-    println!("begin async benchmark...");
-
-    // print config
-    output[CONFIG_LOCATION] = util::format_config(args[THREAD_NUM].parse::<i32>().unwrap(),
-                                                  args[LOOP_NUM].parse::<i32>().unwrap());
-
-    let mut server = None;
-    let addr = &args[ADDR];
-
-    if args[RUN_SERVER] == String::from("true") {
-        server = Some(async_std::task::spawn(async_thrift_test::server::run_server(Clone::clone(addr))));
-        if args[RUN_CLIENT] != String::from("true") {
-            println!("server is online");
-            server.unwrap().await;
-
-            return;
-        }
-    }
-    if args[RUN_CLIENT] == String::from("true") {
-        let loop_num = args[LOOP_NUM].parse::<i32>().unwrap();
-        let coroutine_num = args[THREAD_NUM].parse::<i32>().unwrap();
-        let (s, r) = async_std::sync::channel((coroutine_num + (coroutine_num * loop_num)) as usize);
-        for _i in 0..(loop_num * coroutine_num) {
-            s.send(vec![0;1]).await;
-        }
-        // 0 marks that all jobs has been done
-        for _i in 0..coroutine_num {
-            s.send(vec![0;0]).await;
+/// Start the server of a scenario on its own thread.
+///
+/// Servers are never stopped, the next scenario uses the next port.
+fn spawn_server(scenario: &Scenario, addr: String) -> thread::JoinHandle<()> {
+    let scenario = scenario.clone();
+    thread::spawn(move || {
+        let res = match scenario.runtime {
+            Runtime::AsyncStd => task::block_on(async_thrift_test::server::run_server(addr.clone(), scenario))
+                .map_err(|e| format!("{:?}", e)),
+            Runtime::Tokio => {
+                let mut rt = tokio::runtime::Runtime::new().expect("cannot create a tokio runtime");
+                rt.block_on(async_thrift_test_tokio::server::run_server(addr.clone(), scenario))
+                    .map_err(|e| format!("{:?}", e))
+            }
+            Runtime::Sync => sync_thrift_test::server::run(addr.as_str(), &scenario)
+                .map_err(|e| format!("{:?}", e)),
+        };
+        if let Err(e) = res {
+            eprintln!("server on {} stopped: {}", addr, e);
         }
+    })
+}
 
-        // time
-        let mut list = Vec::new();
-
-        for _i in 0..args[THREAD_NUM].parse::<i32>().unwrap() {
-            // build client
-            list.push(async_std::task::spawn(async_thrift_test::client::run_client(Clone::clone(addr), args[LOOP_NUM].parse::<i32>().unwrap(), r.clone())));
+/// Run the clients of a scenario to the end.
+///
+/// Returns the latencies of every client that did not fail, the number of
+/// failed clients and the measured time in ms.
+fn run_clients(scenario: &Scenario, addr: &str) -> (Vec<Vec<i64>>, usize, i64) {
+    let start = Instant::now();
+    let results: Vec<Result<Vec<i64>, String>> = match scenario.runtime {
+        Runtime::AsyncStd => task::block_on(async {
+            let list = (0..scenario.concurrency)
+                .map(|i| task::spawn(async_thrift_test::client::run_client(addr.to_owned(), scenario.clone(), start, i)))
+                .collect::<Vec<_>>();
+            join_all(list).await.into_iter().map(|r| r.map_err(|e| format!("{:?}", e))).collect()
+        }),
+        Runtime::Tokio => {
+            let mut rt = tokio::runtime::Runtime::new().expect("cannot create a tokio runtime");
+            rt.block_on(async {
+                let list = (0..scenario.concurrency)
+                    .map(|i| tokio::spawn(async_thrift_test_tokio::client::run_client(addr.to_owned(), scenario.clone(), start, i)))
+                    .collect::<Vec<_>>();
+                join_all(list).await.into_iter().map(|r| match r {
+                    Ok(r) => r.map_err(|e| format!("{:?}", e)),
+                    Err(e) => Err(format!("{:?}", e)),
+                }).collect()
+            })
         }
-
-        println!("all jobs generated!");
-
-        let start = time::Instant::now();
-
-        // time clock start here
-        let raw_time_result = join_all(list).await;
-
-        // time clock end here;
-        let end = time::Instant::now();
-
-        // to collect time result from client
-        let mut res = Vec::new();
-        for task in raw_time_result {
-            res.push(task.unwrap());
+        Runtime::Sync => {
+            let list = (0..scenario.concurrency).map(|i| {
+                let addr = addr.to_owned();
+                let scenario = scenario.clone();
+                thread::spawn(move || {
+                    let stream = std::net::TcpStream::connect(addr.as_str()).map_err(|e| format!("{:?}", e))?;
+                    sync_thrift_test::client::run(stream, scenario, start, i).map_err(|e| format!("{:?}", e))
+                })
+            }).collect::<Vec<_>>();
+            list.into_iter().map(|t| t.join().unwrap_or_else(|_| Err(String::from("client panicked")))).collect()
         }
-
-        // handle raw time result to statistic
-        let time_statistic = handle_time(res);
-
-        if !PRINT_CSV {
-            output[ASYNC_LOCATION] = util::format_result(String::from("async"), args[THREAD_NUM].parse::<i64>().unwrap() * args[LOOP_NUM].parse::<i64>().unwrap(),
-                                                         (end - start).whole_milliseconds() as i64,
-                                                         time_statistic[0], time_statistic[1],
-                                                         time_statistic[2], time_statistic[3],
-                                                         time_statistic[4], time_statistic[5],
-                                                         time_statistic[6]);
-        } else {
-            output[ASYNC_LOCATION] = util::format_result_csv(String::from("async"), args[THREAD_NUM].parse::<i64>().unwrap(),
-                                                             args[LOOP_NUM].parse::<i64>().unwrap(),
-                                                             (end - start).whole_milliseconds() as i64,
-                                                             time_statistic[0], time_statistic[1],
-                                                             time_statistic[2], time_statistic[3],
-                                                             time_statistic[4], time_statistic[5],
-                                                             time_statistic[6]);
+    };
+    let (measure_from, _) = scenario.phases(start);
+    let total_time_in_ms = Instant::now().saturating_duration_since(measure_from).as_millis() as i64;
+
+    let mut time_arrays = Vec::new();
+    let mut failed_clients = 0;
+    for res in results {
+        match res {
+            Ok(time_array) => time_arrays.push(time_array),
+            Err(e) => {
+                eprintln!("client failed: {}", e);
+                failed_clients += 1;
+            }
         }
     }
-
-    if args[RUN_SERVER] == String::from("true") {
-        server.unwrap().cancel().await;
-    }
-
-    println!("async finished!");
+    (time_arrays, failed_clients, total_time_in_ms)
 }
 
-
-// run async server and client
-async fn run_async_tokio_both(output: &mut Vec<String>, args: Arc<Vec<String>>) {
-    println!("begin async tokio benchmark...");
-
-    // print config
-    output[CONFIG_LOCATION] = util::format_config(args[THREAD_NUM].parse::<i32>().unwrap(),
-                                                  args[LOOP_NUM].parse::<i32>().unwrap());
-
-    let mut server = None;
-    let addr = &args[ADDR];
-
-    if args[RUN_SERVER] == String::from("true") {
-        server = Some(tokio::task::spawn(async_thrift_test_tokio::server::run_server(Clone::clone(addr))));
-        if args[RUN_CLIENT] != String::from("true") {
-            println!("server is online");
-            server.unwrap().await;
-
-            return;
-        }
+fn run(opt: &Opt) -> Result<(), String> {
+    let scenarios = opt.scenarios()?;
+    for scenario in &scenarios {
+        scenario.validate()?;
     }
 
-    if args[RUN_CLIENT] == String::from("true") {
-        // time
-        let mut list = Vec::new();
-
-        let loop_num = args[LOOP_NUM].parse::<i32>().unwrap();
-        let coroutine_num = args[THREAD_NUM].parse::<i32>().unwrap();
-        let (s, r) = async_std::sync::channel((coroutine_num + (coroutine_num * loop_num)) as usize);
-        for _i in 0..(loop_num * coroutine_num) {
-            s.send(1).await;
-        }
-        // 0 marks that all jobs has been done
-        for _i in 0..coroutine_num {
-            s.send(0).await;
-        }
+    util::print_welcome();
 
-        for _i in 0..args[THREAD_NUM].parse::<i32>().unwrap() {
-            // build client
-            list.push(tokio::task::spawn(async_thrift_test_tokio::client::run_client(Clone::clone(addr), args[LOOP_NUM].parse::<i32>().unwrap(),r.clone())));
+    let mut servers = Vec::new();
+    let mut reports = Vec::new();
+    let mut output = Vec::new();
+    for (i, scenario) in scenarios.iter().enumerate() {
+        let addr = util::scenario_addr(&opt.addr, i)?;
+
+        if !opt.client_only {
+            servers.push(spawn_server(scenario, addr.clone()));
+            if !util::wait_for_server(&addr) {
+                return Err(format!("{}: the server on {} did not start", scenario.label(), addr));
+            }
+            println!("{}: server is online on {}", scenario.label(), addr);
         }
-        
-        let start = time::Instant::now();
-        // time clock start here
-        let raw_time_result = join_all(list).await;
-
-        // time clock end here;
-        let end = time::Instant::now();
-
-        // to collect time result from client
-        let mut res = Vec::new();
-        for task in raw_time_result {
-            res.push(task.unwrap().unwrap());
+        if opt.server_only {
+            continue;
         }
 
-        // handle raw time result to statistic
-        let time_statistic = handle_time(res);
-
-        if !PRINT_CSV {
-            output[ASYNC_TOKIO_LOCATION] = util::format_result(String::from("async tokio"), args[THREAD_NUM].parse::<i64>().unwrap() * args[LOOP_NUM].parse::<i64>().unwrap(),
-                                                               (end - start).whole_milliseconds() as i64,
-                                                               time_statistic[0], time_statistic[1],
-                                                               time_statistic[2], time_statistic[3],
-                                                               time_statistic[4], time_statistic[5],
-                                                               time_statistic[6]);
-        } else {
-            output[ASYNC_TOKIO_LOCATION] = util::format_result_csv(String::from("async tokio"), args[THREAD_NUM].parse::<i64>().unwrap(),
-                                                                   args[LOOP_NUM].parse::<i64>().unwrap(),
-                                                                   (end - start).whole_milliseconds() as i64,
-                                                                   time_statistic[0], time_statistic[1],
-                                                                   time_statistic[2], time_statistic[3],
-                                                                   time_statistic[4], time_statistic[5],
-                                                                   time_statistic[6]);
+        println!("begin {} benchmark...", scenario.label());
+        let (time_arrays, failed_clients, total_time_in_ms) = run_clients(scenario, &addr);
+        match Report::new(scenario.clone(), time_arrays, failed_clients, total_time_in_ms) {
+            Some(report) => {
+                output.push(report.format(opt.csv));
+                reports.push(report);
+            }
+            None => eprintln!("{}: no call finished", scenario.label()),
         }
+        println!("{} finished!", scenario.label());
     }
 
-    if args[RUN_SERVER] == String::from("true") {
-        server.unwrap().remote_handle();
+    if opt.server_only {
+        for server in servers {
+            let _ = server.join();
+        }
+        return Ok(());
     }
 
+    util::print_result(&output);
 
-    println!("async tokio finished!");
+    if let Some(ref path) = opt.json {
+        let file = File::create(path).map_err(|e| format!("cannot create {}: {}", path.display(), e))?;
+        serde_json::to_writer_pretty(file, &reports).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    }
+    Ok(())
 }
 
 fn main() {
-    let mut args: Vec<String> = vec![String::from(DEFAULT_RUN_CLIENT),
-                                     String::from(DEFAULT_RUN_SERVER),
-                                     String::from(DEFAULT_RUN_SYNC),
-                                     String::from(DEFAULT_RUN_ASYNC),
-                                     String::from(DEFAULT_RUN_ASYNC_TOKIO),
-                                     String::from(DEFAULT_THREAD_NUM),
-                                     String::from(DEFAULT_LOOP_NUM),
-                                     String::from(DEFAULT_ADDR)];
-
-    parse_args(&mut args);
-
-    println!("{:?}", &args);
-
-
-    let mut output = vec![String::new(), String::new(), String::new(), String::new()];
-
-    util::print_welcome();
-
-    let arc_args = Arc::new(args);
-    // async part
-    if arc_args[RUN_ASYNC] == String::from("true") {
-        task::block_on(run_async_both(&mut output, Arc::clone(&arc_args)));
-    }
-
-    // async tokio part
-    if arc_args[RUN_ASYNC_TOKIO] == String::from("true") {
-        task::block_on(run_async_tokio_both(&mut output, Arc::clone(&arc_args)));
+    let opt = Opt::from_args();
+    if let Err(e) = run(&opt) {
+        eprintln!("benchmark: {}", e);
+        process::exit(1);
     }
-
-    // sync part
-    if arc_args[RUN_SYNC] == String::from("true") {
-        run_sync_both(&mut output, Arc::clone(&arc_args));
-    }
-    util::print_result(&output);
 }
-
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// A method of the Benchmark service.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Method {
+    Ping,
+    /// Echo `payload_size` bytes.
+    Echo,
+    /// Echo a record of `record_items` items.
+    EchoRecord,
+}
+
+/// Relative weights of the methods called by a scenario, "ping=1,echo=3"
+/// on the command line.
+///
+/// A mix without weights calls `echo` when the scenario has a payload and
+/// `ping` otherwise.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Mix {
+    pub ping: u32,
+    pub echo: u32,
+    pub echo_record: u32,
+}
+
+impl Mix {
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    fn total(&self) -> u32 {
+        self.ping + self.echo + self.echo_record
+    }
+}
+
+impl FromStr for Mix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mix = Mix::default();
+        for part in s.split(',').filter(|p| !p.is_empty()) {
+            let (method, weight) = match part.find('=') {
+                Some(i) => (&part[..i], &part[i + 1..]),
+                None => (part, "1"),
+            };
+            let weight = weight.parse::<u32>().map_err(|_| format!("invalid weight {:?} of {}", weight, method))?;
+            match method {
+                "ping" => mix.ping = weight,
+                "echo" => mix.echo = weight,
+                "echo_record" => mix.echo_record = weight,
+                _ => return Err(format!("unknown method {:?}, expected \"ping\", \"echo\" or \"echo_record\"", method)),
+            }
+        }
+        Ok(mix)
+    }
+}
+
+impl fmt::Display for Mix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let weights = [("ping", self.ping), ("echo", self.echo), ("echo_record", self.echo_record)];
+        let parts = weights.iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(method, weight)| format!("{}={}", method, weight))
+            .collect::<Vec<_>>();
+        f.write_str(&parts.join(","))
+    }
+}
+
+/// Picks the method of every call of one connection at random by weight.
+pub struct MethodPicker {
+    mix: Mix,
+    rng: StdRng,
+}
+
+impl MethodPicker {
+    /// `mix` must not be empty, see `Scenario::mix`.
+    pub fn new(mix: Mix) -> MethodPicker {
+        MethodPicker { mix, rng: StdRng::from_entropy() }
+    }
+
+    pub fn next_method(&mut self) -> Method {
+        let mut n = self.rng.gen_range(0, self.mix.total());
+        if n < self.mix.ping {
+            return Method::Ping;
+        }
+        n -= self.mix.ping;
+        if n < self.mix.echo {
+            return Method::Echo;
+        }
+        Method::EchoRecord
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::scenario::{Arrival, Scenario};
+
+/// Decides when the calls of one connection are sent.
+///
+/// In closed loop (a scenario without `rate`) every call is sent as soon as
+/// the previous one returns. In open loop every connection sends its share
+/// of `rate` on a schedule that does not depend on the server: a call that
+/// cannot be sent on time because the previous one is still in flight is
+/// sent late, and its latency is measured from the time it should have been
+/// sent, so that queueing delay is not hidden (coordinated omission).
+pub struct Pacer {
+    /// Next intended send time, `None` in closed loop.
+    next: Option<Instant>,
+    /// Mean time between the calls of this connection in seconds.
+    interval: f64,
+    arrival: Arrival,
+    rng: StdRng,
+}
+
+impl Pacer {
+    /// The pacer of the connection at `index` of a run started at `start`.
+    pub fn new(scenario: &Scenario, start: Instant, index: usize) -> Pacer {
+        let mut pacer = Pacer {
+            next: None,
+            interval: scenario.concurrency as f64 / scenario.rate as f64,
+            arrival: scenario.arrival,
+            rng: StdRng::from_entropy(),
+        };
+        if scenario.rate > 0 {
+            // spread the first calls of the connections over one interval
+            let offset = match pacer.arrival {
+                Arrival::Fixed => pacer.interval * index as f64 / scenario.concurrency as f64,
+                Arrival::Poisson => pacer.gap(),
+            };
+            pacer.next = Some(start + Duration::from_secs_f64(offset));
+        }
+        pacer
+    }
+
+    /// Intended send time of the next call, which may be in the past.
+    pub fn next_send(&mut self) -> Instant {
+        match self.next {
+            Some(intended) => {
+                let gap = self.gap();
+                self.next = Some(intended + Duration::from_secs_f64(gap));
+                intended
+            }
+            None => Instant::now(),
+        }
+    }
+
+    /// Time between two calls of the schedule in seconds.
+    fn gap(&mut self) -> f64 {
+        match self.arrival {
+            Arrival::Fixed => self.interval,
+            // exponentially distributed gaps make a Poisson process
+            Arrival::Poisson => -self.interval * (1.0 - self.rng.gen::<f64>()).ln(),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::mix::Mix;
+
+/// Which rpc implementation serves and sends the calls.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Runtime {
+    /// async_thrift on async-std.
+    AsyncStd,
+    /// async_thrift_tokio on tokio.
+    Tokio,
+    /// The `thrift` crate with one thread per connection.
+    Sync,
+}
+
+impl FromStr for Runtime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "async-std" => Ok(Runtime::AsyncStd),
+            "tokio" => Ok(Runtime::Tokio),
+            "sync" => Ok(Runtime::Sync),
+            _ => Err(format!("unknown runtime {:?}, expected \"async-std\", \"tokio\" or \"sync\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Runtime::AsyncStd => f.write_str("async-std"),
+            Runtime::Tokio => f.write_str("tokio"),
+            Runtime::Sync => f.write_str("sync"),
+        }
+    }
+}
+
+/// How messages are delimited on a connection.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transport {
+    Buffered,
+    Framed,
+}
+
+impl FromStr for Transport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "buffered" => Ok(Transport::Buffered),
+            "framed" => Ok(Transport::Framed),
+            _ => Err(format!("unknown transport {:?}, expected \"buffered\" or \"framed\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transport::Buffered => f.write_str("buffered"),
+            Transport::Framed => f.write_str("framed"),
+        }
+    }
+}
+
+/// How messages are encoded.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Protocol {
+    Binary,
+    /// Only implemented by the sync runtime.
+    Compact,
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary" => Ok(Protocol::Binary),
+            "compact" => Ok(Protocol::Compact),
+            _ => Err(format!("unknown protocol {:?}, expected \"binary\" or \"compact\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Protocol::Binary => f.write_str("binary"),
+            Protocol::Compact => f.write_str("compact"),
+        }
+    }
+}
+
+/// When the calls of an open-loop scenario are sent.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Arrival {
+    /// At a fixed interval.
+    Fixed,
+    /// At exponentially distributed intervals.
+    Poisson,
+}
+
+impl FromStr for Arrival {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fixed" => Ok(Arrival::Fixed),
+            "poisson" => Ok(Arrival::Poisson),
+            _ => Err(format!("unknown arrival {:?}, expected \"fixed\" or \"poisson\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Arrival {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arrival::Fixed => f.write_str("fixed"),
+            Arrival::Poisson => f.write_str("poisson"),
+        }
+    }
+}
+
+/// One benchmark run: a server and `concurrency` connections calling it,
+/// first for `warmup_secs` unmeasured, then for `duration_secs` measured.
+///
+/// Without `rate` the connections call back to back (closed loop), with it
+/// they send `rate` calls per second in total on a schedule (open loop).
+///
+/// Every field may be left out of a scenario file and takes the value of
+/// `Scenario::default()`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Scenario {
+    /// Title of the result table, generated from the other fields when empty.
+    pub name: String,
+    pub runtime: Runtime,
+    pub transport: Transport,
+    pub protocol: Protocol,
+    /// Bytes sent and echoed back by every `echo` call.
+    pub payload_size: usize,
+    /// Items, map entries and labels of the record of every `echo_record`
+    /// call.
+    pub record_items: usize,
+    /// Methods called, `echo` with a payload and `ping` without when empty.
+    pub mix: Mix,
+    /// Connections, each with one call in flight.
+    pub concurrency: usize,
+    /// Target calls per second over all connections, 0 for closed loop.
+    pub rate: u64,
+    pub arrival: Arrival,
+    pub warmup_secs: u64,
+    pub duration_secs: u64,
+    /// SO_REUSEPORT acceptors of the async-std server.
+    pub acceptors: usize,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Scenario {
+            name: String::new(),
+            runtime: Runtime::AsyncStd,
+            transport: Transport::Buffered,
+            protocol: Protocol::Binary,
+            payload_size: 0,
+            record_items: 10,
+            mix: Mix::default(),
+            concurrency: 100,
+            rate: 0,
+            arrival: Arrival::Fixed,
+            warmup_secs: 2,
+            duration_secs: 10,
+            acceptors: 1,
+        }
+    }
+}
+
+impl Scenario {
+    /// The name, or a summary of the scenario when it has none.
+    pub fn label(&self) -> String {
+        if !self.name.is_empty() {
+            return self.name.clone();
+        }
+        let mut label = format!("{} {} {}", self.runtime, self.transport, self.protocol);
+        if self.acceptors > 1 {
+            label.push_str(&format!(" ({} acceptors)", self.acceptors));
+        }
+        if !self.mix.is_empty() {
+            label.push_str(&format!(" mix {}", self.mix));
+        }
+        if self.payload_size > 0 && self.mix().echo > 0 {
+            label.push_str(&format!(" echo {}B", self.payload_size));
+        }
+        if self.mix().echo_record > 0 {
+            label.push_str(&format!(" record {} items", self.record_items));
+        }
+        if self.rate > 0 {
+            label.push_str(&format!(" {} {}/s", self.arrival, self.rate));
+        }
+        label
+    }
+
+    /// Check that the runtime supports the scenario.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.concurrency == 0 || self.duration_secs == 0 {
+            return Err(format!("{}: concurrency and duration_secs must be positive", self.label()));
+        }
+        if self.protocol != Protocol::Binary && self.runtime != Runtime::Sync {
+            return Err(format!("{}: the {} runtime only implements the binary protocol", self.label(), self.runtime));
+        }
+        if self.acceptors > 1 && self.runtime != Runtime::AsyncStd {
+            return Err(format!("{}: only the async-std server has multiple acceptors", self.label()));
+        }
+        Ok(())
+    }
+
+    /// The methods called by the scenario.
+    pub fn mix(&self) -> Mix {
+        if !self.mix.is_empty() {
+            self.mix
+        } else if self.payload_size > 0 {
+            Mix { echo: 1, ..Mix::default() }
+        } else {
+            Mix { ping: 1, ..Mix::default() }
+        }
+    }
+
+    /// When the calls of a run started at `start` begin to be measured, and
+    /// when no more calls are started.
+    pub fn phases(&self, start: Instant) -> (Instant, Instant) {
+        let measure_from = start + Duration::from_secs(self.warmup_secs);
+        (measure_from, measure_from + Duration::from_secs(self.duration_secs))
+    }
+}
+
+/// Read a scenario file, a JSON array of scenarios.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Scenario>, String> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("cannot parse {}: {}", path.display(), e))
+}
@@ -1,58 +1,80 @@
-// add extern crates here, or in your lib.rs
-extern crate ordered_float;
-extern crate thrift;
-extern crate try_from;
-
-// generated Rust module
-
-
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::TcpStream;
 use std::thread;
+use std::time::Instant;
 
-use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol};
+use thrift::OrderedFloat;
+use thrift::protocol::{TBinaryInputProtocol, TBinaryOutputProtocol, TCompactInputProtocol, TCompactOutputProtocol};
 use thrift::protocol::{TInputProtocol, TOutputProtocol};
-use thrift::transport::{TFramedReadTransport, TFramedWriteTransport};
-use thrift::transport::{TIoChannel, TTcpChannel};
-
-use crate::sync_thrift_test::tutorial::{CalculatorSyncClient, TCalculatorSyncClient};
-
-use self::thrift::protocol::{TBinaryInputProtocol, TBinaryOutputProtocol};
-use self::thrift::transport::{TBufferedReadTransport, TBufferedReadTransportFactory, TBufferedWriteTransport};
-
-pub fn run(stream: TcpStream, loop_num: i32) -> thrift::Result<(Box<Vec<i64>>)> {
-    //
-    // build client
-    //
-
-    // println!("connect to server on 127.0.0.1:9090");
-
+use thrift::transport::{TBufferedReadTransport, TBufferedWriteTransport, TFramedReadTransport, TFramedWriteTransport};
+use thrift::transport::{TIoChannel, TReadTransport, TTcpChannel, TWriteTransport};
+
+use crate::mix::{Method, MethodPicker};
+use crate::pacer::Pacer;
+use crate::scenario::{Protocol, Scenario, Transport};
+use crate::sync_thrift_test::bench::{BenchmarkSyncClient, Item, Record, TBenchmarkSyncClient};
+
+/// Call the server on the connection at `index` until the scenario ends.
+///
+/// Returns the latencies in ns of the calls started after the warmup.
+pub fn run(stream: TcpStream, scenario: Scenario, start: Instant, index: usize) -> thrift::Result<Vec<i64>> {
+    // calls are written in pieces, do not hold them back for an ack
+    stream.set_nodelay(true)?;
     let channel = TTcpChannel::with_stream(stream);
 
-    let mut time_array = Vec::with_capacity(loop_num as usize);
-
     let (i_chan, o_chan) = channel.split()?;
 
-    let i_prot = TBinaryInputProtocol::new(
-        TBufferedReadTransport::new(i_chan), true,
-    );
-    let o_prot = TBinaryOutputProtocol::new(
-        TBufferedWriteTransport::new(o_chan), true,
-    );
-
-    let mut client = CalculatorSyncClient::new(i_prot, o_prot);
-    for _i in 0..loop_num {
-        let before = time::Instant::now();
-        client.ping()?;
-        let end = time::Instant::now();
-
-        time_array.push((end - before).num_nanoseconds().unwrap());
+    let (i_tran, o_tran): (Box<dyn TReadTransport + Send>, Box<dyn TWriteTransport + Send>) = match scenario.transport {
+        Transport::Buffered => (Box::new(TBufferedReadTransport::new(i_chan)), Box::new(TBufferedWriteTransport::new(o_chan))),
+        Transport::Framed => (Box::new(TFramedReadTransport::new(i_chan)), Box::new(TFramedWriteTransport::new(o_chan))),
+    };
+
+    let (i_prot, o_prot): (Box<dyn TInputProtocol + Send>, Box<dyn TOutputProtocol + Send>) = match scenario.protocol {
+        Protocol::Binary => (Box::new(TBinaryInputProtocol::new(i_tran, true)), Box::new(TBinaryOutputProtocol::new(o_tran, true))),
+        Protocol::Compact => (Box::new(TCompactInputProtocol::new(i_tran)), Box::new(TCompactOutputProtocol::new(o_tran))),
+    };
+
+    let mut client = BenchmarkSyncClient::new(i_prot, o_prot);
+
+    let (measure_from, end) = scenario.phases(start);
+    let payload = vec![0u8; scenario.payload_size];
+    let record = record(scenario.record_items);
+    let mut picker = MethodPicker::new(scenario.mix());
+    let mut time_array = Vec::new();
+    let mut pacer = Pacer::new(&scenario, start, index);
+
+    loop {
+        let intended = pacer.next_send();
+        if intended >= end {
+            break;
+        }
+        let now = Instant::now();
+        if intended > now {
+            thread::sleep(intended - now);
+        }
+        match picker.next_method() {
+            Method::Ping => client.ping()?,
+            Method::Echo => {
+                client.echo(payload.clone())?;
+            }
+            Method::EchoRecord => {
+                client.echo_record(record.clone())?;
+            }
+        }
+        if intended >= measure_from {
+            time_array.push(intended.elapsed().as_nanos() as i64);
+        }
     }
 
-    //
-    // println!("final result {}", sum);
-    // println!("Test pass, It's time to cheer!");
-
-    // done!
-    // println!("finish client");
-    Ok((Box::new(time_array)))
-}
\ No newline at end of file
+    Ok(time_array)
+}
+
+/// The record echoed by `echo_record`, with `items` items, map entries and
+/// labels.
+fn record(items: usize) -> Record {
+    let item = |i: usize| Item::new(i as i64, format!("item {}", i), OrderedFloat::from(i as f64), (0..8).collect::<Vec<i32>>());
+    Record::new(0, "record".to_owned(), item(0),
+                (0..items).map(item).collect::<Vec<_>>(),
+                (0..items).map(|i| (format!("item {}", i), item(i))).collect::<BTreeMap<_, _>>(),
+                (0..items).map(|i| format!("label {}", i)).collect::<BTreeSet<_>>())
+}
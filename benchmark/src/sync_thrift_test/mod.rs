@@ -1,3 +1,3 @@
 pub mod server;
-mod tutorial;
-pub mod client;
\ No newline at end of file
+mod bench;
+pub mod client;
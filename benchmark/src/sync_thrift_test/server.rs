@@ -1,53 +1,86 @@
+use std::net::TcpListener;
+use std::sync::Arc;
+
+use threadpool::ThreadPool;
 use thrift::protocol::{
     TBinaryInputProtocolFactory, TBinaryOutputProtocolFactory, TCompactInputProtocolFactory,
-    TCompactOutputProtocolFactory, TInputProtocolFactory, TOutputProtocolFactory,
+    TCompactOutputProtocolFactory, TInputProtocol, TInputProtocolFactory, TOutputProtocol, TOutputProtocolFactory,
 };
-use thrift::server::TServer;
+use thrift::server::TProcessor;
 use thrift::transport::{TBufferedReadTransportFactory, TBufferedWriteTransportFactory, TFramedReadTransportFactory, TFramedWriteTransportFactory, TReadTransportFactory, TWriteTransportFactory};
+use thrift::transport::{TIoChannel, TTcpChannel};
 
-use crate::sync_thrift_test::tutorial::{CalculatorSyncHandler, CalculatorSyncProcessor};
-
-pub fn run(addr: &str) -> thrift::Result<()> {
-    let protocol = "binary";
-    let listen_address = addr;
+use crate::scenario::{Protocol, Scenario, Transport};
+use crate::sync_thrift_test::bench::{BenchmarkSyncHandler, BenchmarkSyncProcessor, Record};
 
-    let r_transport_factory = TBufferedReadTransportFactory::new();
-    let w_transport_factory = TBufferedWriteTransportFactory::new();
+pub fn run(addr: &str, scenario: &Scenario) -> thrift::Result<()> {
+    let (r_transport_factory, w_transport_factory): (
+        Box<dyn TReadTransportFactory>,
+        Box<dyn TWriteTransportFactory>,
+    ) = match scenario.transport {
+        Transport::Buffered => (
+            Box::new(TBufferedReadTransportFactory::new()),
+            Box::new(TBufferedWriteTransportFactory::new()),
+        ),
+        Transport::Framed => (
+            Box::new(TFramedReadTransportFactory::new()),
+            Box::new(TFramedWriteTransportFactory::new()),
+        ),
+    };
 
     let (i_protocol_factory, o_protocol_factory): (
-        Box<TInputProtocolFactory>,
-        Box<TOutputProtocolFactory>,
-    ) = match &*protocol {
-        "binary" => (
+        Box<dyn TInputProtocolFactory>,
+        Box<dyn TOutputProtocolFactory>,
+    ) = match scenario.protocol {
+        Protocol::Binary => (
             Box::new(TBinaryInputProtocolFactory::new()),
             Box::new(TBinaryOutputProtocolFactory::new()),
         ),
-        "compact" => (
+        Protocol::Compact => (
             Box::new(TCompactInputProtocolFactory::new()),
             Box::new(TCompactOutputProtocolFactory::new()),
         ),
-        unknown => {
-            return Err(format!("unsupported transport type {}", unknown).into());
-        }
     };
 
-    let processor = CalculatorSyncProcessor::new(PartHandler {});
-    let mut server = TServer::new(
-        r_transport_factory,
-        i_protocol_factory,
-        w_transport_factory,
-        o_protocol_factory,
-        processor,
-        1,
-    );
-
-    server.listen(listen_address)
+    let processor = Arc::new(BenchmarkSyncProcessor::new(PartHandler {}));
+    // every connection occupies a worker thread until it is closed
+    let pool = ThreadPool::new(scenario.concurrency);
+
+    // the accept loop of thrift::server::TServer, which cannot set TCP_NODELAY:
+    // replies are written in pieces, do not hold them back for an ack
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        stream.set_nodelay(true)?;
+        let (r_chan, w_chan) = TTcpChannel::with_stream(stream).split()?;
+        let i_prot = i_protocol_factory.create(r_transport_factory.create(Box::new(r_chan)));
+        let o_prot = o_protocol_factory.create(w_transport_factory.create(Box::new(w_chan)));
+        let processor = processor.clone();
+        pool.execute(move || serve_connection(&*processor, i_prot, o_prot));
+    }
+    Ok(())
+}
+
+// process calls until the client closes the connection
+fn serve_connection<P: TProcessor>(processor: &P, mut i_prot: Box<dyn TInputProtocol + Send>, mut o_prot: Box<dyn TOutputProtocol + Send>) {
+    while processor.process(&mut *i_prot, &mut *o_prot).is_ok() {}
 }
 
 struct PartHandler;
 
-impl CalculatorSyncHandler for PartHandler {
+impl BenchmarkSyncHandler for PartHandler {
     fn handle_ping(&self) -> thrift::Result<()> {
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn handle_echo(&self, payload: Vec<u8>) -> thrift::Result<Vec<u8>> {
+        Ok(payload)
+    }
+
+    fn handle_echo_record(&self, record: Record) -> thrift::Result<Record> {
+        Ok(record)
+    }
+}
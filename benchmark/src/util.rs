@@ -1,40 +1,46 @@
-use std::borrow::Borrow;
-use std::env;
-
-/// print time result in md table
-pub fn format_result(mode: String, call_num: i64, total_time_in_ms: i64,
-                     avg_time: i64, per_50_time: i64, per_90_time: i64,
-                     per_95_time: i64, per_99_time: i64,
-                     per_999_time: i64, max_time: i64) -> String {
+use std::thread;
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+use serde::Serialize;
+
+use crate::scenario::Scenario;
+
+/// print time result in md table, times in us
+pub fn format_result(mode: String, call_num: i64, total_time_in_ms: i64, latency: &Latency) -> String {
     format!("###{}
 |  total time |   query per second  |  avg time   |  per 50 time |  per 90 time |  per 95 time |  per 99 time | per 99.9 time | max time |
 |  ---------  |   ----------------  | ----------  | ------------ | ------------ | ------------ | ------------ | ----------- |  -------- |
 |    {} ms  |        {}        |    {} us   |    {} us   |     {} us    |    {} us    |   {} us   |   {}  us  |   {}  us  |"
-            , mode, total_time_in_ms, (1000 * call_num / total_time_in_ms), avg_time / 1000, per_50_time / 1000,
-            per_90_time / 1000, per_95_time / 1000, per_99_time / 1000, per_999_time / 1000, max_time / 1000)
+            , mode, total_time_in_ms, (1000 * call_num / total_time_in_ms), latency.avg, latency.per_50,
+            latency.per_90, latency.per_95, latency.per_99, latency.per_999, latency.max)
 }
 
-/// print time result in csv
-pub fn format_result_csv(mode: String, client_num: i64, loop_num: i64, total_time_in_ms: i64,
-                         avg_time: i64, per_50_time: i64, per_90_time: i64,
-                         per_95_time: i64, per_99_time: i64,
-                         per_999_time: i64, max_time: i64) -> String {
-    let call_num = client_num * loop_num;
-
+/// print time result in csv, times in us
+pub fn format_result_csv(mode: String, client_num: i64, call_num: i64, total_time_in_ms: i64, latency: &Latency) -> String {
     format!("### {}
-client_num,loop_num,call_num,total_time,qps,avg,per50,per90,per95,per_99,per_999,max
-{},{},{},{},{},{},{},{},{},{},{},{}"
-            , mode, client_num, loop_num, call_num, total_time_in_ms, (1000 * call_num / total_time_in_ms),
-            avg_time / 1000, per_50_time / 1000, per_90_time / 1000, per_95_time / 1000, per_99_time / 1000, per_999_time / 1000, max_time / 1000)
+client_num,call_num,total_time,qps,avg,per50,per90,per95,per_99,per_999,max
+{},{},{},{},{},{},{},{},{},{},{}"
+            , mode, client_num, call_num, total_time_in_ms, (1000 * call_num / total_time_in_ms),
+            latency.avg, latency.per_50, latency.per_90, latency.per_95, latency.per_99, latency.per_999, latency.max)
 }
 
-/// print config result in md table
-pub fn format_config(thread_num: i32, loop_num: i32) -> String {
+/// print scenario config in md table
+pub fn format_scenario(scenario: &Scenario) -> String {
     format!("###config
-|  thread num   | loop num  | total call |
-|  -----------  | --------  | ---------- |
-|      {}      |    {}    |    {}    |",
-            format_i32(thread_num), format_i32(loop_num), format_i32(thread_num * loop_num))
+|  runtime  | transport | protocol | mix | payload size | record items | concurrency | rate | warmup | duration |
+|  -------  | --------- | -------- | --- | ------------ | ------------ | ----------- | ---- | ------ | -------- |
+|    {}    |    {}    |    {}    |    {}    |    {} B    |    {}    |    {}    |    {}    |    {} s    |    {} s    |",
+            scenario.runtime, scenario.transport, scenario.protocol, scenario.mix(), scenario.payload_size,
+            scenario.record_items, format_i32(scenario.concurrency as i32), format_rate(scenario), scenario.warmup_secs, scenario.duration_secs)
+}
+
+/// target rate of an open-loop scenario, "closed loop" without one
+fn format_rate(scenario: &Scenario) -> String {
+    if scenario.rate == 0 {
+        return String::from("closed loop");
+    }
+    format!("{} /s {}", format_i32(scenario.rate as i32), scenario.arrival)
 }
 
 /// format i32 for human
@@ -49,7 +55,7 @@ fn format_i32(mut i: i32) -> String {
     let mut count = 0;
     while i > 0 {
         if count == 3 {
-            res.insert_str(0, "_");
+            res.insert(0, '_');
             count = 0;
         }
         count += 1;
@@ -71,7 +77,7 @@ pub fn print_welcome() {
 }
 
 /// print benchmark result
-pub fn print_result(output: &Vec<String>) {
+pub fn print_result(output: &[String]) {
     println!();
     println!();
     println!("---------------------------   Benchmark Finished! --------------------------");
@@ -81,48 +87,153 @@ pub fn print_result(output: &Vec<String>) {
     }
 }
 
-pub fn handle_time(time_arrays: Vec<Box<Vec<i64>>>) -> Box<Vec<i64>> {
+pub fn handle_time(time_arrays: Vec<Vec<i64>>) -> Vec<i64> {
     let mut sum = 0;
     let mut count = 0;
     let mut times: Vec<i64> = Vec::new();
-    for time_array_result in time_arrays {
-        let time_array: &Vec<i64> = time_array_result.borrow();
+    for time_array in time_arrays {
         for time in time_array {
-            times.push(*time);
+            times.push(time);
             sum += time;
             count += 1;
         }
     }
 
     times.sort();
-    let mut res = Vec::new();
-    // avg
-    res.push(sum / count);
-    // per 50
-    res.push(times[times.len() / 2]);
-    // per 90
-    res.push(times[(times.len() / 10) * 9]);
-    // per 95
-    res.push(times[(times.len() / 100) * 95]);
-    // per 99
-    res.push(times[(times.len() / 100) * 99]);
-    // per 99.9
-    res.push(times[(times.len() / 1000) * 999]);
-    // max time
-    res.push(times[times.len() - 1]);
-
-    return Box::new(res);
+    vec![
+        // avg
+        sum / count,
+        // per 50
+        times[times.len() / 2],
+        // per 90
+        times[(times.len() / 10) * 9],
+        // per 95
+        times[(times.len() / 100) * 95],
+        // per 99
+        times[(times.len() / 100) * 99],
+        // per 99.9
+        times[(times.len() / 1000) * 999],
+        // max time
+        times[times.len() - 1],
+    ]
 }
 
-/// parse command line args
-pub fn parse_args(args: &mut Vec<String>) {
-    let mut loc = 1000000;
-    for s in env::args() {
-        if loc == 1000000 {
-            loc = 0;
+/// Same statistics as `handle_time` from an HDR histogram, times in us.
+///
+/// The histogram keeps 3 significant digits, so its percentiles are within
+/// 0.1% of the exact ones and do not depend on how the samples are indexed.
+/// Latencies above a minute are recorded as a minute.
+pub fn handle_time_hdr(time_arrays: &[Vec<i64>]) -> Latency {
+    let mut histogram = Histogram::<u64>::new_with_max(60_000_000, 3).expect("a minute in us is trackable");
+    for time_array in time_arrays {
+        for &time in time_array.iter() {
+            // ns to us, rounded up so that no call records 0
+            histogram.saturating_record(((time.max(0) + 999) / 1000) as u64);
+        }
+    }
+    let at = |quantile: f64| histogram.value_at_quantile(quantile) as i64;
+    Latency {
+        avg: histogram.mean() as i64,
+        per_50: at(0.5),
+        per_90: at(0.9),
+        per_95: at(0.95),
+        per_99: at(0.99),
+        per_999: at(0.999),
+        max: histogram.max() as i64,
+    }
+}
+
+/// Address of the server of the scenario at `index`: the port of `addr`
+/// plus `index`, so that servers which cannot be stopped do not collide.
+pub fn scenario_addr(addr: &str, index: usize) -> Result<String, String> {
+    let (host, port) = match addr.rfind(':') {
+        Some(i) => (&addr[..i], &addr[i + 1..]),
+        None => return Err(format!("address {:?} has no port", addr)),
+    };
+    let port = port.parse::<usize>().map_err(|_| format!("address {:?} has an invalid port", addr))?;
+    Ok(format!("{}:{}", host, port + index))
+}
+
+/// wait up to 5 seconds for a server to accept connections on `addr`
+pub fn wait_for_server(addr: &str) -> bool {
+    for _ in 0..100 {
+        if std::net::TcpStream::connect(addr).is_ok() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    false
+}
+
+/// Latencies of a run in us.
+#[derive(Debug, Serialize)]
+pub struct Latency {
+    pub avg: i64,
+    pub per_50: i64,
+    pub per_90: i64,
+    pub per_95: i64,
+    pub per_99: i64,
+    pub per_999: i64,
+    pub max: i64,
+}
+
+/// Result of a scenario, written out by `--json`.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub scenario: Scenario,
+    /// Measured calls, those started after the warmup.
+    pub call_num: i64,
+    /// Connections that failed before the end of the run.
+    pub failed_clients: usize,
+    pub total_time_in_ms: i64,
+    pub qps: i64,
+    /// Percentiles of the sorted latencies, from `handle_time`.
+    pub latency_us: Latency,
+    /// Percentiles of the HDR histogram of the latencies.
+    pub latency_hdr_us: Latency,
+}
+
+impl Report {
+    /// Compute the report from the latencies in ns returned by every client.
+    pub fn new(scenario: Scenario, time_arrays: Vec<Vec<i64>>, failed_clients: usize, total_time_in_ms: i64) -> Option<Report> {
+        let call_num = time_arrays.iter().map(|t| t.len() as i64).sum::<i64>();
+        if call_num == 0 || total_time_in_ms == 0 {
+            return None;
+        }
+        let latency_hdr_us = handle_time_hdr(&time_arrays);
+        let time_statistic = handle_time(time_arrays);
+        Some(Report {
+            scenario,
+            call_num,
+            failed_clients,
+            total_time_in_ms,
+            qps: 1000 * call_num / total_time_in_ms,
+            latency_us: Latency {
+                avg: time_statistic[0] / 1000,
+                per_50: time_statistic[1] / 1000,
+                per_90: time_statistic[2] / 1000,
+                per_95: time_statistic[3] / 1000,
+                per_99: time_statistic[4] / 1000,
+                per_999: time_statistic[5] / 1000,
+                max: time_statistic[6] / 1000,
+            },
+            latency_hdr_us,
+        })
+    }
+
+    /// print the report in md tables, or csv when `csv` is set
+    pub fn format(&self, csv: bool) -> String {
+        let l = &self.latency_us;
+        let h = &self.latency_hdr_us;
+        let hdr_label = format!("{} (hdr)", self.scenario.label());
+        if csv {
+            format!("{}\n\n{}",
+                    format_result_csv(self.scenario.label(), self.scenario.concurrency as i64, self.call_num, self.total_time_in_ms, l),
+                    format_result_csv(hdr_label, self.scenario.concurrency as i64, self.call_num, self.total_time_in_ms, h))
         } else {
-            args[loc] = s;
-            loc += 1;
+            format!("{}\n\n{}\n\n{}", format_scenario(&self.scenario),
+                    format_result(self.scenario.label(), self.call_num, self.total_time_in_ms, l),
+                    format_result(hdr_label, self.call_num, self.total_time_in_ms, h))
         }
     }
-}
\ No newline at end of file
+}
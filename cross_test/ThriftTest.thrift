@@ -0,0 +1,68 @@
+/*
+ * The part of Apache Thrift's test/ThriftTest.thrift exercised by the
+ * cross-language tests. Names and field ids match the original, so the
+ * servers and clients of other Thrift implementations can take part.
+ *
+ * Rust code is generated into src/thrift_test.rs and Go code into
+ * go_client/src/thrifttest.
+ */
+
+namespace go thrifttest
+namespace rs thrift_test
+
+struct Xtruct
+{
+  1:  string string_thing,
+  4:  i8     byte_thing,
+  9:  i32    i32_thing,
+  11: i64    i64_thing
+}
+
+struct Xtruct2
+{
+  1: i8     byte_thing,
+  2: Xtruct struct_thing,
+  3: i32    i32_thing
+}
+
+exception Xception {
+  1: i32 errorCode,
+  2: string message
+}
+
+service ThriftTest
+{
+  /** Prints "testVoid()" and returns nothing. */
+  void         testVoid(),
+
+  /** Returns `thing` unchanged, as do all the other test* methods taking a `thing`. */
+  string       testString(1: string thing),
+  bool         testBool(1: bool thing),
+  i8           testByte(1: i8 thing),
+  i32          testI32(1: i32 thing),
+  i64          testI64(1: i64 thing),
+  double       testDouble(1: double thing),
+  binary       testBinary(1: binary thing),
+  Xtruct       testStruct(1: Xtruct thing),
+  Xtruct2      testNest(1: Xtruct2 thing),
+  map<i32,i32> testMap(1: map<i32,i32> thing),
+  map<string,string> testStringMap(1: map<string,string> thing),
+  set<i32>     testSet(1: set<i32> thing),
+  list<i32>    testList(1: list<i32> thing),
+
+  /**
+   * Returns a map of maps, for example for hello = 1:
+   * {-4 => {-4 => -4, -3 => -3, -2 => -2, -1 => -1, }, 4 => {1 => 1, 2 => 2, 3 => 3, 4 => 4, }, }
+   */
+  map<i32,map<i32,i32>> testMapMap(1: i32 hello),
+
+  /**
+   * Throws Xception(errorCode = 1001, message = arg) when arg is "Xception",
+   * fails with an application exception when arg is "TException" and
+   * returns nothing otherwise.
+   */
+  void testException(1: string arg) throws(1: Xception err1),
+
+  /** Sleeps for secondsToSleep seconds before returning, without a reply. */
+  oneway void testOneway(1:i32 secondsToSleep)
+}
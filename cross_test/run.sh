@@ -0,0 +1,90 @@
+#!/bin/bash
+
+# Run the Rust ThriftTest server against the Go client and the Go server
+# against the Rust client, for every transport/protocol combination.
+# The exit code is the number of failed combinations.
+
+REPO=$(cd "$(dirname "$0")/.." && pwd)
+BIN_DIR=$REPO/target/cross_test
+LOG_DIR=$BIN_DIR/log
+
+## config parameter
+PORT=${PORT:-9090};
+TRANSPORTS="buffered framed";
+PROTOCOLS="binary compact";
+CLIENT_TIMEOUT=30;
+##
+
+mkdir -p "$BIN_DIR" "$LOG_DIR" || exit 1
+
+echo "building the rust server and client";
+cargo build --release --package cross_test --manifest-path "$REPO/Cargo.toml" || exit 1
+cp "$REPO/target/release/cross_test_server" "$BIN_DIR/rs_server" || exit 1
+cp "$REPO/target/release/cross_test_client" "$BIN_DIR/rs_client" || exit 1
+
+echo "building the go server and client";
+# the vendored thrift lib is 0.13.0, see go_client/README.md
+export GOPATH=$REPO/go_client
+export GO111MODULE=off
+go build -o "$BIN_DIR/go_server" cross_test_server || exit 1
+go build -o "$BIN_DIR/go_client" cross_test_client || exit 1
+
+# wait until something listens on $PORT
+wait_for_port() {
+    for _ in $(seq 50); do
+        (echo > "/dev/tcp/127.0.0.1/$PORT") 2> /dev/null && return 0
+        sleep 0.1
+    done
+    return 1
+}
+
+# run_pair <server> <client> <transport> <protocol>
+run_pair() {
+    local name="$1-$2_$3_$4"
+    local server_log="$LOG_DIR/${name}_server.log"
+    local client_log="$LOG_DIR/${name}_client.log"
+
+    # the go programs take single-dash flags, the rust ones double-dash flags
+    local server_args="--port=$PORT --transport=$3 --protocol=$4"
+    local client_args="--port=$PORT --transport=$3 --protocol=$4"
+    [ "$1" = "go" ] && server_args="-port=$PORT -transport=$3 -protocol=$4"
+    [ "$2" = "go" ] && client_args="-port=$PORT -transport=$3 -protocol=$4"
+
+    "$BIN_DIR/$1_server" $server_args > "$server_log" 2>&1 &
+    local server_pid=$!
+
+    local result
+    if ! wait_for_port; then
+        result="server did not start"
+    else
+        timeout "$CLIENT_TIMEOUT" "$BIN_DIR/$2_client" $client_args > "$client_log" 2>&1
+        local code=$?
+        case $code in
+            0) result="ok" ;;
+            124) result="timed out" ;;
+            *) result="failed ($code)" ;;
+        esac
+    fi
+
+    kill "$server_pid" 2> /dev/null
+    wait "$server_pid" 2> /dev/null
+
+    printf "%-10s %-10s %-10s %-10s %s\n" "$1" "$2" "$3" "$4" "$result"
+    if [ "$result" != "ok" ]; then
+        FAILED=$((FAILED + 1))
+        echo "    see $server_log and $client_log"
+    fi
+}
+
+FAILED=0
+printf "%-10s %-10s %-10s %-10s %s\n" "server" "client" "transport" "protocol" "result"
+for transport in $TRANSPORTS; do
+    for protocol in $PROTOCOLS; do
+        run_pair rs go "$transport" "$protocol"
+        run_pair go rs "$transport" "$protocol"
+        run_pair rs rs "$transport" "$protocol"
+    done
+done
+
+echo "$FAILED combination(s) failed"
+exit $FAILED
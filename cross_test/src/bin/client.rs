@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::process;
+use std::time::{Duration, Instant};
+
+use async_std::task;
+use structopt::StructOpt;
+
+use async_thrift::OrderedFloat;
+
+use async_thrift_tools::codec::{ProtocolKind, TCodec, TransportKind};
+use cross_test::thrift_test::{TThriftTestSyncClient, ThriftTestSyncClient, Xception, Xtruct, Xtruct2};
+
+// exit code bits, the same as those of Apache Thrift's test clients
+const ERR_BASETYPES: i32 = 1;
+const ERR_STRUCTS: i32 = 2;
+const ERR_CONTAINERS: i32 = 4;
+const ERR_EXCEPTIONS: i32 = 8;
+const ERR_UNKNOWN: i32 = 64;
+
+/// Call every method of a ThriftTest server and check the replies.
+///
+/// The exit code has a bit set for every kind of test that failed.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "cross_test_client")]
+struct Opt {
+    /// Host of the server.
+    #[structopt(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Port of the server.
+    #[structopt(long, default_value = "9090")]
+    port: u16,
+
+    /// Transport: buffered or framed.
+    #[structopt(long, default_value = "buffered")]
+    transport: TransportKind,
+
+    /// Protocol: binary or compact.
+    #[structopt(long, default_value = "binary")]
+    protocol: ProtocolKind,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let address = format!("{}:{}", opt.host, opt.port);
+    let codec = TCodec::new(opt.transport, opt.protocol);
+
+    let failed = task::block_on(async {
+        match codec.connect(&address).await {
+            Ok((i_prot, o_prot)) => run_tests(&mut ThriftTestSyncClient::new(i_prot, o_prot)).await,
+            Err(e) => {
+                eprintln!("cannot connect to {}: {:?}", address, e);
+                ERR_UNKNOWN
+            }
+        }
+    });
+    process::exit(failed);
+}
+
+/// Collects the failed checks of a test run.
+struct TChecks {
+    failed: i32,
+}
+
+impl TChecks {
+    fn check<T: Debug + PartialEq>(&mut self, error_bit: i32, call: &str, expected: T, actual: async_thrift::Result<T>) {
+        match actual {
+            Ok(ref actual) if *actual == expected => println!("{}: ok", call),
+            Ok(actual) => self.fail(error_bit, call, format!("expected {:?} but got {:?}", expected, actual)),
+            Err(e) => self.fail(error_bit, call, format!("failed: {:?}", e)),
+        }
+    }
+
+    fn fail(&mut self, error_bit: i32, call: &str, message: String) {
+        eprintln!("{}: {}", call, message);
+        self.failed |= error_bit;
+    }
+}
+
+async fn run_tests<C: TThriftTestSyncClient + Send>(client: &mut C) -> i32 {
+    let mut checks = TChecks { failed: 0 };
+
+    checks.check(ERR_BASETYPES, "testVoid()", (), client.test_void().await);
+    for s in &["", "Test", "ünicode ✓ テスト", &"long ".repeat(1000)] {
+        let call = format!("testString({:.20?})", s);
+        checks.check(ERR_BASETYPES, &call, s.to_string(), client.test_string(s.to_string()).await);
+    }
+    for &b in &[true, false] {
+        checks.check(ERR_BASETYPES, &format!("testBool({})", b), b, client.test_bool(b).await);
+    }
+    for &i in &[0, 1, -1, i8::MIN, i8::MAX] {
+        checks.check(ERR_BASETYPES, &format!("testByte({})", i), i, client.test_byte(i).await);
+    }
+    for &i in &[0, -1, 190_000_013, i32::MIN, i32::MAX] {
+        checks.check(ERR_BASETYPES, &format!("testI32({})", i), i, client.test_i32(i).await);
+    }
+    for &i in &[0, -34_359_738_368, 1_099_511_627_775, i64::MIN, i64::MAX] {
+        checks.check(ERR_BASETYPES, &format!("testI64({})", i), i, client.test_i64(i).await);
+    }
+    for &d in &[0.0, -5.2098523, -0.000_341_012_439_638_598_3, 1.0e300, f64::MIN_POSITIVE] {
+        let d = OrderedFloat::from(d);
+        checks.check(ERR_BASETYPES, &format!("testDouble({})", d), d, client.test_double(d).await);
+    }
+    let bytes: Vec<u8> = (0..=255).collect();
+    checks.check(ERR_BASETYPES, "testBinary(0..=255)", bytes.clone(), client.test_binary(bytes).await);
+
+    let xtruct = Xtruct::new("Zero".to_owned(), 1, -3, -5);
+    checks.check(ERR_STRUCTS, "testStruct(..)", xtruct.clone(), client.test_struct(xtruct.clone()).await);
+    let xtruct2 = Xtruct2::new(1, xtruct, 5);
+    checks.check(ERR_STRUCTS, "testNest(..)", xtruct2.clone(), client.test_nest(xtruct2).await);
+
+    let map: BTreeMap<i32, i32> = (0..5).map(|i| (i, i - 10)).collect();
+    checks.check(ERR_CONTAINERS, "testMap(..)", map.clone(), client.test_map(map).await);
+    let string_map: BTreeMap<String, String> = vec![("a", "2"), ("b", "blah"), ("some", "thing"), ("ünicode", "✓")]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect();
+    checks.check(ERR_CONTAINERS, "testStringMap(..)", string_map.clone(), client.test_string_map(string_map).await);
+    let set: BTreeSet<i32> = (-2..3).collect();
+    checks.check(ERR_CONTAINERS, "testSet(..)", set.clone(), client.test_set(set).await);
+    let list: Vec<i32> = (-2..3).collect();
+    checks.check(ERR_CONTAINERS, "testList(..)", list.clone(), client.test_list(list).await);
+    checks.check(ERR_CONTAINERS, "testList([])", Vec::new(), client.test_list(Vec::new()).await);
+    let mut map_map = BTreeMap::new();
+    map_map.insert(-4, (-4..0).map(|i| (i, i)).collect::<BTreeMap<i32, i32>>());
+    map_map.insert(4, (1..5).map(|i| (i, i)).collect());
+    checks.check(ERR_CONTAINERS, "testMapMap(1)", map_map, client.test_map_map(1).await);
+
+    // a oneway call returns without waiting for the server to finish it
+    let start = Instant::now();
+    match client.test_oneway(1).await {
+        Ok(()) if start.elapsed() < Duration::from_millis(500) => println!("testOneway(1): ok"),
+        Ok(()) => checks.fail(ERR_BASETYPES, "testOneway(1)", format!("took {:?}", start.elapsed())),
+        Err(e) => checks.fail(ERR_BASETYPES, "testOneway(1)", format!("failed: {:?}", e)),
+    }
+    checks.check(ERR_BASETYPES, "testVoid() after testOneway(1)", (), client.test_void().await);
+
+    match client.test_exception("Xception".to_owned()).await {
+        Err(async_thrift::Error::User(e)) => match e.downcast_ref::<Xception>() {
+            Some(x) if *x == Xception::new(1001, "Xception".to_owned()) => println!("testException(\"Xception\"): ok"),
+            _ => checks.fail(ERR_EXCEPTIONS, "testException(\"Xception\")", format!("unexpected exception {:?}", e)),
+        },
+        res => checks.fail(ERR_EXCEPTIONS, "testException(\"Xception\")", format!("expected Xception but got {:?}", res)),
+    }
+    checks.check(ERR_EXCEPTIONS, "testException(\"success\")", (), client.test_exception("success".to_owned()).await);
+    // last, as some servers close the connection after an application exception
+    match client.test_exception("TException".to_owned()).await {
+        Err(async_thrift::Error::Application(_)) => println!("testException(\"TException\"): ok"),
+        res => checks.fail(
+            ERR_EXCEPTIONS,
+            "testException(\"TException\")",
+            format!("expected an application exception but got {:?}", res),
+        ),
+    }
+
+    checks.failed
+}
@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::process;
+use std::time::Duration;
+
+use async_std::task;
+use async_trait::async_trait;
+use structopt::StructOpt;
+
+use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocolFactory, TAsyncBinaryOutputProtocolFactory};
+use async_thrift::protocol::async_compact::{TAsyncCompactInputProtocolFactory, TAsyncCompactOutputProtocolFactory};
+use async_thrift::protocol::{TAsyncInputProtocolFactory, TAsyncOutputProtocolFactory};
+use async_thrift::server::asynced::TAsyncServer;
+use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransportFactory, TAsyncBufferedWriteTransportFactory};
+use async_thrift::transport::async_framed::{TAsyncFramedReadTransportFactory, TAsyncFramedWriteTransportFactory};
+use async_thrift::transport::{TAsyncReadTransportFactory, TAsyncWriteTransportFactory};
+use async_thrift::{ApplicationError, ApplicationErrorKind, OrderedFloat};
+
+use async_thrift_tools::codec::{ProtocolKind, TransportKind};
+use cross_test::thrift_test::{ThriftTestSyncHandler, ThriftTestSyncProcessor, Xception, Xtruct, Xtruct2};
+
+/// Serve the ThriftTest service for the cross-language tests.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "cross_test_server")]
+struct Opt {
+    /// Port to listen on.
+    #[structopt(long, default_value = "9090")]
+    port: u16,
+
+    /// Transport: buffered or framed.
+    #[structopt(long, default_value = "buffered")]
+    transport: TransportKind,
+
+    /// Protocol: binary or compact.
+    #[structopt(long, default_value = "binary")]
+    protocol: ProtocolKind,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let address = format!("127.0.0.1:{}", opt.port);
+    println!("serving ThriftTest on {} ({} {})", address, opt.transport, opt.protocol);
+
+    let res = match opt.transport {
+        TransportKind::Buffered => serve(
+            &address,
+            opt.protocol,
+            TAsyncBufferedReadTransportFactory::new(),
+            TAsyncBufferedWriteTransportFactory::new(),
+        ),
+        TransportKind::Framed => serve(
+            &address,
+            opt.protocol,
+            TAsyncFramedReadTransportFactory::new(),
+            TAsyncFramedWriteTransportFactory::new(),
+        ),
+    };
+    if let Err(e) = res {
+        eprintln!("cross_test_server: {:?}", e);
+        process::exit(1);
+    }
+}
+
+fn serve<RTF, WTF>(
+    address: &str,
+    protocol: ProtocolKind,
+    r_trans_factory: RTF,
+    w_trans_factory: WTF,
+) -> async_thrift::Result<()>
+    where
+        RTF: TAsyncReadTransportFactory + 'static,
+        WTF: TAsyncWriteTransportFactory + 'static,
+{
+    match protocol {
+        ProtocolKind::Binary => listen(
+            address,
+            r_trans_factory,
+            TAsyncBinaryInputProtocolFactory::new(),
+            w_trans_factory,
+            TAsyncBinaryOutputProtocolFactory::new(),
+        ),
+        ProtocolKind::Compact => listen(
+            address,
+            r_trans_factory,
+            TAsyncCompactInputProtocolFactory::new(),
+            w_trans_factory,
+            TAsyncCompactOutputProtocolFactory::new(),
+        ),
+    }
+}
+
+fn listen<RTF, IPF, WTF, OPF>(
+    address: &str,
+    r_trans_factory: RTF,
+    i_proto_factory: IPF,
+    w_trans_factory: WTF,
+    o_proto_factory: OPF,
+) -> async_thrift::Result<()>
+    where
+        RTF: TAsyncReadTransportFactory + 'static,
+        IPF: TAsyncInputProtocolFactory + 'static,
+        WTF: TAsyncWriteTransportFactory + 'static,
+        OPF: TAsyncOutputProtocolFactory + 'static,
+{
+    let mut server = TAsyncServer::new(
+        r_trans_factory,
+        i_proto_factory,
+        w_trans_factory,
+        o_proto_factory,
+        ThriftTestSyncProcessor::new(TestHandler),
+    );
+    task::block_on(server.listen(address))
+}
+
+/// Echoes its arguments back, like the handlers of Apache Thrift's test
+/// servers.
+struct TestHandler;
+
+#[async_trait]
+impl ThriftTestSyncHandler for TestHandler {
+    async fn handle_test_void(&self) -> async_thrift::Result<()> {
+        println!("testVoid()");
+        Ok(())
+    }
+
+    async fn handle_test_string(&self, thing: String) -> async_thrift::Result<String> {
+        println!("testString({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_bool(&self, thing: bool) -> async_thrift::Result<bool> {
+        println!("testBool({})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_byte(&self, thing: i8) -> async_thrift::Result<i8> {
+        println!("testByte({})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_i32(&self, thing: i32) -> async_thrift::Result<i32> {
+        println!("testI32({})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_i64(&self, thing: i64) -> async_thrift::Result<i64> {
+        println!("testI64({})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_double(&self, thing: OrderedFloat<f64>) -> async_thrift::Result<OrderedFloat<f64>> {
+        println!("testDouble({})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_binary(&self, thing: Vec<u8>) -> async_thrift::Result<Vec<u8>> {
+        println!("testBinary({} bytes)", thing.len());
+        Ok(thing)
+    }
+
+    async fn handle_test_struct(&self, thing: Xtruct) -> async_thrift::Result<Xtruct> {
+        println!("testStruct({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_nest(&self, thing: Xtruct2) -> async_thrift::Result<Xtruct2> {
+        println!("testNest({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_map(&self, thing: BTreeMap<i32, i32>) -> async_thrift::Result<BTreeMap<i32, i32>> {
+        println!("testMap({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_string_map(&self, thing: BTreeMap<String, String>) -> async_thrift::Result<BTreeMap<String, String>> {
+        println!("testStringMap({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_set(&self, thing: BTreeSet<i32>) -> async_thrift::Result<BTreeSet<i32>> {
+        println!("testSet({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_list(&self, thing: Vec<i32>) -> async_thrift::Result<Vec<i32>> {
+        println!("testList({:?})", thing);
+        Ok(thing)
+    }
+
+    async fn handle_test_map_map(&self, hello: i32) -> async_thrift::Result<BTreeMap<i32, BTreeMap<i32, i32>>> {
+        println!("testMapMap({})", hello);
+        let mut map_map = BTreeMap::new();
+        map_map.insert(-4, (-4..0).map(|i| (i, i)).collect());
+        map_map.insert(4, (1..5).map(|i| (i, i)).collect());
+        Ok(map_map)
+    }
+
+    async fn handle_test_exception(&self, arg: String) -> async_thrift::Result<()> {
+        println!("testException({:?})", arg);
+        match arg.as_str() {
+            "Xception" => Err(Xception::new(1001, arg).into()),
+            "TException" => Err(async_thrift::Error::Application(ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                "this is a TException",
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    async fn handle_test_oneway(&self, seconds_to_sleep: i32) -> async_thrift::Result<()> {
+        println!("testOneway({}): sleeping", seconds_to_sleep);
+        task::sleep(Duration::from_secs(seconds_to_sleep.max(0) as u64)).await;
+        println!("testOneway({}): done", seconds_to_sleep);
+        Ok(())
+    }
+}
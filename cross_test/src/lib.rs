@@ -0,0 +1,8 @@
+//! Rust side of the cross-language tests.
+//!
+//! `cross_test_server` and `cross_test_client` implement the `ThriftTest`
+//! service of `ThriftTest.thrift` the same way as the test programs of
+//! Apache Thrift, and `run.sh` pairs them with their Go counterparts in
+//! `go_client` for every transport and protocol both sides support.
+
+pub mod thrift_test;
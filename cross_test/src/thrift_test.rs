@@ -0,0 +1,3587 @@
+// Autogenerated by Thrift Compiler ()
+// DO NOT EDIT UNLESS YOU ARE SURE THAT YOU KNOW WHAT YOU ARE DOING
+
+#![allow(unused_imports)]
+#![allow(unused_extern_crates)]
+#![cfg_attr(feature = "cargo-clippy", allow(too_many_arguments, type_complexity))]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate async_thrift;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::{From, TryFrom};
+use std::default::Default;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use async_thrift::{ApplicationError, ApplicationErrorKind, ProtocolError, ProtocolErrorKind, TThriftClient};
+use async_thrift::protocol::{TFieldIdentifier, TListIdentifier, TMapIdentifier, TMessageIdentifier, TMessageType, TAsyncInputProtocol, TAsyncOutputProtocol, TSetIdentifier, TStructIdentifier, TType};
+use async_thrift::protocol::field_id;
+use async_thrift::protocol::verify_expected_message_type;
+use async_thrift::protocol::verify_expected_sequence_number;
+use async_thrift::protocol::verify_expected_service_call;
+use async_thrift::protocol::verify_required_field_exists;
+use async_trait::async_trait;
+use async_thrift::server::TAsyncProcessor;
+use async_thrift::OrderedFloat;
+
+//
+// Xtruct
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Xtruct {
+  pub string_thing: Option<String>,
+  pub byte_thing: Option<i8>,
+  pub i32_thing: Option<i32>,
+  pub i64_thing: Option<i64>,
+}
+
+impl Xtruct {
+  pub fn new<F1, F4, F9, F11>(string_thing: F1, byte_thing: F4, i32_thing: F9, i64_thing: F11) -> Xtruct where F1: Into<Option<String>>, F4: Into<Option<i8>>, F9: Into<Option<i32>>, F11: Into<Option<i64>> {
+    Xtruct {
+      string_thing: string_thing.into(),
+      byte_thing: byte_thing.into(),
+      i32_thing: i32_thing.into(),
+      i64_thing: i64_thing.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Xtruct> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<String> = Some("".to_owned());
+    let mut f_4: Option<i8> = Some(0);
+    let mut f_9: Option<i32> = Some(0);
+    let mut f_11: Option<i64> = Some(0);
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_string().await?;
+          f_1 = Some(val);
+        },
+        4 => {
+          let val = i_prot.read_i8().await?;
+          f_4 = Some(val);
+        },
+        9 => {
+          let val = i_prot.read_i32().await?;
+          f_9 = Some(val);
+        },
+        11 => {
+          let val = i_prot.read_i64().await?;
+          f_11 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Xtruct {
+      string_thing: f_1,
+      byte_thing: f_4,
+      i32_thing: f_9,
+      i64_thing: f_11,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("Xtruct");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.string_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("string_thing", TType::String, 1)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(fld_var) = self.byte_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("byte_thing", TType::I08, 4)).await?;
+      o_prot.write_i8(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(fld_var) = self.i32_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("i32_thing", TType::I32, 9)).await?;
+      o_prot.write_i32(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(fld_var) = self.i64_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("i64_thing", TType::I64, 11)).await?;
+      o_prot.write_i64(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+async_thrift::impl_serializable!(Xtruct);
+
+impl Default for Xtruct {
+  fn default() -> Self {
+    Xtruct{
+      string_thing: Some("".to_owned()),
+      byte_thing: Some(0),
+      i32_thing: Some(0),
+      i64_thing: Some(0),
+    }
+  }
+}
+
+//
+// Xtruct2
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Xtruct2 {
+  pub byte_thing: Option<i8>,
+  pub struct_thing: Option<Xtruct>,
+  pub i32_thing: Option<i32>,
+}
+
+impl Xtruct2 {
+  pub fn new<F1, F2, F3>(byte_thing: F1, struct_thing: F2, i32_thing: F3) -> Xtruct2 where F1: Into<Option<i8>>, F2: Into<Option<Xtruct>>, F3: Into<Option<i32>> {
+    Xtruct2 {
+      byte_thing: byte_thing.into(),
+      struct_thing: struct_thing.into(),
+      i32_thing: i32_thing.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Xtruct2> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i8> = Some(0);
+    let mut f_2: Option<Xtruct> = None;
+    let mut f_3: Option<i32> = Some(0);
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i8().await?;
+          f_1 = Some(val);
+        },
+        2 => {
+          let val = Xtruct::read_from_in_protocol(i_prot).await?;
+          f_2 = Some(val);
+        },
+        3 => {
+          let val = i_prot.read_i32().await?;
+          f_3 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Xtruct2 {
+      byte_thing: f_1,
+      struct_thing: f_2,
+      i32_thing: f_3,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("Xtruct2");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.byte_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("byte_thing", TType::I08, 1)).await?;
+      o_prot.write_i8(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.struct_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("struct_thing", TType::Struct, 2)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(fld_var) = self.i32_thing {
+      o_prot.write_field_begin(&TFieldIdentifier::new("i32_thing", TType::I32, 3)).await?;
+      o_prot.write_i32(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+async_thrift::impl_serializable!(Xtruct2);
+
+impl Default for Xtruct2 {
+  fn default() -> Self {
+    Xtruct2{
+      byte_thing: Some(0),
+      struct_thing: None,
+      i32_thing: Some(0),
+    }
+  }
+}
+
+//
+// Xception
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Xception {
+  pub error_code: Option<i32>,
+  pub message: Option<String>,
+}
+
+impl Xception {
+  pub fn new<F1, F2>(error_code: F1, message: F2) -> Xception where F1: Into<Option<i32>>, F2: Into<Option<String>> {
+    Xception {
+      error_code: error_code.into(),
+      message: message.into(),
+    }
+  }
+  pub async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<Xception> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i32> = Some(0);
+    let mut f_2: Option<String> = Some("".to_owned());
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i32().await?;
+          f_1 = Some(val);
+        },
+        2 => {
+          let val = i_prot.read_string().await?;
+          f_2 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = Xception {
+      error_code: f_1,
+      message: f_2,
+    };
+    Ok(ret)
+  }
+  pub async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("Xception");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.error_code {
+      o_prot.write_field_begin(&TFieldIdentifier::new("errorCode", TType::I32, 1)).await?;
+      o_prot.write_i32(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    if let Some(ref fld_var) = self.message {
+      o_prot.write_field_begin(&TFieldIdentifier::new("message", TType::String, 2)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+async_thrift::impl_serializable!(Xception);
+
+impl Default for Xception {
+  fn default() -> Self {
+    Xception{
+      error_code: Some(0),
+      message: Some("".to_owned()),
+    }
+  }
+}
+
+impl Error for Xception {}
+
+impl From<Xception> for async_thrift::Error {
+  fn from(e: Xception) -> Self {
+    async_thrift::Error::User(Box::new(e))
+  }
+}
+
+impl Display for Xception {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "remote service threw Xception")
+  }
+}
+
+//
+// ThriftTest service client
+//
+
+#[async_trait]
+pub trait TThriftTestSyncClient {
+  async fn test_void(&mut self) -> async_thrift::Result<()>;
+  async fn test_string(&mut self, thing: String) -> async_thrift::Result<String>;
+  async fn test_bool(&mut self, thing: bool) -> async_thrift::Result<bool>;
+  async fn test_byte(&mut self, thing: i8) -> async_thrift::Result<i8>;
+  async fn test_i32(&mut self, thing: i32) -> async_thrift::Result<i32>;
+  async fn test_i64(&mut self, thing: i64) -> async_thrift::Result<i64>;
+  async fn test_double(&mut self, thing: OrderedFloat<f64>) -> async_thrift::Result<OrderedFloat<f64>>;
+  async fn test_binary(&mut self, thing: Vec<u8>) -> async_thrift::Result<Vec<u8>>;
+  async fn test_struct(&mut self, thing: Xtruct) -> async_thrift::Result<Xtruct>;
+  async fn test_nest(&mut self, thing: Xtruct2) -> async_thrift::Result<Xtruct2>;
+  async fn test_map(&mut self, thing: BTreeMap<i32, i32>) -> async_thrift::Result<BTreeMap<i32, i32>>;
+  async fn test_string_map(&mut self, thing: BTreeMap<String, String>) -> async_thrift::Result<BTreeMap<String, String>>;
+  async fn test_set(&mut self, thing: BTreeSet<i32>) -> async_thrift::Result<BTreeSet<i32>>;
+  async fn test_list(&mut self, thing: Vec<i32>) -> async_thrift::Result<Vec<i32>>;
+  async fn test_map_map(&mut self, hello: i32) -> async_thrift::Result<BTreeMap<i32, BTreeMap<i32, i32>>>;
+  async fn test_exception(&mut self, arg: String) -> async_thrift::Result<()>;
+  async fn test_oneway(&mut self, seconds_to_sleep: i32) -> async_thrift::Result<()>;
+}
+
+pub trait TThriftTestSyncClientMarker {}
+
+pub struct ThriftTestSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {
+  _i_prot: IP,
+  _o_prot: OP,
+  _sequence_number: i32,
+}
+
+impl <IP, OP> ThriftTestSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {
+  pub fn new(input_protocol: IP, output_protocol: OP) -> ThriftTestSyncClient<IP, OP> {
+    ThriftTestSyncClient { _i_prot: input_protocol, _o_prot: output_protocol, _sequence_number: 0 }
+  }
+}
+
+impl <IP, OP> TThriftClient for ThriftTestSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {
+  fn i_prot_mut(&mut self) -> &mut (dyn TAsyncInputProtocol + Send) { &mut self._i_prot }
+  fn o_prot_mut(&mut self) -> &mut (dyn TAsyncOutputProtocol + Send) { &mut self._o_prot }
+  fn sequence_number(&self) -> i32 { self._sequence_number }
+  fn increment_sequence_number(&mut self) -> i32 { self._sequence_number += 1; self._sequence_number }
+}
+
+impl <IP, OP> TThriftTestSyncClientMarker for ThriftTestSyncClient<IP, OP> where IP: TAsyncInputProtocol, OP: TAsyncOutputProtocol {}
+
+#[async_trait]
+impl <C: TThriftClient + TThriftTestSyncClientMarker+ Send> TThriftTestSyncClient for C {
+  async fn test_void(&mut self) -> async_thrift::Result<()> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testVoid", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestVoidArgs {  };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testVoid", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestVoidResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_string(&mut self, thing: String) -> async_thrift::Result<String> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testString", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestStringArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testString", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestStringResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_bool(&mut self, thing: bool) -> async_thrift::Result<bool> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testBool", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestBoolArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testBool", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestBoolResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_byte(&mut self, thing: i8) -> async_thrift::Result<i8> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testByte", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestByteArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testByte", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestByteResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_i32(&mut self, thing: i32) -> async_thrift::Result<i32> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testI32", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestI32Args { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testI32", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestI32Result::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_i64(&mut self, thing: i64) -> async_thrift::Result<i64> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testI64", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestI64Args { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testI64", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestI64Result::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_double(&mut self, thing: OrderedFloat<f64>) -> async_thrift::Result<OrderedFloat<f64>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testDouble", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestDoubleArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testDouble", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestDoubleResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_binary(&mut self, thing: Vec<u8>) -> async_thrift::Result<Vec<u8>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testBinary", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestBinaryArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testBinary", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestBinaryResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_struct(&mut self, thing: Xtruct) -> async_thrift::Result<Xtruct> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testStruct", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestStructArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testStruct", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestStructResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_nest(&mut self, thing: Xtruct2) -> async_thrift::Result<Xtruct2> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testNest", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestNestArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testNest", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestNestResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_map(&mut self, thing: BTreeMap<i32, i32>) -> async_thrift::Result<BTreeMap<i32, i32>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testMap", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestMapArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testMap", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestMapResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_string_map(&mut self, thing: BTreeMap<String, String>) -> async_thrift::Result<BTreeMap<String, String>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testStringMap", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestStringMapArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testStringMap", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestStringMapResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_set(&mut self, thing: BTreeSet<i32>) -> async_thrift::Result<BTreeSet<i32>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testSet", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestSetArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testSet", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestSetResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_list(&mut self, thing: Vec<i32>) -> async_thrift::Result<Vec<i32>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testList", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestListArgs { thing: thing };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testList", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestListResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_map_map(&mut self, hello: i32) -> async_thrift::Result<BTreeMap<i32, BTreeMap<i32, i32>>> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testMapMap", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestMapMapArgs { hello: hello };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testMapMap", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestMapMapResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_exception(&mut self, arg: String) -> async_thrift::Result<()> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testException", TMessageType::Call, self.sequence_number());
+        let call_args = ThriftTestTestExceptionArgs { arg: arg };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    {
+      let message_ident = self.i_prot_mut().read_message_begin().await?;
+      verify_expected_sequence_number(self.sequence_number(), message_ident.sequence_number)?;
+      verify_expected_service_call("testException", &message_ident.name)?;
+      if message_ident.message_type == TMessageType::Exception {
+        let remote_error = async_thrift::Error::read_application_error_from_in_protocol(self.i_prot_mut()).await?;
+        self.i_prot_mut().read_message_end().await?;
+        return Err(async_thrift::Error::Application(remote_error))
+      }
+      verify_expected_message_type(TMessageType::Reply, message_ident.message_type)?;
+      let result = ThriftTestTestExceptionResult::read_from_in_protocol(self.i_prot_mut()).await?;
+      self.i_prot_mut().read_message_end().await?;
+      result.ok_or()
+    }
+  }
+  async fn test_oneway(&mut self, seconds_to_sleep: i32) -> async_thrift::Result<()> {
+    (
+      {
+        self.increment_sequence_number();
+        let message_ident = TMessageIdentifier::new("testOneway", TMessageType::OneWay, self.sequence_number());
+        let call_args = ThriftTestTestOnewayArgs { seconds_to_sleep: seconds_to_sleep };
+        self.o_prot_mut().write_message_begin(&message_ident).await?;
+        call_args.write_to_out_protocol(self.o_prot_mut()).await?;
+        self.o_prot_mut().write_message_end().await?;
+        self.o_prot_mut().flush().await
+      }
+    )?;
+    Ok(())
+  }
+}
+
+//
+// ThriftTest service processor
+//
+
+#[async_trait]
+pub trait ThriftTestSyncHandler {
+  async fn handle_test_void(&self) -> async_thrift::Result<()>;
+  async fn handle_test_string(&self, thing: String) -> async_thrift::Result<String>;
+  async fn handle_test_bool(&self, thing: bool) -> async_thrift::Result<bool>;
+  async fn handle_test_byte(&self, thing: i8) -> async_thrift::Result<i8>;
+  async fn handle_test_i32(&self, thing: i32) -> async_thrift::Result<i32>;
+  async fn handle_test_i64(&self, thing: i64) -> async_thrift::Result<i64>;
+  async fn handle_test_double(&self, thing: OrderedFloat<f64>) -> async_thrift::Result<OrderedFloat<f64>>;
+  async fn handle_test_binary(&self, thing: Vec<u8>) -> async_thrift::Result<Vec<u8>>;
+  async fn handle_test_struct(&self, thing: Xtruct) -> async_thrift::Result<Xtruct>;
+  async fn handle_test_nest(&self, thing: Xtruct2) -> async_thrift::Result<Xtruct2>;
+  async fn handle_test_map(&self, thing: BTreeMap<i32, i32>) -> async_thrift::Result<BTreeMap<i32, i32>>;
+  async fn handle_test_string_map(&self, thing: BTreeMap<String, String>) -> async_thrift::Result<BTreeMap<String, String>>;
+  async fn handle_test_set(&self, thing: BTreeSet<i32>) -> async_thrift::Result<BTreeSet<i32>>;
+  async fn handle_test_list(&self, thing: Vec<i32>) -> async_thrift::Result<Vec<i32>>;
+  async fn handle_test_map_map(&self, hello: i32) -> async_thrift::Result<BTreeMap<i32, BTreeMap<i32, i32>>>;
+  async fn handle_test_exception(&self, arg: String) -> async_thrift::Result<()>;
+  async fn handle_test_oneway(&self, seconds_to_sleep: i32) -> async_thrift::Result<()>;
+}
+
+pub struct ThriftTestSyncProcessor<H: ThriftTestSyncHandler> {
+  handler: H,
+}
+
+impl <H: ThriftTestSyncHandler> ThriftTestSyncProcessor<H> {
+  pub fn new(handler: H) -> ThriftTestSyncProcessor<H> {
+    ThriftTestSyncProcessor {
+      handler,
+    }
+  }
+  async fn process_test_void(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_void(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_string(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_string(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_bool(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_bool(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_byte(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_byte(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_i32(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_i32(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_i64(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_i64(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_double(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_double(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_binary(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_binary(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_struct(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_struct(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_nest(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_nest(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_map(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_map(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_string_map(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_string_map(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_set(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_set(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_list(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_list(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_map_map(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_map_map(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_exception(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_exception(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+  async fn process_test_oneway(&self, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    TThriftTestProcessFunctions::process_test_oneway(&self.handler, incoming_sequence_number, i_prot, o_prot).await
+  }
+}
+
+pub struct TThriftTestProcessFunctions;
+
+impl TThriftTestProcessFunctions {
+  pub async fn process_test_void<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    ThriftTestTestVoidArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_void().await {
+      Ok(_) => {
+        let message_ident = TMessageIdentifier::new("testVoid", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestVoidResult {  };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testVoid", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testVoid", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_string<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestStringArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_string(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testString", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestStringResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testString", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testString", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_bool<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestBoolArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_bool(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testBool", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestBoolResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testBool", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testBool", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_byte<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestByteArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_byte(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testByte", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestByteResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testByte", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testByte", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_i32<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestI32Args::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_i32(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testI32", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestI32Result { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testI32", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testI32", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_i64<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestI64Args::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_i64(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testI64", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestI64Result { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testI64", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testI64", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_double<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestDoubleArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_double(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testDouble", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestDoubleResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testDouble", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testDouble", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_binary<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestBinaryArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_binary(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testBinary", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestBinaryResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testBinary", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testBinary", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_struct<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestStructArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_struct(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testStruct", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestStructResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testStruct", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testStruct", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_nest<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestNestArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_nest(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testNest", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestNestResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testNest", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testNest", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_map<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestMapArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_map(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testMap", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestMapResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testMap", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testMap", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_string_map<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestStringMapArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_string_map(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testStringMap", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestStringMapResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testStringMap", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testStringMap", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_set<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestSetArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_set(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testSet", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestSetResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testSet", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testSet", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_list<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestListArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_list(args.thing).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testList", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestListResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testList", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testList", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_map_map<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestMapMapArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_map_map(args.hello).await {
+      Ok(handler_return) => {
+        let message_ident = TMessageIdentifier::new("testMapMap", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestMapMapResult { result_value: Some(handler_return) };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testMapMap", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testMapMap", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_exception<H: ThriftTestSyncHandler>(handler: &H, incoming_sequence_number: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestExceptionArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_exception(args.arg).await {
+      Ok(_) => {
+        let message_ident = TMessageIdentifier::new("testException", TMessageType::Reply, incoming_sequence_number);
+        o_prot.write_message_begin(&message_ident).await?;
+        let ret = ThriftTestTestExceptionResult { err1: None };
+        ret.write_to_out_protocol(o_prot).await?;
+        o_prot.write_message_end().await?;
+        o_prot.flush().await
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::User(usr_err) => {
+            if usr_err.downcast_ref::<Xception>().is_some() {
+              let err = usr_err.downcast::<Xception>().expect("downcast already checked");
+              let ret_err = ThriftTestTestExceptionResult{ err1: Some(*err) };
+              let message_ident = TMessageIdentifier::new("testException", TMessageType::Reply, incoming_sequence_number);
+              o_prot.write_message_begin(&message_ident).await?;
+              ret_err.write_to_out_protocol(o_prot).await?;
+              o_prot.write_message_end().await?;
+              o_prot.flush().await
+            } else {
+              let ret_err = {
+                ApplicationError::new(
+                  ApplicationErrorKind::Unknown,
+                  usr_err.to_string()
+                )
+              };
+              let message_ident = TMessageIdentifier::new("testException", TMessageType::Exception, incoming_sequence_number);
+              o_prot.write_message_begin(&message_ident).await?;
+              async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+              o_prot.write_message_end().await?;
+              o_prot.flush().await
+            }
+          },
+          async_thrift::Error::Application(app_err) => {
+            let message_ident = TMessageIdentifier::new("testException", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&app_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            let message_ident = TMessageIdentifier::new("testException", TMessageType::Exception, incoming_sequence_number);
+            o_prot.write_message_begin(&message_ident).await?;
+            async_thrift::Error::write_application_error_to_out_protocol(&ret_err, o_prot).await?;
+            o_prot.write_message_end().await?;
+            o_prot.flush().await
+          },
+        }
+      },
+    }
+  }
+  pub async fn process_test_oneway<H: ThriftTestSyncHandler>(handler: &H, _: i32, i_prot: &mut (dyn TAsyncInputProtocol + Send), _: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let args = ThriftTestTestOnewayArgs::read_from_in_protocol(i_prot).await?;
+    match handler.handle_test_oneway(args.seconds_to_sleep).await {
+      Ok(_) => {
+        Ok(())
+      },
+      Err(e) => {
+        match e {
+          async_thrift::Error::Application(app_err) => {
+            Err(async_thrift::Error::Application(app_err))
+          },
+          _ => {
+            let ret_err = {
+              ApplicationError::new(
+                ApplicationErrorKind::Unknown,
+                e.to_string()
+              )
+            };
+            Err(async_thrift::Error::Application(ret_err))
+          },
+        }
+      },
+    }
+  }
+}
+
+#[async_trait]
+impl <H: ThriftTestSyncHandler + Send + Sync> TAsyncProcessor for ThriftTestSyncProcessor<H> {
+  async fn process(&self, i_prot: &mut (dyn TAsyncInputProtocol + Send), o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let message_ident = i_prot.read_message_begin().await?;
+    let res = match &*message_ident.name {
+      "testVoid" => {
+        self.process_test_void(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testString" => {
+        self.process_test_string(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testBool" => {
+        self.process_test_bool(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testByte" => {
+        self.process_test_byte(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testI32" => {
+        self.process_test_i32(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testI64" => {
+        self.process_test_i64(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testDouble" => {
+        self.process_test_double(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testBinary" => {
+        self.process_test_binary(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testStruct" => {
+        self.process_test_struct(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testNest" => {
+        self.process_test_nest(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testMap" => {
+        self.process_test_map(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testStringMap" => {
+        self.process_test_string_map(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testSet" => {
+        self.process_test_set(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testList" => {
+        self.process_test_list(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testMapMap" => {
+        self.process_test_map_map(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testException" => {
+        self.process_test_exception(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      "testOneway" => {
+        self.process_test_oneway(message_ident.sequence_number, i_prot, o_prot).await
+      },
+      method => {
+        Err(
+          async_thrift::Error::Application(
+            ApplicationError::new(
+              ApplicationErrorKind::UnknownMethod,
+              format!("unknown method {}", method)
+            )
+          )
+        )
+      },
+    };
+    async_thrift::server::handle_process_result(&message_ident, res, o_prot).await
+  }
+}
+
+//
+// ThriftTestTestVoidArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestVoidArgs {
+}
+
+impl ThriftTestTestVoidArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestVoidArgs> {
+    i_prot.read_struct_begin().await?;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestVoidArgs {
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testVoid_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestVoidResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestVoidResult {
+}
+
+impl ThriftTestTestVoidResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestVoidResult> {
+    i_prot.read_struct_begin().await?;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestVoidResult {};
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestVoidResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<()> {
+    Ok(())
+  }
+}
+
+//
+// ThriftTestTestStringArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestStringArgs {
+  thing: String,
+}
+
+impl ThriftTestTestStringArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestStringArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<String> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_string().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestStringArgs.thing", &f_1)?;
+    let ret = ThriftTestTestStringArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testString_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::String, 1)).await?;
+    o_prot.write_string(&self.thing).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestStringResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestStringResult {
+  result_value: Option<String>,
+}
+
+impl ThriftTestTestStringResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestStringResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<String> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_string().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestStringResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestStringResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::String, 0)).await?;
+      o_prot.write_string(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<String> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestString"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestBoolArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestBoolArgs {
+  thing: bool,
+}
+
+impl ThriftTestTestBoolArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestBoolArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<bool> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_bool().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestBoolArgs.thing", &f_1)?;
+    let ret = ThriftTestTestBoolArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testBool_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Bool, 1)).await?;
+    o_prot.write_bool(self.thing).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestBoolResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestBoolResult {
+  result_value: Option<bool>,
+}
+
+impl ThriftTestTestBoolResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestBoolResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<bool> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_bool().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestBoolResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestBoolResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Bool, 0)).await?;
+      o_prot.write_bool(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<bool> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestBool"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestByteArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestByteArgs {
+  thing: i8,
+}
+
+impl ThriftTestTestByteArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestByteArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i8> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i8().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestByteArgs.thing", &f_1)?;
+    let ret = ThriftTestTestByteArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testByte_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::I08, 1)).await?;
+    o_prot.write_i8(self.thing).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestByteResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestByteResult {
+  result_value: Option<i8>,
+}
+
+impl ThriftTestTestByteResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestByteResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<i8> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_i8().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestByteResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestByteResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::I08, 0)).await?;
+      o_prot.write_i8(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<i8> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestByte"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestI32Args
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestI32Args {
+  thing: i32,
+}
+
+impl ThriftTestTestI32Args {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestI32Args> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i32> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i32().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestI32Args.thing", &f_1)?;
+    let ret = ThriftTestTestI32Args {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testI32_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::I32, 1)).await?;
+    o_prot.write_i32(self.thing).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestI32Result
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestI32Result {
+  result_value: Option<i32>,
+}
+
+impl ThriftTestTestI32Result {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestI32Result> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<i32> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_i32().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestI32Result {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestI32Result");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::I32, 0)).await?;
+      o_prot.write_i32(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<i32> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestI32"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestI64Args
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestI64Args {
+  thing: i64,
+}
+
+impl ThriftTestTestI64Args {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestI64Args> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i64> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i64().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestI64Args.thing", &f_1)?;
+    let ret = ThriftTestTestI64Args {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testI64_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::I64, 1)).await?;
+    o_prot.write_i64(self.thing).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestI64Result
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestI64Result {
+  result_value: Option<i64>,
+}
+
+impl ThriftTestTestI64Result {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestI64Result> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<i64> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_i64().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestI64Result {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestI64Result");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::I64, 0)).await?;
+      o_prot.write_i64(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<i64> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestI64"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestDoubleArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestDoubleArgs {
+  thing: OrderedFloat<f64>,
+}
+
+impl ThriftTestTestDoubleArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestDoubleArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<OrderedFloat<f64>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = OrderedFloat::from(i_prot.read_double().await?);
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestDoubleArgs.thing", &f_1)?;
+    let ret = ThriftTestTestDoubleArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testDouble_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Double, 1)).await?;
+    o_prot.write_double(self.thing.into()).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestDoubleResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestDoubleResult {
+  result_value: Option<OrderedFloat<f64>>,
+}
+
+impl ThriftTestTestDoubleResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestDoubleResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<OrderedFloat<f64>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = OrderedFloat::from(i_prot.read_double().await?);
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestDoubleResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestDoubleResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Double, 0)).await?;
+      o_prot.write_double(fld_var.into()).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<OrderedFloat<f64>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestDouble"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestBinaryArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestBinaryArgs {
+  thing: Vec<u8>,
+}
+
+impl ThriftTestTestBinaryArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestBinaryArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Vec<u8>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_bytes().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestBinaryArgs.thing", &f_1)?;
+    let ret = ThriftTestTestBinaryArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testBinary_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::String, 1)).await?;
+    o_prot.write_bytes(&self.thing).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestBinaryResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestBinaryResult {
+  result_value: Option<Vec<u8>>,
+}
+
+impl ThriftTestTestBinaryResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestBinaryResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<Vec<u8>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = i_prot.read_bytes().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestBinaryResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestBinaryResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::String, 0)).await?;
+      o_prot.write_bytes(fld_var).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<Vec<u8>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestBinary"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestStructArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestStructArgs {
+  thing: Xtruct,
+}
+
+impl ThriftTestTestStructArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestStructArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Xtruct> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = Xtruct::read_from_in_protocol(i_prot).await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestStructArgs.thing", &f_1)?;
+    let ret = ThriftTestTestStructArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testStruct_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Struct, 1)).await?;
+    self.thing.write_to_out_protocol(o_prot).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestStructResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestStructResult {
+  result_value: Option<Xtruct>,
+}
+
+impl ThriftTestTestStructResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestStructResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<Xtruct> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = Xtruct::read_from_in_protocol(i_prot).await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestStructResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestStructResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Struct, 0)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<Xtruct> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestStruct"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestNestArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestNestArgs {
+  thing: Xtruct2,
+}
+
+impl ThriftTestTestNestArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestNestArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Xtruct2> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = Xtruct2::read_from_in_protocol(i_prot).await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestNestArgs.thing", &f_1)?;
+    let ret = ThriftTestTestNestArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testNest_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Struct, 1)).await?;
+    self.thing.write_to_out_protocol(o_prot).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestNestResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestNestResult {
+  result_value: Option<Xtruct2>,
+}
+
+impl ThriftTestTestNestResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestNestResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<Xtruct2> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let val = Xtruct2::read_from_in_protocol(i_prot).await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestNestResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestNestResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Struct, 0)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<Xtruct2> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestNest"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestMapArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestMapArgs {
+  thing: BTreeMap<i32, i32>,
+}
+
+impl ThriftTestTestMapArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestMapArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<BTreeMap<i32, i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<i32, i32> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_0 = i_prot.read_i32().await?;
+            let map_val_1 = i_prot.read_i32().await?;
+            val.insert(map_key_0, map_val_1);
+          }
+          i_prot.read_map_end().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestMapArgs.thing", &f_1)?;
+    let ret = ThriftTestTestMapArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testMap_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Map, 1)).await?;
+    o_prot.write_map_begin(&TMapIdentifier::new(TType::I32, TType::I32, self.thing.len() as i32)).await?;
+    for (k, v) in &self.thing {
+      o_prot.write_i32(*k).await?;
+      o_prot.write_i32(*v).await?;
+    }
+    o_prot.write_map_end().await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestMapResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestMapResult {
+  result_value: Option<BTreeMap<i32, i32>>,
+}
+
+impl ThriftTestTestMapResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestMapResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<BTreeMap<i32, i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<i32, i32> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_2 = i_prot.read_i32().await?;
+            let map_val_3 = i_prot.read_i32().await?;
+            val.insert(map_key_2, map_val_3);
+          }
+          i_prot.read_map_end().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestMapResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestMapResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Map, 0)).await?;
+      o_prot.write_map_begin(&TMapIdentifier::new(TType::I32, TType::I32, fld_var.len() as i32)).await?;
+      for (k, v) in fld_var {
+        o_prot.write_i32(*k).await?;
+        o_prot.write_i32(*v).await?;
+      }
+      o_prot.write_map_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<BTreeMap<i32, i32>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestMap"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestStringMapArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestStringMapArgs {
+  thing: BTreeMap<String, String>,
+}
+
+impl ThriftTestTestStringMapArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestStringMapArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<BTreeMap<String, String>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<String, String> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_4 = i_prot.read_string().await?;
+            let map_val_5 = i_prot.read_string().await?;
+            val.insert(map_key_4, map_val_5);
+          }
+          i_prot.read_map_end().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestStringMapArgs.thing", &f_1)?;
+    let ret = ThriftTestTestStringMapArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testStringMap_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Map, 1)).await?;
+    o_prot.write_map_begin(&TMapIdentifier::new(TType::String, TType::String, self.thing.len() as i32)).await?;
+    for (k, v) in &self.thing {
+      o_prot.write_string(k).await?;
+      o_prot.write_string(v).await?;
+    }
+    o_prot.write_map_end().await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestStringMapResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestStringMapResult {
+  result_value: Option<BTreeMap<String, String>>,
+}
+
+impl ThriftTestTestStringMapResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestStringMapResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<BTreeMap<String, String>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<String, String> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_6 = i_prot.read_string().await?;
+            let map_val_7 = i_prot.read_string().await?;
+            val.insert(map_key_6, map_val_7);
+          }
+          i_prot.read_map_end().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestStringMapResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestStringMapResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Map, 0)).await?;
+      o_prot.write_map_begin(&TMapIdentifier::new(TType::String, TType::String, fld_var.len() as i32)).await?;
+      for (k, v) in fld_var {
+        o_prot.write_string(k).await?;
+        o_prot.write_string(v).await?;
+      }
+      o_prot.write_map_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<BTreeMap<String, String>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestStringMap"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestSetArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestSetArgs {
+  thing: BTreeSet<i32>,
+}
+
+impl ThriftTestTestSetArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestSetArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<BTreeSet<i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let set_ident = i_prot.read_set_begin().await?;
+          let mut val: BTreeSet<i32> = BTreeSet::new();
+          for _ in 0..set_ident.size {
+            let set_elem_8 = i_prot.read_i32().await?;
+            val.insert(set_elem_8);
+          }
+          i_prot.read_set_end().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestSetArgs.thing", &f_1)?;
+    let ret = ThriftTestTestSetArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testSet_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::Set, 1)).await?;
+    o_prot.write_set_begin(&TSetIdentifier::new(TType::I32, self.thing.len() as i32)).await?;
+    for e in &self.thing {
+      o_prot.write_i32(*e).await?;
+    }
+    o_prot.write_set_end().await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestSetResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestSetResult {
+  result_value: Option<BTreeSet<i32>>,
+}
+
+impl ThriftTestTestSetResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestSetResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<BTreeSet<i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let set_ident = i_prot.read_set_begin().await?;
+          let mut val: BTreeSet<i32> = BTreeSet::new();
+          for _ in 0..set_ident.size {
+            let set_elem_9 = i_prot.read_i32().await?;
+            val.insert(set_elem_9);
+          }
+          i_prot.read_set_end().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestSetResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestSetResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Set, 0)).await?;
+      o_prot.write_set_begin(&TSetIdentifier::new(TType::I32, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        o_prot.write_i32(*e).await?;
+      }
+      o_prot.write_set_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<BTreeSet<i32>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestSet"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestListArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestListArgs {
+  thing: Vec<i32>,
+}
+
+impl ThriftTestTestListArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestListArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Vec<i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let list_ident = i_prot.read_list_begin().await?;
+          let mut val: Vec<i32> = Vec::with_capacity(list_ident.size as usize);
+          for _ in 0..list_ident.size {
+            let list_elem_10 = i_prot.read_i32().await?;
+            val.push(list_elem_10);
+          }
+          i_prot.read_list_end().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestListArgs.thing", &f_1)?;
+    let ret = ThriftTestTestListArgs {
+      thing: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testList_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("thing", TType::List, 1)).await?;
+    o_prot.write_list_begin(&TListIdentifier::new(TType::I32, self.thing.len() as i32)).await?;
+    for e in &self.thing {
+      o_prot.write_i32(*e).await?;
+    }
+    o_prot.write_list_end().await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestListResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestListResult {
+  result_value: Option<Vec<i32>>,
+}
+
+impl ThriftTestTestListResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestListResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<Vec<i32>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let list_ident = i_prot.read_list_begin().await?;
+          let mut val: Vec<i32> = Vec::with_capacity(list_ident.size as usize);
+          for _ in 0..list_ident.size {
+            let list_elem_11 = i_prot.read_i32().await?;
+            val.push(list_elem_11);
+          }
+          i_prot.read_list_end().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestListResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestListResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::List, 0)).await?;
+      o_prot.write_list_begin(&TListIdentifier::new(TType::I32, fld_var.len() as i32)).await?;
+      for e in fld_var {
+        o_prot.write_i32(*e).await?;
+      }
+      o_prot.write_list_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<Vec<i32>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestList"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestMapMapArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestMapMapArgs {
+  hello: i32,
+}
+
+impl ThriftTestTestMapMapArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestMapMapArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i32> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i32().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestMapMapArgs.hello", &f_1)?;
+    let ret = ThriftTestTestMapMapArgs {
+      hello: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testMapMap_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("hello", TType::I32, 1)).await?;
+    o_prot.write_i32(self.hello).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestMapMapResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestMapMapResult {
+  result_value: Option<BTreeMap<i32, BTreeMap<i32, i32>>>,
+}
+
+impl ThriftTestTestMapMapResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestMapMapResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_0: Option<BTreeMap<i32, BTreeMap<i32, i32>>> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        0 => {
+          let map_ident = i_prot.read_map_begin().await?;
+          let mut val: BTreeMap<i32, BTreeMap<i32, i32>> = BTreeMap::new();
+          for _ in 0..map_ident.size {
+            let map_key_12 = i_prot.read_i32().await?;
+            let map_ident = i_prot.read_map_begin().await?;
+            let mut map_val_13: BTreeMap<i32, i32> = BTreeMap::new();
+            for _ in 0..map_ident.size {
+              let map_key_14 = i_prot.read_i32().await?;
+              let map_val_15 = i_prot.read_i32().await?;
+              map_val_13.insert(map_key_14, map_val_15);
+            }
+            i_prot.read_map_end().await?;
+            val.insert(map_key_12, map_val_13);
+          }
+          i_prot.read_map_end().await?;
+          f_0 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestMapMapResult {
+      result_value: f_0,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestMapMapResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.result_value {
+      o_prot.write_field_begin(&TFieldIdentifier::new("result_value", TType::Map, 0)).await?;
+      o_prot.write_map_begin(&TMapIdentifier::new(TType::I32, TType::Map, fld_var.len() as i32)).await?;
+      for (k, v) in fld_var {
+        o_prot.write_i32(*k).await?;
+        o_prot.write_map_begin(&TMapIdentifier::new(TType::I32, TType::I32, v.len() as i32)).await?;
+        for (k, v) in v {
+          o_prot.write_i32(*k).await?;
+          o_prot.write_i32(*v).await?;
+        }
+        o_prot.write_map_end().await?;
+      }
+      o_prot.write_map_end().await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<BTreeMap<i32, BTreeMap<i32, i32>>> {
+    if self.result_value.is_some() {
+      Ok(self.result_value.unwrap())
+    } else {
+      Err(
+        async_thrift::Error::Application(
+          ApplicationError::new(
+            ApplicationErrorKind::MissingResult,
+            "no result received for ThriftTestTestMapMap"
+          )
+        )
+      )
+    }
+  }
+}
+
+//
+// ThriftTestTestExceptionArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestExceptionArgs {
+  arg: String,
+}
+
+impl ThriftTestTestExceptionArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestExceptionArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<String> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_string().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestExceptionArgs.arg", &f_1)?;
+    let ret = ThriftTestTestExceptionArgs {
+      arg: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testException_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("arg", TType::String, 1)).await?;
+    o_prot.write_string(&self.arg).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
+//
+// ThriftTestTestExceptionResult
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestExceptionResult {
+  err1: Option<Xception>,
+}
+
+impl ThriftTestTestExceptionResult {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestExceptionResult> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<Xception> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = Xception::read_from_in_protocol(i_prot).await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    let ret = ThriftTestTestExceptionResult {
+      err1: f_1,
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("ThriftTestTestExceptionResult");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    if let Some(ref fld_var) = self.err1 {
+      o_prot.write_field_begin(&TFieldIdentifier::new("err1", TType::Struct, 1)).await?;
+      fld_var.write_to_out_protocol(o_prot).await?;
+      o_prot.write_field_end().await?;
+      ()
+    } else {
+      ()
+    }
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+  fn ok_or(self) -> async_thrift::Result<()> {
+    if self.err1.is_some() {
+      Err(async_thrift::Error::User(Box::new(self.err1.unwrap())))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+//
+// ThriftTestTestOnewayArgs
+//
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ThriftTestTestOnewayArgs {
+  seconds_to_sleep: i32,
+}
+
+impl ThriftTestTestOnewayArgs {
+  async fn read_from_in_protocol(i_prot: &mut (dyn TAsyncInputProtocol + Send)) -> async_thrift::Result<ThriftTestTestOnewayArgs> {
+    i_prot.read_struct_begin().await?;
+    let mut f_1: Option<i32> = None;
+    loop {
+      let field_ident = i_prot.read_field_begin().await?;
+      if field_ident.field_type == TType::Stop {
+        break;
+      }
+      let field_id = field_id(&field_ident)?;
+      match field_id {
+        1 => {
+          let val = i_prot.read_i32().await?;
+          f_1 = Some(val);
+        },
+        _ => {
+          i_prot.skip(field_ident.field_type).await?;
+        },
+      };
+      i_prot.read_field_end().await?;
+    }
+    i_prot.read_struct_end().await?;
+    verify_required_field_exists("ThriftTestTestOnewayArgs.seconds_to_sleep", &f_1)?;
+    let ret = ThriftTestTestOnewayArgs {
+      seconds_to_sleep: f_1.expect("auto-generated code should have checked for presence of required fields"),
+    };
+    Ok(ret)
+  }
+  async fn write_to_out_protocol(&self, o_prot: &mut (dyn TAsyncOutputProtocol + Send)) -> async_thrift::Result<()> {
+    let struct_ident = TStructIdentifier::new("testOneway_args");
+    o_prot.write_struct_begin(&struct_ident).await?;
+    o_prot.write_field_begin(&TFieldIdentifier::new("secondsToSleep", TType::I32, 1)).await?;
+    o_prot.write_i32(self.seconds_to_sleep).await?;
+    o_prot.write_field_end().await?;
+    o_prot.write_field_stop().await?;
+    o_prot.write_struct_end().await
+  }
+}
+
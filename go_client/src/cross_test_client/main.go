@@ -0,0 +1,210 @@
+package main
+
+import (
+	"context"
+	"flag"
+	"fmt"
+	"github.com/apache/thrift/lib/go/thrift"
+	"os"
+	"reflect"
+	"sort"
+	"strings"
+	"thrifttest"
+	"time"
+)
+
+// exit code bits, the same as those of Apache Thrift's test clients
+const (
+	ERR_BASETYPES  = 1
+	ERR_STRUCTS    = 2
+	ERR_CONTAINERS = 4
+	ERR_EXCEPTIONS = 8
+	ERR_UNKNOWN    = 64
+)
+
+var defaultCtx = context.Background()
+
+func Usage() {
+	fmt.Fprint(os.Stderr, "Usage of ", os.Args[0], ":\n")
+	flag.PrintDefaults()
+	fmt.Fprint(os.Stderr, "\n")
+}
+
+func main() {
+	flag.Usage = Usage
+	host := flag.String("host", "127.0.0.1", "Host of the server")
+	port := flag.Int("port", 9090, "Port of the server")
+	transport := flag.String("transport", "buffered", "Specify the transport (buffered, framed)")
+	protocol := flag.String("protocol", "binary", "Specify the protocol (binary, compact, json)")
+
+	flag.Parse()
+
+	client, err := connect(fmt.Sprintf("%s:%d", *host, *port), *transport, *protocol)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "error opening client:", err)
+		os.Exit(ERR_UNKNOWN)
+	}
+	os.Exit(runTests(client))
+}
+
+func connect(addr string, transport string, protocol string) (*thrifttest.ThriftTestClient, error) {
+	socket, err := thrift.NewTSocket(addr)
+	if err != nil {
+		return nil, err
+	}
+
+	var trans thrift.TTransport
+	switch transport {
+	case "buffered":
+		trans = thrift.NewTBufferedTransport(socket, 8192)
+	case "framed":
+		trans = thrift.NewTFramedTransport(socket)
+	default:
+		return nil, fmt.Errorf("invalid transport %q", transport)
+	}
+
+	var protocolFactory thrift.TProtocolFactory
+	switch protocol {
+	case "binary":
+		protocolFactory = thrift.NewTBinaryProtocolFactoryDefault()
+	case "compact":
+		protocolFactory = thrift.NewTCompactProtocolFactory()
+	case "json":
+		protocolFactory = thrift.NewTJSONProtocolFactory()
+	default:
+		return nil, fmt.Errorf("invalid protocol %q", protocol)
+	}
+
+	if err := trans.Open(); err != nil {
+		return nil, err
+	}
+	iprot := protocolFactory.GetProtocol(trans)
+	oprot := protocolFactory.GetProtocol(trans)
+	return thrifttest.NewThriftTestClient(thrift.NewTStandardClient(iprot, oprot)), nil
+}
+
+// checks collects the failed checks of a test run.
+type checks struct {
+	failed int
+}
+
+func (c *checks) check(errorBit int, call string, expected interface{}, actual interface{}, err error) {
+	if err != nil {
+		c.fail(errorBit, call, fmt.Sprintf("failed: %v", err))
+	} else if !reflect.DeepEqual(expected, actual) {
+		c.fail(errorBit, call, fmt.Sprintf("expected %v but got %v", expected, actual))
+	} else {
+		fmt.Printf("%s: ok\n", call)
+	}
+}
+
+func (c *checks) fail(errorBit int, call string, message string) {
+	fmt.Fprintf(os.Stderr, "%s: %s\n", call, message)
+	c.failed |= errorBit
+}
+
+func abbreviate(s string) string {
+	if len(s) > 20 {
+		return s[:20] + "..."
+	}
+	return s
+}
+
+func runTests(client *thrifttest.ThriftTestClient) int {
+	c := &checks{}
+
+	err := client.TestVoid(defaultCtx)
+	c.check(ERR_BASETYPES, "testVoid()", nil, nil, err)
+	for _, s := range []string{"", "Test", "ünicode ✓ テスト", strings.Repeat("long ", 1000)} {
+		r, err := client.TestString(defaultCtx, s)
+		c.check(ERR_BASETYPES, fmt.Sprintf("testString(%q)", abbreviate(s)), s, r, err)
+	}
+	for _, b := range []bool{true, false} {
+		r, err := client.TestBool(defaultCtx, b)
+		c.check(ERR_BASETYPES, fmt.Sprintf("testBool(%v)", b), b, r, err)
+	}
+	for _, i := range []int8{0, 1, -1, -128, 127} {
+		r, err := client.TestByte(defaultCtx, i)
+		c.check(ERR_BASETYPES, fmt.Sprintf("testByte(%d)", i), i, r, err)
+	}
+	for _, i := range []int32{0, -1, 190000013, -2147483648, 2147483647} {
+		r, err := client.TestI32(defaultCtx, i)
+		c.check(ERR_BASETYPES, fmt.Sprintf("testI32(%d)", i), i, r, err)
+	}
+	for _, i := range []int64{0, -34359738368, 1099511627775, -9223372036854775808, 9223372036854775807} {
+		r, err := client.TestI64(defaultCtx, i)
+		c.check(ERR_BASETYPES, fmt.Sprintf("testI64(%d)", i), i, r, err)
+	}
+	for _, d := range []float64{0, -5.2098523, -0.000341012439638598279, 1.0e300, 2.2250738585072014e-308} {
+		r, err := client.TestDouble(defaultCtx, d)
+		c.check(ERR_BASETYPES, fmt.Sprintf("testDouble(%v)", d), d, r, err)
+	}
+	bin := make([]byte, 256)
+	for i := range bin {
+		bin[i] = byte(i)
+	}
+	rBin, err := client.TestBinary(defaultCtx, bin)
+	c.check(ERR_BASETYPES, "testBinary(0..=255)", bin, rBin, err)
+
+	xtruct := &thrifttest.Xtruct{StringThing: "Zero", ByteThing: 1, I32Thing: -3, I64Thing: -5}
+	rXtruct, err := client.TestStruct(defaultCtx, xtruct)
+	c.check(ERR_STRUCTS, "testStruct(..)", xtruct, rXtruct, err)
+	xtruct2 := &thrifttest.Xtruct2{ByteThing: 1, StructThing: xtruct, I32Thing: 5}
+	rXtruct2, err := client.TestNest(defaultCtx, xtruct2)
+	c.check(ERR_STRUCTS, "testNest(..)", xtruct2, rXtruct2, err)
+
+	m := map[int32]int32{0: -10, 1: -9, 2: -8, 3: -7, 4: -6}
+	rMap, err := client.TestMap(defaultCtx, m)
+	c.check(ERR_CONTAINERS, "testMap(..)", m, rMap, err)
+	sm := map[string]string{"a": "2", "b": "blah", "some": "thing", "ünicode": "✓"}
+	rStringMap, err := client.TestStringMap(defaultCtx, sm)
+	c.check(ERR_CONTAINERS, "testStringMap(..)", sm, rStringMap, err)
+	set := []int32{-2, -1, 0, 1, 2}
+	rSet, err := client.TestSet(defaultCtx, set)
+	// sets have no order, servers may return them sorted differently
+	sort.Slice(rSet, func(i, j int) bool { return rSet[i] < rSet[j] })
+	c.check(ERR_CONTAINERS, "testSet(..)", set, rSet, err)
+	list := []int32{-2, -1, 0, 1, 2}
+	rList, err := client.TestList(defaultCtx, list)
+	c.check(ERR_CONTAINERS, "testList(..)", list, rList, err)
+	rList, err = client.TestList(defaultCtx, []int32{})
+	c.check(ERR_CONTAINERS, "testList([])", []int32{}, rList, err)
+	mapMap := map[int32]map[int32]int32{
+		-4: {-4: -4, -3: -3, -2: -2, -1: -1},
+		4:  {1: 1, 2: 2, 3: 3, 4: 4},
+	}
+	rMapMap, err := client.TestMapMap(defaultCtx, 1)
+	c.check(ERR_CONTAINERS, "testMapMap(1)", mapMap, rMapMap, err)
+
+	// a oneway call returns without waiting for the server to finish it
+	start := time.Now()
+	if err := client.TestOneway(defaultCtx, 1); err != nil {
+		c.fail(ERR_BASETYPES, "testOneway(1)", fmt.Sprintf("failed: %v", err))
+	} else if elapsed := time.Since(start); elapsed >= 500*time.Millisecond {
+		c.fail(ERR_BASETYPES, "testOneway(1)", fmt.Sprintf("took %v", elapsed))
+	} else {
+		fmt.Println("testOneway(1): ok")
+	}
+	err = client.TestVoid(defaultCtx)
+	c.check(ERR_BASETYPES, "testVoid() after testOneway(1)", nil, nil, err)
+
+	err = client.TestException(defaultCtx, "Xception")
+	if x, ok := err.(*thrifttest.Xception); !ok {
+		c.fail(ERR_EXCEPTIONS, `testException("Xception")`, fmt.Sprintf("expected Xception but got %T %v", err, err))
+	} else if x.ErrorCode != 1001 || x.Message != "Xception" {
+		c.fail(ERR_EXCEPTIONS, `testException("Xception")`, fmt.Sprintf("unexpected exception %v", x))
+	} else {
+		fmt.Println(`testException("Xception"): ok`)
+	}
+	err = client.TestException(defaultCtx, "success")
+	c.check(ERR_EXCEPTIONS, `testException("success")`, nil, nil, err)
+	// last, as some servers close the connection after an application exception
+	err = client.TestException(defaultCtx, "TException")
+	if _, ok := err.(thrift.TApplicationException); !ok {
+		c.fail(ERR_EXCEPTIONS, `testException("TException")`, fmt.Sprintf("expected an application exception but got %T %v", err, err))
+	} else {
+		fmt.Println(`testException("TException"): ok`)
+	}
+
+	return c.failed
+}
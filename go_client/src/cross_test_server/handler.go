@@ -0,0 +1,115 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"thrifttest"
+	"time"
+)
+
+// TestHandler echoes its arguments back, like the handlers of Apache
+// Thrift's test servers.
+type TestHandler struct {
+}
+
+func NewTestHandler() *TestHandler {
+	return &TestHandler{}
+}
+
+func (p *TestHandler) TestVoid(ctx context.Context) (err error) {
+	fmt.Print("testVoid()\n")
+	return nil
+}
+
+func (p *TestHandler) TestString(ctx context.Context, thing string) (r string, err error) {
+	fmt.Printf("testString(%q)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestBool(ctx context.Context, thing bool) (r bool, err error) {
+	fmt.Printf("testBool(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestByte(ctx context.Context, thing int8) (r int8, err error) {
+	fmt.Printf("testByte(%d)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestI32(ctx context.Context, thing int32) (r int32, err error) {
+	fmt.Printf("testI32(%d)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestI64(ctx context.Context, thing int64) (r int64, err error) {
+	fmt.Printf("testI64(%d)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestDouble(ctx context.Context, thing float64) (r float64, err error) {
+	fmt.Printf("testDouble(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestBinary(ctx context.Context, thing []byte) (r []byte, err error) {
+	fmt.Printf("testBinary(%d bytes)\n", len(thing))
+	return thing, nil
+}
+
+func (p *TestHandler) TestStruct(ctx context.Context, thing *thrifttest.Xtruct) (r *thrifttest.Xtruct, err error) {
+	fmt.Printf("testStruct(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestNest(ctx context.Context, thing *thrifttest.Xtruct2) (r *thrifttest.Xtruct2, err error) {
+	fmt.Printf("testNest(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestMap(ctx context.Context, thing map[int32]int32) (r map[int32]int32, err error) {
+	fmt.Printf("testMap(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestStringMap(ctx context.Context, thing map[string]string) (r map[string]string, err error) {
+	fmt.Printf("testStringMap(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestSet(ctx context.Context, thing []int32) (r []int32, err error) {
+	fmt.Printf("testSet(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestList(ctx context.Context, thing []int32) (r []int32, err error) {
+	fmt.Printf("testList(%v)\n", thing)
+	return thing, nil
+}
+
+func (p *TestHandler) TestMapMap(ctx context.Context, hello int32) (r map[int32]map[int32]int32, err error) {
+	fmt.Printf("testMapMap(%d)\n", hello)
+	r = map[int32]map[int32]int32{
+		-4: {-4: -4, -3: -3, -2: -2, -1: -1},
+		4:  {1: 1, 2: 2, 3: 3, 4: 4},
+	}
+	return r, nil
+}
+
+func (p *TestHandler) TestException(ctx context.Context, arg string) (err error) {
+	fmt.Printf("testException(%q)\n", arg)
+	switch arg {
+	case "Xception":
+		return &thrifttest.Xception{ErrorCode: 1001, Message: arg}
+	case "TException":
+		return errors.New("this is a TException")
+	}
+	return nil
+}
+
+func (p *TestHandler) TestOneway(ctx context.Context, secondsToSleep int32) (err error) {
+	fmt.Printf("testOneway(%d): sleeping\n", secondsToSleep)
+	time.Sleep(time.Second * time.Duration(secondsToSleep))
+	fmt.Printf("testOneway(%d): done\n", secondsToSleep)
+	return nil
+}
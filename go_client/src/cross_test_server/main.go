@@ -0,0 +1,71 @@
+package main
+
+import (
+	"flag"
+	"fmt"
+	"github.com/apache/thrift/lib/go/thrift"
+	"os"
+	"thrifttest"
+)
+
+func Usage() {
+	fmt.Fprint(os.Stderr, "Usage of ", os.Args[0], ":\n")
+	flag.PrintDefaults()
+	fmt.Fprint(os.Stderr, "\n")
+}
+
+func main() {
+	flag.Usage = Usage
+	port := flag.Int("port", 9090, "Port to listen on")
+	transport := flag.String("transport", "buffered", "Specify the transport (buffered, framed)")
+	protocol := flag.String("protocol", "binary", "Specify the protocol (binary, compact, json)")
+
+	flag.Parse()
+
+	transportFactory, protocolFactory, err := factories(*transport, *protocol)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		Usage()
+		os.Exit(1)
+	}
+
+	addr := fmt.Sprintf("127.0.0.1:%d", *port)
+	serverTransport, err := thrift.NewTServerSocket(addr)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, "error opening socket:", err)
+		os.Exit(1)
+	}
+	processor := thrifttest.NewThriftTestProcessor(NewTestHandler())
+	server := thrift.NewTSimpleServer4(processor, serverTransport, transportFactory, protocolFactory)
+
+	fmt.Printf("serving ThriftTest on %s (%s %s)\n", addr, *transport, *protocol)
+	if err := server.Serve(); err != nil {
+		fmt.Fprintln(os.Stderr, "error running server:", err)
+		os.Exit(1)
+	}
+}
+
+func factories(transport string, protocol string) (thrift.TTransportFactory, thrift.TProtocolFactory, error) {
+	var transportFactory thrift.TTransportFactory
+	switch transport {
+	case "buffered":
+		transportFactory = thrift.NewTBufferedTransportFactory(8192)
+	case "framed":
+		transportFactory = thrift.NewTFramedTransportFactory(thrift.NewTTransportFactory())
+	default:
+		return nil, nil, fmt.Errorf("invalid transport %q", transport)
+	}
+
+	var protocolFactory thrift.TProtocolFactory
+	switch protocol {
+	case "binary":
+		protocolFactory = thrift.NewTBinaryProtocolFactoryDefault()
+	case "compact":
+		protocolFactory = thrift.NewTCompactProtocolFactory()
+	case "json":
+		protocolFactory = thrift.NewTJSONProtocolFactory()
+	default:
+		return nil, nil, fmt.Errorf("invalid protocol %q", protocol)
+	}
+	return transportFactory, protocolFactory, nil
+}
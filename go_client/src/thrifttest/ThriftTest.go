@@ -0,0 +1,4994 @@
+// Autogenerated by Thrift Compiler (0.13.0)
+// DO NOT EDIT UNLESS YOU ARE SURE THAT YOU KNOW WHAT YOU ARE DOING
+
+package thrifttest
+
+import(
+	"bytes"
+	"context"
+	"reflect"
+	"fmt"
+	"github.com/apache/thrift/lib/go/thrift"
+)
+
+// (needed to ensure safety because of naive import list construction.)
+var _ = thrift.ZERO
+var _ = fmt.Printf
+var _ = context.Background
+var _ = reflect.DeepEqual
+var _ = bytes.Equal
+
+
+// Attributes:
+//  - StringThing
+//  - ByteThing
+//  - I32Thing
+//  - I64Thing
+type Xtruct struct {
+  StringThing string `thrift:"string_thing,1" db:"string_thing" json:"string_thing"`
+  // unused fields # 2 to 3
+  ByteThing int8 `thrift:"byte_thing,4" db:"byte_thing" json:"byte_thing"`
+  // unused fields # 5 to 8
+  I32Thing int32 `thrift:"i32_thing,9" db:"i32_thing" json:"i32_thing"`
+  // unused field # 10
+  I64Thing int64 `thrift:"i64_thing,11" db:"i64_thing" json:"i64_thing"`
+}
+
+func NewXtruct() *Xtruct {
+  return &Xtruct{}
+}
+
+
+func (p *Xtruct) GetStringThing() string {
+  return p.StringThing
+}
+
+func (p *Xtruct) GetByteThing() int8 {
+  return p.ByteThing
+}
+
+func (p *Xtruct) GetI32Thing() int32 {
+  return p.I32Thing
+}
+
+func (p *Xtruct) GetI64Thing() int64 {
+  return p.I64Thing
+}
+
+func (p *Xtruct) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    case 4:
+      if fieldTypeId == thrift.BYTE {
+        if err := p.ReadField4(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    case 9:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField9(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    case 11:
+      if fieldTypeId == thrift.I64 {
+        if err := p.ReadField11(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *Xtruct)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadString(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.StringThing = v
+}
+  return nil
+}
+
+func (p *Xtruct)  ReadField4(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadByte(); err != nil {
+  return thrift.PrependError("error reading field 4: ", err)
+} else {
+  temp := int8(v)
+  p.ByteThing = temp
+}
+  return nil
+}
+
+func (p *Xtruct)  ReadField9(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 9: ", err)
+} else {
+  p.I32Thing = v
+}
+  return nil
+}
+
+func (p *Xtruct)  ReadField11(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI64(); err != nil {
+  return thrift.PrependError("error reading field 11: ", err)
+} else {
+  p.I64Thing = v
+}
+  return nil
+}
+
+func (p *Xtruct) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("Xtruct"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+    if err := p.writeField4(oprot); err != nil { return err }
+    if err := p.writeField9(oprot); err != nil { return err }
+    if err := p.writeField11(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *Xtruct) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("string_thing", thrift.STRING, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:string_thing: ", p), err) }
+  if err := oprot.WriteString(string(p.StringThing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.string_thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:string_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct) writeField4(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("byte_thing", thrift.BYTE, 4); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 4:byte_thing: ", p), err) }
+  if err := oprot.WriteByte(int8(p.ByteThing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.byte_thing (4) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 4:byte_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct) writeField9(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("i32_thing", thrift.I32, 9); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 9:i32_thing: ", p), err) }
+  if err := oprot.WriteI32(int32(p.I32Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.i32_thing (9) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 9:i32_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct) writeField11(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("i64_thing", thrift.I64, 11); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 11:i64_thing: ", p), err) }
+  if err := oprot.WriteI64(int64(p.I64Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.i64_thing (11) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 11:i64_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("Xtruct(%+v)", *p)
+}
+
+// Attributes:
+//  - ByteThing
+//  - StructThing
+//  - I32Thing
+type Xtruct2 struct {
+  ByteThing int8 `thrift:"byte_thing,1" db:"byte_thing" json:"byte_thing"`
+  StructThing *Xtruct `thrift:"struct_thing,2" db:"struct_thing" json:"struct_thing"`
+  I32Thing int32 `thrift:"i32_thing,3" db:"i32_thing" json:"i32_thing"`
+}
+
+func NewXtruct2() *Xtruct2 {
+  return &Xtruct2{}
+}
+
+
+func (p *Xtruct2) GetByteThing() int8 {
+  return p.ByteThing
+}
+var Xtruct2_StructThing_DEFAULT *Xtruct
+func (p *Xtruct2) GetStructThing() *Xtruct {
+  if !p.IsSetStructThing() {
+    return Xtruct2_StructThing_DEFAULT
+  }
+return p.StructThing
+}
+
+func (p *Xtruct2) GetI32Thing() int32 {
+  return p.I32Thing
+}
+func (p *Xtruct2) IsSetStructThing() bool {
+  return p.StructThing != nil
+}
+
+func (p *Xtruct2) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.BYTE {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    case 2:
+      if fieldTypeId == thrift.STRUCT {
+        if err := p.ReadField2(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    case 3:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField3(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *Xtruct2)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadByte(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  temp := int8(v)
+  p.ByteThing = temp
+}
+  return nil
+}
+
+func (p *Xtruct2)  ReadField2(iprot thrift.TProtocol) error {
+  p.StructThing = &Xtruct{}
+  if err := p.StructThing.Read(iprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error reading struct: ", p.StructThing), err)
+  }
+  return nil
+}
+
+func (p *Xtruct2)  ReadField3(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 3: ", err)
+} else {
+  p.I32Thing = v
+}
+  return nil
+}
+
+func (p *Xtruct2) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("Xtruct2"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+    if err := p.writeField2(oprot); err != nil { return err }
+    if err := p.writeField3(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *Xtruct2) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("byte_thing", thrift.BYTE, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:byte_thing: ", p), err) }
+  if err := oprot.WriteByte(int8(p.ByteThing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.byte_thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:byte_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct2) writeField2(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("struct_thing", thrift.STRUCT, 2); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 2:struct_thing: ", p), err) }
+  if err := p.StructThing.Write(oprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error writing struct: ", p.StructThing), err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 2:struct_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct2) writeField3(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("i32_thing", thrift.I32, 3); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 3:i32_thing: ", p), err) }
+  if err := oprot.WriteI32(int32(p.I32Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.i32_thing (3) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 3:i32_thing: ", p), err) }
+  return err
+}
+
+func (p *Xtruct2) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("Xtruct2(%+v)", *p)
+}
+
+// Attributes:
+//  - ErrorCode
+//  - Message
+type Xception struct {
+  ErrorCode int32 `thrift:"errorCode,1" db:"errorCode" json:"errorCode"`
+  Message string `thrift:"message,2" db:"message" json:"message"`
+}
+
+func NewXception() *Xception {
+  return &Xception{}
+}
+
+
+func (p *Xception) GetErrorCode() int32 {
+  return p.ErrorCode
+}
+
+func (p *Xception) GetMessage() string {
+  return p.Message
+}
+
+func (p *Xception) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    case 2:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField2(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *Xception)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.ErrorCode = v
+}
+  return nil
+}
+
+func (p *Xception)  ReadField2(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadString(); err != nil {
+  return thrift.PrependError("error reading field 2: ", err)
+} else {
+  p.Message = v
+}
+  return nil
+}
+
+func (p *Xception) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("Xception"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+    if err := p.writeField2(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *Xception) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("errorCode", thrift.I32, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:errorCode: ", p), err) }
+  if err := oprot.WriteI32(int32(p.ErrorCode)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.errorCode (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:errorCode: ", p), err) }
+  return err
+}
+
+func (p *Xception) writeField2(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("message", thrift.STRING, 2); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 2:message: ", p), err) }
+  if err := oprot.WriteString(string(p.Message)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.message (2) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 2:message: ", p), err) }
+  return err
+}
+
+func (p *Xception) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("Xception(%+v)", *p)
+}
+
+func (p *Xception) Error() string {
+  return p.String()
+}
+
+type ThriftTest interface {
+  TestVoid(ctx context.Context) (err error)
+  // Parameters:
+  //  - Thing
+  TestString(ctx context.Context, thing string) (r string, err error)
+  // Parameters:
+  //  - Thing
+  TestBool(ctx context.Context, thing bool) (r bool, err error)
+  // Parameters:
+  //  - Thing
+  TestByte(ctx context.Context, thing int8) (r int8, err error)
+  // Parameters:
+  //  - Thing
+  TestI32(ctx context.Context, thing int32) (r int32, err error)
+  // Parameters:
+  //  - Thing
+  TestI64(ctx context.Context, thing int64) (r int64, err error)
+  // Parameters:
+  //  - Thing
+  TestDouble(ctx context.Context, thing float64) (r float64, err error)
+  // Parameters:
+  //  - Thing
+  TestBinary(ctx context.Context, thing []byte) (r []byte, err error)
+  // Parameters:
+  //  - Thing
+  TestStruct(ctx context.Context, thing *Xtruct) (r *Xtruct, err error)
+  // Parameters:
+  //  - Thing
+  TestNest(ctx context.Context, thing *Xtruct2) (r *Xtruct2, err error)
+  // Parameters:
+  //  - Thing
+  TestMap(ctx context.Context, thing map[int32]int32) (r map[int32]int32, err error)
+  // Parameters:
+  //  - Thing
+  TestStringMap(ctx context.Context, thing map[string]string) (r map[string]string, err error)
+  // Parameters:
+  //  - Thing
+  TestSet(ctx context.Context, thing []int32) (r []int32, err error)
+  // Parameters:
+  //  - Thing
+  TestList(ctx context.Context, thing []int32) (r []int32, err error)
+  // Parameters:
+  //  - Hello
+  TestMapMap(ctx context.Context, hello int32) (r map[int32]map[int32]int32, err error)
+  // Parameters:
+  //  - Arg
+  TestException(ctx context.Context, arg string) (err error)
+  // Parameters:
+  //  - SecondsToSleep
+  TestOneway(ctx context.Context, secondsToSleep int32) (err error)
+}
+
+type ThriftTestClient struct {
+  c thrift.TClient
+}
+
+func NewThriftTestClientFactory(t thrift.TTransport, f thrift.TProtocolFactory) *ThriftTestClient {
+  return &ThriftTestClient{
+    c: thrift.NewTStandardClient(f.GetProtocol(t), f.GetProtocol(t)),
+  }
+}
+
+func NewThriftTestClientProtocol(t thrift.TTransport, iprot thrift.TProtocol, oprot thrift.TProtocol) *ThriftTestClient {
+  return &ThriftTestClient{
+    c: thrift.NewTStandardClient(iprot, oprot),
+  }
+}
+
+func NewThriftTestClient(c thrift.TClient) *ThriftTestClient {
+  return &ThriftTestClient{
+    c: c,
+  }
+}
+
+func (p *ThriftTestClient) Client_() thrift.TClient {
+  return p.c
+}
+func (p *ThriftTestClient) TestVoid(ctx context.Context) (err error) {
+  var _args0 ThriftTestTestVoidArgs
+  var _result1 ThriftTestTestVoidResult
+  if err = p.Client_().Call(ctx, "testVoid", &_args0, &_result1); err != nil {
+    return
+  }
+  return nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestString(ctx context.Context, thing string) (r string, err error) {
+  var _args2 ThriftTestTestStringArgs
+  _args2.Thing = thing
+  var _result3 ThriftTestTestStringResult
+  if err = p.Client_().Call(ctx, "testString", &_args2, &_result3); err != nil {
+    return
+  }
+  return _result3.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestBool(ctx context.Context, thing bool) (r bool, err error) {
+  var _args4 ThriftTestTestBoolArgs
+  _args4.Thing = thing
+  var _result5 ThriftTestTestBoolResult
+  if err = p.Client_().Call(ctx, "testBool", &_args4, &_result5); err != nil {
+    return
+  }
+  return _result5.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestByte(ctx context.Context, thing int8) (r int8, err error) {
+  var _args6 ThriftTestTestByteArgs
+  _args6.Thing = thing
+  var _result7 ThriftTestTestByteResult
+  if err = p.Client_().Call(ctx, "testByte", &_args6, &_result7); err != nil {
+    return
+  }
+  return _result7.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestI32(ctx context.Context, thing int32) (r int32, err error) {
+  var _args8 ThriftTestTestI32Args
+  _args8.Thing = thing
+  var _result9 ThriftTestTestI32Result
+  if err = p.Client_().Call(ctx, "testI32", &_args8, &_result9); err != nil {
+    return
+  }
+  return _result9.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestI64(ctx context.Context, thing int64) (r int64, err error) {
+  var _args10 ThriftTestTestI64Args
+  _args10.Thing = thing
+  var _result11 ThriftTestTestI64Result
+  if err = p.Client_().Call(ctx, "testI64", &_args10, &_result11); err != nil {
+    return
+  }
+  return _result11.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestDouble(ctx context.Context, thing float64) (r float64, err error) {
+  var _args12 ThriftTestTestDoubleArgs
+  _args12.Thing = thing
+  var _result13 ThriftTestTestDoubleResult
+  if err = p.Client_().Call(ctx, "testDouble", &_args12, &_result13); err != nil {
+    return
+  }
+  return _result13.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestBinary(ctx context.Context, thing []byte) (r []byte, err error) {
+  var _args14 ThriftTestTestBinaryArgs
+  _args14.Thing = thing
+  var _result15 ThriftTestTestBinaryResult
+  if err = p.Client_().Call(ctx, "testBinary", &_args14, &_result15); err != nil {
+    return
+  }
+  return _result15.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestStruct(ctx context.Context, thing *Xtruct) (r *Xtruct, err error) {
+  var _args16 ThriftTestTestStructArgs
+  _args16.Thing = thing
+  var _result17 ThriftTestTestStructResult
+  if err = p.Client_().Call(ctx, "testStruct", &_args16, &_result17); err != nil {
+    return
+  }
+  return _result17.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestNest(ctx context.Context, thing *Xtruct2) (r *Xtruct2, err error) {
+  var _args18 ThriftTestTestNestArgs
+  _args18.Thing = thing
+  var _result19 ThriftTestTestNestResult
+  if err = p.Client_().Call(ctx, "testNest", &_args18, &_result19); err != nil {
+    return
+  }
+  return _result19.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestMap(ctx context.Context, thing map[int32]int32) (r map[int32]int32, err error) {
+  var _args20 ThriftTestTestMapArgs
+  _args20.Thing = thing
+  var _result21 ThriftTestTestMapResult
+  if err = p.Client_().Call(ctx, "testMap", &_args20, &_result21); err != nil {
+    return
+  }
+  return _result21.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestStringMap(ctx context.Context, thing map[string]string) (r map[string]string, err error) {
+  var _args22 ThriftTestTestStringMapArgs
+  _args22.Thing = thing
+  var _result23 ThriftTestTestStringMapResult
+  if err = p.Client_().Call(ctx, "testStringMap", &_args22, &_result23); err != nil {
+    return
+  }
+  return _result23.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestSet(ctx context.Context, thing []int32) (r []int32, err error) {
+  var _args24 ThriftTestTestSetArgs
+  _args24.Thing = thing
+  var _result25 ThriftTestTestSetResult
+  if err = p.Client_().Call(ctx, "testSet", &_args24, &_result25); err != nil {
+    return
+  }
+  return _result25.GetSuccess(), nil
+}
+// Parameters:
+//  - Thing
+func (p *ThriftTestClient) TestList(ctx context.Context, thing []int32) (r []int32, err error) {
+  var _args26 ThriftTestTestListArgs
+  _args26.Thing = thing
+  var _result27 ThriftTestTestListResult
+  if err = p.Client_().Call(ctx, "testList", &_args26, &_result27); err != nil {
+    return
+  }
+  return _result27.GetSuccess(), nil
+}
+// Parameters:
+//  - Hello
+func (p *ThriftTestClient) TestMapMap(ctx context.Context, hello int32) (r map[int32]map[int32]int32, err error) {
+  var _args28 ThriftTestTestMapMapArgs
+  _args28.Hello = hello
+  var _result29 ThriftTestTestMapMapResult
+  if err = p.Client_().Call(ctx, "testMapMap", &_args28, &_result29); err != nil {
+    return
+  }
+  return _result29.GetSuccess(), nil
+}
+// Parameters:
+//  - Arg
+func (p *ThriftTestClient) TestException(ctx context.Context, arg string) (err error) {
+  var _args30 ThriftTestTestExceptionArgs
+  _args30.Arg = arg
+  var _result31 ThriftTestTestExceptionResult
+  if err = p.Client_().Call(ctx, "testException", &_args30, &_result31); err != nil {
+    return
+  }
+  switch {
+  case _result31.Err1!= nil:
+    return _result31.Err1
+  }
+
+  return nil
+}
+// Parameters:
+//  - SecondsToSleep
+func (p *ThriftTestClient) TestOneway(ctx context.Context, secondsToSleep int32) (err error) {
+  var _args32 ThriftTestTestOnewayArgs
+  _args32.SecondsToSleep = secondsToSleep
+  if err := p.Client_().Call(ctx, "testOneway", &_args32, nil); err != nil {
+    return err
+  }
+  return nil
+}
+
+type ThriftTestProcessor struct {
+  processorMap map[string]thrift.TProcessorFunction
+  handler ThriftTest
+}
+
+func (p *ThriftTestProcessor) AddToProcessorMap(key string, processor thrift.TProcessorFunction) {
+  p.processorMap[key] = processor
+}
+
+func (p *ThriftTestProcessor) GetProcessorFunction(key string) (processor thrift.TProcessorFunction, ok bool) {
+  processor, ok = p.processorMap[key]
+  return processor, ok
+}
+
+func (p *ThriftTestProcessor) ProcessorMap() map[string]thrift.TProcessorFunction {
+  return p.processorMap
+}
+
+func NewThriftTestProcessor(handler ThriftTest) *ThriftTestProcessor {
+
+  self33 := &ThriftTestProcessor{handler:handler, processorMap:make(map[string]thrift.TProcessorFunction)}
+  self33.processorMap["testVoid"] = &thriftTestProcessorTestVoid{handler:handler}
+  self33.processorMap["testString"] = &thriftTestProcessorTestString{handler:handler}
+  self33.processorMap["testBool"] = &thriftTestProcessorTestBool{handler:handler}
+  self33.processorMap["testByte"] = &thriftTestProcessorTestByte{handler:handler}
+  self33.processorMap["testI32"] = &thriftTestProcessorTestI32{handler:handler}
+  self33.processorMap["testI64"] = &thriftTestProcessorTestI64{handler:handler}
+  self33.processorMap["testDouble"] = &thriftTestProcessorTestDouble{handler:handler}
+  self33.processorMap["testBinary"] = &thriftTestProcessorTestBinary{handler:handler}
+  self33.processorMap["testStruct"] = &thriftTestProcessorTestStruct{handler:handler}
+  self33.processorMap["testNest"] = &thriftTestProcessorTestNest{handler:handler}
+  self33.processorMap["testMap"] = &thriftTestProcessorTestMap{handler:handler}
+  self33.processorMap["testStringMap"] = &thriftTestProcessorTestStringMap{handler:handler}
+  self33.processorMap["testSet"] = &thriftTestProcessorTestSet{handler:handler}
+  self33.processorMap["testList"] = &thriftTestProcessorTestList{handler:handler}
+  self33.processorMap["testMapMap"] = &thriftTestProcessorTestMapMap{handler:handler}
+  self33.processorMap["testException"] = &thriftTestProcessorTestException{handler:handler}
+  self33.processorMap["testOneway"] = &thriftTestProcessorTestOneway{handler:handler}
+return self33
+}
+
+func (p *ThriftTestProcessor) Process(ctx context.Context, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  name, _, seqId, err := iprot.ReadMessageBegin()
+  if err != nil { return false, err }
+  if processor, ok := p.GetProcessorFunction(name); ok {
+    return processor.Process(ctx, seqId, iprot, oprot)
+  }
+  iprot.Skip(thrift.STRUCT)
+  iprot.ReadMessageEnd()
+  x34 := thrift.NewTApplicationException(thrift.UNKNOWN_METHOD, "Unknown function " + name)
+  oprot.WriteMessageBegin(name, thrift.EXCEPTION, seqId)
+  x34.Write(oprot)
+  oprot.WriteMessageEnd()
+  oprot.Flush(ctx)
+  return false, x34
+
+}
+
+type thriftTestProcessorTestVoid struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestVoid) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestVoidArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testVoid", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestVoidResult{}
+  var err2 error
+  if err2 = p.handler.TestVoid(ctx); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testVoid: " + err2.Error())
+    oprot.WriteMessageBegin("testVoid", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  }
+  if err2 = oprot.WriteMessageBegin("testVoid", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestString struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestString) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestStringArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testString", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestStringResult{}
+var retval string
+  var err2 error
+  if retval, err2 = p.handler.TestString(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testString: " + err2.Error())
+    oprot.WriteMessageBegin("testString", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = &retval
+}
+  if err2 = oprot.WriteMessageBegin("testString", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestBool struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestBool) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestBoolArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testBool", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestBoolResult{}
+var retval bool
+  var err2 error
+  if retval, err2 = p.handler.TestBool(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testBool: " + err2.Error())
+    oprot.WriteMessageBegin("testBool", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = &retval
+}
+  if err2 = oprot.WriteMessageBegin("testBool", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestByte struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestByte) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestByteArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testByte", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestByteResult{}
+var retval int8
+  var err2 error
+  if retval, err2 = p.handler.TestByte(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testByte: " + err2.Error())
+    oprot.WriteMessageBegin("testByte", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = &retval
+}
+  if err2 = oprot.WriteMessageBegin("testByte", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestI32 struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestI32) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestI32Args{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testI32", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestI32Result{}
+var retval int32
+  var err2 error
+  if retval, err2 = p.handler.TestI32(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testI32: " + err2.Error())
+    oprot.WriteMessageBegin("testI32", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = &retval
+}
+  if err2 = oprot.WriteMessageBegin("testI32", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestI64 struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestI64) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestI64Args{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testI64", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestI64Result{}
+var retval int64
+  var err2 error
+  if retval, err2 = p.handler.TestI64(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testI64: " + err2.Error())
+    oprot.WriteMessageBegin("testI64", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = &retval
+}
+  if err2 = oprot.WriteMessageBegin("testI64", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestDouble struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestDouble) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestDoubleArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testDouble", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestDoubleResult{}
+var retval float64
+  var err2 error
+  if retval, err2 = p.handler.TestDouble(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testDouble: " + err2.Error())
+    oprot.WriteMessageBegin("testDouble", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = &retval
+}
+  if err2 = oprot.WriteMessageBegin("testDouble", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestBinary struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestBinary) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestBinaryArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testBinary", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestBinaryResult{}
+var retval []byte
+  var err2 error
+  if retval, err2 = p.handler.TestBinary(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testBinary: " + err2.Error())
+    oprot.WriteMessageBegin("testBinary", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testBinary", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestStruct struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestStruct) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestStructArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testStruct", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestStructResult{}
+var retval *Xtruct
+  var err2 error
+  if retval, err2 = p.handler.TestStruct(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testStruct: " + err2.Error())
+    oprot.WriteMessageBegin("testStruct", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testStruct", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestNest struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestNest) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestNestArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testNest", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestNestResult{}
+var retval *Xtruct2
+  var err2 error
+  if retval, err2 = p.handler.TestNest(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testNest: " + err2.Error())
+    oprot.WriteMessageBegin("testNest", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testNest", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestMap struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestMap) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestMapArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testMap", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestMapResult{}
+var retval map[int32]int32
+  var err2 error
+  if retval, err2 = p.handler.TestMap(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testMap: " + err2.Error())
+    oprot.WriteMessageBegin("testMap", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testMap", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestStringMap struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestStringMap) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestStringMapArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testStringMap", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestStringMapResult{}
+var retval map[string]string
+  var err2 error
+  if retval, err2 = p.handler.TestStringMap(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testStringMap: " + err2.Error())
+    oprot.WriteMessageBegin("testStringMap", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testStringMap", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestSet struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestSet) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestSetArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testSet", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestSetResult{}
+var retval []int32
+  var err2 error
+  if retval, err2 = p.handler.TestSet(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testSet: " + err2.Error())
+    oprot.WriteMessageBegin("testSet", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testSet", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestList struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestList) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestListArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testList", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestListResult{}
+var retval []int32
+  var err2 error
+  if retval, err2 = p.handler.TestList(ctx, args.Thing); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testList: " + err2.Error())
+    oprot.WriteMessageBegin("testList", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testList", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestMapMap struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestMapMap) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestMapMapArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testMapMap", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestMapMapResult{}
+var retval map[int32]map[int32]int32
+  var err2 error
+  if retval, err2 = p.handler.TestMapMap(ctx, args.Hello); err2 != nil {
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testMapMap: " + err2.Error())
+    oprot.WriteMessageBegin("testMapMap", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  } else {
+    result.Success = retval
+}
+  if err2 = oprot.WriteMessageBegin("testMapMap", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestException struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestException) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestExceptionArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    x := thrift.NewTApplicationException(thrift.PROTOCOL_ERROR, err.Error())
+    oprot.WriteMessageBegin("testException", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  result := ThriftTestTestExceptionResult{}
+  var err2 error
+  if err2 = p.handler.TestException(ctx, args.Arg); err2 != nil {
+  switch v := err2.(type) {
+    case *Xception:
+  result.Err1 = v
+    default:
+    x := thrift.NewTApplicationException(thrift.INTERNAL_ERROR, "Internal error processing testException: " + err2.Error())
+    oprot.WriteMessageBegin("testException", thrift.EXCEPTION, seqId)
+    x.Write(oprot)
+    oprot.WriteMessageEnd()
+    oprot.Flush(ctx)
+    return true, err2
+  }
+  }
+  if err2 = oprot.WriteMessageBegin("testException", thrift.REPLY, seqId); err2 != nil {
+    err = err2
+  }
+  if err2 = result.Write(oprot); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.WriteMessageEnd(); err == nil && err2 != nil {
+    err = err2
+  }
+  if err2 = oprot.Flush(ctx); err == nil && err2 != nil {
+    err = err2
+  }
+  if err != nil {
+    return
+  }
+  return true, err
+}
+
+type thriftTestProcessorTestOneway struct {
+  handler ThriftTest
+}
+
+func (p *thriftTestProcessorTestOneway) Process(ctx context.Context, seqId int32, iprot, oprot thrift.TProtocol) (success bool, err thrift.TException) {
+  args := ThriftTestTestOnewayArgs{}
+  if err = args.Read(iprot); err != nil {
+    iprot.ReadMessageEnd()
+    return false, err
+  }
+
+  iprot.ReadMessageEnd()
+  var err2 error
+  if err2 = p.handler.TestOneway(ctx, args.SecondsToSleep); err2 != nil {
+    return true, err2
+  }
+  return true, nil
+}
+
+
+// HELPER FUNCTIONS AND STRUCTURES
+
+type ThriftTestTestVoidArgs struct {
+}
+
+func NewThriftTestTestVoidArgs() *ThriftTestTestVoidArgs {
+  return &ThriftTestTestVoidArgs{}
+}
+
+
+func (p *ThriftTestTestVoidArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    if err := iprot.Skip(fieldTypeId); err != nil {
+      return err
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestVoidArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testVoid_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestVoidArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestVoidArgs(%+v)", *p)
+}
+
+type ThriftTestTestVoidResult struct {
+}
+
+func NewThriftTestTestVoidResult() *ThriftTestTestVoidResult {
+  return &ThriftTestTestVoidResult{}
+}
+
+
+func (p *ThriftTestTestVoidResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    if err := iprot.Skip(fieldTypeId); err != nil {
+      return err
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestVoidResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testVoid_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestVoidResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestVoidResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestStringArgs struct {
+  Thing string `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestStringArgs() *ThriftTestTestStringArgs {
+  return &ThriftTestTestStringArgs{}
+}
+
+
+func (p *ThriftTestTestStringArgs) GetThing() string {
+  return p.Thing
+}
+
+func (p *ThriftTestTestStringArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStringArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadString(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Thing = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestStringArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testString_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestStringArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.STRING, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteString(string(p.Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestStringArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestStringArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestStringResult struct {
+  Success *string `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestStringResult() *ThriftTestTestStringResult {
+  return &ThriftTestTestStringResult{}
+}
+
+var ThriftTestTestStringResult_Success_DEFAULT string
+func (p *ThriftTestTestStringResult) GetSuccess() string {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestStringResult_Success_DEFAULT
+  }
+return *p.Success
+}
+func (p *ThriftTestTestStringResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestStringResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStringResult)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadString(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  p.Success = &v
+}
+  return nil
+}
+
+func (p *ThriftTestTestStringResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testString_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestStringResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.STRING, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteString(string(*p.Success)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestStringResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestStringResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestBoolArgs struct {
+  Thing bool `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestBoolArgs() *ThriftTestTestBoolArgs {
+  return &ThriftTestTestBoolArgs{}
+}
+
+
+func (p *ThriftTestTestBoolArgs) GetThing() bool {
+  return p.Thing
+}
+
+func (p *ThriftTestTestBoolArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.BOOL {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestBoolArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadBool(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Thing = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestBoolArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testBool_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestBoolArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.BOOL, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteBool(bool(p.Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestBoolArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestBoolArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestBoolResult struct {
+  Success *bool `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestBoolResult() *ThriftTestTestBoolResult {
+  return &ThriftTestTestBoolResult{}
+}
+
+var ThriftTestTestBoolResult_Success_DEFAULT bool
+func (p *ThriftTestTestBoolResult) GetSuccess() bool {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestBoolResult_Success_DEFAULT
+  }
+return *p.Success
+}
+func (p *ThriftTestTestBoolResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestBoolResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.BOOL {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestBoolResult)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadBool(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  p.Success = &v
+}
+  return nil
+}
+
+func (p *ThriftTestTestBoolResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testBool_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestBoolResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.BOOL, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteBool(bool(*p.Success)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestBoolResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestBoolResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestByteArgs struct {
+  Thing int8 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestByteArgs() *ThriftTestTestByteArgs {
+  return &ThriftTestTestByteArgs{}
+}
+
+
+func (p *ThriftTestTestByteArgs) GetThing() int8 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestByteArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.BYTE {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestByteArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadByte(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  temp := int8(v)
+  p.Thing = temp
+}
+  return nil
+}
+
+func (p *ThriftTestTestByteArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testByte_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestByteArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.BYTE, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteByte(int8(p.Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestByteArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestByteArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestByteResult struct {
+  Success *int8 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestByteResult() *ThriftTestTestByteResult {
+  return &ThriftTestTestByteResult{}
+}
+
+var ThriftTestTestByteResult_Success_DEFAULT int8
+func (p *ThriftTestTestByteResult) GetSuccess() int8 {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestByteResult_Success_DEFAULT
+  }
+return *p.Success
+}
+func (p *ThriftTestTestByteResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestByteResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.BYTE {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestByteResult)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadByte(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  temp := int8(v)
+  p.Success = &temp
+}
+  return nil
+}
+
+func (p *ThriftTestTestByteResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testByte_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestByteResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.BYTE, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteByte(int8(*p.Success)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestByteResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestByteResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestI32Args struct {
+  Thing int32 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestI32Args() *ThriftTestTestI32Args {
+  return &ThriftTestTestI32Args{}
+}
+
+
+func (p *ThriftTestTestI32Args) GetThing() int32 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestI32Args) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestI32Args)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Thing = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestI32Args) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testI32_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestI32Args) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.I32, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteI32(int32(p.Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestI32Args) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestI32Args(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestI32Result struct {
+  Success *int32 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestI32Result() *ThriftTestTestI32Result {
+  return &ThriftTestTestI32Result{}
+}
+
+var ThriftTestTestI32Result_Success_DEFAULT int32
+func (p *ThriftTestTestI32Result) GetSuccess() int32 {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestI32Result_Success_DEFAULT
+  }
+return *p.Success
+}
+func (p *ThriftTestTestI32Result) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestI32Result) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestI32Result)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  p.Success = &v
+}
+  return nil
+}
+
+func (p *ThriftTestTestI32Result) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testI32_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestI32Result) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.I32, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteI32(int32(*p.Success)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestI32Result) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestI32Result(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestI64Args struct {
+  Thing int64 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestI64Args() *ThriftTestTestI64Args {
+  return &ThriftTestTestI64Args{}
+}
+
+
+func (p *ThriftTestTestI64Args) GetThing() int64 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestI64Args) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.I64 {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestI64Args)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI64(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Thing = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestI64Args) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testI64_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestI64Args) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.I64, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteI64(int64(p.Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestI64Args) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestI64Args(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestI64Result struct {
+  Success *int64 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestI64Result() *ThriftTestTestI64Result {
+  return &ThriftTestTestI64Result{}
+}
+
+var ThriftTestTestI64Result_Success_DEFAULT int64
+func (p *ThriftTestTestI64Result) GetSuccess() int64 {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestI64Result_Success_DEFAULT
+  }
+return *p.Success
+}
+func (p *ThriftTestTestI64Result) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestI64Result) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.I64 {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestI64Result)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI64(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  p.Success = &v
+}
+  return nil
+}
+
+func (p *ThriftTestTestI64Result) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testI64_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestI64Result) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.I64, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteI64(int64(*p.Success)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestI64Result) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestI64Result(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestDoubleArgs struct {
+  Thing float64 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestDoubleArgs() *ThriftTestTestDoubleArgs {
+  return &ThriftTestTestDoubleArgs{}
+}
+
+
+func (p *ThriftTestTestDoubleArgs) GetThing() float64 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestDoubleArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.DOUBLE {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestDoubleArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadDouble(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Thing = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestDoubleArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testDouble_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestDoubleArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.DOUBLE, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteDouble(float64(p.Thing)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestDoubleArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestDoubleArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestDoubleResult struct {
+  Success *float64 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestDoubleResult() *ThriftTestTestDoubleResult {
+  return &ThriftTestTestDoubleResult{}
+}
+
+var ThriftTestTestDoubleResult_Success_DEFAULT float64
+func (p *ThriftTestTestDoubleResult) GetSuccess() float64 {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestDoubleResult_Success_DEFAULT
+  }
+return *p.Success
+}
+func (p *ThriftTestTestDoubleResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestDoubleResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.DOUBLE {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestDoubleResult)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadDouble(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  p.Success = &v
+}
+  return nil
+}
+
+func (p *ThriftTestTestDoubleResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testDouble_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestDoubleResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.DOUBLE, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteDouble(float64(*p.Success)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestDoubleResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestDoubleResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestBinaryArgs struct {
+  Thing []byte `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestBinaryArgs() *ThriftTestTestBinaryArgs {
+  return &ThriftTestTestBinaryArgs{}
+}
+
+
+func (p *ThriftTestTestBinaryArgs) GetThing() []byte {
+  return p.Thing
+}
+
+func (p *ThriftTestTestBinaryArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestBinaryArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadBinary(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Thing = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestBinaryArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testBinary_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestBinaryArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.STRING, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteBinary(p.Thing); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.thing (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestBinaryArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestBinaryArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestBinaryResult struct {
+  Success []byte `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestBinaryResult() *ThriftTestTestBinaryResult {
+  return &ThriftTestTestBinaryResult{}
+}
+
+var ThriftTestTestBinaryResult_Success_DEFAULT []byte
+
+func (p *ThriftTestTestBinaryResult) GetSuccess() []byte {
+  return p.Success
+}
+func (p *ThriftTestTestBinaryResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestBinaryResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestBinaryResult)  ReadField0(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadBinary(); err != nil {
+  return thrift.PrependError("error reading field 0: ", err)
+} else {
+  p.Success = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestBinaryResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testBinary_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestBinaryResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.STRING, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteBinary(p.Success); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T.success (0) field write error: ", p), err) }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestBinaryResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestBinaryResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestStructArgs struct {
+  Thing *Xtruct `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestStructArgs() *ThriftTestTestStructArgs {
+  return &ThriftTestTestStructArgs{}
+}
+
+var ThriftTestTestStructArgs_Thing_DEFAULT *Xtruct
+func (p *ThriftTestTestStructArgs) GetThing() *Xtruct {
+  if !p.IsSetThing() {
+    return ThriftTestTestStructArgs_Thing_DEFAULT
+  }
+return p.Thing
+}
+func (p *ThriftTestTestStructArgs) IsSetThing() bool {
+  return p.Thing != nil
+}
+
+func (p *ThriftTestTestStructArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRUCT {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStructArgs)  ReadField1(iprot thrift.TProtocol) error {
+  p.Thing = &Xtruct{}
+  if err := p.Thing.Read(iprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error reading struct: ", p.Thing), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStructArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testStruct_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestStructArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.STRUCT, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := p.Thing.Write(oprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error writing struct: ", p.Thing), err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestStructArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestStructArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestStructResult struct {
+  Success *Xtruct `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestStructResult() *ThriftTestTestStructResult {
+  return &ThriftTestTestStructResult{}
+}
+
+var ThriftTestTestStructResult_Success_DEFAULT *Xtruct
+func (p *ThriftTestTestStructResult) GetSuccess() *Xtruct {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestStructResult_Success_DEFAULT
+  }
+return p.Success
+}
+func (p *ThriftTestTestStructResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestStructResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.STRUCT {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStructResult)  ReadField0(iprot thrift.TProtocol) error {
+  p.Success = &Xtruct{}
+  if err := p.Success.Read(iprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error reading struct: ", p.Success), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStructResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testStruct_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestStructResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.STRUCT, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := p.Success.Write(oprot); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T error writing struct: ", p.Success), err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestStructResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestStructResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestNestArgs struct {
+  Thing *Xtruct2 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestNestArgs() *ThriftTestTestNestArgs {
+  return &ThriftTestTestNestArgs{}
+}
+
+var ThriftTestTestNestArgs_Thing_DEFAULT *Xtruct2
+func (p *ThriftTestTestNestArgs) GetThing() *Xtruct2 {
+  if !p.IsSetThing() {
+    return ThriftTestTestNestArgs_Thing_DEFAULT
+  }
+return p.Thing
+}
+func (p *ThriftTestTestNestArgs) IsSetThing() bool {
+  return p.Thing != nil
+}
+
+func (p *ThriftTestTestNestArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRUCT {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestNestArgs)  ReadField1(iprot thrift.TProtocol) error {
+  p.Thing = &Xtruct2{}
+  if err := p.Thing.Read(iprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error reading struct: ", p.Thing), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestNestArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testNest_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestNestArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.STRUCT, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := p.Thing.Write(oprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error writing struct: ", p.Thing), err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestNestArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestNestArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestNestResult struct {
+  Success *Xtruct2 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestNestResult() *ThriftTestTestNestResult {
+  return &ThriftTestTestNestResult{}
+}
+
+var ThriftTestTestNestResult_Success_DEFAULT *Xtruct2
+func (p *ThriftTestTestNestResult) GetSuccess() *Xtruct2 {
+  if !p.IsSetSuccess() {
+    return ThriftTestTestNestResult_Success_DEFAULT
+  }
+return p.Success
+}
+func (p *ThriftTestTestNestResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestNestResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.STRUCT {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestNestResult)  ReadField0(iprot thrift.TProtocol) error {
+  p.Success = &Xtruct2{}
+  if err := p.Success.Read(iprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error reading struct: ", p.Success), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestNestResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testNest_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestNestResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.STRUCT, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := p.Success.Write(oprot); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T error writing struct: ", p.Success), err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestNestResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestNestResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestMapArgs struct {
+  Thing map[int32]int32 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestMapArgs() *ThriftTestTestMapArgs {
+  return &ThriftTestTestMapArgs{}
+}
+
+
+func (p *ThriftTestTestMapArgs) GetThing() map[int32]int32 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestMapArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.MAP {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapArgs)  ReadField1(iprot thrift.TProtocol) error {
+  _, _, size, err := iprot.ReadMapBegin()
+  if err != nil {
+    return thrift.PrependError("error reading map begin: ", err)
+  }
+  tMap := make(map[int32]int32, size)
+  p.Thing =  tMap
+  for i := 0; i < size; i ++ {
+var _key35 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _key35 = v
+}
+var _val36 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _val36 = v
+}
+    p.Thing[_key35] = _val36
+  }
+  if err := iprot.ReadMapEnd(); err != nil {
+    return thrift.PrependError("error reading map end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testMap_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestMapArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.MAP, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteMapBegin(thrift.I32, thrift.I32, len(p.Thing)); err != nil {
+    return thrift.PrependError("error writing map begin: ", err)
+  }
+  for k, v := range p.Thing {
+    if err := oprot.WriteI32(int32(k)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+    if err := oprot.WriteI32(int32(v)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+  }
+  if err := oprot.WriteMapEnd(); err != nil {
+    return thrift.PrependError("error writing map end: ", err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestMapArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestMapArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestMapResult struct {
+  Success map[int32]int32 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestMapResult() *ThriftTestTestMapResult {
+  return &ThriftTestTestMapResult{}
+}
+
+var ThriftTestTestMapResult_Success_DEFAULT map[int32]int32
+
+func (p *ThriftTestTestMapResult) GetSuccess() map[int32]int32 {
+  return p.Success
+}
+func (p *ThriftTestTestMapResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestMapResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.MAP {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapResult)  ReadField0(iprot thrift.TProtocol) error {
+  _, _, size, err := iprot.ReadMapBegin()
+  if err != nil {
+    return thrift.PrependError("error reading map begin: ", err)
+  }
+  tMap := make(map[int32]int32, size)
+  p.Success =  tMap
+  for i := 0; i < size; i ++ {
+var _key37 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _key37 = v
+}
+var _val38 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _val38 = v
+}
+    p.Success[_key37] = _val38
+  }
+  if err := iprot.ReadMapEnd(); err != nil {
+    return thrift.PrependError("error reading map end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testMap_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestMapResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.MAP, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteMapBegin(thrift.I32, thrift.I32, len(p.Success)); err != nil {
+      return thrift.PrependError("error writing map begin: ", err)
+    }
+    for k, v := range p.Success {
+      if err := oprot.WriteI32(int32(k)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+      if err := oprot.WriteI32(int32(v)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+    }
+    if err := oprot.WriteMapEnd(); err != nil {
+      return thrift.PrependError("error writing map end: ", err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestMapResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestMapResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestStringMapArgs struct {
+  Thing map[string]string `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestStringMapArgs() *ThriftTestTestStringMapArgs {
+  return &ThriftTestTestStringMapArgs{}
+}
+
+
+func (p *ThriftTestTestStringMapArgs) GetThing() map[string]string {
+  return p.Thing
+}
+
+func (p *ThriftTestTestStringMapArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.MAP {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStringMapArgs)  ReadField1(iprot thrift.TProtocol) error {
+  _, _, size, err := iprot.ReadMapBegin()
+  if err != nil {
+    return thrift.PrependError("error reading map begin: ", err)
+  }
+  tMap := make(map[string]string, size)
+  p.Thing =  tMap
+  for i := 0; i < size; i ++ {
+var _key39 string
+    if v, err := iprot.ReadString(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _key39 = v
+}
+var _val40 string
+    if v, err := iprot.ReadString(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _val40 = v
+}
+    p.Thing[_key39] = _val40
+  }
+  if err := iprot.ReadMapEnd(); err != nil {
+    return thrift.PrependError("error reading map end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStringMapArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testStringMap_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestStringMapArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.MAP, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteMapBegin(thrift.STRING, thrift.STRING, len(p.Thing)); err != nil {
+    return thrift.PrependError("error writing map begin: ", err)
+  }
+  for k, v := range p.Thing {
+    if err := oprot.WriteString(string(k)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+    if err := oprot.WriteString(string(v)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+  }
+  if err := oprot.WriteMapEnd(); err != nil {
+    return thrift.PrependError("error writing map end: ", err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestStringMapArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestStringMapArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestStringMapResult struct {
+  Success map[string]string `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestStringMapResult() *ThriftTestTestStringMapResult {
+  return &ThriftTestTestStringMapResult{}
+}
+
+var ThriftTestTestStringMapResult_Success_DEFAULT map[string]string
+
+func (p *ThriftTestTestStringMapResult) GetSuccess() map[string]string {
+  return p.Success
+}
+func (p *ThriftTestTestStringMapResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestStringMapResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.MAP {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStringMapResult)  ReadField0(iprot thrift.TProtocol) error {
+  _, _, size, err := iprot.ReadMapBegin()
+  if err != nil {
+    return thrift.PrependError("error reading map begin: ", err)
+  }
+  tMap := make(map[string]string, size)
+  p.Success =  tMap
+  for i := 0; i < size; i ++ {
+var _key41 string
+    if v, err := iprot.ReadString(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _key41 = v
+}
+var _val42 string
+    if v, err := iprot.ReadString(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _val42 = v
+}
+    p.Success[_key41] = _val42
+  }
+  if err := iprot.ReadMapEnd(); err != nil {
+    return thrift.PrependError("error reading map end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestStringMapResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testStringMap_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestStringMapResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.MAP, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteMapBegin(thrift.STRING, thrift.STRING, len(p.Success)); err != nil {
+      return thrift.PrependError("error writing map begin: ", err)
+    }
+    for k, v := range p.Success {
+      if err := oprot.WriteString(string(k)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+      if err := oprot.WriteString(string(v)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+    }
+    if err := oprot.WriteMapEnd(); err != nil {
+      return thrift.PrependError("error writing map end: ", err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestStringMapResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestStringMapResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestSetArgs struct {
+  Thing []int32 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestSetArgs() *ThriftTestTestSetArgs {
+  return &ThriftTestTestSetArgs{}
+}
+
+
+func (p *ThriftTestTestSetArgs) GetThing() []int32 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestSetArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.SET {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestSetArgs)  ReadField1(iprot thrift.TProtocol) error {
+  _, size, err := iprot.ReadSetBegin()
+  if err != nil {
+    return thrift.PrependError("error reading set begin: ", err)
+  }
+  tSlice := make([]int32, 0, size)
+  p.Thing = tSlice
+  for i := 0; i < size; i ++ {
+var _elem43 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _elem43 = v
+}
+    p.Thing = append(p.Thing, _elem43)
+  }
+  if err := iprot.ReadSetEnd(); err != nil {
+    return thrift.PrependError("error reading set end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestSetArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testSet_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestSetArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.SET, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteSetBegin(thrift.I32, len(p.Thing)); err != nil {
+    return thrift.PrependError("error writing set begin: ", err)
+  }
+  for i := 0; i<len(p.Thing); i++ {
+    for j := i+1; j<len(p.Thing); j++ {
+      if reflect.DeepEqual(p.Thing[i],p.Thing[j]) { 
+        return thrift.PrependError("", fmt.Errorf("%T error writing set field: slice is not unique", p.Thing[i]))
+      }
+    }
+  }
+  for _, v := range p.Thing {
+    if err := oprot.WriteI32(int32(v)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+  }
+  if err := oprot.WriteSetEnd(); err != nil {
+    return thrift.PrependError("error writing set end: ", err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestSetArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestSetArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestSetResult struct {
+  Success []int32 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestSetResult() *ThriftTestTestSetResult {
+  return &ThriftTestTestSetResult{}
+}
+
+var ThriftTestTestSetResult_Success_DEFAULT []int32
+
+func (p *ThriftTestTestSetResult) GetSuccess() []int32 {
+  return p.Success
+}
+func (p *ThriftTestTestSetResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestSetResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.SET {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestSetResult)  ReadField0(iprot thrift.TProtocol) error {
+  _, size, err := iprot.ReadSetBegin()
+  if err != nil {
+    return thrift.PrependError("error reading set begin: ", err)
+  }
+  tSlice := make([]int32, 0, size)
+  p.Success = tSlice
+  for i := 0; i < size; i ++ {
+var _elem44 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _elem44 = v
+}
+    p.Success = append(p.Success, _elem44)
+  }
+  if err := iprot.ReadSetEnd(); err != nil {
+    return thrift.PrependError("error reading set end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestSetResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testSet_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestSetResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.SET, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteSetBegin(thrift.I32, len(p.Success)); err != nil {
+      return thrift.PrependError("error writing set begin: ", err)
+    }
+    for i := 0; i<len(p.Success); i++ {
+      for j := i+1; j<len(p.Success); j++ {
+        if reflect.DeepEqual(p.Success[i],p.Success[j]) { 
+          return thrift.PrependError("", fmt.Errorf("%T error writing set field: slice is not unique", p.Success[i]))
+        }
+      }
+    }
+    for _, v := range p.Success {
+      if err := oprot.WriteI32(int32(v)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+    }
+    if err := oprot.WriteSetEnd(); err != nil {
+      return thrift.PrependError("error writing set end: ", err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestSetResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestSetResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Thing
+type ThriftTestTestListArgs struct {
+  Thing []int32 `thrift:"thing,1" db:"thing" json:"thing"`
+}
+
+func NewThriftTestTestListArgs() *ThriftTestTestListArgs {
+  return &ThriftTestTestListArgs{}
+}
+
+
+func (p *ThriftTestTestListArgs) GetThing() []int32 {
+  return p.Thing
+}
+
+func (p *ThriftTestTestListArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.LIST {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestListArgs)  ReadField1(iprot thrift.TProtocol) error {
+  _, size, err := iprot.ReadListBegin()
+  if err != nil {
+    return thrift.PrependError("error reading list begin: ", err)
+  }
+  tSlice := make([]int32, 0, size)
+  p.Thing = tSlice
+  for i := 0; i < size; i ++ {
+var _elem45 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _elem45 = v
+}
+    p.Thing = append(p.Thing, _elem45)
+  }
+  if err := iprot.ReadListEnd(); err != nil {
+    return thrift.PrependError("error reading list end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestListArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testList_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestListArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("thing", thrift.LIST, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:thing: ", p), err) }
+  if err := oprot.WriteListBegin(thrift.I32, len(p.Thing)); err != nil {
+    return thrift.PrependError("error writing list begin: ", err)
+  }
+  for _, v := range p.Thing {
+    if err := oprot.WriteI32(int32(v)); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+  }
+  if err := oprot.WriteListEnd(); err != nil {
+    return thrift.PrependError("error writing list end: ", err)
+  }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:thing: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestListArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestListArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestListResult struct {
+  Success []int32 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestListResult() *ThriftTestTestListResult {
+  return &ThriftTestTestListResult{}
+}
+
+var ThriftTestTestListResult_Success_DEFAULT []int32
+
+func (p *ThriftTestTestListResult) GetSuccess() []int32 {
+  return p.Success
+}
+func (p *ThriftTestTestListResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestListResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.LIST {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestListResult)  ReadField0(iprot thrift.TProtocol) error {
+  _, size, err := iprot.ReadListBegin()
+  if err != nil {
+    return thrift.PrependError("error reading list begin: ", err)
+  }
+  tSlice := make([]int32, 0, size)
+  p.Success = tSlice
+  for i := 0; i < size; i ++ {
+var _elem46 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _elem46 = v
+}
+    p.Success = append(p.Success, _elem46)
+  }
+  if err := iprot.ReadListEnd(); err != nil {
+    return thrift.PrependError("error reading list end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestListResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testList_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestListResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.LIST, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteListBegin(thrift.I32, len(p.Success)); err != nil {
+      return thrift.PrependError("error writing list begin: ", err)
+    }
+    for _, v := range p.Success {
+      if err := oprot.WriteI32(int32(v)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+    }
+    if err := oprot.WriteListEnd(); err != nil {
+      return thrift.PrependError("error writing list end: ", err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestListResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestListResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Hello
+type ThriftTestTestMapMapArgs struct {
+  Hello int32 `thrift:"hello,1" db:"hello" json:"hello"`
+}
+
+func NewThriftTestTestMapMapArgs() *ThriftTestTestMapMapArgs {
+  return &ThriftTestTestMapMapArgs{}
+}
+
+
+func (p *ThriftTestTestMapMapArgs) GetHello() int32 {
+  return p.Hello
+}
+
+func (p *ThriftTestTestMapMapArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapMapArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Hello = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestMapMapArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testMapMap_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestMapMapArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("hello", thrift.I32, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:hello: ", p), err) }
+  if err := oprot.WriteI32(int32(p.Hello)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.hello (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:hello: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestMapMapArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestMapMapArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Success
+type ThriftTestTestMapMapResult struct {
+  Success map[int32]map[int32]int32 `thrift:"success,0" db:"success" json:"success,omitempty"`
+}
+
+func NewThriftTestTestMapMapResult() *ThriftTestTestMapMapResult {
+  return &ThriftTestTestMapMapResult{}
+}
+
+var ThriftTestTestMapMapResult_Success_DEFAULT map[int32]map[int32]int32
+
+func (p *ThriftTestTestMapMapResult) GetSuccess() map[int32]map[int32]int32 {
+  return p.Success
+}
+func (p *ThriftTestTestMapMapResult) IsSetSuccess() bool {
+  return p.Success != nil
+}
+
+func (p *ThriftTestTestMapMapResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 0:
+      if fieldTypeId == thrift.MAP {
+        if err := p.ReadField0(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapMapResult)  ReadField0(iprot thrift.TProtocol) error {
+  _, _, size, err := iprot.ReadMapBegin()
+  if err != nil {
+    return thrift.PrependError("error reading map begin: ", err)
+  }
+  tMap := make(map[int32]map[int32]int32, size)
+  p.Success =  tMap
+  for i := 0; i < size; i ++ {
+var _key47 int32
+    if v, err := iprot.ReadI32(); err != nil {
+    return thrift.PrependError("error reading field 0: ", err)
+} else {
+    _key47 = v
+}
+var _val48 map[int32]int32
+    {
+      _, _, size, err := iprot.ReadMapBegin()
+      if err != nil {
+        return thrift.PrependError("error reading map begin: ", err)
+      }
+      tMap := make(map[int32]int32, size)
+      _val48 =  tMap
+      for i := 0; i < size; i ++ {
+var _key49 int32
+        if v, err := iprot.ReadI32(); err != nil {
+        return thrift.PrependError("error reading field 0: ", err)
+} else {
+        _key49 = v
+}
+var _val50 int32
+        if v, err := iprot.ReadI32(); err != nil {
+        return thrift.PrependError("error reading field 0: ", err)
+} else {
+        _val50 = v
+}
+        _val48[_key49] = _val50
+      }
+      if err := iprot.ReadMapEnd(); err != nil {
+        return thrift.PrependError("error reading map end: ", err)
+      }
+    }
+    p.Success[_key47] = _val48
+  }
+  if err := iprot.ReadMapEnd(); err != nil {
+    return thrift.PrependError("error reading map end: ", err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestMapMapResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testMapMap_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField0(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestMapMapResult) writeField0(oprot thrift.TProtocol) (err error) {
+  if p.IsSetSuccess() {
+    if err := oprot.WriteFieldBegin("success", thrift.MAP, 0); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 0:success: ", p), err) }
+    if err := oprot.WriteMapBegin(thrift.I32, thrift.MAP, len(p.Success)); err != nil {
+      return thrift.PrependError("error writing map begin: ", err)
+    }
+    for k, v := range p.Success {
+      if err := oprot.WriteI32(int32(k)); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+      if err := oprot.WriteMapBegin(thrift.I32, thrift.I32, len(v)); err != nil {
+        return thrift.PrependError("error writing map begin: ", err)
+      }
+      for k2, v2 := range v {
+        if err := oprot.WriteI32(int32(k2)); err != nil {
+        return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+        if err := oprot.WriteI32(int32(v2)); err != nil {
+        return thrift.PrependError(fmt.Sprintf("%T. (0) field write error: ", p), err) }
+      }
+      if err := oprot.WriteMapEnd(); err != nil {
+        return thrift.PrependError("error writing map end: ", err)
+      }
+    }
+    if err := oprot.WriteMapEnd(); err != nil {
+      return thrift.PrependError("error writing map end: ", err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 0:success: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestMapMapResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestMapMapResult(%+v)", *p)
+}
+
+// Attributes:
+//  - Arg
+type ThriftTestTestExceptionArgs struct {
+  Arg string `thrift:"arg,1" db:"arg" json:"arg"`
+}
+
+func NewThriftTestTestExceptionArgs() *ThriftTestTestExceptionArgs {
+  return &ThriftTestTestExceptionArgs{}
+}
+
+
+func (p *ThriftTestTestExceptionArgs) GetArg() string {
+  return p.Arg
+}
+
+func (p *ThriftTestTestExceptionArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRING {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestExceptionArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadString(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.Arg = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestExceptionArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testException_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestExceptionArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("arg", thrift.STRING, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:arg: ", p), err) }
+  if err := oprot.WriteString(string(p.Arg)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.arg (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:arg: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestExceptionArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestExceptionArgs(%+v)", *p)
+}
+
+// Attributes:
+//  - Err1
+type ThriftTestTestExceptionResult struct {
+  Err1 *Xception `thrift:"err1,1" db:"err1" json:"err1,omitempty"`
+}
+
+func NewThriftTestTestExceptionResult() *ThriftTestTestExceptionResult {
+  return &ThriftTestTestExceptionResult{}
+}
+
+var ThriftTestTestExceptionResult_Err1_DEFAULT *Xception
+func (p *ThriftTestTestExceptionResult) GetErr1() *Xception {
+  if !p.IsSetErr1() {
+    return ThriftTestTestExceptionResult_Err1_DEFAULT
+  }
+return p.Err1
+}
+func (p *ThriftTestTestExceptionResult) IsSetErr1() bool {
+  return p.Err1 != nil
+}
+
+func (p *ThriftTestTestExceptionResult) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.STRUCT {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestExceptionResult)  ReadField1(iprot thrift.TProtocol) error {
+  p.Err1 = &Xception{}
+  if err := p.Err1.Read(iprot); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T error reading struct: ", p.Err1), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestExceptionResult) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testException_result"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestExceptionResult) writeField1(oprot thrift.TProtocol) (err error) {
+  if p.IsSetErr1() {
+    if err := oprot.WriteFieldBegin("err1", thrift.STRUCT, 1); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:err1: ", p), err) }
+    if err := p.Err1.Write(oprot); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T error writing struct: ", p.Err1), err)
+    }
+    if err := oprot.WriteFieldEnd(); err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T write field end error 1:err1: ", p), err) }
+  }
+  return err
+}
+
+func (p *ThriftTestTestExceptionResult) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestExceptionResult(%+v)", *p)
+}
+
+// Attributes:
+//  - SecondsToSleep
+type ThriftTestTestOnewayArgs struct {
+  SecondsToSleep int32 `thrift:"secondsToSleep,1" db:"secondsToSleep" json:"secondsToSleep"`
+}
+
+func NewThriftTestTestOnewayArgs() *ThriftTestTestOnewayArgs {
+  return &ThriftTestTestOnewayArgs{}
+}
+
+
+func (p *ThriftTestTestOnewayArgs) GetSecondsToSleep() int32 {
+  return p.SecondsToSleep
+}
+
+func (p *ThriftTestTestOnewayArgs) Read(iprot thrift.TProtocol) error {
+  if _, err := iprot.ReadStructBegin(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read error: ", p), err)
+  }
+
+
+  for {
+    _, fieldTypeId, fieldId, err := iprot.ReadFieldBegin()
+    if err != nil {
+      return thrift.PrependError(fmt.Sprintf("%T field %d read error: ", p, fieldId), err)
+    }
+    if fieldTypeId == thrift.STOP { break; }
+    switch fieldId {
+    case 1:
+      if fieldTypeId == thrift.I32 {
+        if err := p.ReadField1(iprot); err != nil {
+          return err
+        }
+      } else {
+        if err := iprot.Skip(fieldTypeId); err != nil {
+          return err
+        }
+      }
+    default:
+      if err := iprot.Skip(fieldTypeId); err != nil {
+        return err
+      }
+    }
+    if err := iprot.ReadFieldEnd(); err != nil {
+      return err
+    }
+  }
+  if err := iprot.ReadStructEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T read struct end error: ", p), err)
+  }
+  return nil
+}
+
+func (p *ThriftTestTestOnewayArgs)  ReadField1(iprot thrift.TProtocol) error {
+  if v, err := iprot.ReadI32(); err != nil {
+  return thrift.PrependError("error reading field 1: ", err)
+} else {
+  p.SecondsToSleep = v
+}
+  return nil
+}
+
+func (p *ThriftTestTestOnewayArgs) Write(oprot thrift.TProtocol) error {
+  if err := oprot.WriteStructBegin("testOneway_args"); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write struct begin error: ", p), err) }
+  if p != nil {
+    if err := p.writeField1(oprot); err != nil { return err }
+  }
+  if err := oprot.WriteFieldStop(); err != nil {
+    return thrift.PrependError("write field stop error: ", err) }
+  if err := oprot.WriteStructEnd(); err != nil {
+    return thrift.PrependError("write struct stop error: ", err) }
+  return nil
+}
+
+func (p *ThriftTestTestOnewayArgs) writeField1(oprot thrift.TProtocol) (err error) {
+  if err := oprot.WriteFieldBegin("secondsToSleep", thrift.I32, 1); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field begin error 1:secondsToSleep: ", p), err) }
+  if err := oprot.WriteI32(int32(p.SecondsToSleep)); err != nil {
+  return thrift.PrependError(fmt.Sprintf("%T.secondsToSleep (1) field write error: ", p), err) }
+  if err := oprot.WriteFieldEnd(); err != nil {
+    return thrift.PrependError(fmt.Sprintf("%T write field end error 1:secondsToSleep: ", p), err) }
+  return err
+}
+
+func (p *ThriftTestTestOnewayArgs) String() string {
+  if p == nil {
+    return "<nil>"
+  }
+  return fmt.Sprintf("ThriftTestTestOnewayArgs(%+v)", *p)
+}
+
+
@@ -0,0 +1,155 @@
+use std::convert::TryFrom;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use async_std::task;
+use serde_json::Value;
+use structopt::StructOpt;
+
+use async_thrift::protocol::async_value::{read_message, write_message, TValue};
+use async_thrift::protocol::{TMessageIdentifier, TMessageType};
+use async_thrift::ApplicationErrorKind;
+
+use async_thrift_tools::codec::{ProtocolKind, TCodec, TransportKind};
+use async_thrift_tools::idl::{self, TDocument, TFunctionDef};
+use async_thrift_tools::json;
+
+/// Call a method of a Thrift server and print the reply.
+///
+/// Without --idl, ARGS is an object of typed values keyed by field id,
+/// such as '{"1": {"i32": 7}, "2": {"list<string>": ["a"]}}', and the
+/// reply is printed with field ids. With --idl, ARGS is keyed by argument
+/// name and holds plain JSON values, and the reply is printed as JSON.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "thrift-call")]
+struct Opt {
+    /// Address of the server.
+    #[structopt(long, default_value = "127.0.0.1:9090")]
+    address: String,
+
+    /// Transport of the server: buffered or framed.
+    #[structopt(long, default_value = "buffered")]
+    transport: TransportKind,
+
+    /// Protocol of the server: binary or compact.
+    #[structopt(long, default_value = "binary")]
+    protocol: ProtocolKind,
+
+    /// Thrift IDL file defining the method, used for argument and field
+    /// names.
+    #[structopt(long, parse(from_os_str))]
+    idl: Option<PathBuf>,
+
+    /// Service defining the method, if several services of the IDL file
+    /// define it.
+    #[structopt(long)]
+    service: Option<String>,
+
+    /// Send a oneway message and do not wait for a reply. Implied by
+    /// oneway functions of the IDL file.
+    #[structopt(long)]
+    oneway: bool,
+
+    /// Method to call. Multiplexed services are called as `Service:method`.
+    method: String,
+
+    /// Arguments of the call as JSON.
+    #[structopt(default_value = "{}")]
+    args: String,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    if let Err(e) = task::block_on(run(opt)) {
+        eprintln!("thrift-call: {}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(opt: Opt) -> Result<(), String> {
+    let document = match opt.idl {
+        Some(ref path) => {
+            let source = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            Some(idl::parse(&source).map_err(|e| format!("{}: {}", path.display(), e))?)
+        }
+        None => None,
+    };
+    // a multiplexed method name is prefixed with its service
+    let function_name = opt.method.rsplit(':').next().unwrap_or(&opt.method);
+    let function = match document {
+        Some(ref document) => Some(document.function(opt.service.as_deref(), function_name)?),
+        None => None,
+    };
+
+    let args: Value = serde_json::from_str(&opt.args).map_err(|e| format!("arguments are not valid JSON: {}", e))?;
+    let args = match (&document, function) {
+        (Some(document), Some(function)) => json::to_struct(&args, &function.args, document),
+        _ => json::typed_struct(&args),
+    }.map_err(|e| format!("invalid arguments: {}", e))?;
+
+    let oneway = opt.oneway || function.is_some_and(|function| function.oneway);
+    let message_type = if oneway { TMessageType::OneWay } else { TMessageType::Call };
+    let call_ident = TMessageIdentifier::new(opt.method.as_str(), message_type, 1);
+
+    let codec = TCodec::new(opt.transport, opt.protocol);
+    let reply = call(&codec, &opt.address, &call_ident, &args)
+        .await
+        .map_err(|e| format!("call to {} failed: {:?}", opt.address, e))?;
+
+    let (reply_ident, body) = match reply {
+        Some(reply) => reply,
+        None => return Ok(()),
+    };
+    match reply_ident.message_type {
+        TMessageType::Exception => Err(application_exception(&body)),
+        _ => {
+            print_reply(&body, document.as_ref().zip(function));
+            Ok(())
+        }
+    }
+}
+
+async fn call(
+    codec: &TCodec,
+    address: &str,
+    call_ident: &TMessageIdentifier,
+    args: &TValue,
+) -> async_thrift::Result<Option<(TMessageIdentifier, TValue)>> {
+    let (mut i_prot, mut o_prot) = codec.connect(address).await?;
+    write_message(&mut *o_prot, call_ident, args).await?;
+    o_prot.flush().await?;
+    if call_ident.message_type == TMessageType::OneWay {
+        return Ok(None);
+    }
+    read_message(&mut *i_prot).await.map(Some)
+}
+
+fn print_reply(body: &TValue, schema: Option<(&TDocument, &TFunctionDef)>) {
+    match schema {
+        Some((document, function)) => {
+            let reply = json::from_struct(body, &function.result_fields(), document);
+            println!("{}", serde_json::to_string_pretty(&reply).unwrap());
+        }
+        None => println!("{:#}", body),
+    }
+}
+
+// application exceptions carry a message in field 1 and a kind in field 2
+fn application_exception(body: &TValue) -> String {
+    let fields = match body {
+        TValue::Struct(fields) => fields,
+        _ => return format!("server replied with exception {}", body),
+    };
+    let message = match fields.get(&1) {
+        Some(TValue::String(message)) => message.as_str(),
+        _ => "",
+    };
+    match fields.get(&2) {
+        Some(TValue::I32(kind)) => match ApplicationErrorKind::try_from(*kind) {
+            Ok(kind) => format!("server replied with {:?} exception: {}", kind, message),
+            Err(_) => format!("server replied with exception of kind {}: {}", kind, message),
+        },
+        _ => format!("server replied with exception: {}", message),
+    }
+}
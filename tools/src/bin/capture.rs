@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+use std::process;
+
+use async_std::task;
+use structopt::StructOpt;
+
+use async_thrift_tools::capture::{read_capture, TCaptureWriter};
+use async_thrift_tools::codec::{ProtocolKind, TCodec, TransportKind};
+use async_thrift_tools::proxy::TProxy;
+use async_thrift_tools::replay::{capture_duration, TReplayer};
+
+/// Record Thrift traffic to a file and replay it against a server.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "thrift-capture")]
+enum Opt {
+    /// Forward connections to a server and record every call and reply.
+    Record {
+        /// Address to accept connections on.
+        #[structopt(long, default_value = "127.0.0.1:9090")]
+        listen: String,
+
+        /// Address of the server to record.
+        #[structopt(long)]
+        upstream: String,
+
+        /// File to write the capture to.
+        #[structopt(long, parse(from_os_str))]
+        file: PathBuf,
+
+        /// Transport of both sides: buffered or framed.
+        #[structopt(long, default_value = "buffered")]
+        transport: TransportKind,
+
+        /// Protocol of both sides: binary or compact.
+        #[structopt(long, default_value = "binary")]
+        protocol: ProtocolKind,
+    },
+    /// Replay a capture and report replies that differ from the recorded ones.
+    Replay {
+        /// Address of the server to replay against.
+        #[structopt(long)]
+        target: String,
+
+        /// Capture file to replay.
+        #[structopt(long, parse(from_os_str))]
+        file: PathBuf,
+
+        /// Send calls with their captured inter-arrival times instead of
+        /// as fast as possible.
+        #[structopt(long)]
+        timing: bool,
+
+        /// Transport of the server: buffered or framed.
+        #[structopt(long, default_value = "buffered")]
+        transport: TransportKind,
+
+        /// Protocol of the server: binary or compact.
+        #[structopt(long, default_value = "binary")]
+        protocol: ProtocolKind,
+    },
+}
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    match Opt::from_args() {
+        Opt::Record { listen, upstream, file, transport, protocol } => {
+            let capture = match TCaptureWriter::create(&file) {
+                Ok(capture) => capture,
+                Err(e) => {
+                    eprintln!("thrift-capture: cannot create {}: {}", file.display(), e);
+                    process::exit(1);
+                }
+            };
+            let codec = TCodec::new(transport, protocol);
+            let proxy = TProxy::new(codec, codec)
+                .with_default_upstream(upstream.as_str())
+                .with_capture(capture);
+
+            log::info!("recording {} traffic on {} for {} to {}", codec, listen, upstream, file.display());
+            if let Err(e) = task::block_on(proxy.listen(&listen)) {
+                eprintln!("thrift-capture: {:?}", e);
+                process::exit(1);
+            }
+        }
+        Opt::Replay { target, file, timing, transport, protocol } => {
+            let messages = match read_capture(&file) {
+                Ok(messages) => messages,
+                Err(e) => {
+                    eprintln!("thrift-capture: cannot read {}: {}", file.display(), e);
+                    process::exit(1);
+                }
+            };
+            println!(
+                "replaying {} messages captured over {:?} against {}",
+                messages.len(),
+                capture_duration(&messages),
+                target,
+            );
+
+            let replayer = TReplayer::new(TCodec::new(transport, protocol)).with_timing(timing);
+            let report = task::block_on(replayer.replay(messages, &target));
+
+            for mismatch in &report.mismatches {
+                println!(
+                    "connection {}: {} (seqid {}) replied differently",
+                    mismatch.connection, mismatch.call.name, mismatch.call.sequence_number,
+                );
+                println!("  expected {:?} {}", mismatch.expected.0.message_type, mismatch.expected.1);
+                println!("  actual   {:?} {}", mismatch.actual.0.message_type, mismatch.actual.1);
+            }
+            for error in &report.errors {
+                println!("{}", error);
+            }
+            println!(
+                "{} calls on {} connections: {} matched, {} mismatched, {} unchecked, {} errors",
+                report.calls,
+                report.connections,
+                report.matched,
+                report.mismatches.len(),
+                report.unchecked,
+                report.errors.len(),
+            );
+
+            if !report.is_success() {
+                process::exit(1);
+            }
+        }
+    }
+}
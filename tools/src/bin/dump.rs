@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use async_std::task;
+use structopt::StructOpt;
+
+use async_thrift::protocol::async_binary::TAsyncBinaryInputProtocol;
+use async_thrift::protocol::async_debug::TAsyncDebugInputProtocol;
+use async_thrift::protocol::async_value::read_value;
+use async_thrift::protocol::{TAsyncInputProtocol, TType};
+use async_thrift::transport::async_mem::TAsyncBufferChannel;
+use async_thrift::TransportErrorKind;
+
+use async_thrift_tools::capture::{read_capture, TDirection, MAGIC};
+use async_thrift_tools::codec::{ProtocolKind, TCodec, TransportKind};
+
+/// Print the Thrift messages in a file as trees of fields.
+///
+/// The file is either a capture written by thrift-capture or the raw bytes
+/// sent one way over a connection, such as a payload saved from a packet
+/// capture.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "thrift-dump")]
+struct Opt {
+    /// Transport of raw files: buffered or framed. Ignored for captures.
+    #[structopt(long, default_value = "buffered")]
+    transport: TransportKind,
+
+    /// Protocol of raw files: binary or compact. Ignored for captures.
+    #[structopt(long, default_value = "binary")]
+    protocol: ProtocolKind,
+
+    /// File to decode.
+    #[structopt(parse(from_os_str))]
+    file: PathBuf,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    if let Err(e) = task::block_on(run(opt)) {
+        eprintln!("thrift-dump: {}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(opt: Opt) -> Result<(), String> {
+    let bytes = fs::read(&opt.file).map_err(|e| format!("cannot read {}: {}", opt.file.display(), e))?;
+
+    if !bytes.starts_with(MAGIC) {
+        let channel = TAsyncBufferChannel::with_readable_bytes(bytes);
+        let count = dump(TCodec::new(opt.transport, opt.protocol).input(channel)).await?;
+        println!("{} messages", count);
+        return Ok(());
+    }
+
+    let messages = read_capture(&opt.file).map_err(|e| format!("cannot read {}: {}", opt.file.display(), e))?;
+    for message in &messages {
+        let direction = match message.direction {
+            TDirection::ToServer => "client to server",
+            TDirection::ToClient => "server to client",
+        };
+        println!("+{:?} connection {}, {}", message.elapsed, message.connection, direction);
+        let channel = TAsyncBufferChannel::with_readable_bytes(message.message.clone());
+        dump(Box::new(TAsyncBinaryInputProtocol::new(channel, true))).await?;
+    }
+    println!("{} messages", messages.len());
+    Ok(())
+}
+
+/// Print every message read from `i_prot` and return how many there were.
+async fn dump(i_prot: Box<dyn TAsyncInputProtocol + Send>) -> Result<usize, String> {
+    let mut i_prot = TAsyncDebugInputProtocol::with_sink(i_prot, |tree| println!("{}\n", tree));
+
+    let mut count = 0;
+    loop {
+        match i_prot.read_message_begin().await {
+            Ok(_) => {}
+            Err(async_thrift::Error::Transport(ref e)) if e.kind == TransportErrorKind::EndOfFile => return Ok(count),
+            Err(e) => return Err(format!("cannot decode message {}: {:?}", count + 1, e)),
+        }
+        // the tree of a message that cannot be decoded is printed up to the failure
+        read_value(&mut i_prot, TType::Struct)
+            .await
+            .map_err(|e| format!("cannot decode message {}: {:?}", count + 1, e))?;
+        i_prot
+            .read_message_end()
+            .await
+            .map_err(|e| format!("cannot decode message {}: {:?}", count + 1, e))?;
+        count += 1;
+    }
+}
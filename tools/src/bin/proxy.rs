@@ -0,0 +1,72 @@
+use std::process;
+
+use async_std::task;
+use structopt::StructOpt;
+
+use async_thrift_tools::codec::{ProtocolKind, TCodec, TransportKind};
+use async_thrift_tools::proxy::{TProxy, TRoute};
+
+/// Route Thrift calls to upstream servers by method name.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "thrift-proxy")]
+struct Opt {
+    /// Address to accept connections on.
+    #[structopt(long, default_value = "127.0.0.1:9090")]
+    listen: String,
+
+    /// Send calls matching PATTERN to ADDRESS, given as PATTERN=ADDRESS.
+    /// Patterns ending in `*` match method name prefixes, such as
+    /// `Calculator:*`. Routes are tried in order.
+    #[structopt(long = "route", number_of_values = 1)]
+    routes: Vec<TRoute>,
+
+    /// Address to send calls to when no route matches.
+    #[structopt(long)]
+    upstream: Option<String>,
+
+    /// Transport of client connections: buffered or framed.
+    #[structopt(long, default_value = "buffered")]
+    transport: TransportKind,
+
+    /// Protocol of client connections: binary or compact.
+    #[structopt(long, default_value = "binary")]
+    protocol: ProtocolKind,
+
+    /// Transport of upstream connections. Defaults to --transport.
+    #[structopt(long)]
+    upstream_transport: Option<TransportKind>,
+
+    /// Protocol of upstream connections. Defaults to --protocol.
+    #[structopt(long)]
+    upstream_protocol: Option<ProtocolKind>,
+}
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let opt = Opt::from_args();
+
+    if opt.routes.is_empty() && opt.upstream.is_none() {
+        eprintln!("thrift-proxy: at least one --route or an --upstream is required");
+        process::exit(2);
+    }
+
+    let downstream = TCodec::new(opt.transport, opt.protocol);
+    let upstream = TCodec::new(
+        opt.upstream_transport.unwrap_or(opt.transport),
+        opt.upstream_protocol.unwrap_or(opt.protocol),
+    );
+
+    let mut proxy = TProxy::new(downstream, upstream);
+    for route in opt.routes {
+        proxy = proxy.with_route(route);
+    }
+    if let Some(address) = opt.upstream {
+        proxy = proxy.with_default_upstream(address);
+    }
+
+    log::info!("proxying {} on {} to {}", downstream, opt.listen, upstream);
+    if let Err(e) = task::block_on(proxy.listen(&opt.listen)) {
+        eprintln!("thrift-proxy: {:?}", e);
+        process::exit(1);
+    }
+}
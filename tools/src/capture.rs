@@ -0,0 +1,272 @@
+//! Capture files of Thrift traffic.
+//!
+//! A capture file starts with `MAGIC`, followed by one record per message.
+//! All integers are big-endian.
+//!
+//! | field      | bytes    | contents                                              |
+//! |------------|----------|-------------------------------------------------------|
+//! | elapsed    | 8        | microseconds between the start of the capture and the message |
+//! | connection | 8        | id of the client connection the message was sent on  |
+//! | direction  | 1        | 0 for messages to the server, 1 for messages to the client |
+//! | length     | 4        | length of the message                                 |
+//! | message    | `length` | the message in the strict binary protocol, unframed   |
+//!
+//! Messages are stored in the binary protocol whatever the captured
+//! connection used, so captures can be replayed with any transport and
+//! protocol.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+use async_thrift::protocol::async_value::{read_message, write_message, TValue};
+use async_thrift::protocol::TMessageIdentifier;
+use async_thrift::transport::async_mem::TAsyncBufferChannel;
+
+/// First bytes of every capture file.
+pub const MAGIC: &[u8; 5] = b"TCAP\x01";
+
+/// Which way a captured message travelled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TDirection {
+    /// A call or oneway message sent by a client.
+    ToServer,
+    /// A reply or exception sent by a server.
+    ToClient,
+}
+
+/// A message read from a capture file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TCapturedMessage {
+    /// Time between the start of the capture and the message.
+    pub elapsed: Duration,
+    /// Id of the client connection the message was sent on.
+    pub connection: u64,
+    pub direction: TDirection,
+    /// The message in the strict binary protocol.
+    pub message: Vec<u8>,
+}
+
+impl TCapturedMessage {
+    /// Decode the captured message.
+    pub async fn decode(&self) -> async_thrift::Result<(TMessageIdentifier, TValue)> {
+        decode_message(self.message.clone()).await
+    }
+}
+
+/// Encode a message in the strict binary protocol.
+pub async fn encode_message(message_ident: &TMessageIdentifier, body: &TValue) -> async_thrift::Result<Vec<u8>> {
+    let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+    write_message(&mut o_prot, message_ident, body).await?;
+    Ok(o_prot.transport.take_write_bytes())
+}
+
+/// Decode a message encoded in the strict binary protocol.
+pub async fn decode_message(bytes: Vec<u8>) -> async_thrift::Result<(TMessageIdentifier, TValue)> {
+    let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+    read_message(&mut i_prot).await
+}
+
+/// Appends messages to a capture file.
+///
+/// Records are written by a thread of their own, so recording never blocks
+/// the caller on the file. The thread flushes the file whenever it has
+/// written every pending record, so that a capture interrupted by a signal
+/// is complete up to its last quiet moment. Dropping the writer writes
+/// every pending record before returning.
+#[derive(Debug)]
+pub struct TCaptureWriter {
+    started: Instant,
+    records: Mutex<Option<Sender<Vec<u8>>>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl TCaptureWriter {
+    /// Create a capture file at `path`, replacing any existing file.
+    ///
+    /// Message times are measured from the moment the file is created.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<TCaptureWriter> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.flush()?;
+
+        let (records, pending) = mpsc::channel();
+        let writer = thread::Builder::new()
+            .name("thrift-capture".to_owned())
+            .spawn(move || write_records(file, pending))?;
+        Ok(TCaptureWriter {
+            started: Instant::now(),
+            records: Mutex::new(Some(records)),
+            writer: Some(writer),
+        })
+    }
+
+    /// Append `message`, encoded in the strict binary protocol, to the
+    /// capture.
+    ///
+    /// Fails once writing to the file has failed.
+    pub fn record(&self, connection: u64, direction: TDirection, message: &[u8]) -> io::Result<()> {
+        let elapsed = self.started.elapsed().as_micros() as u64;
+        let direction = match direction {
+            TDirection::ToServer => 0u8,
+            TDirection::ToClient => 1u8,
+        };
+
+        let mut record = Vec::with_capacity(21 + message.len());
+        record.extend_from_slice(&elapsed.to_be_bytes());
+        record.extend_from_slice(&connection.to_be_bytes());
+        record.push(direction);
+        record.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        record.extend_from_slice(message);
+
+        let sent = match *self.records.lock().unwrap() {
+            Some(ref records) => records.send(record).is_ok(),
+            None => false,
+        };
+        if sent {
+            Ok(())
+        } else {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "capture writer stopped"))
+        }
+    }
+}
+
+impl Drop for TCaptureWriter {
+    fn drop(&mut self) {
+        // closing the channel lets the writer finish the pending records
+        self.records.get_mut().unwrap().take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+fn write_records(mut file: BufWriter<File>, pending: Receiver<Vec<u8>>) {
+    let res = (|| -> io::Result<()> {
+        loop {
+            let record = match pending.try_recv() {
+                Ok(record) => record,
+                Err(TryRecvError::Empty) => {
+                    file.flush()?;
+                    match pending.recv() {
+                        Ok(record) => record,
+                        Err(_) => return Ok(()),
+                    }
+                }
+                Err(TryRecvError::Disconnected) => return file.flush(),
+            };
+            file.write_all(&record)?;
+        }
+    })();
+
+    if let Err(e) = res {
+        error!("failed to write capture, recording stopped: {}", e);
+    }
+}
+
+/// Read every message of the capture file at `path`, in capture order.
+pub fn read_capture<P: AsRef<Path>>(path: P) -> io::Result<Vec<TCapturedMessage>> {
+    let mut file = BufReader::new(File::open(path)?);
+
+    let mut magic = [0u8; 5];
+    file.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not a capture file"));
+    }
+
+    let mut messages = Vec::new();
+    loop {
+        let mut header = [0u8; 21];
+        // a capture may only end between records
+        match file.read(&mut header[..1])? {
+            0 => return Ok(messages),
+            _ => file.read_exact(&mut header[1..])?,
+        }
+
+        let mut field = [0u8; 8];
+        field.copy_from_slice(&header[..8]);
+        let elapsed = Duration::from_micros(u64::from_be_bytes(field));
+        field.copy_from_slice(&header[8..16]);
+        let connection = u64::from_be_bytes(field);
+        let direction = match header[16] {
+            0 => TDirection::ToServer,
+            1 => TDirection::ToClient,
+            d => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown message direction {}", d),
+                ))
+            }
+        };
+        let mut length = [0u8; 4];
+        length.copy_from_slice(&header[17..]);
+
+        let mut message = vec![0u8; u32::from_be_bytes(length) as usize];
+        file.read_exact(&mut message)?;
+        messages.push(TCapturedMessage { elapsed, connection, direction, message });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    use async_std::task::block_on;
+
+    use async_thrift::protocol::async_compact::{TAsyncCompactInputProtocol, TAsyncCompactOutputProtocol};
+    use async_thrift::protocol::TMessageType;
+
+    use super::*;
+
+    #[test]
+    fn must_read_recorded_messages() {
+        let path = std::env::temp_dir().join(format!("thrift-capture-{}.tcap", std::process::id()));
+        let capture = TCaptureWriter::create(&path).unwrap();
+        capture.record(1, TDirection::ToServer, b"call").unwrap();
+        capture.record(2, TDirection::ToClient, b"").unwrap();
+        // dropping the writer writes the pending records
+        drop(capture);
+
+        let messages = read_capture(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!((messages[0].connection, messages[0].direction), (1, TDirection::ToServer));
+        assert_eq!(messages[0].message, b"call".to_vec());
+        assert_eq!((messages[1].connection, messages[1].direction), (2, TDirection::ToClient));
+        assert!(messages[1].message.is_empty());
+        assert!(messages[0].elapsed <= messages[1].elapsed);
+    }
+
+    #[test]
+    fn must_encode_empty_map_read_from_compact() {
+        let message_ident = TMessageIdentifier::new("get", TMessageType::Call, 1);
+        let mut fields = BTreeMap::new();
+        fields.insert(1, TValue::Map { key_type: None, value_type: None, entries: vec![] });
+        let body = TValue::Struct(fields);
+
+        let mut o_prot = TAsyncCompactOutputProtocol::new(TAsyncBufferChannel::new());
+        block_on(write_message(&mut o_prot, &message_ident, &body)).unwrap();
+        let bytes = o_prot.transport.take_write_bytes();
+
+        let mut i_prot = TAsyncCompactInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes));
+        let (message_ident, body) = block_on(read_message(&mut i_prot)).unwrap();
+        let message = block_on(encode_message(&message_ident, &body)).unwrap();
+
+        let (decoded_ident, decoded) = block_on(decode_message(message)).unwrap();
+        assert_eq!(decoded_ident, message_ident);
+        match decoded {
+            TValue::Struct(fields) => match &fields[&1] {
+                TValue::Map { entries, .. } => assert!(entries.is_empty()),
+                value => panic!("unexpected field {:?}", value),
+            },
+            value => panic!("unexpected body {:?}", value),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Transport and protocol selection for the tools.
+
+use std::fmt;
+use std::str::FromStr;
+
+use async_std::net::TcpStream;
+
+use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+use async_thrift::protocol::async_compact::{TAsyncCompactInputProtocol, TAsyncCompactOutputProtocol};
+use async_thrift::protocol::{TAsyncInputProtocol, TAsyncOutputProtocol};
+use async_thrift::transport::async_buffered::{TAsyncBufferedReadTransport, TAsyncBufferedWriteTransport};
+use async_thrift::transport::async_framed::{TAsyncFramedReadTransport, TAsyncFramedWriteTransport};
+use async_thrift::transport::async_socket::TAsyncTcpChannel;
+use async_thrift::transport::{AsyncRead, AsyncWrite, TAsyncIoChannel, TAsyncReadTransport, TAsyncWriteTransport};
+
+/// Input and output protocol of one connection.
+pub type TProtocolPair = (Box<dyn TAsyncInputProtocol + Send>, Box<dyn TAsyncOutputProtocol + Send>);
+
+/// How messages are delimited on a connection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransportKind {
+    /// Messages follow each other without delimiters.
+    Buffered,
+    /// Every message is prefixed with its length.
+    Framed,
+}
+
+impl FromStr for TransportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "buffered" => Ok(TransportKind::Buffered),
+            "framed" => Ok(TransportKind::Framed),
+            _ => Err(format!("unknown transport {:?}, expected \"buffered\" or \"framed\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransportKind::Buffered => f.write_str("buffered"),
+            TransportKind::Framed => f.write_str("framed"),
+        }
+    }
+}
+
+/// How messages are encoded on a connection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProtocolKind {
+    /// The strict binary protocol.
+    Binary,
+    /// The compact protocol.
+    Compact,
+}
+
+impl FromStr for ProtocolKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary" => Ok(ProtocolKind::Binary),
+            "compact" => Ok(ProtocolKind::Compact),
+            _ => Err(format!("unknown protocol {:?}, expected \"binary\" or \"compact\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for ProtocolKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolKind::Binary => f.write_str("binary"),
+            ProtocolKind::Compact => f.write_str("compact"),
+        }
+    }
+}
+
+/// Transport and protocol spoken on one side of a connection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TCodec {
+    pub transport: TransportKind,
+    pub protocol: ProtocolKind,
+}
+
+impl TCodec {
+    /// Create a `TCodec` that uses `transport` and `protocol`.
+    pub fn new(transport: TransportKind, protocol: ProtocolKind) -> TCodec {
+        TCodec { transport, protocol }
+    }
+
+    /// Create an input protocol that reads messages from `channel`.
+    pub fn input<C>(&self, channel: C) -> Box<dyn TAsyncInputProtocol + Send>
+        where
+            C: AsyncRead + Send + 'static,
+    {
+        let transport: Box<dyn TAsyncReadTransport + Send> = match self.transport {
+            TransportKind::Buffered => Box::new(TAsyncBufferedReadTransport::new(channel)),
+            TransportKind::Framed => Box::new(TAsyncFramedReadTransport::new(channel)),
+        };
+        match self.protocol {
+            ProtocolKind::Binary => Box::new(TAsyncBinaryInputProtocol::new(transport, true)),
+            ProtocolKind::Compact => Box::new(TAsyncCompactInputProtocol::new(transport)),
+        }
+    }
+
+    /// Create an output protocol that writes messages to `channel`.
+    pub fn output<C>(&self, channel: C) -> Box<dyn TAsyncOutputProtocol + Send>
+        where
+            C: AsyncWrite + Send + 'static,
+    {
+        let transport: Box<dyn TAsyncWriteTransport + Send> = match self.transport {
+            TransportKind::Buffered => Box::new(TAsyncBufferedWriteTransport::new(channel)),
+            TransportKind::Framed => Box::new(TAsyncFramedWriteTransport::new(channel)),
+        };
+        match self.protocol {
+            ProtocolKind::Binary => Box::new(TAsyncBinaryOutputProtocol::new(transport, true)),
+            ProtocolKind::Compact => Box::new(TAsyncCompactOutputProtocol::new(transport)),
+        }
+    }
+
+    /// Create the protocols of a connection over `stream`.
+    pub fn open(&self, stream: TcpStream) -> async_thrift::Result<TProtocolPair> {
+        let (read_half, write_half) = TAsyncTcpChannel::with_stream(stream).split()?;
+        Ok((self.input(read_half), self.output(write_half)))
+    }
+
+    /// Connect to `address` and create the protocols of the connection.
+    pub async fn connect(&self, address: &str) -> async_thrift::Result<TProtocolPair> {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+        self.open(stream)
+    }
+}
+
+impl fmt::Display for TCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.transport, self.protocol)
+    }
+}
@@ -0,0 +1,799 @@
+//! A minimal parser for Thrift IDL files.
+//!
+//! Only what is needed to name and type the fields of calls is kept:
+//! structs, unions, exceptions, enums, typedefs and services. Namespaces,
+//! includes, constants, default values and annotations are parsed and
+//! dropped. Types from included files are not resolved.
+
+use std::collections::HashMap;
+
+use async_thrift::protocol::TType;
+
+/// A type as written in IDL.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TTypeRef {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    Double,
+    String,
+    Binary,
+    List(Box<TTypeRef>),
+    Set(Box<TTypeRef>),
+    Map(Box<TTypeRef>, Box<TTypeRef>),
+    /// A struct whose fields are only known by id. Never produced by IDL
+    /// files, only by `parse_type`.
+    AnyStruct,
+    /// A struct, union, exception, enum or typedef.
+    Named(String),
+}
+
+/// A field of a struct, an argument list or a throws clause.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TFieldDef {
+    pub id: i16,
+    pub name: String,
+    pub field_type: TTypeRef,
+}
+
+/// A struct, union or exception.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TStructDef {
+    pub name: String,
+    pub fields: Vec<TFieldDef>,
+}
+
+/// An enum and its values.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TEnumDef {
+    pub name: String,
+    pub values: Vec<(String, i32)>,
+}
+
+/// A function of a service.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TFunctionDef {
+    pub name: String,
+    pub oneway: bool,
+    /// The return type, `None` for `void` functions.
+    pub returns: Option<TTypeRef>,
+    pub args: Vec<TFieldDef>,
+    pub throws: Vec<TFieldDef>,
+}
+
+impl TFunctionDef {
+    /// Return the fields of the struct replies to this function are sent
+    /// in: `success` with id 0 for the return value, then the exceptions.
+    pub fn result_fields(&self) -> Vec<TFieldDef> {
+        let success = self.returns.as_ref().map(|returns| TFieldDef {
+            id: 0,
+            name: "success".to_owned(),
+            field_type: returns.clone(),
+        });
+        success.into_iter().chain(self.throws.iter().cloned()).collect()
+    }
+}
+
+/// A service.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TServiceDef {
+    pub name: String,
+    pub extends: Option<String>,
+    pub functions: Vec<TFunctionDef>,
+}
+
+/// The definitions of an IDL file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TDocument {
+    pub structs: HashMap<String, TStructDef>,
+    pub enums: HashMap<String, TEnumDef>,
+    pub typedefs: HashMap<String, TTypeRef>,
+    pub services: HashMap<String, TServiceDef>,
+}
+
+/// A `TTypeRef` with typedefs followed.
+#[derive(Clone, Copy, Debug)]
+pub enum TResolved<'a> {
+    /// A type that is not a named struct or enum.
+    Plain(&'a TTypeRef),
+    Struct(&'a TStructDef),
+    Enum(&'a TEnumDef),
+}
+
+impl TDocument {
+    /// Follow typedefs until `type_ref` is a struct, an enum or an unnamed
+    /// type.
+    pub fn resolve<'a>(&'a self, type_ref: &'a TTypeRef) -> Result<TResolved<'a>, String> {
+        let mut type_ref = type_ref;
+        // typedefs may refer to each other, but not forever
+        for _ in 0..32 {
+            let name = match type_ref {
+                TTypeRef::Named(name) => local_name(name),
+                _ => return Ok(TResolved::Plain(type_ref)),
+            };
+            if let Some(def) = self.structs.get(name) {
+                return Ok(TResolved::Struct(def));
+            }
+            if let Some(def) = self.enums.get(name) {
+                return Ok(TResolved::Enum(def));
+            }
+            type_ref = self.typedefs.get(name).ok_or_else(|| format!("unknown type {}", name))?;
+        }
+        Err(format!("typedef cycle at {:?}", type_ref))
+    }
+
+    /// Return the type `type_ref` has on the wire.
+    pub fn wire_type(&self, type_ref: &TTypeRef) -> Result<TType, String> {
+        Ok(match self.resolve(type_ref)? {
+            TResolved::Struct(_) => TType::Struct,
+            TResolved::Enum(_) => TType::I32,
+            TResolved::Plain(type_ref) => match type_ref {
+                TTypeRef::Bool => TType::Bool,
+                TTypeRef::I8 => TType::I08,
+                TTypeRef::I16 => TType::I16,
+                TTypeRef::I32 => TType::I32,
+                TTypeRef::I64 => TType::I64,
+                TTypeRef::Double => TType::Double,
+                TTypeRef::String | TTypeRef::Binary => TType::String,
+                TTypeRef::List(_) => TType::List,
+                TTypeRef::Set(_) => TType::Set,
+                TTypeRef::Map(_, _) => TType::Map,
+                TTypeRef::AnyStruct => TType::Struct,
+                TTypeRef::Named(name) => return Err(format!("unknown type {}", name)),
+            },
+        })
+    }
+
+    /// Find function `name` of `service`, including the functions it
+    /// inherits. Without a service, the function must be defined by
+    /// exactly one service.
+    pub fn function(&self, service: Option<&str>, name: &str) -> Result<&TFunctionDef, String> {
+        if let Some(service) = service {
+            let service = self.services.get(service).ok_or_else(|| format!("unknown service {}", service))?;
+            return self
+                .service_function(service, name)
+                .ok_or_else(|| format!("service {} has no function {}", service.name, name));
+        }
+
+        // inherited functions are only found in the service defining them
+        let mut found: Vec<(&str, &TFunctionDef)> = Vec::new();
+        for service in self.services.values() {
+            if let Some(function) = service.functions.iter().find(|function| function.name == name) {
+                found.push((&service.name, function));
+            }
+        }
+        match found.len() {
+            0 => Err(format!("unknown function {}", name)),
+            1 => Ok(found[0].1),
+            _ => {
+                let mut names: Vec<&str> = found.iter().map(|(service, _)| *service).collect();
+                names.sort();
+                Err(format!("function {} is defined by several services ({}), pick one", name, names.join(", ")))
+            }
+        }
+    }
+
+    fn service_function<'a>(&'a self, service: &'a TServiceDef, name: &str) -> Option<&'a TFunctionDef> {
+        let mut service = Some(service);
+        // guard against services that extend each other
+        for _ in 0..32 {
+            let current = service?;
+            if let Some(function) = current.functions.iter().find(|function| function.name == name) {
+                return Some(function);
+            }
+            service = current.extends.as_ref().and_then(|base| self.services.get(local_name(base)));
+        }
+        None
+    }
+}
+
+// names from included files are prefixed with the file name
+fn local_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Parse the IDL in `source`.
+pub fn parse(source: &str) -> Result<TDocument, String> {
+    TParser::new(source)?.document()
+}
+
+/// Parse a type written as in IDL, such as `map<string, list<i32>>`.
+///
+/// `struct` stands for a struct whose fields are given by id.
+pub fn parse_type(source: &str) -> Result<TTypeRef, String> {
+    let mut parser = TParser::new(source)?;
+    let type_ref = parser.type_ref()?;
+    match parser.peek() {
+        None => Ok(type_ref),
+        Some(token) => Err(format!("unexpected {} after type", token)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TToken {
+    Ident(String),
+    Number(String),
+    Literal(String),
+    Punct(char),
+}
+
+impl std::fmt::Display for TToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TToken::Ident(s) | TToken::Number(s) => write!(f, "`{}`", s),
+            TToken::Literal(s) => write!(f, "{:?}", s),
+            TToken::Punct(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+struct TParser {
+    tokens: Vec<(TToken, usize)>,
+    pos: usize,
+}
+
+impl TParser {
+    fn new(source: &str) -> Result<TParser, String> {
+        Ok(TParser { tokens: tokenize(source)?, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<&TToken> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&TToken> {
+        self.tokens.get(self.pos + offset).map(|(token, _)| token)
+    }
+
+    fn error(&self, message: String) -> String {
+        match self.tokens.get(self.pos).or_else(|| self.tokens.last()) {
+            Some((_, line)) => format!("line {}: {}", line, message),
+            None => message,
+        }
+    }
+
+    fn next(&mut self) -> Result<TToken, String> {
+        match self.tokens.get(self.pos) {
+            Some((token, _)) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => Err(self.error("unexpected end of file".to_owned())),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.next()? {
+            TToken::Ident(s) => Ok(s),
+            token => {
+                self.pos -= 1;
+                Err(self.error(format!("expected a name but found {}", token)))
+            }
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.next()? {
+            TToken::Punct(p) if p == c => Ok(()),
+            token => {
+                self.pos -= 1;
+                Err(self.error(format!("expected `{}` but found {}", c, token)))
+            }
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&TToken::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(TToken::Ident(s)) if s == keyword => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eat_separator(&mut self) {
+        if !self.eat(',') {
+            self.eat(';');
+        }
+    }
+
+    /// Skip a bracketed group starting at the current `open` token.
+    fn skip_group(&mut self, open: char, close: char) -> Result<(), String> {
+        self.expect(open)?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                TToken::Punct(c) if c == open => depth += 1,
+                TToken::Punct(c) if c == close => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_annotations(&mut self) -> Result<(), String> {
+        if self.peek() == Some(&TToken::Punct('(')) {
+            self.skip_group('(', ')')?;
+        }
+        Ok(())
+    }
+
+    fn skip_const_value(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some(TToken::Punct('[')) => self.skip_group('[', ']'),
+            Some(TToken::Punct('{')) => self.skip_group('{', '}'),
+            _ => self.next().map(|_| ()),
+        }
+    }
+
+    fn document(&mut self) -> Result<TDocument, String> {
+        let mut document = TDocument::default();
+        while self.peek().is_some() {
+            match self.ident()?.as_str() {
+                "namespace" => {
+                    // the scope may be `*`
+                    self.next()?;
+                    self.ident()?;
+                    self.skip_annotations()?;
+                }
+                "include" | "cpp_include" => {
+                    self.next()?;
+                }
+                "typedef" => {
+                    let type_ref = self.type_ref()?;
+                    let name = self.ident()?;
+                    self.skip_annotations()?;
+                    document.typedefs.insert(name, type_ref);
+                }
+                "const" => {
+                    self.type_ref()?;
+                    self.ident()?;
+                    self.expect('=')?;
+                    self.skip_const_value()?;
+                }
+                "enum" => {
+                    let def = self.enum_def()?;
+                    document.enums.insert(def.name.clone(), def);
+                }
+                "struct" | "union" | "exception" => {
+                    let def = self.struct_def()?;
+                    document.structs.insert(def.name.clone(), def);
+                }
+                "service" => {
+                    let def = self.service_def()?;
+                    document.services.insert(def.name.clone(), def);
+                }
+                keyword => {
+                    self.pos -= 1;
+                    return Err(self.error(format!("unexpected `{}`", keyword)));
+                }
+            }
+            self.eat_separator();
+        }
+        Ok(document)
+    }
+
+    fn type_ref(&mut self) -> Result<TTypeRef, String> {
+        let type_ref = match self.ident()?.as_str() {
+            "bool" => TTypeRef::Bool,
+            "byte" | "i8" => TTypeRef::I8,
+            "i16" => TTypeRef::I16,
+            "i32" => TTypeRef::I32,
+            "i64" => TTypeRef::I64,
+            "double" => TTypeRef::Double,
+            "string" => TTypeRef::String,
+            "binary" => TTypeRef::Binary,
+            "struct" => TTypeRef::AnyStruct,
+            "list" => {
+                self.expect('<')?;
+                let element_type = self.type_ref()?;
+                self.expect('>')?;
+                TTypeRef::List(Box::new(element_type))
+            }
+            "set" => {
+                self.expect('<')?;
+                let element_type = self.type_ref()?;
+                self.expect('>')?;
+                TTypeRef::Set(Box::new(element_type))
+            }
+            "map" => {
+                self.expect('<')?;
+                let key_type = self.type_ref()?;
+                self.expect(',')?;
+                let value_type = self.type_ref()?;
+                self.expect('>')?;
+                TTypeRef::Map(Box::new(key_type), Box::new(value_type))
+            }
+            name => TTypeRef::Named(name.to_owned()),
+        };
+        self.skip_annotations()?;
+        Ok(type_ref)
+    }
+
+    fn enum_def(&mut self) -> Result<TEnumDef, String> {
+        let name = self.ident()?;
+        let mut values = Vec::new();
+        let mut next_value = 0;
+        self.expect('{')?;
+        while !self.eat('}') {
+            let value_name = self.ident()?;
+            let value = if self.eat('=') {
+                self.number()? as i32
+            } else {
+                next_value
+            };
+            next_value = value.wrapping_add(1);
+            values.push((value_name, value));
+            self.skip_annotations()?;
+            self.eat_separator();
+        }
+        self.skip_annotations()?;
+        Ok(TEnumDef { name, values })
+    }
+
+    fn struct_def(&mut self) -> Result<TStructDef, String> {
+        let name = self.ident()?;
+        self.eat_ident("xsd_all");
+        let fields = self.fields('{', '}')?;
+        self.skip_annotations()?;
+        Ok(TStructDef { name, fields })
+    }
+
+    fn service_def(&mut self) -> Result<TServiceDef, String> {
+        let name = self.ident()?;
+        let extends = if self.eat_ident("extends") {
+            Some(self.ident()?)
+        } else {
+            None
+        };
+
+        let mut functions = Vec::new();
+        self.expect('{')?;
+        while !self.eat('}') {
+            let oneway = self.eat_ident("oneway");
+            let returns = if self.eat_ident("void") {
+                None
+            } else {
+                Some(self.type_ref()?)
+            };
+            let name = self.ident()?;
+            let args = self.fields('(', ')')?;
+            let throws = if self.eat_ident("throws") {
+                self.fields('(', ')')?
+            } else {
+                Vec::new()
+            };
+            self.skip_annotations()?;
+            self.eat_separator();
+            functions.push(TFunctionDef { name, oneway, returns, args, throws });
+        }
+        self.skip_annotations()?;
+        Ok(TServiceDef { name, extends, functions })
+    }
+
+    fn fields(&mut self, open: char, close: char) -> Result<Vec<TFieldDef>, String> {
+        let mut fields = Vec::new();
+        // fields without an id are numbered -1, -2, ... like the compiler does
+        let mut implicit_id = 0;
+        self.expect(open)?;
+        while !self.eat(close) {
+            let id = match (self.peek(), self.peek_at(1)) {
+                (Some(TToken::Number(_)), Some(TToken::Punct(':'))) => {
+                    let id = self.number()? as i16;
+                    self.expect(':')?;
+                    id
+                }
+                _ => {
+                    implicit_id -= 1;
+                    implicit_id
+                }
+            };
+            if !self.eat_ident("required") {
+                self.eat_ident("optional");
+            }
+            let field_type = self.type_ref()?;
+            let name = self.ident()?;
+            if self.eat('=') {
+                self.skip_const_value()?;
+            }
+            self.skip_annotations()?;
+            self.eat_separator();
+            fields.push(TFieldDef { id, name, field_type });
+        }
+        Ok(fields)
+    }
+
+    fn number(&mut self) -> Result<i64, String> {
+        match self.next()? {
+            TToken::Number(s) => {
+                let (negative, digits) = match s.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, s.strip_prefix('+').unwrap_or(&s)),
+                };
+                let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+                    Some(hex) => i64::from_str_radix(hex, 16),
+                    None => digits.parse::<i64>(),
+                };
+                match value {
+                    Ok(value) if negative => Ok(-value),
+                    Ok(value) => Ok(value),
+                    Err(_) => {
+                        self.pos -= 1;
+                        Err(self.error(format!("expected an integer but found `{}`", s)))
+                    }
+                }
+            }
+            token => {
+                self.pos -= 1;
+                Err(self.error(format!("expected an integer but found {}", token)))
+            }
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(TToken, usize)>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut line = 1;
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).cloned();
+        if c == '\n' {
+            line += 1;
+            pos += 1;
+        } else if c.is_whitespace() {
+            pos += 1;
+        } else if c == '#' || (c == '/' && next == Some('/')) {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let start_line = line;
+            pos += 2;
+            loop {
+                match chars.get(pos) {
+                    Some('*') if chars.get(pos + 1) == Some(&'/') => break,
+                    Some(c) => {
+                        if *c == '\n' {
+                            line += 1;
+                        }
+                        pos += 1;
+                    }
+                    None => return Err(format!("line {}: unterminated comment", start_line)),
+                }
+            }
+            pos += 2;
+        } else if c == '"' || c == '\'' {
+            let mut literal = String::new();
+            pos += 1;
+            loop {
+                match chars.get(pos) {
+                    Some(&q) if q == c => break,
+                    Some('\\') if pos + 1 < chars.len() => {
+                        literal.push(chars[pos + 1]);
+                        pos += 2;
+                    }
+                    Some(&other) => {
+                        if other == '\n' {
+                            line += 1;
+                        }
+                        literal.push(other);
+                        pos += 1;
+                    }
+                    None => return Err(format!("line {}: unterminated string", line)),
+                }
+            }
+            pos += 1;
+            tokens.push((TToken::Literal(literal), line));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_' || chars[pos] == '.') {
+                pos += 1;
+            }
+            tokens.push((TToken::Ident(chars[start..pos].iter().collect()), line));
+        } else if c.is_ascii_digit() || ((c == '-' || c == '+') && next.is_some_and(|n| n.is_ascii_digit())) {
+            let start = pos;
+            pos += 1;
+            while pos < chars.len() {
+                let d = chars[pos];
+                let exponent_sign = (d == '-' || d == '+') && matches!(chars[pos - 1], 'e' | 'E') && !chars[start..pos].contains(&'x');
+                if d.is_ascii_alphanumeric() || d == '.' || exponent_sign {
+                    pos += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push((TToken::Number(chars[start..pos].iter().collect()), line));
+        } else {
+            tokens.push((TToken::Punct(c), line));
+            pos += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDL: &str = r#"
+        include "shared.thrift"
+        namespace rs tutorial
+        namespace * tutorial
+
+        # a comment
+        // another comment
+        /* a comment
+           over two lines */
+
+        typedef i32 MyInteger
+        typedef list<MyInteger> Numbers (cpp.type = "std::vector<int>")
+
+        const i32 INT32CONSTANT = 9853
+        const map<string, string> MAPCONSTANT = {'hello': 'world', "goodnight": "moon"}
+        const list<i32> LISTCONSTANT = [1, 2, 3];
+
+        enum Operation {
+            ADD = 1,
+            SUBTRACT,
+            MULTIPLY = 0x10;
+            DIVIDE (annotation = "x")
+        }
+
+        struct Work {
+            1: i32 num1 = 0,
+            2: required i32 num2,
+            3: optional Operation op = Operation.ADD,
+            4: optional string comment = "a \"quoted\" comment",
+            5: map<string, list<double>> values = {"a": [1.5, -2e-3]},
+        } (annotation = "y")
+
+        union Either { 1: string text; 2: binary data }
+
+        exception InvalidOperation {
+            1: i32 whatOp
+            2: string why
+        }
+
+        service Calculator extends shared.SharedService {
+            void ping(),
+            i32 calculate(1: i32 logid, 2: Work w) throws (1: InvalidOperation ouch),
+            oneway void zip()
+        }
+    "#;
+
+    fn field(id: i16, name: &str, field_type: TTypeRef) -> TFieldDef {
+        TFieldDef { id, name: name.to_owned(), field_type }
+    }
+
+    #[test]
+    fn must_parse_definitions() {
+        let document = parse(IDL).unwrap();
+
+        assert_eq!(document.typedefs["MyInteger"], TTypeRef::I32);
+        assert_eq!(document.typedefs["Numbers"], TTypeRef::List(Box::new(TTypeRef::Named("MyInteger".to_owned()))));
+        assert_eq!(
+            document.enums["Operation"].values,
+            vec![
+                ("ADD".to_owned(), 1),
+                ("SUBTRACT".to_owned(), 2),
+                ("MULTIPLY".to_owned(), 16),
+                ("DIVIDE".to_owned(), 17),
+            ],
+        );
+        assert_eq!(
+            document.structs["Work"].fields,
+            vec![
+                field(1, "num1", TTypeRef::I32),
+                field(2, "num2", TTypeRef::I32),
+                field(3, "op", TTypeRef::Named("Operation".to_owned())),
+                field(4, "comment", TTypeRef::String),
+                field(5, "values", TTypeRef::Map(
+                    Box::new(TTypeRef::String),
+                    Box::new(TTypeRef::List(Box::new(TTypeRef::Double))),
+                )),
+            ],
+        );
+        assert_eq!(
+            document.structs["Either"].fields,
+            vec![field(1, "text", TTypeRef::String), field(2, "data", TTypeRef::Binary)],
+        );
+        assert_eq!(document.structs["InvalidOperation"].fields.len(), 2);
+
+        let service = &document.services["Calculator"];
+        assert_eq!(service.extends, Some("shared.SharedService".to_owned()));
+        assert_eq!(service.functions.len(), 3);
+        let calculate = &service.functions[1];
+        assert_eq!(calculate.returns, Some(TTypeRef::I32));
+        assert_eq!(calculate.args, vec![
+            field(1, "logid", TTypeRef::I32),
+            field(2, "w", TTypeRef::Named("Work".to_owned())),
+        ]);
+        assert_eq!(calculate.throws, vec![field(1, "ouch", TTypeRef::Named("InvalidOperation".to_owned()))]);
+        assert_eq!(calculate.result_fields()[0], field(0, "success", TTypeRef::I32));
+        assert_eq!(calculate.result_fields()[1], calculate.throws[0]);
+        assert!(!service.functions[0].oneway && service.functions[0].returns.is_none());
+        assert!(service.functions[2].oneway);
+    }
+
+    #[test]
+    fn must_number_fields_without_ids() {
+        let document = parse("struct Old { string a; 5: i64 b; i16 c }").unwrap();
+        assert_eq!(
+            document.structs["Old"].fields,
+            vec![field(-1, "a", TTypeRef::String), field(5, "b", TTypeRef::I64), field(-2, "c", TTypeRef::I16)],
+        );
+    }
+
+    #[test]
+    fn must_resolve_typedefs() {
+        let document = parse(IDL).unwrap();
+        let numbers = TTypeRef::Named("Numbers".to_owned());
+        match document.resolve(&numbers).unwrap() {
+            TResolved::Plain(TTypeRef::List(element_type)) => {
+                assert_eq!(document.wire_type(element_type).unwrap(), TType::I32);
+            }
+            resolved => panic!("unexpected type {:?}", resolved),
+        }
+        assert_eq!(document.wire_type(&numbers).unwrap(), TType::List);
+        assert_eq!(document.wire_type(&TTypeRef::Named("Operation".to_owned())).unwrap(), TType::I32);
+        // names from included files resolve to local definitions of the same name
+        assert_eq!(document.wire_type(&TTypeRef::Named("other.Work".to_owned())).unwrap(), TType::Struct);
+        assert!(document.wire_type(&TTypeRef::Named("Missing".to_owned())).is_err());
+
+        let cycle = parse("typedef B A typedef A B").unwrap();
+        assert!(cycle.resolve(&TTypeRef::Named("A".to_owned())).is_err());
+    }
+
+    #[test]
+    fn must_find_inherited_functions() {
+        let document = parse(r#"
+            service Base { void ping() }
+            service Derived extends Base { i32 add(1: i32 a, 2: i32 b) }
+            service Other { void ping() }
+        "#).unwrap();
+
+        assert_eq!(document.function(Some("Derived"), "ping").unwrap().name, "ping");
+        assert_eq!(document.function(None, "add").unwrap().args.len(), 2);
+        assert_eq!(
+            document.function(None, "ping").unwrap_err(),
+            "function ping is defined by several services (Base, Other), pick one",
+        );
+        assert!(document.function(Some("Other"), "add").is_err());
+        assert!(document.function(Some("Missing"), "ping").is_err());
+    }
+
+    #[test]
+    fn must_report_line_of_errors() {
+        assert_eq!(parse("struct A {\n  1: i32\n}").unwrap_err(), "line 3: expected a name but found `}`");
+        assert_eq!(parse("/* never closed\n").unwrap_err(), "line 1: unterminated comment");
+        assert_eq!(parse("\nfoo").unwrap_err(), "line 2: unexpected `foo`");
+    }
+
+    #[test]
+    fn must_parse_types() {
+        assert_eq!(
+            parse_type("map<string, list<i32>>").unwrap(),
+            TTypeRef::Map(Box::new(TTypeRef::String), Box::new(TTypeRef::List(Box::new(TTypeRef::I32)))),
+        );
+        assert_eq!(parse_type("set<byte>").unwrap(), TTypeRef::Set(Box::new(TTypeRef::I8)));
+        assert_eq!(parse_type("struct").unwrap(), TTypeRef::AnyStruct);
+        assert_eq!(parse_type("Work").unwrap(), TTypeRef::Named("Work".to_owned()));
+        assert!(parse_type("list<i32> x").is_err());
+        assert!(parse_type("list<i32").is_err());
+    }
+}
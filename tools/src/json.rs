@@ -0,0 +1,407 @@
+//! Conversion between JSON and schema-less Thrift values.
+//!
+//! With an IDL document, JSON is plain: structs are objects keyed by field
+//! name (or id) and every value gets its type from the IDL. Without one,
+//! values are *typed*: objects with a single member whose key is the type
+//! in IDL syntax, such as `{"i32": 7}` or `{"list<string>": ["a", "b"]}`.
+//! Structs are then written as `{"struct": {"1": {"i32": 7}}}`, with typed
+//! field values keyed by field id.
+//!
+//! Binary values are given as strings, whose UTF-8 bytes are sent, or as
+//! arrays of byte values. Maps are objects when their keys are strings or
+//! numbers and arrays of `[key, value]` pairs otherwise.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Number, Value};
+
+use async_thrift::protocol::async_value::TValue;
+use async_thrift::protocol::TType;
+use async_thrift::OrderedFloat;
+
+use crate::idl::{parse_type, TDocument, TFieldDef, TResolved, TTypeRef};
+
+/// Convert a JSON object of typed values keyed by field id into a struct.
+pub fn typed_struct(json: &Value) -> Result<TValue, String> {
+    let members = json.as_object().ok_or_else(|| format!("expected an object of fields but got {}", json))?;
+
+    let mut fields = BTreeMap::new();
+    for (key, value) in members {
+        let id = key.parse::<i16>().map_err(|_| format!("field id {:?} is not a number", key))?;
+        fields.insert(id, typed_value(value).map_err(|e| format!("field {}: {}", id, e))?);
+    }
+    Ok(TValue::Struct(fields))
+}
+
+/// Convert a typed value, such as `{"i64": 3}`, into a `TValue`.
+pub fn typed_value(json: &Value) -> Result<TValue, String> {
+    match json.as_object() {
+        Some(members) if members.len() == 1 => {
+            let (type_name, value) = members.iter().next().unwrap();
+            let type_ref = parse_type(type_name)?;
+            to_value(value, &type_ref, &TDocument::default())
+        }
+        _ => Err(format!("expected a typed value such as {{\"i32\": 1}} but got {}", json)),
+    }
+}
+
+/// Convert a JSON object keyed by field name or id into a struct with
+/// `fields`.
+pub fn to_struct(json: &Value, fields: &[TFieldDef], document: &TDocument) -> Result<TValue, String> {
+    let members = json.as_object().ok_or_else(|| format!("expected an object of fields but got {}", json))?;
+
+    let mut values = BTreeMap::new();
+    for (key, value) in members {
+        let field = fields
+            .iter()
+            .find(|field| field.name == *key || field.id.to_string() == *key)
+            .ok_or_else(|| format!("unknown field {:?}", key))?;
+        let value = to_value(value, &field.field_type, document).map_err(|e| format!("{}: {}", field.name, e))?;
+        values.insert(field.id, value);
+    }
+    Ok(TValue::Struct(values))
+}
+
+/// Convert `json` into a value of type `type_ref`.
+pub fn to_value(json: &Value, type_ref: &TTypeRef, document: &TDocument) -> Result<TValue, String> {
+    let type_ref = match document.resolve(type_ref)? {
+        TResolved::Struct(def) => return to_struct(json, &def.fields, document),
+        TResolved::Enum(def) => {
+            return match json {
+                Value::String(name) => def
+                    .values
+                    .iter()
+                    .find(|(value_name, _)| value_name == name)
+                    .map(|(_, value)| TValue::I32(*value))
+                    .ok_or_else(|| format!("{} has no value {}", def.name, name)),
+                _ => Ok(TValue::I32(integer(json)? as i32)),
+            };
+        }
+        TResolved::Plain(type_ref) => type_ref,
+    };
+
+    match type_ref {
+        TTypeRef::Bool => json.as_bool().map(TValue::Bool).ok_or_else(|| format!("expected a bool but got {}", json)),
+        TTypeRef::I8 => Ok(TValue::I8(in_range(json, i8::MIN as i64, i8::MAX as i64)? as i8)),
+        TTypeRef::I16 => Ok(TValue::I16(in_range(json, i16::MIN as i64, i16::MAX as i64)? as i16)),
+        TTypeRef::I32 => Ok(TValue::I32(in_range(json, i32::MIN as i64, i32::MAX as i64)? as i32)),
+        TTypeRef::I64 => Ok(TValue::I64(integer(json)?)),
+        TTypeRef::Double => json
+            .as_f64()
+            .map(|d| TValue::Double(OrderedFloat::from(d)))
+            .ok_or_else(|| format!("expected a number but got {}", json)),
+        TTypeRef::String => json
+            .as_str()
+            .map(|s| TValue::String(s.to_owned()))
+            .ok_or_else(|| format!("expected a string but got {}", json)),
+        TTypeRef::Binary => match json {
+            Value::String(s) => Ok(TValue::Binary(s.as_bytes().to_vec())),
+            Value::Array(bytes) => bytes
+                .iter()
+                .map(|byte| in_range(byte, 0, 255).map(|byte| byte as u8))
+                .collect::<Result<Vec<u8>, String>>()
+                .map(TValue::Binary),
+            _ => Err(format!("expected a string or an array of bytes but got {}", json)),
+        },
+        TTypeRef::List(element_type) => Ok(TValue::List {
+            element_type: document.wire_type(element_type)?,
+            elements: to_elements(json, element_type, document)?,
+        }),
+        TTypeRef::Set(element_type) => Ok(TValue::Set {
+            element_type: document.wire_type(element_type)?,
+            elements: to_elements(json, element_type, document)?,
+        }),
+        TTypeRef::Map(key_type, value_type) => {
+            let entries = match json {
+                Value::Object(members) => members
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok((
+                            to_value(&map_key(key, key_type, document)?, key_type, document)?,
+                            to_value(value, value_type, document)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+                Value::Array(pairs) => pairs
+                    .iter()
+                    .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                        Some([key, value]) => Ok((
+                            to_value(key, key_type, document)?,
+                            to_value(value, value_type, document)?,
+                        )),
+                        _ => Err(format!("expected a [key, value] pair but got {}", pair)),
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+                _ => return Err(format!("expected an object or an array of pairs but got {}", json)),
+            };
+            Ok(TValue::Map {
+                key_type: Some(document.wire_type(key_type)?),
+                value_type: Some(document.wire_type(value_type)?),
+                entries,
+            })
+        }
+        TTypeRef::AnyStruct => typed_struct(json),
+        TTypeRef::Named(name) => Err(format!("unknown type {}", name)),
+    }
+}
+
+fn to_elements(json: &Value, element_type: &TTypeRef, document: &TDocument) -> Result<Vec<TValue>, String> {
+    json.as_array()
+        .ok_or_else(|| format!("expected an array but got {}", json))?
+        .iter()
+        .map(|element| to_value(element, element_type, document))
+        .collect()
+}
+
+// object keys are always strings, numeric keys have to be parsed back
+fn map_key(key: &str, key_type: &TTypeRef, document: &TDocument) -> Result<Value, String> {
+    match document.wire_type(key_type)? {
+        TType::String => Ok(Value::String(key.to_owned())),
+        TType::I32 if key.parse::<i64>().is_err() => Ok(Value::String(key.to_owned())),
+        _ => serde_json::from_str(key).map_err(|_| format!("map key {:?} is not a {:?}", key, key_type)),
+    }
+}
+
+fn integer(json: &Value) -> Result<i64, String> {
+    json.as_i64().ok_or_else(|| format!("expected an integer but got {}", json))
+}
+
+fn in_range(json: &Value, min: i64, max: i64) -> Result<i64, String> {
+    let value = integer(json)?;
+    if value < min || value > max {
+        return Err(format!("{} is out of range {}..={}", value, min, max));
+    }
+    Ok(value)
+}
+
+/// Convert a struct with `fields` into a JSON object keyed by field name.
+///
+/// Fields the IDL does not know are keyed by id and converted without
+/// their type.
+pub fn from_struct(value: &TValue, fields: &[TFieldDef], document: &TDocument) -> Value {
+    let values = match value {
+        TValue::Struct(values) => values,
+        _ => return untyped(value),
+    };
+
+    let mut members = Map::new();
+    for (id, value) in values {
+        match fields.iter().find(|field| field.id == *id) {
+            Some(field) => members.insert(field.name.clone(), from_value(value, &field.field_type, document)),
+            None => members.insert(id.to_string(), untyped(value)),
+        };
+    }
+    Value::Object(members)
+}
+
+/// Convert `value` of type `type_ref` into JSON.
+///
+/// Parts of `value` that do not have the expected type are converted
+/// without their type.
+pub fn from_value(value: &TValue, type_ref: &TTypeRef, document: &TDocument) -> Value {
+    let type_ref = match document.resolve(type_ref) {
+        Ok(TResolved::Struct(def)) => return from_struct(value, &def.fields, document),
+        Ok(TResolved::Enum(def)) => {
+            return match value {
+                TValue::I32(i) => def
+                    .values
+                    .iter()
+                    .find(|(_, v)| v == i)
+                    .map(|(name, _)| Value::String(name.clone()))
+                    .unwrap_or_else(|| Value::from(*i)),
+                _ => untyped(value),
+            };
+        }
+        Ok(TResolved::Plain(type_ref)) => type_ref,
+        Err(_) => return untyped(value),
+    };
+
+    match (type_ref, value) {
+        (TTypeRef::List(element_type), TValue::List { elements, .. })
+        | (TTypeRef::Set(element_type), TValue::Set { elements, .. }) => Value::Array(
+            elements.iter().map(|element| from_value(element, element_type, document)).collect(),
+        ),
+        (TTypeRef::Map(key_type, value_type), TValue::Map { entries, .. }) => from_entries(
+            entries
+                .iter()
+                .map(|(key, value)| (from_value(key, key_type, document), from_value(value, value_type, document))),
+        ),
+        // a binary field holding valid UTF-8 is decoded as a string
+        (TTypeRef::Binary, TValue::String(s)) => Value::String(s.clone()),
+        _ => untyped(value),
+    }
+}
+
+/// Convert `value` into JSON without knowing its type.
+///
+/// Structs become objects keyed by field id and binary values become
+/// arrays of bytes.
+pub fn untyped(value: &TValue) -> Value {
+    match value {
+        TValue::Bool(b) => Value::Bool(*b),
+        TValue::I8(i) => Value::from(*i),
+        TValue::I16(i) => Value::from(*i),
+        TValue::I32(i) => Value::from(*i),
+        TValue::I64(i) => Value::from(*i),
+        TValue::Double(d) => Number::from_f64(d.into_inner()).map(Value::Number).unwrap_or(Value::Null),
+        TValue::String(s) => Value::String(s.clone()),
+        TValue::Binary(b) => Value::Array(b.iter().map(|byte| Value::from(*byte)).collect()),
+        TValue::Struct(fields) => Value::Object(
+            fields.iter().map(|(id, value)| (id.to_string(), untyped(value))).collect(),
+        ),
+        TValue::List { elements, .. } | TValue::Set { elements, .. } => {
+            Value::Array(elements.iter().map(untyped).collect())
+        }
+        TValue::Map { entries, .. } => from_entries(entries.iter().map(|(key, value)| (untyped(key), untyped(value)))),
+    }
+}
+
+// maps become objects when every key can be an object key
+fn from_entries<I: Iterator<Item = (Value, Value)>>(entries: I) -> Value {
+    let entries: Vec<(Value, Value)> = entries.collect();
+    if entries.iter().all(|(key, _)| key.is_string() || key.is_number()) {
+        Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| match key {
+                    Value::String(s) => (s, value),
+                    key => (key.to_string(), value),
+                })
+                .collect(),
+        )
+    } else {
+        Value::Array(entries.into_iter().map(|(key, value)| Value::Array(vec![key, value])).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task::block_on;
+    use serde_json::json;
+
+    use async_thrift::protocol::async_binary::{TAsyncBinaryInputProtocol, TAsyncBinaryOutputProtocol};
+    use async_thrift::protocol::async_value::{read_value, write_value};
+    use async_thrift::transport::async_mem::TAsyncBufferChannel;
+
+    use crate::idl::parse;
+
+    use super::*;
+
+    const IDL: &str = r#"
+        typedef i64 Id
+        enum Color { RED = 1, GREEN = 2 }
+        struct Point { 1: double x, 2: double y }
+        struct Everything {
+            1: bool flag,
+            2: byte tiny,
+            3: i16 small,
+            4: i32 medium,
+            5: Id large,
+            6: double ratio,
+            7: string name,
+            8: binary data,
+            9: binary text,
+            10: Point origin,
+            11: list<i32> numbers,
+            12: set<string> tags,
+            13: map<string, Color> colors,
+            14: map<i32, list<Point>> paths,
+            15: map<Point, bool> visited,
+        }
+    "#;
+
+    // writes `value` with the binary protocol and reads it back
+    fn over_the_wire(value: &TValue) -> TValue {
+        let mut o_prot = TAsyncBinaryOutputProtocol::new(TAsyncBufferChannel::new(), true);
+        block_on(write_value(&mut o_prot, value)).unwrap();
+        let bytes = o_prot.transport.take_write_bytes();
+
+        let mut i_prot = TAsyncBinaryInputProtocol::new(TAsyncBufferChannel::with_readable_bytes(bytes), true);
+        block_on(read_value(&mut i_prot, value.ttype())).unwrap()
+    }
+
+    #[test]
+    fn must_round_trip_every_type_with_idl() {
+        let document = parse(IDL).unwrap();
+        let fields = &document.structs["Everything"].fields;
+        let json = json!({
+            "flag": true,
+            "tiny": -128,
+            "small": 32767,
+            "medium": -7,
+            "large": 9007199254740993i64,
+            "ratio": 0.25,
+            "name": "thrift",
+            "data": [0, 159, 255],
+            "text": "héllo",
+            "origin": {"x": 1.5, "y": -2.0},
+            "numbers": [1, 2, 3],
+            "tags": ["a", "b"],
+            "colors": {"red": "RED", "unknown": 3},
+            "paths": {"1": [{"x": 0.0, "y": 0.0}], "-2": []},
+            "visited": [[{"x": 1.0, "y": 2.0}, true]],
+        });
+
+        let value = to_struct(&json, fields, &document).unwrap();
+        let value = over_the_wire(&value);
+        assert_eq!(from_struct(&value, fields, &document), json);
+    }
+
+    #[test]
+    fn must_accept_field_ids_and_keep_unknown_fields() {
+        let document = parse(IDL).unwrap();
+        let fields = &document.structs["Point"].fields;
+
+        let value = to_struct(&json!({"1": 3.0}), fields, &document).unwrap();
+        assert_eq!(from_struct(&value, fields, &document), json!({"x": 3.0}));
+
+        let mut values = BTreeMap::new();
+        values.insert(2, TValue::Double(OrderedFloat::from(4.0)));
+        values.insert(9, TValue::String("extra".to_owned()));
+        assert_eq!(from_struct(&TValue::Struct(values), fields, &document), json!({"y": 4.0, "9": "extra"}));
+
+        assert_eq!(to_struct(&json!({"z": 1.0}), fields, &document).unwrap_err(), "unknown field \"z\"");
+    }
+
+    #[test]
+    fn must_round_trip_every_type_without_idl() {
+        let typed = [
+            (json!({"bool": false}), json!(false)),
+            (json!({"byte": 127}), json!(127)),
+            (json!({"i16": -32768}), json!(-32768)),
+            (json!({"i32": 7}), json!(7)),
+            (json!({"i64": -9007199254740993i64}), json!(-9007199254740993i64)),
+            (json!({"double": -0.5}), json!(-0.5)),
+            (json!({"string": "text"}), json!("text")),
+            (json!({"binary": [0, 255]}), json!([0, 255])),
+            (json!({"list<i16>": [1, 2]}), json!([1, 2])),
+            (json!({"set<string>": ["a"]}), json!(["a"])),
+            (json!({"map<i32, string>": {"1": "one"}}), json!({"1": "one"})),
+            (json!({"map<list<i8>, bool>": [[[1], true]]}), json!([[[1], true]])),
+            (json!({"struct": {"1": {"i32": 7}, "2": {"struct": {}}}}), json!({"1": 7, "2": {}})),
+        ];
+
+        for (json, expected) in typed.iter() {
+            let value = over_the_wire(&typed_value(json).unwrap());
+            assert_eq!(untyped(&value), *expected, "{}", json);
+        }
+
+        let value = typed_struct(&json!({"1": {"list<string>": ["x"]}})).unwrap();
+        assert_eq!(untyped(&over_the_wire(&value)), json!({"1": ["x"]}));
+    }
+
+    #[test]
+    fn must_reject_invalid_values() {
+        assert_eq!(typed_value(&json!({"byte": 128})).unwrap_err(), "128 is out of range -128..=127");
+        assert_eq!(typed_value(&json!({"binary": [256]})).unwrap_err(), "256 is out of range 0..=255");
+        assert!(typed_value(&json!({"i32": 1.5})).is_err());
+        assert!(typed_value(&json!({"bool": 1})).is_err());
+        assert!(typed_value(&json!({"i32": 1, "i64": 2})).is_err());
+        assert!(typed_value(&json!({"map<i32, i32>": [[1]]})).is_err());
+        assert!(typed_struct(&json!({"one": {"i32": 1}})).is_err());
+
+        let document = parse(IDL).unwrap();
+        let color = TTypeRef::Named("Color".to_owned());
+        assert_eq!(to_value(&json!("BLUE"), &color, &document).unwrap_err(), "Color has no value BLUE");
+    }
+}
@@ -0,0 +1,11 @@
+//! Command-line tools for working with Thrift services.
+
+#[macro_use]
+extern crate log;
+
+pub mod capture;
+pub mod codec;
+pub mod idl;
+pub mod json;
+pub mod proxy;
+pub mod replay;
@@ -0,0 +1,239 @@
+//! A proxy that routes Thrift calls to upstream servers by method name.
+//!
+//! Messages are decoded without their schema and re-encoded for the
+//! upstream, so both sides may use different transports and protocols.
+//! Every downstream connection gets its own upstream connections, which
+//! keeps sequence numbers and call order intact.
+//!
+//! With a capture, the proxy also records every call and upstream reply
+//! it forwards.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_std::net::{TcpListener, TcpStream};
+use async_std::prelude::*;
+use async_std::task;
+
+use async_thrift::protocol::async_value::{read_message, read_value, write_message, TValue};
+use async_thrift::protocol::{TMessageIdentifier, TMessageType, TType};
+use async_thrift::server::handle_process_result;
+use async_thrift::{new_application_error, ApplicationErrorKind, TransportErrorKind};
+
+use crate::capture::{encode_message, TCaptureWriter, TDirection};
+use crate::codec::{TCodec, TProtocolPair};
+
+/// Sends calls whose method matches `pattern` to `upstream`.
+///
+/// A pattern ending in `*` matches every method starting with the rest of
+/// the pattern, so `Calculator:*` routes all calls to a multiplexed
+/// `Calculator` service. Other patterns must match the method exactly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TRoute {
+    pattern: String,
+    upstream: String,
+}
+
+impl TRoute {
+    /// Create a `TRoute` that sends calls matching `pattern` to `upstream`.
+    pub fn new<P: Into<String>, U: Into<String>>(pattern: P, upstream: U) -> TRoute {
+        TRoute { pattern: pattern.into(), upstream: upstream.into() }
+    }
+
+    /// Return `true` if calls to `method` take this route.
+    pub fn matches(&self, method: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => method == self.pattern,
+        }
+    }
+}
+
+/// Parses routes written as `PATTERN=ADDRESS`.
+impl FromStr for TRoute {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.rfind('=') {
+            Some(index) if index > 0 && index + 1 < s.len() => Ok(TRoute::new(&s[..index], &s[index + 1..])),
+            _ => Err(format!("route {:?} is not of the form PATTERN=ADDRESS", s)),
+        }
+    }
+}
+
+/// Routing Thrift proxy.
+///
+/// Calls no route matches go to the default upstream, or are answered
+/// with an `UnknownMethod` exception if there is none. Calls whose
+/// upstream cannot be reached are answered with an `InternalError`
+/// exception.
+#[derive(Debug)]
+pub struct TProxy {
+    routes: Vec<TRoute>,
+    default_upstream: Option<String>,
+    downstream: TCodec,
+    upstream: TCodec,
+    capture: Option<TCaptureWriter>,
+    connections: AtomicU64,
+}
+
+impl TProxy {
+    /// Create a `TProxy` that speaks `downstream` to its clients and
+    /// `upstream` to the servers it forwards to.
+    pub fn new(downstream: TCodec, upstream: TCodec) -> TProxy {
+        TProxy {
+            routes: Vec::new(),
+            default_upstream: None,
+            downstream,
+            upstream,
+            capture: None,
+            connections: AtomicU64::new(0),
+        }
+    }
+
+    /// Add a route. Routes are tried in the order they were added.
+    pub fn with_route(mut self, route: TRoute) -> TProxy {
+        self.routes.push(route);
+        self
+    }
+
+    /// Forward calls no route matches to `address`.
+    pub fn with_default_upstream<S: Into<String>>(mut self, address: S) -> TProxy {
+        self.default_upstream = Some(address.into());
+        self
+    }
+
+    /// Record forwarded calls and their replies to `capture`.
+    pub fn with_capture(mut self, capture: TCaptureWriter) -> TProxy {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Return the address calls to `method` are forwarded to.
+    pub fn upstream_for(&self, method: &str) -> Option<&str> {
+        self.routes
+            .iter()
+            .find(|route| route.matches(method))
+            .map(|route| route.upstream.as_str())
+            .or(self.default_upstream.as_deref())
+    }
+
+    /// Accept connections on `listen_address` and forward their calls.
+    ///
+    /// Return `Err` if the listening socket cannot be created.
+    pub async fn listen(self, listen_address: &str) -> async_thrift::Result<()> {
+        let listener = TcpListener::bind(listen_address).await?;
+        let proxy = Arc::new(self);
+
+        let mut incoming = listener.incoming();
+        while let Some(stream) = incoming.next().await {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("failed to accept connection: {:?}", e);
+                    continue;
+                }
+            };
+            let proxy = proxy.clone();
+            task::spawn(async move {
+                let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+                if let Err(e) = proxy.serve_connection(stream).await {
+                    warn!("closed connection from {}: {:?}", peer, e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    async fn serve_connection(&self, stream: TcpStream) -> async_thrift::Result<()> {
+        stream.set_nodelay(true)?;
+        let (mut i_prot, mut o_prot) = self.downstream.open(stream)?;
+        let mut upstreams = HashMap::new();
+        let connection = self.connections.fetch_add(1, Ordering::Relaxed);
+
+        loop {
+            let message_ident = match i_prot.read_message_begin().await {
+                Ok(message_ident) => message_ident,
+                Err(async_thrift::Error::Transport(ref e)) if e.kind == TransportErrorKind::EndOfFile => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            let body = read_value(&mut *i_prot, TType::Struct).await?;
+            i_prot.read_message_end().await?;
+            self.record(connection, TDirection::ToServer, &message_ident, &body).await;
+
+            match self.forward(&mut upstreams, &message_ident, &body).await {
+                Ok(Some((reply_ident, reply))) => {
+                    self.record(connection, TDirection::ToClient, &reply_ident, &reply).await;
+                    write_message(&mut *o_prot, &reply_ident, &reply).await?;
+                    o_prot.flush().await?;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("failed to forward {} (seqid {}): {:?}", message_ident.name, message_ident.sequence_number, e);
+                    if message_ident.message_type != TMessageType::OneWay {
+                        handle_process_result(&message_ident, Err(e), &mut *o_prot).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn record(&self, connection: u64, direction: TDirection, message_ident: &TMessageIdentifier, body: &TValue) {
+        if let Some(ref capture) = self.capture {
+            let res = match encode_message(message_ident, body).await {
+                Ok(message) => capture.record(connection, direction, &message).map_err(From::from),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = res {
+                warn!("failed to capture {} (seqid {}): {:?}", message_ident.name, message_ident.sequence_number, e);
+            }
+        }
+    }
+
+    /// Send a message to its upstream and return the reply, if one is
+    /// expected.
+    async fn forward(
+        &self,
+        upstreams: &mut HashMap<String, TProtocolPair>,
+        message_ident: &TMessageIdentifier,
+        body: &TValue,
+    ) -> async_thrift::Result<Option<(TMessageIdentifier, TValue)>> {
+        let address = self.upstream_for(&message_ident.name).ok_or_else(|| {
+            new_application_error(
+                ApplicationErrorKind::UnknownMethod,
+                format!("no upstream for method {}", message_ident.name),
+            )
+        })?;
+
+        if !upstreams.contains_key(address) {
+            let upstream = self.upstream.connect(address).await.map_err(|e| upstream_error(address, e))?;
+            upstreams.insert(address.to_owned(), upstream);
+        }
+        let (up_i_prot, up_o_prot) = upstreams.get_mut(address).unwrap();
+
+        let res = async {
+            write_message(&mut **up_o_prot, message_ident, body).await?;
+            up_o_prot.flush().await?;
+            if message_ident.message_type == TMessageType::OneWay {
+                Ok(None)
+            } else {
+                read_message(&mut **up_i_prot).await.map(Some)
+            }
+        }.await;
+
+        // the upstream connection is in an unknown state after a failure
+        res.map_err(|e| {
+            upstreams.remove(address);
+            upstream_error(address, e)
+        })
+    }
+}
+
+fn upstream_error(address: &str, e: async_thrift::Error) -> async_thrift::Error {
+    new_application_error(
+        ApplicationErrorKind::InternalError,
+        format!("upstream {} failed: {:?}", address, e),
+    )
+}
@@ -0,0 +1,272 @@
+//! Replay of captured traffic against a server.
+//!
+//! Every captured connection is replayed on a connection of its own, with
+//! its calls sent in their captured order. Each reply is compared with the
+//! reply captured for the same call.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use async_std::task;
+
+use async_thrift::protocol::async_value::{read_message, write_message, TValue};
+use async_thrift::protocol::{TMessageIdentifier, TMessageType};
+
+use crate::capture::{TCapturedMessage, TDirection};
+use crate::codec::TCodec;
+
+/// A reply that differs from the captured one.
+#[derive(Clone, Debug)]
+pub struct TMismatch {
+    /// Id of the captured connection the call was made on.
+    pub connection: u64,
+    /// The replayed call.
+    pub call: TMessageIdentifier,
+    pub expected: (TMessageIdentifier, TValue),
+    pub actual: (TMessageIdentifier, TValue),
+}
+
+/// Outcome of a replay.
+#[derive(Clone, Debug, Default)]
+pub struct TReplayReport {
+    /// Number of connections replayed.
+    pub connections: usize,
+    /// Number of calls and oneway messages sent.
+    pub calls: usize,
+    /// Number of replies equal to the captured reply.
+    pub matched: usize,
+    /// Number of replies with no captured reply to compare to.
+    pub unchecked: usize,
+    pub mismatches: Vec<TMismatch>,
+    /// Failures that stopped the replay of a connection or skipped a call.
+    pub errors: Vec<String>,
+}
+
+impl TReplayReport {
+    /// Return `true` if every reply matched and nothing failed.
+    pub fn is_success(&self) -> bool {
+        self.mismatches.is_empty() && self.errors.is_empty()
+    }
+
+    fn merge(&mut self, other: TReplayReport) {
+        self.connections += other.connections;
+        self.calls += other.calls;
+        self.matched += other.matched;
+        self.unchecked += other.unchecked;
+        self.mismatches.extend(other.mismatches);
+        self.errors.extend(other.errors);
+    }
+}
+
+/// Replays captured traffic.
+#[derive(Clone, Copy, Debug)]
+pub struct TReplayer {
+    codec: TCodec,
+    timing: bool,
+}
+
+impl TReplayer {
+    /// Create a `TReplayer` that connects to servers with `codec`.
+    ///
+    /// Calls are sent as fast as the server answers them.
+    pub fn new(codec: TCodec) -> TReplayer {
+        TReplayer { codec, timing: false }
+    }
+
+    /// Send every call no earlier than it was captured, relative to the
+    /// start of the replay and the first captured message.
+    pub fn with_timing(mut self, timing: bool) -> TReplayer {
+        self.timing = timing;
+        self
+    }
+
+    /// Replay `messages` against the server at `address`.
+    pub async fn replay(&self, messages: Vec<TCapturedMessage>, address: &str) -> TReplayReport {
+        // captures made by a proxy that ran for a while start late
+        let first = messages.iter().map(|message| message.elapsed).min().unwrap_or_default();
+        let mut connections: BTreeMap<u64, Vec<TCapturedMessage>> = BTreeMap::new();
+        for message in messages {
+            connections.entry(message.connection).or_default().push(message);
+        }
+
+        let started = Instant::now();
+        let replays: Vec<_> = connections
+            .into_iter()
+            .map(|(connection, messages)| {
+                let replayer = *self;
+                let address = address.to_owned();
+                task::spawn(async move {
+                    replayer.replay_connection(connection, messages, &address, started, first).await
+                })
+            })
+            .collect();
+
+        let mut report = TReplayReport::default();
+        for replay in replays {
+            report.merge(replay.await);
+        }
+        report
+    }
+
+    async fn replay_connection(
+        &self,
+        connection: u64,
+        messages: Vec<TCapturedMessage>,
+        address: &str,
+        started: Instant,
+        first: Duration,
+    ) -> TReplayReport {
+        let mut report = TReplayReport { connections: 1, ..TReplayReport::default() };
+        let (mut i_prot, mut o_prot) = match self.codec.connect(address).await {
+            Ok(protocols) => protocols,
+            Err(e) => {
+                report.errors.push(format!("connection {}: cannot connect to {}: {:?}", connection, address, e));
+                return report;
+            }
+        };
+
+        let mut messages = messages.into_iter().peekable();
+        while let Some(message) = messages.next() {
+            // replies are only read as the answer to their call
+            if message.direction != TDirection::ToServer {
+                continue;
+            }
+            let expected = match messages.peek() {
+                Some(reply) if reply.direction == TDirection::ToClient => messages.next(),
+                _ => None,
+            };
+
+            let (call_ident, body) = match message.decode().await {
+                Ok(call) => call,
+                Err(e) => {
+                    report.errors.push(format!("connection {}: skipped undecodable call: {:?}", connection, e));
+                    continue;
+                }
+            };
+
+            if self.timing {
+                let due = started + message.elapsed.saturating_sub(first);
+                task::sleep(due.saturating_duration_since(Instant::now())).await;
+            }
+
+            report.calls += 1;
+            let res = async {
+                write_message(&mut *o_prot, &call_ident, &body).await?;
+                o_prot.flush().await?;
+                if call_ident.message_type == TMessageType::OneWay {
+                    Ok(None)
+                } else {
+                    read_message(&mut *i_prot).await.map(Some)
+                }
+            }.await;
+
+            let actual = match res {
+                Ok(Some(actual)) => actual,
+                Ok(None) => continue,
+                Err(e) => {
+                    report.errors.push(format!(
+                        "connection {}: call to {} (seqid {}) failed: {:?}",
+                        connection, call_ident.name, call_ident.sequence_number, e,
+                    ));
+                    return report;
+                }
+            };
+
+            let expected = match expected {
+                Some(expected) => match expected.decode().await {
+                    Ok(expected) => expected,
+                    Err(e) => {
+                        report.errors.push(format!("connection {}: undecodable captured reply: {:?}", connection, e));
+                        continue;
+                    }
+                },
+                None => {
+                    report.unchecked += 1;
+                    continue;
+                }
+            };
+
+            if same_reply(&expected, &actual) {
+                report.matched += 1;
+            } else {
+                report.mismatches.push(TMismatch { connection, call: call_ident, expected, actual });
+            }
+        }
+        report
+    }
+}
+
+// sequence numbers are replayed as captured, so they are not compared
+fn same_reply(expected: &(TMessageIdentifier, TValue), actual: &(TMessageIdentifier, TValue)) -> bool {
+    expected.0.name == actual.0.name
+        && expected.0.message_type == actual.0.message_type
+        && expected.1 == actual.1
+}
+
+/// Return the time between the first and the last of `messages`.
+pub fn capture_duration(messages: &[TCapturedMessage]) -> Duration {
+    match (messages.first(), messages.last()) {
+        (Some(first), Some(last)) => last.elapsed.saturating_sub(first.elapsed),
+        _ => Duration::from_secs(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use async_std::future::timeout;
+    use async_std::net::TcpListener;
+
+    use crate::capture::encode_message;
+    use crate::codec::{ProtocolKind, TransportKind};
+
+    use super::*;
+
+    fn captured(elapsed: Duration, direction: TDirection, message_ident: &TMessageIdentifier, body: &TValue) -> TCapturedMessage {
+        TCapturedMessage {
+            elapsed,
+            connection: 1,
+            direction,
+            message: task::block_on(encode_message(message_ident, body)).unwrap(),
+        }
+    }
+
+    #[test]
+    fn must_time_calls_from_first_captured_message() {
+        let codec = TCodec::new(TransportKind::Framed, ProtocolKind::Compact);
+        let call_ident = TMessageIdentifier::new("ping", TMessageType::Call, 1);
+        let reply_ident = TMessageIdentifier::new("ping", TMessageType::Reply, 1);
+        let mut fields = BTreeMap::new();
+        fields.insert(0, TValue::I32(7));
+        let reply = TValue::Struct(fields);
+
+        // the capture started an hour before the call
+        let started = Duration::from_secs(3600);
+        let messages = vec![
+            captured(started, TDirection::ToServer, &call_ident, &TValue::Struct(BTreeMap::new())),
+            captured(started + Duration::from_millis(1), TDirection::ToClient, &reply_ident, &reply),
+        ];
+
+        task::block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap().to_string();
+            let server_reply = reply.clone();
+            let server = task::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let (mut i_prot, mut o_prot) = codec.open(stream).unwrap();
+                let (message_ident, _) = read_message(&mut *i_prot).await.unwrap();
+                let reply_ident = TMessageIdentifier::new(message_ident.name, TMessageType::Reply, message_ident.sequence_number);
+                write_message(&mut *o_prot, &reply_ident, &server_reply).await.unwrap();
+                o_prot.flush().await.unwrap();
+            });
+
+            let replayer = TReplayer::new(codec).with_timing(true);
+            let report = timeout(Duration::from_secs(10), replayer.replay(messages, &address)).await.unwrap();
+            server.await;
+
+            assert!(report.is_success(), "{:?}", report);
+            assert_eq!((report.connections, report.calls, report.matched), (1, 1, 1));
+        });
+    }
+}